@@ -0,0 +1,12 @@
+// Measures the fixtures of the workloads, run by "cargo bench --features bench"
+use criterion::{criterion_group, criterion_main, Criterion};
+use mllwriter::bench::FIXTURES;
+
+fn fixtures(c: &mut Criterion) {
+    for fixture in FIXTURES {
+        c.bench_function(fixture.name, |b| b.iter(fixture.run));
+    }
+}
+
+criterion_group!(benches, fixtures);
+criterion_main!(benches);
@@ -0,0 +1,516 @@
+//! The procedural macros of mllwriter, they are used by the macros of the crate mllwriter, e.g. ```mll_write!()```.
+//!
+//! The template of ```mll_write!()``` is parsed when it is compiled: its markup becomes the calls of the writer and
+//! its errors, like an unknown placeholder or an element which isn't closed, are errors of the compiler.
+
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use proc_macro::{Delimiter, Literal, Span, TokenStream, TokenTree};
+
+/// Compiles the template of ```mll_write!()``` into the calls of the writer, it is called as
+/// ```__mll_write!($crate, (wr), "template", name = (value), ...)``` by ```mll_write!()```
+#[proc_macro]
+pub fn mll_write(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err((message, span)) => compile_error(&message, span),
+    }
+}
+
+type Error = (String, Span);
+
+// A part of a text or of an attribute value
+enum Part {
+    Literal(String),
+    // a character reference of the text, e.g. "&nbsp;", which is written as it is
+    Reference(String),
+    // the index of the value of a placeholder
+    Value(usize),
+}
+
+// A call of the writer
+enum Call {
+    Text(Vec<Part>),
+    LineFeed,
+    LineFeedInc,
+    DecIndentStep,
+    Open(String),
+    Single(String),
+    Property(String, Vec<Part>),
+    Close,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut args = split_args(input);
+    if args.len() < 3 {
+        return Err((
+            "mll_write!() needs a writer and a template".to_string(),
+            Span::call_site(),
+        ));
+    }
+    let mut values = Vec::new();
+    let mut names = Vec::new();
+    for arg in args.drain(3..) {
+        match arg.as_slice() {
+            [TokenTree::Ident(name), TokenTree::Punct(eq), value] if eq.as_char() == '=' => {
+                names.push(name.to_string());
+                values.push(value.clone());
+            }
+            _ => return Err(("expected 'name = value'".to_string(), Span::call_site())),
+        }
+    }
+    let (krate, wr, template) = match (&args[0][..], &args[1][..], &args[2][..]) {
+        ([krate], [wr], [template]) => (krate.clone(), wr.clone(), unwrap_group(template.clone())),
+        _ => {
+            return Err((
+                "expected a writer and a template".to_string(),
+                Span::call_site(),
+            ))
+        }
+    };
+    let span = template.span();
+    let literal = match template {
+        TokenTree::Literal(literal) => literal,
+        _ => return Err(("the template has to be a string literal".to_string(), span)),
+    };
+    let source = unquote(&literal.to_string())
+        .ok_or_else(|| ("the template has to be a string literal".to_string(), span))?;
+    let mut parser = Parser {
+        names: &names,
+        used: vec![false; names.len()],
+        levels: vec![0],
+        open: Vec::new(),
+        calls: Vec::new(),
+    };
+    parser.parse(&source).map_err(|message| (message, span))?;
+    if let Some(i) = parser.used.iter().position(|used| !used) {
+        return Err((
+            format!("the value '{}' isn't used by the template", names[i]),
+            span,
+        ));
+    }
+    let code = generate(&parser.calls, values.len());
+    let tokens = TokenStream::from_str(&code).expect("the generated code is valid");
+    Ok(substitute(tokens, &krate, &wr, &values))
+}
+
+// Splits the input at its commas, the expressions are in parentheses
+fn split_args(input: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut args = vec![Vec::new()];
+    for token in input {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(Vec::new()),
+            _ => args.last_mut().expect("one argument at least").push(token),
+        }
+    }
+    if args.last().is_some_and(|arg| arg.is_empty()) {
+        args.pop();
+    }
+    args
+}
+
+// A fragment of a macro_rules!-macro, like the literal, is passed in an invisible group
+fn unwrap_group(token: TokenTree) -> TokenTree {
+    match token {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            match group.stream().into_iter().collect::<Vec<_>>().as_slice() {
+                [token] => unwrap_group(token.clone()),
+                _ => TokenTree::Group(group),
+            }
+        }
+        token => token,
+    }
+}
+
+// Returns the content of a string literal, or None if it isn't one
+fn unquote(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let content = raw.get(hashes + 1..raw.len().checked_sub(hashes + 1)?)?;
+        return Some(content.to_string());
+    }
+    let content = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => unquoted.push('\n'),
+            'r' => unquoted.push('\r'),
+            't' => unquoted.push('\t'),
+            '0' => unquoted.push('\0'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                unquoted.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                let hex: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                unquoted.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // A line continuation skips the line-feed and the whitespace of the next line
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+struct Parser<'a> {
+    names: &'a [String],
+    used: Vec<bool>,
+    // the indents of the template lines, which are written at the current and the outer indents of the writer
+    levels: Vec<usize>,
+    // the elements opened by the template
+    open: Vec<String>,
+    calls: Vec<Call>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self, template: &str) -> Result<(), String> {
+        let mut chars = template.char_indices().peekable();
+        let mut text_start = 0;
+        while let Some((i, c)) = chars.next() {
+            if c == '<' {
+                self.text(&template[text_start..i])?;
+                self.tag(template, &mut chars)?;
+                text_start = chars.peek().map_or(template.len(), |(i, _)| *i);
+            }
+        }
+        self.text(&template[text_start..])?;
+        match self.open.last() {
+            Some(tag) => Err(format!("'{}' isn't closed", tag)),
+            None => Ok(()),
+        }
+    }
+
+    // Writes the text between two tags, its line-feeds follow the indent of the template lines
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        let mut lines = text.split('\n').peekable();
+        if let Some(first) = lines.next() {
+            if lines.peek().is_none() || !first.trim().is_empty() {
+                self.write_text(first)?;
+            }
+        }
+        while let Some(line) = lines.next() {
+            let content = line.trim_start_matches([' ', '\t']);
+            // Blank lines are dropped
+            if content.is_empty() && lines.peek().is_some() {
+                continue;
+            }
+            self.line_feed(line.len() - content.len());
+            self.write_text(content)?;
+        }
+        Ok(())
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), String> {
+        if !text.is_empty() {
+            let parts = self.interpolate(text, false)?;
+            self.calls.push(Call::Text(parts));
+        }
+        Ok(())
+    }
+
+    fn line_feed(&mut self, indent: usize) {
+        let current = self.levels.last().copied().unwrap_or_default();
+        if indent > current {
+            self.levels.push(indent);
+            self.calls.push(Call::LineFeedInc);
+            return;
+        }
+        while self.levels.len() > 1 && self.levels.last().is_some_and(|l| *l > indent) {
+            self.levels.pop();
+            self.calls.push(Call::DecIndentStep);
+        }
+        self.calls.push(Call::LineFeed);
+    }
+
+    // Writes the tag, which starts at the '<' in front of chars
+    fn tag(&mut self, template: &str, chars: &mut Peekable<CharIndices>) -> Result<(), String> {
+        let end_tag = chars.next_if(|(_, c)| *c == '/').is_some();
+        let name = take_while(template, chars, |c| {
+            !c.is_whitespace() && c != '/' && c != '>'
+        });
+        if name.is_empty() || name.starts_with('!') || name.starts_with('?') {
+            return Err(format!("unsupported markup '<{}'", name));
+        }
+        if end_tag {
+            skip_whitespace(chars);
+            expect(chars, '>', name)?;
+            return self.close(name);
+        }
+        let mut properties = Vec::new();
+        loop {
+            skip_whitespace(chars);
+            match chars.next() {
+                Some((_, '>')) => {
+                    self.calls.push(Call::Open(name.to_string()));
+                    self.open.push(name.to_string());
+                    break;
+                }
+                Some((_, '/')) => {
+                    expect(chars, '>', name)?;
+                    self.calls.push(Call::Single(name.to_string()));
+                    break;
+                }
+                Some((i, _)) => {
+                    let rest = &template[i..];
+                    let len = rest
+                        .find(|c: char| c.is_whitespace() || c == '=' || c == '/' || c == '>')
+                        .unwrap_or(rest.len());
+                    let attribute = &rest[..len];
+                    while chars.next_if(|(j, _)| *j < i + len).is_some() {}
+                    let value = if chars.next_if(|(_, c)| *c == '=').is_some() {
+                        let quote = match chars.next() {
+                            Some((_, quote @ ('"' | '\''))) => quote,
+                            _ => return Err(format!("the value of '{}' isn't quoted", attribute)),
+                        };
+                        let value = take_while(template, chars, |c| c != quote);
+                        expect(chars, quote, name)?;
+                        self.interpolate(value, true)?
+                    } else {
+                        Vec::new()
+                    };
+                    properties.push(Call::Property(attribute.to_string(), value));
+                }
+                None => return Err(format!("the tag '{}' isn't closed by '>'", name)),
+            }
+        }
+        self.calls.extend(properties);
+        Ok(())
+    }
+
+    // Closes the element, an element opened in front of the template can be closed as well
+    fn close(&mut self, name: &str) -> Result<(), String> {
+        match self.open.pop() {
+            Some(open) if open != name => {
+                Err(format!("the end-tag '{}' doesn't match '{}'", name, open))
+            }
+            _ => {
+                self.calls.push(Call::Close);
+                Ok(())
+            }
+        }
+    }
+
+    // Splits the text into its literal parts, its character references and its placeholders. The writer escapes
+    // an attribute value, so its references are replaced by their characters.
+    fn interpolate(&mut self, text: &str, attribute: bool) -> Result<Vec<Part>, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = text;
+        while let Some(i) = rest.find(['{', '}', '&']) {
+            literal.push_str(&rest[..i]);
+            let c = &rest[i..i + 1];
+            if c == "&" {
+                let end = rest[i..].find(';').map(|end| i + end + 1);
+                match end.map(|end| (&rest[i..end], end)) {
+                    Some((reference, end)) if is_reference(reference) => {
+                        if attribute {
+                            literal.push(decode(reference)?);
+                        } else {
+                            parts.extend(take(&mut literal));
+                            parts.push(Part::Reference(reference.to_string()));
+                        }
+                        rest = &rest[end..];
+                    }
+                    _ => {
+                        literal.push('&');
+                        rest = &rest[i + 1..];
+                    }
+                }
+                continue;
+            }
+            if rest[i + 1..].starts_with(c) {
+                literal.push_str(c);
+                rest = &rest[i + 2..];
+                continue;
+            }
+            let end = match (c, rest[i..].find('}')) {
+                ("{", Some(end)) => i + end,
+                _ => return Err(format!("unmatched '{}' in '{}'", c, text)),
+            };
+            let name = &rest[i + 1..end];
+            let index = self
+                .names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| format!("the placeholder '{{{}}}' has no value", name))?;
+            self.used[index] = true;
+            parts.extend(take(&mut literal));
+            parts.push(Part::Value(index));
+            rest = &rest[end + 1..];
+        }
+        literal.push_str(rest);
+        parts.extend(take(&mut literal));
+        Ok(parts)
+    }
+}
+
+// Takes the literal text collected so far as a part
+fn take(literal: &mut String) -> Option<Part> {
+    (!literal.is_empty()).then(|| Part::Literal(std::mem::take(literal)))
+}
+
+// Returns true if the text is a character reference, e.g. "&amp;" or "&#39;"
+fn is_reference(text: &str) -> bool {
+    let reference = &text[1..text.len() - 1];
+    match reference.strip_prefix('#') {
+        Some(number) => match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => !reference.is_empty() && reference.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+// Returns the character of a reference in an attribute value, only the ones of XML and numbers are known
+fn decode(reference: &str) -> Result<char, String> {
+    let decoded = match &reference[1..reference.len() - 1] {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        number => number.strip_prefix('#').and_then(|number| {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => number.parse(),
+            };
+            code.ok().and_then(char::from_u32)
+        }),
+    };
+    decoded.ok_or_else(|| {
+        format!(
+            "the reference '{}' isn't known in an attribute value, write its character instead",
+            reference
+        )
+    })
+}
+
+// Returns the characters up to the first one, which doesn't match
+fn take_while<'t>(
+    template: &'t str,
+    chars: &mut Peekable<CharIndices>,
+    f: impl Fn(char) -> bool,
+) -> &'t str {
+    let start = chars.peek().map_or(template.len(), |(i, _)| *i);
+    while chars.next_if(|(_, c)| f(*c)).is_some() {}
+    let end = chars.peek().map_or(template.len(), |(i, _)| *i);
+    &template[start..end]
+}
+
+fn skip_whitespace(chars: &mut Peekable<CharIndices>) {
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Peekable<CharIndices>, expected: char, tag: &str) -> Result<(), String> {
+    match chars.next() {
+        Some((_, c)) if c == expected => Ok(()),
+        _ => Err(format!("expected '{}' in the tag '{}'", expected, tag)),
+    }
+}
+
+// Generates the calls, "__mll_crate", "__mll_wr" and "__mll_value_N" are substituted by the tokens of the input
+fn generate(calls: &[Call], values: usize) -> String {
+    let bindings: String = (0..values)
+        .map(|i| format!("&__mll_value_{},", i))
+        .collect();
+    let names: String = (0..values).map(|i| format!("__v{},", i)).collect();
+    let mut code = format!(
+        "match ({}) {{ ({}) => __mll_wr.write_template(|__wr| {{ use __mll_crate::MLLWriter as _;",
+        bindings, names
+    );
+    for call in calls {
+        let call = match call {
+            Call::Text(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    Part::Literal(literal) => format!("__wr.try_text({})?;", string(literal)),
+                    Part::Reference(reference) => format!("__wr.try_raw({})?;", string(reference)),
+                    part => format!("__wr.try_text(&{})?;", value(part)),
+                })
+                .collect(),
+            Call::LineFeed => "__wr.line_feed(1);".to_string(),
+            Call::LineFeedInc => "__wr.line_feed_inc();".to_string(),
+            Call::DecIndentStep => "__wr.dec_indent_step();".to_string(),
+            Call::Open(name) => format!("__wr.try_open_tag({})?;", string(name)),
+            Call::Single(name) => format!("__wr.try_single_tag({})?;", string(name)),
+            Call::Property(name, parts) if parts.is_empty() => {
+                format!("__wr.try_add_property({}, \"\")?;", string(name))
+            }
+            Call::Property(name, parts) => {
+                let parts: Vec<String> = parts.iter().map(value).collect();
+                format!(
+                    "__wr.try_add_property({}, &[{}].concat())?;",
+                    string(name),
+                    parts.join(",")
+                )
+            }
+            Call::Close => "__wr.try_close_tag()?;".to_string(),
+        };
+        code.push_str(&call);
+    }
+    code.push_str("::core::result::Result::Ok(()) }) }");
+    code
+}
+
+// Returns the expression of a literal part or of a value as String
+fn value(part: &Part) -> String {
+    match part {
+        Part::Literal(literal) | Part::Reference(literal) => {
+            format!("::std::string::String::from({})", string(literal))
+        }
+        Part::Value(i) => format!("::std::string::ToString::to_string(__v{})", i),
+    }
+}
+
+fn string(text: &str) -> String {
+    Literal::string(text).to_string()
+}
+
+// Replaces the placeholders of the generated code by the tokens of the input
+fn substitute(
+    tokens: TokenStream,
+    krate: &TokenTree,
+    wr: &TokenTree,
+    values: &[TokenTree],
+) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                "__mll_crate" => krate.clone(),
+                "__mll_wr" => wr.clone(),
+                name => match name.strip_prefix("__mll_value_") {
+                    Some(i) => values[i.parse::<usize>().expect("the index of a value")].clone(),
+                    None => TokenTree::Ident(ident),
+                },
+            },
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), krate, wr, values);
+                let mut substituted = proc_macro::Group::new(group.delimiter(), stream);
+                substituted.set_span(group.span());
+                TokenTree::Group(substituted)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+// Returns the error as compile_error!() at the span of the template
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let tokens = TokenStream::from_str(&format!("::core::compile_error!({})", string(message)))
+        .expect("the error is valid");
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
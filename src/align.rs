@@ -0,0 +1,140 @@
+//! Column-aligned property output.
+//!
+//! With alignment enabled, the properties of all entries of a block, which are written on their own lines,
+//! are padded so they line up in columns. In XML and HTML each property of sibling elements starts at the
+//! same column, in JSON the values of the properties of a block line up. The padding is computed when the
+//! block gets closed and inserted retroactively into the content.
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! wr.core.set_auto_layout(true);
+//! wr.core.set_align_properties(true);
+//! wr.open_tag("config");
+//! wr.single_tag_w_property("add", "key", "Name");
+//! wr.add_property("value", "1");
+//! wr.single_tag_w_property("property", "key", "LongName");
+//! wr.add_property("value", "2");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<config>\n  <add      key=\"Name\"     value=\"1\">\n  <property key=\"LongName\" value=\"2\">\n</config>"
+//! );
+//! ```
+
+use crate::WriterCore;
+
+// One entry of a block, which is aligned with its siblings
+#[derive(Debug, Clone)]
+struct AlignEntry {
+    // content-offset of the start of the entry
+    start: usize,
+    // content-offsets where padding can be inserted, one per column
+    splits: Vec<usize>,
+}
+
+// The entries of an open block
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AlignBlock {
+    // None marks an entry which doesn't start on its own line, so it is not aligned
+    entries: Vec<Option<AlignEntry>>,
+}
+
+impl WriterCore {
+    /// Enables or disables the column-alignment of properties
+    pub fn set_align_properties(&mut self, align_properties: bool) {
+        self.align_properties = align_properties;
+    }
+
+    // Called when a new block gets opened
+    pub(crate) fn align_open_block(&mut self) {
+        if self.align_properties {
+            self.align_stack.push(AlignBlock::default());
+        }
+    }
+
+    // Called when a new entry starts at the end of content. Entries which don't start on their own line
+    // are not aligned.
+    pub(crate) fn align_entry(&mut self, content: &str) {
+        if !self.align_properties {
+            return;
+        }
+        let line_start = content.rfind('\n').map_or(0, |i| i + 1);
+        let own_line = content[line_start..].trim().is_empty();
+        if let Some(block) = self.align_stack.last_mut() {
+            block.entries.push(own_line.then(|| AlignEntry {
+                start: content.len(),
+                splits: Vec::new(),
+            }));
+        }
+    }
+
+    // Called when a new column of the current entry starts at the given content-offset
+    pub(crate) fn align_split(&mut self, offset: usize) {
+        if let Some(Some(entry)) = self
+            .align_stack
+            .last_mut()
+            .and_then(|b| b.entries.last_mut())
+        {
+            entry.splits.push(offset);
+        }
+    }
+
+    // Called when the content at the offset got replaced by a text of another length, moves the later splits
+    pub(crate) fn align_shift(&mut self, offset: usize, old_len: usize, new_len: usize) {
+        if let Some(Some(entry)) = self
+            .align_stack
+            .last_mut()
+            .and_then(|b| b.entries.last_mut())
+        {
+            for split in entry.splits.iter_mut().filter(|split| **split > offset) {
+                *split = *split + new_len - old_len;
+            }
+        }
+    }
+
+    // Called when a block gets closed, inserts the padding into content
+    pub(crate) fn align_close_block(&mut self, content: &mut String) {
+        if !self.align_properties {
+            return;
+        }
+        let block = match self.align_stack.pop() {
+            Some(block) => block,
+            None => return,
+        };
+        let entries: Vec<&AlignEntry> = block
+            .entries
+            .iter()
+            .flatten()
+            .filter(|e| !e.splits.is_empty())
+            .collect();
+        let columns = entries.iter().map(|e| e.splits.len()).max().unwrap_or(0);
+        // Compute the padding column by column, the padding of a column shifts all following ones
+        let mut shift = vec![0; entries.len()];
+        let mut inserts: Vec<(usize, usize)> = Vec::new();
+        for c in 0..columns {
+            // The width of the entries in front of column c, None if the entry has no column c
+            let widths: Vec<Option<usize>> = entries
+                .iter()
+                .zip(shift.iter())
+                .map(|(e, s)| e.splits.get(c).map(|split| split - e.start + s))
+                .collect();
+            let target = widths.iter().flatten().max().copied().unwrap_or(0);
+            for (i, width) in widths.iter().enumerate() {
+                if let Some(width) = width {
+                    let pad = target - width;
+                    if pad > 0 {
+                        inserts.push((entries[i].splits[c], pad));
+                    }
+                    shift[i] += pad;
+                }
+            }
+        }
+        // Insert from the back, so the offsets in front stay valid. The closed block is the tail of the
+        // content, so the offsets of the outer blocks are not affected.
+        inserts.sort_unstable();
+        for &(offset, pad) in inserts.iter().rev() {
+            content.insert_str(offset, &" ".repeat(pad));
+        }
+    }
+}
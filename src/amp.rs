@@ -0,0 +1,341 @@
+//! AMP-documents, which have to follow the constraints of AMP.
+//!
+//! In AMP-mode the HTMLWriter accepts only the elements, which AMP allows, and the components of AMP ("amp-*"),
+//! other elements, e.g. own scripts, and event handlers are reported as violations like in the strict mode. The
+//! 'html'-element gets the attribute "amp", and the head can only be closed after the required boilerplate was
+//! written by ```amp_boilerplate()```.
+//! Images are written by ```image()```, which substitutes 'img' by 'amp-img'.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_amp(true);
+//! wr.open_tag("html");
+//! wr.open_tag("head");
+//! wr.amp_boilerplate("https://example.com/article.html");
+//! wr.close_tag();
+//! wr.open_tag("body");
+//! wr.image("/cat.jpg", 640, 480, "A cat");
+//! wr.close_tag();
+//! wr.close_tag();
+//! assert!(wr.content.starts_with("<html amp=\"\"><head><meta charset=\"utf-8\">"));
+//! assert!(wr.content.contains(
+//!     "<body><amp-img src=\"/cat.jpg\" width=\"640\" height=\"480\" layout=\"responsive\" alt=\"A cat\"></amp-img>"
+//! ));
+//! ```
+
+use crate::{check, escape_attr, HTMLWriter, WriterCore, WriterError};
+
+// The elements, which AMP allows besides its components, e.g. "style" for the one with "amp-custom", the scripts
+// of AMP are written by the writer
+const ALLOWED_TAGS: [&str; 108] = [
+    "a",
+    "abbr",
+    "address",
+    "article",
+    "aside",
+    "b",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "caption",
+    "circle",
+    "cite",
+    "clippath",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "datalist",
+    "dd",
+    "defs",
+    "del",
+    "desc",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "ellipse",
+    "em",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "g",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "i",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "line",
+    "link",
+    "main",
+    "mark",
+    "meta",
+    "meter",
+    "nav",
+    "noscript",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "path",
+    "polygon",
+    "polyline",
+    "pre",
+    "progress",
+    "q",
+    "rect",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "section",
+    "select",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "svg",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "title",
+    "tr",
+    "track",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
+
+// The elements, which AMP replaces by own ones, with the reason
+const REPLACED_TAGS: [(&str, &str); 5] = [
+    ("img", "images are written as amp-img, see image()"),
+    ("video", "videos are written as amp-video"),
+    ("audio", "audios are written as amp-audio"),
+    ("iframe", "frames are written as amp-iframe"),
+    (
+        "script",
+        "only the runtime and the components of AMP are allowed, see amp_boilerplate()",
+    ),
+];
+
+const AMP_RUNTIME: &str = "https://cdn.ampproject.org/v0.js";
+
+const BOILERPLATE_STYLE: &str = "body{-webkit-animation:-amp-start 8s steps(1,end) 0s 1 normal both;\
+-moz-animation:-amp-start 8s steps(1,end) 0s 1 normal both;-ms-animation:-amp-start 8s steps(1,end) 0s 1 normal both;\
+animation:-amp-start 8s steps(1,end) 0s 1 normal both}@-webkit-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@-moz-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@-ms-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@-o-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}\
+@keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}";
+
+const BOILERPLATE_NOSCRIPT: &str =
+    "body{-webkit-animation:none;-moz-animation:none;-ms-animation:none;animation:none}";
+
+impl HTMLWriter {
+    /// Enables or disables the AMP-mode
+    pub fn set_amp(&mut self, amp: bool) {
+        self.amp = amp;
+    }
+
+    /// Writes the required elements of the head of an AMP-document: the charset, the AMP-runtime, the viewport,
+    /// the canonical link to the regular page and the boilerplate style
+    pub fn amp_boilerplate(&mut self, canonical_url: &str) {
+        if self.core.block_stack.last().map(String::as_str) != Some("head") {
+            check(Err(WriterError::AmpViolation {
+                name: "amp_boilerplate".to_string(),
+                reason: "the boilerplate belongs into the head",
+            }))
+        }
+        self.add_void("meta", &[("charset", "utf-8")]);
+        self.amp_raw(&format!(
+            "{}></script>",
+            start_tag(
+                &self.core,
+                "script",
+                &[("async", String::new()), ("src", AMP_RUNTIME.to_string())]
+            )
+        ));
+        self.add_meta("viewport", "width=device-width");
+        self.add_link("canonical", canonical_url);
+        self.amp_raw(&format!(
+            "<style amp-boilerplate>{}</style>",
+            BOILERPLATE_STYLE
+        ));
+        self.amp_raw(&format!(
+            "<noscript><style amp-boilerplate>{}</style></noscript>",
+            BOILERPLATE_NOSCRIPT
+        ));
+        self.amp_boilerplate = true;
+    }
+
+    /// Loads an AMP-component, e.g. "amp-carousel" in version "0.1"
+    pub fn amp_component(&mut self, name: &str, version: &str) {
+        let src = format!("https://cdn.ampproject.org/v0/{}-{}.js", name, version);
+        self.amp_raw(&format!(
+            "{}></script>",
+            start_tag(
+                &self.core,
+                "script",
+                &[
+                    ("async", String::new()),
+                    ("custom-element", name.to_string()),
+                    ("src", src)
+                ]
+            )
+        ));
+    }
+
+    /// Writes an image with its size, in AMP-mode as 'amp-img' with a responsive layout
+    pub fn image(&mut self, src: &str, width: u32, height: u32, alt: &str) {
+        let (width, height) = (width.to_string(), height.to_string());
+        if !self.amp {
+            self.add_void(
+                "img",
+                &[
+                    ("src", src),
+                    ("width", &width),
+                    ("height", &height),
+                    ("alt", alt),
+                ],
+            );
+            return;
+        }
+        let attributes = [
+            ("src", src.to_string()),
+            ("width", width),
+            ("height", height),
+            ("layout", "responsive".to_string()),
+            ("alt", alt.to_string()),
+        ];
+        self.amp_raw(&format!(
+            "{}></amp-img>",
+            start_tag(&self.core, "amp-img", &attributes)
+        ));
+    }
+
+    // Writes markup, which the notation of the writer doesn't allow, e.g. the tags of AMP
+    fn amp_raw(&mut self, markup: &str) {
+        check(self.core.begin_markup("amp_raw"));
+        self.core.layout_node(&mut self.content);
+        self.content.push_str(markup);
+    }
+}
+
+// Checks if AMP allows the element
+pub(crate) fn check_tag(tag: &str) -> Result<(), WriterError> {
+    if tag.starts_with("amp-") || ALLOWED_TAGS.contains(&tag) {
+        return Ok(());
+    }
+    let reason = REPLACED_TAGS
+        .iter()
+        .find(|(replaced, _)| *replaced == tag)
+        .map_or("AMP doesn't allow the element", |(_, reason)| reason);
+    Err(WriterError::AmpViolation {
+        name: tag.to_string(),
+        reason,
+    })
+}
+
+// Checks if AMP allows the attribute, event handlers are replaced by the attribute "on" of AMP
+pub(crate) fn check_attribute(name: &str) -> Result<(), WriterError> {
+    if name.starts_with("on") && name != "on" {
+        return Err(WriterError::AmpViolation {
+            name: name.to_string(),
+            reason: "event handlers aren't allowed, use the attribute 'on'",
+        });
+    }
+    Ok(())
+}
+
+// Checks if the head can be closed
+pub(crate) fn check_head(boilerplate: bool) -> Result<(), WriterError> {
+    if boilerplate {
+        Ok(())
+    } else {
+        Err(WriterError::AmpViolation {
+            name: "head".to_string(),
+            reason: "the head needs the AMP-boilerplate, see amp_boilerplate()",
+        })
+    }
+}
+
+// Returns the start-tag without the closing '>', the values are quoted by the QuoteStyle of the core
+fn start_tag(core: &WriterCore, tag: &str, attributes: &[(&str, String)]) -> String {
+    let mut start = format!("<{}", tag);
+    for (name, value) in attributes {
+        start.push_str(&core.property_string(name, &escape_attr(value)));
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn amp_violations() {
+        assert!(check_tag("iframe").is_err());
+        assert!(check_tag("blink").is_err());
+        assert!(check_tag("section").is_ok());
+        assert!(check_tag("amp-carousel").is_ok());
+        assert!(check_attribute("onclick").is_err());
+        assert!(check_attribute("on").is_ok());
+        let mut wr = HTMLWriter::new();
+        wr.image("/a.png", 1, 2, "a");
+        assert_eq!(
+            wr.content,
+            "<img src=\"/a.png\" width=\"1\" height=\"2\" alt=\"a\">"
+        );
+        wr.content.clear();
+        wr.set_amp(true);
+        wr.amp_component("amp-carousel", "0.1");
+        assert_eq!(
+            wr.content,
+            "<script async=\"\" custom-element=\"amp-carousel\" \
+             src=\"https://cdn.ampproject.org/v0/amp-carousel-0.1.js\"></script>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the head needs the AMP-boilerplate")]
+    fn amp_head_without_boilerplate() {
+        let mut wr = HTMLWriter::new();
+        wr.set_amp(true);
+        wr.open_tag("head");
+        wr.close_tag();
+    }
+}
@@ -0,0 +1,305 @@
+//! Typed attributes of HTML-elements.
+//!
+//! ```wr.add_attr()``` takes an [`Attr`], so a typo in the name of a common attribute doesn't compile. Data- and
+//! ARIA-attributes get their prefix, e.g. ```Attr::Data("user-id", ..)``` is written as "data-user-id". Values are
+//! escaped like in ```add_property()```, which stays available for all other attributes.
+//!
+//! ```add_attr()``` also takes a name-value pair, e.g. ```("data-id", "7")```, which becomes the typed attribute of
+//! the name. Names without a typed attribute are kept as ```Attr::Other```.
+//!
+//! ```wr.add_class()``` and ```wr.add_style()``` merge into the 'class'- and 'style'-attribute of the last start-tag,
+//! so an element never gets them twice.
+//!
+//! ```
+//! # use mllwriter::{Attr, HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("a");
+//! wr.add_attr(Attr::Href("/cart".to_string()));
+//! wr.add_attr(Attr::Class("button primary".to_string()));
+//! wr.add_attr(Attr::Data("item-count".to_string(), "3".to_string()));
+//! wr.add_attr(Attr::Hidden);
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<a href=\"/cart\" class=\"button primary\" data-item-count=\"3\" hidden=\"\"></a>"
+//! );
+//! ```
+
+use crate::escape::unescape;
+use crate::properties::{merge_class, merge_style};
+use crate::{check, check_attribute_name, escape_attr, HTMLWriter, Properties, WriterError};
+
+/// The common attributes of HTML-elements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attr {
+    /// The unique identifier of the element
+    Id(String),
+    /// The classes separated by spaces, see ```add_class()``` to merge them
+    Class(String),
+    /// The CSS-declarations, see ```add_style()``` to merge them
+    Style(String),
+    /// The advisory information, e.g. shown as tooltip
+    Title(String),
+    /// The language of the content, e.g. "de-DE"
+    Lang(String),
+    /// The URL of a link
+    Href(String),
+    /// The URL of an embedded resource
+    Src(String),
+    /// The alternative text of an image
+    Alt(String),
+    /// The relation of a link, e.g. "stylesheet"
+    Rel(String),
+    /// The browsing context of a link, e.g. "_blank"
+    Target(String),
+    /// The type of an input or a resource, e.g. "checkbox"
+    Type(String),
+    /// The name of a form control
+    Name(String),
+    /// The value of a form control
+    Value(String),
+    /// The id of the labeled form control
+    For(String),
+    /// The ARIA-role, e.g. "navigation"
+    Role(String),
+    /// The width in pixels
+    Width(u32),
+    /// The height in pixels
+    Height(u32),
+    /// Boolean attribute "hidden"
+    Hidden,
+    /// Boolean attribute "disabled"
+    Disabled,
+    /// Boolean attribute "checked"
+    Checked,
+    /// Boolean attribute "required"
+    Required,
+    /// "data-" and the key
+    Data(String, String),
+    /// "aria-" and the key
+    Aria(String, String),
+    /// Any other attribute by its name
+    Other(String, String),
+}
+
+impl Attr {
+    /// Returns the name of the attribute, e.g. "data-user-id"
+    pub fn name(&self) -> String {
+        match self {
+            Attr::Id(_) => "id".to_string(),
+            Attr::Class(_) => "class".to_string(),
+            Attr::Style(_) => "style".to_string(),
+            Attr::Title(_) => "title".to_string(),
+            Attr::Lang(_) => "lang".to_string(),
+            Attr::Href(_) => "href".to_string(),
+            Attr::Src(_) => "src".to_string(),
+            Attr::Alt(_) => "alt".to_string(),
+            Attr::Rel(_) => "rel".to_string(),
+            Attr::Target(_) => "target".to_string(),
+            Attr::Type(_) => "type".to_string(),
+            Attr::Name(_) => "name".to_string(),
+            Attr::Value(_) => "value".to_string(),
+            Attr::For(_) => "for".to_string(),
+            Attr::Role(_) => "role".to_string(),
+            Attr::Width(_) => "width".to_string(),
+            Attr::Height(_) => "height".to_string(),
+            Attr::Hidden => "hidden".to_string(),
+            Attr::Disabled => "disabled".to_string(),
+            Attr::Checked => "checked".to_string(),
+            Attr::Required => "required".to_string(),
+            Attr::Data(key, _) => format!("data-{}", key),
+            Attr::Aria(key, _) => format!("aria-{}", key),
+            Attr::Other(name, _) => name.clone(),
+        }
+    }
+
+    /// Returns the value of the attribute, boolean attributes have an empty value
+    pub fn value(&self) -> String {
+        match self {
+            Attr::Id(v)
+            | Attr::Class(v)
+            | Attr::Style(v)
+            | Attr::Title(v)
+            | Attr::Lang(v)
+            | Attr::Href(v)
+            | Attr::Src(v)
+            | Attr::Alt(v)
+            | Attr::Rel(v)
+            | Attr::Target(v)
+            | Attr::Type(v)
+            | Attr::Name(v)
+            | Attr::Value(v)
+            | Attr::For(v)
+            | Attr::Role(v)
+            | Attr::Data(_, v)
+            | Attr::Aria(_, v)
+            | Attr::Other(_, v) => v.clone(),
+            Attr::Width(n) | Attr::Height(n) => n.to_string(),
+            Attr::Hidden | Attr::Disabled | Attr::Checked | Attr::Required => String::new(),
+        }
+    }
+}
+
+impl From<&Attr> for Attr {
+    fn from(attr: &Attr) -> Attr {
+        attr.clone()
+    }
+}
+
+impl From<(&str, &str)> for Attr {
+    /// Returns the typed attribute of the name, a width or height, which isn't a number, stays ```Other```
+    fn from((name, value): (&str, &str)) -> Attr {
+        let v = value.to_string();
+        match name {
+            "id" => Attr::Id(v),
+            "class" => Attr::Class(v),
+            "style" => Attr::Style(v),
+            "title" => Attr::Title(v),
+            "lang" => Attr::Lang(v),
+            "href" => Attr::Href(v),
+            "src" => Attr::Src(v),
+            "alt" => Attr::Alt(v),
+            "rel" => Attr::Rel(v),
+            "target" => Attr::Target(v),
+            "type" => Attr::Type(v),
+            "name" => Attr::Name(v),
+            "value" => Attr::Value(v),
+            "for" => Attr::For(v),
+            "role" => Attr::Role(v),
+            "width" | "height" => match (name, value.parse()) {
+                ("width", Ok(n)) => Attr::Width(n),
+                (_, Ok(n)) => Attr::Height(n),
+                _ => Attr::Other(name.to_string(), v),
+            },
+            "hidden" => Attr::Hidden,
+            "disabled" => Attr::Disabled,
+            "checked" => Attr::Checked,
+            "required" => Attr::Required,
+            _ => match (name.strip_prefix("data-"), name.strip_prefix("aria-")) {
+                (Some(key), _) => Attr::Data(key.to_string(), v),
+                (_, Some(key)) => Attr::Aria(key.to_string(), v),
+                _ => Attr::Other(name.to_string(), v),
+            },
+        }
+    }
+}
+
+impl HTMLWriter {
+    /// Adds the typed attribute to the last start-tag, the value is escaped
+    pub fn add_attr(&mut self, attr: impl Into<Attr>) {
+        check(self.try_add_attr(attr));
+    }
+
+    /// Like ```add_attr()```, but returns the error instead of panicking, e.g. ```InvalidAttributeName``` for an
+    /// invalid key of a data-attribute
+    pub fn try_add_attr(&mut self, attr: impl Into<Attr>) -> Result<(), WriterError> {
+        let attr = attr.into();
+        let name = attr.name();
+        // The keys of data- and aria-attributes may contain '-', e.g. "user-id"
+        if let Attr::Data(..) | Attr::Aria(..) | Attr::Other(..) = &attr {
+            check_attribute_name(&name)?;
+        }
+        self.core.begin_property("add_attr")?;
+        self.write_properties(&Properties::new(&name, &attr.value()), true, "add_attr")
+    }
+
+    /// Adds the classes to the 'class'-attribute of the last start-tag, which is added if missing
+    pub fn add_class(&mut self, class: &str) {
+        check(self.try_add_class(class));
+    }
+
+    /// Like ```add_class()```, but returns the error instead of panicking
+    pub fn try_add_class(&mut self, class: &str) -> Result<(), WriterError> {
+        self.merge_property("class", class, merge_class, "add_class")
+    }
+
+    /// Adds the declaration of the CSS-property to the 'style'-attribute of the last start-tag
+    pub fn add_style(&mut self, property: &str, value: &str) {
+        check(self.try_add_style(property, value));
+    }
+
+    /// Like ```add_style()```, but returns the error instead of panicking
+    pub fn try_add_style(&mut self, property: &str, value: &str) -> Result<(), WriterError> {
+        let declaration = format!("{}: {}", property, value);
+        self.merge_property("style", &declaration, merge_style, "add_style")
+    }
+
+    // Merges the value into the attribute of the last start-tag, or adds the attribute, the merged value is escaped
+    fn merge_property(
+        &mut self,
+        name: &str,
+        value: &str,
+        merge: fn(&str, &str) -> String,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        self.core.begin_property(operation)?;
+        let tag_start = self.content.rfind('<').unwrap_or(0);
+        let quote = self.core.quote_style.quote();
+        let needle = format!(" {}={}", name, quote);
+        let Some(i) = self.content[tag_start..].find(&needle) else {
+            return self.write_property(name, &escape_attr(&merge("", value)), operation);
+        };
+        let start = tag_start + i + needle.len();
+        let end = start + self.content[start..].find(quote).unwrap_or(0);
+        let merged = escape_attr(&merge(&unescape(&self.content[start..end]), value));
+        self.core.align_shift(start, end - start, merged.len());
+        self.content.replace_range(start..end, &merged);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn merged_class_and_style() {
+        let mut wr = HTMLWriter::new();
+        wr.open_tag("div");
+        wr.add_style("width", "auto");
+        wr.add_property("id", "a");
+        wr.add_class("btn");
+        wr.add_class("btn \"x\"");
+        wr.add_style("font", "'a'");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<div style=\"width: auto; font: &#39;a&#39;\" id=\"a\" class=\"btn &quot;x&quot;\"></div>"
+        );
+    }
+
+    #[test]
+    fn attr_names_and_values() {
+        let mut wr = HTMLWriter::new();
+        wr.single_tag("img");
+        wr.add_attr(Attr::Width(640));
+        wr.add_attr(Attr::Alt("\"cat\"".to_string()));
+        wr.add_attr(Attr::Aria("hidden".to_string(), "true".to_string()));
+        assert_eq!(
+            wr.content,
+            "<img width=\"640\" alt=\"&quot;cat&quot;\" aria-hidden=\"true\">"
+        );
+        assert_eq!(
+            wr.try_add_attr(Attr::Data("User".to_string(), String::new())),
+            Err(WriterError::InvalidAttributeName("data-User".to_string()))
+        );
+        wr.add_attr(("data-id", "7"));
+        wr.add_attr(("height", "480"));
+        let loading = Attr::Other("loading".to_string(), "lazy".to_string());
+        wr.add_attr(&loading);
+        assert!(wr
+            .content
+            .ends_with(" data-id=\"7\" height=\"480\" loading=\"lazy\">"));
+        assert_eq!(
+            Attr::from(("aria-label", "x")),
+            Attr::Aria("label".to_string(), "x".to_string())
+        );
+        // AMP-violations are returned, not panicking
+        wr.set_amp(true);
+        assert!(matches!(
+            wr.try_add_attr(("onclick", "go()")),
+            Err(WriterError::AmpViolation { .. })
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+//! Framed comment blocks (banners) at the top of generated files, e.g. for provenance or license notes.
+//!
+//! Each writer puts the banner into its own comment syntax: HTMLWriter and XMLWriter use ```<!-- -->```, the
+//! JSONWriter uses ```//```-line-comments (which makes the document JSONC).
+//! Long notes can be wrapped into lines of a certain width by ```wrap_words()``` before. "--" isn't allowed
+//! inside of markup comments, so their box is drawn with '=' and a "--" in the lines is split into "- -".
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, XMLWriter, BannerStyle};
+//! let mut wr = XMLWriter::new();
+//! wr.file_banner(&["Generated file", "Do not edit!"], BannerStyle::Boxed);
+//! assert_eq!(
+//!     wr.content,
+//!     "<!-- +================+ -->\n<!-- | Generated file | -->\n<!-- | Do not edit!   | -->\n<!-- +================+ -->"
+//! );
+//! ```
+
+/// The frame of a banner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BannerStyle {
+    /// Every line is a comment on its own, without a frame
+    Plain,
+    /// The lines are framed by a line of '=' above and below
+    Framed,
+    /// The lines are put into a box of '-' and '|', markup comments use '=' instead of '-'
+    Boxed,
+}
+
+// Returns the lines of the banner including its frame, without any comment syntax. The horizontal lines of the
+// box are drawn with the border.
+fn frame(lines: &[String], style: BannerStyle, border: char) -> Vec<String> {
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let pad = |l: &str| format!("{}{}", l, " ".repeat(width - l.chars().count()));
+    match style {
+        BannerStyle::Plain => lines.iter().map(|l| l.to_string()).collect(),
+        BannerStyle::Framed => {
+            let rule = "=".repeat(width);
+            let mut framed = vec![rule.clone()];
+            framed.extend(lines.iter().map(|l| pad(l)));
+            framed.push(rule);
+            framed
+        }
+        BannerStyle::Boxed => {
+            let rule = format!("+{}+", border.to_string().repeat(width + 2));
+            let mut boxed = vec![rule.clone()];
+            boxed.extend(lines.iter().map(|l| format!("| {} |", pad(l))));
+            boxed.push(rule);
+            boxed
+        }
+    }
+}
+
+// Writes the banner into content, each line enclosed by open and close of the comment syntax. The lines are
+// separated by the newline and the current indent.
+pub(crate) fn write_banner(
+    content: &mut String,
+    newline: &str,
+    indent: &str,
+    lines: &[&str],
+    style: BannerStyle,
+    open: &str,
+    close: &str,
+) {
+    let markup = open.starts_with("<!--");
+    let lines: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            if markup {
+                split_double_hyphens(line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let border = if markup { '=' } else { '-' };
+    for (i, line) in frame(&lines, style, border).iter().enumerate() {
+        if i > 0 {
+            content.push_str(newline);
+            content.push_str(indent);
+        }
+        content.push_str(open);
+        content.push_str(line.trim_end());
+        // Keep the closing syntax of framed lines in line
+        if !close.is_empty() {
+            content.push_str(&" ".repeat(line.len() - line.trim_end().len()));
+            content.push_str(close);
+        }
+    }
+}
+
+// Splits every "--" into "- -", which isn't allowed inside of markup comments
+fn split_double_hyphens(line: &str) -> String {
+    let mut line = line.to_string();
+    while line.contains("--") {
+        line = line.replace("--", "- -");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner_markup_without_double_hyphens() {
+        let mut content = String::new();
+        let lines = ["a --- b"];
+        write_banner(
+            &mut content,
+            "\n",
+            "",
+            &lines,
+            BannerStyle::Boxed,
+            "<!-- ",
+            " -->",
+        );
+        assert_eq!(
+            content,
+            "<!-- +===========+ -->\n<!-- | a - - - b | -->\n<!-- +===========+ -->"
+        );
+        content.clear();
+        write_banner(
+            &mut content,
+            "\n",
+            "",
+            &lines,
+            BannerStyle::Boxed,
+            "// ",
+            "",
+        );
+        assert_eq!(content, "// +---------+\n// | a --- b |\n// +---------+");
+    }
+}
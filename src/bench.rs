@@ -0,0 +1,121 @@
+//! Workloads of the benchmarks, with the feature "bench".
+//!
+//! The workloads write representative documents: deep nesting for the indent, huge flat arrays for the
+//! separators and attribute-heavy tags for the retroactive insertion of properties. They are generic over the
+//! ```MLLWriter```-trait, so users can measure their own writer setup, e.g. a ```SinkWriter```. The [`FIXTURES`] run
+//! the workloads in fixed sizes, they are measured by ```cargo bench --features bench``` and keep the numbers of
+//! performance-oriented changes comparable.
+//!
+//! ```
+//! # use mllwriter::{bench, HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! bench::deep_nesting(&mut wr, 2);
+//! assert_eq!(wr.content, "<div>\n    <div>\n    </div>\n</div>");
+//! ```
+
+use crate::{HTMLWriter, JSONWriter, MLLWriter, XMLWriter};
+
+/// A workload of a fixed size
+#[derive(Debug, Clone, Copy)]
+pub struct Fixture {
+    /// The name of the fixture in the benchmark
+    pub name: &'static str,
+    /// Writes the document and returns its content
+    pub run: fn() -> String,
+}
+
+/// The fixtures of ```cargo bench```
+pub const FIXTURES: [Fixture; 4] = [
+    Fixture {
+        name: "html_deep_nesting_500",
+        run: || {
+            let mut wr = HTMLWriter::new();
+            deep_nesting(&mut wr, 500);
+            wr.content
+        },
+    },
+    Fixture {
+        name: "xml_deep_nesting_500",
+        run: || {
+            let mut wr = XMLWriter::new();
+            deep_nesting(&mut wr, 500);
+            wr.content
+        },
+    },
+    Fixture {
+        name: "json_flat_array_100000",
+        run: || {
+            let mut wr = JSONWriter::new();
+            flat_array(&mut wr, 100_000);
+            wr.content
+        },
+    },
+    Fixture {
+        name: "html_attribute_heavy_1000x32",
+        run: || {
+            let mut wr = HTMLWriter::new();
+            attribute_heavy(&mut wr, 1000, 32);
+            wr.content
+        },
+    },
+];
+
+/// Writes nested 'div'-elements, each on its own line with the indent of its depth
+pub fn deep_nesting<M: MLLWriter>(wr: &mut M, depth: usize) {
+    for i in 0..depth {
+        if i > 0 {
+            wr.line_feed_inc();
+        }
+        wr.open_tag("div");
+    }
+    for i in 0..depth {
+        if i > 0 {
+            wr.line_feed_dec();
+        } else {
+            wr.line_feed(1);
+        }
+        wr.close_tag();
+    }
+}
+
+/// Writes a JSON-object with an array of numbered items
+pub fn flat_array(wr: &mut JSONWriter, items: usize) {
+    wr.open_tag("");
+    wr.open_array("items");
+    for i in 0..items {
+        wr.add_item(&i.to_string());
+    }
+    wr.close_tag();
+    wr.close_tag();
+}
+
+/// Writes a list of 'span'-elements, each with the number of attributes, whose values have to be escaped
+pub fn attribute_heavy<M: MLLWriter>(wr: &mut M, tags: usize, attributes: usize) {
+    wr.open_tag("div");
+    for i in 0..tags {
+        wr.open_tag("span");
+        for j in 0..attributes {
+            wr.add_property(&format!("a{}", j), &format!("{} & \"{}\"", i, j));
+        }
+        wr.close_tag();
+    }
+    wr.close_tag();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workloads() {
+        let mut wr = JSONWriter::new();
+        flat_array(&mut wr, 2);
+        assert_eq!(wr.content, "{\n  \"items\":\n  [\n    0,\n    1\n  ]\n}");
+        let mut wr = XMLWriter::new();
+        attribute_heavy(&mut wr, 1, 1);
+        assert_eq!(
+            wr.content,
+            "<div><span a0=\"0 &amp; &quot;0&quot;\"></span></div>"
+        );
+    }
+}
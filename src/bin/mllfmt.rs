@@ -0,0 +1,272 @@
+// The command line formatter of the feature "cli": reformats, minifies, validates and converts HTML, XML and JSON by
+// replaying the documents into the writers, e.g. "mllfmt minify page.html" or "cat a.json | mllfmt convert --to yaml".
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use mllwriter::{
+    replay_html, replay_json, replay_json_as_yaml, replay_xml, FinalFormat, FormatMode, HTMLWriter,
+    IndentChar, JSONWriter, JsonDialect, LineEnding, ValidationLevel, WriterCore, WriterError,
+    XMLWriter, YAMLWriter,
+};
+
+const USAGE: &str = "\
+usage: mllfmt [format|minify|validate|convert --to yaml|json|jsonc|json5] [options] [files]
+
+Reads the files, or stdin if there are none or for '-', and writes the result to stdout.
+
+options:
+    --type TYPE           the type of the documents: html, xml, json or jsonc (JSON with comments), by
+                          default the extension of the file
+    --indent N            indents by N spaces
+    --tabs                indents by tabs
+    --crlf                writes \"\\r\\n\" as line-feed
+    --strict              validates HTML against HTML5
+    -w, --write           rewrites the files in place instead of writing to stdout
+    -h, --help            prints this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Format,
+    Minify,
+    Validate,
+    Convert(Target),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Yaml,
+    Json(JsonDialect),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Html,
+    Xml,
+    Json(JsonDialect),
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    command: Option<Command>,
+    kind: Option<Kind>,
+    indent: Option<IndentChar>,
+    crlf: bool,
+    strict: bool,
+    write: bool,
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("mllfmt: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let inputs = if options.files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        options.files.clone()
+    };
+    let mut failed = false;
+    for input in &inputs {
+        if let Err(message) = run(&options, input) {
+            eprintln!("{}: {}", input, message);
+            failed = true;
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// Returns the options, or None if the help is asked for
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "format" | "minify" | "validate" | "convert"
+                if options.command.is_none() && options.files.is_empty() =>
+            {
+                options.command = Some(match arg.as_str() {
+                    "format" => Command::Format,
+                    "minify" => Command::Minify,
+                    "validate" => Command::Validate,
+                    _ => match (args.next().as_deref(), args.next().as_deref()) {
+                        (Some("--to"), Some("yaml")) => Command::Convert(Target::Yaml),
+                        (Some("--to"), Some("json")) => {
+                            Command::Convert(Target::Json(JsonDialect::Json))
+                        }
+                        (Some("--to"), Some("jsonc")) => {
+                            Command::Convert(Target::Json(JsonDialect::Jsonc))
+                        }
+                        (Some("--to"), Some("json5")) => {
+                            Command::Convert(Target::Json(JsonDialect::Json5))
+                        }
+                        _ => {
+                            return Err("convert needs --to yaml, json, jsonc or json5".to_string())
+                        }
+                    },
+                });
+            }
+            "--type" => {
+                options.kind = Some(match args.next().as_deref() {
+                    Some("html") => Kind::Html,
+                    Some("xml") => Kind::Xml,
+                    Some("json") => Kind::Json(JsonDialect::Json),
+                    Some("jsonc") => Kind::Json(JsonDialect::Jsonc),
+                    _ => return Err("--type needs html, xml, json or jsonc".to_string()),
+                });
+            }
+            "--indent" => {
+                let size = args.next().and_then(|size| size.parse().ok());
+                let size = size.ok_or_else(|| "--indent needs a number of spaces".to_string())?;
+                options.indent = Some(IndentChar::Spaces(size));
+            }
+            "--tabs" => options.indent = Some(IndentChar::Tabs),
+            "--crlf" => options.crlf = true,
+            "--strict" => options.strict = true,
+            "-w" | "--write" => options.write = true,
+            "-" => options.files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => options.files.push(arg),
+        }
+    }
+    if options.write && (options.files.is_empty() || options.files.iter().any(|file| file == "-")) {
+        return Err("--write needs files".to_string());
+    }
+    if options.write && options.command == Some(Command::Validate) {
+        return Err("validate doesn't write".to_string());
+    }
+    Ok(Some(options))
+}
+
+// Reformats one input, the errors are the messages for stderr
+fn run(options: &Options, input: &str) -> Result<(), String> {
+    let kind = match options.kind {
+        Some(kind) => kind,
+        None if input == "-" => return Err("the type of stdin needs --type".to_string()),
+        None => match Path::new(input).extension().and_then(|e| e.to_str()) {
+            Some("html" | "htm") => Kind::Html,
+            Some("xml" | "svg" | "xhtml") => Kind::Xml,
+            Some("json") => Kind::Json(JsonDialect::Json),
+            Some("jsonc") => Kind::Json(JsonDialect::Jsonc),
+            Some("json5") => Kind::Json(JsonDialect::Json5),
+            _ => return Err("the type isn't known by the extension, see --type".to_string()),
+        },
+    };
+    let mut text = String::new();
+    if input == "-" {
+        std::io::stdin().read_to_string(&mut text)
+    } else {
+        std::fs::File::open(input).and_then(|mut file| file.read_to_string(&mut text))
+    }
+    .map_err(|e| e.to_string())?;
+    let command = options.command.unwrap_or(Command::Format);
+    let mode = if command == Command::Minify {
+        FormatMode::Minified
+    } else {
+        FormatMode::Pretty
+    };
+    let result = match (kind, command) {
+        (Kind::Json(dialect), Command::Convert(Target::Yaml)) => {
+            let mut wr = YAMLWriter::new();
+            configure(&mut wr.core, options, command);
+            replay_json_as_yaml(&text, dialect, &mut wr).and_then(|_| output(wr, options, input))
+        }
+        (Kind::Json(dialect), command) => {
+            let mut wr = JSONWriter::new();
+            wr.set_format_mode(mode);
+            configure(&mut wr.core, options, command);
+            // The dialect is kept, unless it is converted
+            wr.set_dialect(match command {
+                Command::Convert(Target::Json(target)) => target,
+                _ => dialect,
+            });
+            replay_json(&text, dialect, &mut wr).and_then(|_| output(wr, options, input))
+        }
+        (_, Command::Convert(_)) => return Err("convert reads JSON only".to_string()),
+        (Kind::Html, _) => {
+            let mut wr = HTMLWriter::new();
+            wr.set_format_mode(mode);
+            configure(&mut wr.core, options, command);
+            if options.strict {
+                wr.set_validation_level(ValidationLevel::Html5);
+            }
+            replay_html(&text, &mut wr).and_then(|_| output(wr, options, input))
+        }
+        (Kind::Xml, _) => {
+            let mut wr = XMLWriter::new();
+            wr.set_format_mode(mode);
+            configure(&mut wr.core, options, command);
+            replay_xml(&text, &mut wr).and_then(|_| output(wr, options, input))
+        }
+    };
+    result.map_err(|e| e.to_string())
+}
+
+// Sets the indent, the line-feeds and the end of the content, a minified document doesn't get a line-feed at its end
+fn configure(core: &mut WriterCore, options: &Options, command: Command) {
+    if let Some(indent) = options.indent {
+        core.set_indent_char(indent);
+    }
+    let final_format = if command == Command::Minify {
+        FinalFormat::default()
+    } else {
+        FinalFormat::posix()
+    };
+    core.set_final_format(final_format);
+    if options.crlf {
+        core.set_line_ending(LineEnding::CrLf);
+    }
+}
+
+// The writers, which finish their documents
+trait Finish {
+    fn finish(self) -> Result<String, WriterError>;
+    fn write_to_file_atomic(&self, path: &str) -> Result<(), WriterError>;
+}
+
+macro_rules! finish {
+    ($($writer:ty),*) => {
+        $(
+            impl Finish for $writer {
+                fn finish(self) -> Result<String, WriterError> {
+                    <$writer>::finish(self)
+                }
+
+                fn write_to_file_atomic(&self, path: &str) -> Result<(), WriterError> {
+                    <$writer>::write_to_file_atomic(self, path)
+                }
+            }
+        )*
+    };
+}
+
+finish!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter);
+
+// Writes the finished document to stdout or into the file, a validated one only has to be finished
+fn output<W: Finish>(wr: W, options: &Options, input: &str) -> Result<(), WriterError> {
+    if options.command == Some(Command::Validate) {
+        return wr.finish().map(|_| ());
+    }
+    if options.write {
+        return wr.write_to_file_atomic(input);
+    }
+    let content = wr.finish()?;
+    std::io::stdout()
+        .write_all(content.as_bytes())
+        .map_err(|e| WriterError::WriteFailed {
+            path: "stdout".to_string(),
+            message: e.to_string(),
+        })
+}
@@ -0,0 +1,108 @@
+//! Handing over the content without a copy.
+//!
+//! ```wr.as_bytes()``` borrows the content for socket- or file-APIs, ```wr.into_bytes()``` consumes the writer and
+//! returns the buffer itself. ```wr.take_content()``` moves the content out and starts a new document in the same
+//! writer, with its options, e.g. the indent or the layout, so a server can write one response after the other.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_indent_step_size(2);
+//! wr.open_tag("p");
+//! wr.close_tag();
+//! assert_eq!(wr.as_bytes(), b"<p></p>");
+//! assert_eq!(wr.take_content(), "<p></p>");
+//! wr.open_tag("div");
+//! wr.line_feed_inc();
+//! wr.single_tag("br");
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(wr.into_bytes(), b"<div>\n  <br>\n</div>".to_vec());
+//! ```
+
+use crate::{HTMLWriter, JSONWriter, TOMLWriter, XMLWriter, YAMLWriter};
+
+macro_rules! bytes {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Returns the content as bytes, without a copy
+                pub fn as_bytes(&self) -> &[u8] {
+                    self.content.as_bytes()
+                }
+
+                /// Returns the content as bytes and consumes the writer, without a copy
+                pub fn into_bytes(self) -> Vec<u8> {
+                    self.content.into_bytes()
+                }
+
+                /// Moves the content out of the writer, which starts a new document with the same options
+                pub fn take_content(&mut self) -> String {
+                    let next = self.next_document();
+                    std::mem::replace(self, next).content
+                }
+            }
+        )*
+    };
+}
+
+bytes!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter, TOMLWriter);
+
+impl HTMLWriter {
+    // Returns an empty writer with the options of this one
+    pub(crate) fn next_document(&self) -> HTMLWriter {
+        self.sub_writer()
+    }
+}
+
+impl XMLWriter {
+    // Returns an empty writer with the options of this one
+    pub(crate) fn next_document(&self) -> XMLWriter {
+        XMLWriter {
+            content: String::new(),
+            core: self.core.sub_core(),
+            namespaces: Vec::new(),
+            dtd: Default::default(),
+            void_style: self.void_style,
+        }
+    }
+}
+
+impl JSONWriter {
+    // Returns an empty writer with the options of this one, a recorded schema starts anew
+    pub(crate) fn next_document(&self) -> JSONWriter {
+        JSONWriter {
+            content: String::new(),
+            core: self.core.sub_core(),
+            comment_cnt: 0,
+            blank_lines: 0,
+            path_blocks: 0,
+            schema: self.schema.as_ref().map(|_| Default::default()),
+            arrays: Vec::new(),
+            dialect: self.dialect,
+            comments: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn take_content_starts_anew() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        assert_eq!(wr.take_content(), "<a>");
+        wr.open_tag("b");
+        wr.close_tag();
+        assert_eq!(wr.take_content(), "<b></b>");
+        let mut wr = TOMLWriter::new();
+        wr.open_tag("t");
+        wr.add_property("k", "1");
+        assert_eq!(wr.take_content(), "[t]\nk = 1");
+        wr.add_property("k", "2");
+        assert_eq!(wr.into_bytes(), b"k = 2".to_vec());
+    }
+}
@@ -0,0 +1,185 @@
+//! A cache of fragments, which are rendered once for their key.
+//!
+//! ```wr.render_cached()``` looks the key up in a [`FragmentCache`]. On a miss, the closure writes the fragment like
+//! ```wr.capture()```, which is stored with the indent of the writer. On a hit, the closure isn't called and the
+//! stored fragment is written again, re-indented to the current indent. The key is chosen by the user, e.g. the id
+//! and the version of the data of a card, and must cover everything the fragment depends on. The cache can be
+//! shared between threads, e.g. by the writers of a ```WriterPool```.
+//!
+//! ```
+//! # use mllwriter::{FragmentCache, HTMLWriter, MLLWriter};
+//! let cache = FragmentCache::new();
+//! let mut wr = HTMLWriter::new();
+//! for depth in 0..2 {
+//!     wr.open_tag("div");
+//!     wr.line_feed_inc();
+//!     wr.render_cached(&cache, 42, |wr| {
+//!         wr.open_tag("p");
+//!         wr.line_feed_inc();
+//!         wr.text("card");
+//!         wr.line_feed_dec();
+//!         wr.close_tag();
+//!     });
+//!     if depth == 1 {
+//!         wr.line_feed_dec();
+//!         wr.close_tag();
+//!     }
+//! }
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(cache.len(), 1);
+//! assert_eq!(
+//!     wr.content,
+//!     "<div>\n    <p>\n        card\n    </p><div>\n        <p>\n            card\n        </p>\n    </div>\n</div>"
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use crate::capture::Fragment;
+use crate::{
+    check, HTMLWriter, JSONWriter, MLLWriter, TOMLWriter, WriterError, XMLWriter, YAMLWriter,
+};
+
+/// Rendered fragments with the indent they were rendered at, by their key
+#[derive(Debug)]
+pub struct FragmentCache<K = u64> {
+    fragments: Mutex<HashMap<K, (String, Fragment)>>,
+}
+
+impl<K: Hash + Eq> Default for FragmentCache<K> {
+    fn default() -> Self {
+        FragmentCache {
+            fragments: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Hash + Eq> FragmentCache<K> {
+    /// Returns an empty cache
+    pub fn new() -> FragmentCache<K> {
+        FragmentCache::default()
+    }
+
+    /// Returns the number of cached fragments
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no fragment is cached
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drops the cached fragments, e.g. after the data has changed
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    // Returns the fragment re-indented from the indent it was rendered at to the indent
+    fn get(&self, key: &K, indent: &str) -> Option<String> {
+        self.lock()
+            .get(key)
+            .map(|(rendered_at, fragment)| reindent(fragment, rendered_at, indent))
+    }
+
+    fn insert(&self, key: K, indent: &str, fragment: Fragment) {
+        self.lock().insert(key, (indent.to_string(), fragment));
+    }
+
+    // A panic of another thread doesn't make the fragments invalid
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<K, (String, Fragment)>> {
+        self.fragments.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+macro_rules! render_cached {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Writes the fragment of the key from the cache, or renders it by the closure and caches it
+                pub fn render_cached<K, F>(&mut self, cache: &FragmentCache<K>, key: K, f: F)
+                where
+                    K: Hash + Eq,
+                    F: FnOnce(&mut $writer),
+                {
+                    check(self.try_render_cached(cache, key, f));
+                }
+
+                /// Like ```render_cached()```, but returns the error instead of panicking, e.g. if the fragment has
+                /// unclosed elements
+                pub fn try_render_cached<K, F>(
+                    &mut self,
+                    cache: &FragmentCache<K>,
+                    key: K,
+                    f: F,
+                ) -> Result<(), WriterError>
+                where
+                    K: Hash + Eq,
+                    F: FnOnce(&mut $writer),
+                {
+                    if let Some(fragment) = cache.get(&key, &self.core.indent) {
+                        return self.try_raw(&fragment);
+                    }
+                    let fragment = self.try_capture(f)?;
+                    self.try_raw(&fragment)?;
+                    cache.insert(key, &self.core.indent, fragment);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+render_cached!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter, TOMLWriter);
+
+// Replaces the indent at the start of the lines after the first one
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    if from == to {
+        return text.to_string();
+    }
+    let mut lines = text.split('\n');
+    let mut reindented = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        reindented.push('\n');
+        match line.strip_prefix(from) {
+            Some(rest) => {
+                reindented.push_str(to);
+                reindented.push_str(rest);
+            }
+            None => reindented.push_str(line),
+        }
+    }
+    reindented
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_fragments_are_reindented() {
+        assert_eq!(
+            reindent("<a>\n  <b/>\n</a>", "", "    "),
+            "<a>\n      <b/>\n    </a>"
+        );
+        assert_eq!(reindent("<a>\n    x\n  </a>", "  ", ""), "<a>\n  x\n</a>");
+        let cache: FragmentCache<&str> = FragmentCache::new();
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        assert!(wr
+            .try_render_cached(&cache, "b", |wr| wr.open_tag("b"))
+            .is_err());
+        assert!(cache.is_empty());
+        for tag in ["b", "c"] {
+            wr.render_cached(&cache, "b", |wr| {
+                wr.open_tag(tag);
+                wr.close_tag();
+            });
+        }
+        wr.close_tag();
+        assert_eq!(wr.content, "<a><b></b><b></b></a>");
+    }
+}
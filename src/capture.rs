@@ -0,0 +1,109 @@
+//! Fragments, which are written once and reused.
+//!
+//! ```wr.capture()``` runs the closure against a temporary writer with the options and the indent of the writer,
+//! and returns what it wrote as [`Fragment`]. Expensive, repeated parts of a document, e.g. the header or a card,
+//! are captured once and written by ```wr.raw(&fragment)``` wherever they are needed. A fragment is shared
+//! without a copy, so it can be kept in a cache.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("main");
+//! wr.line_feed_inc();
+//! let card = wr.capture(|wr| {
+//!     wr.open_tag("div");
+//!     wr.line_feed_inc();
+//!     wr.text("card");
+//!     wr.line_feed_dec();
+//!     wr.close_tag();
+//! });
+//! wr.raw(&card);
+//! wr.line_feed(1);
+//! wr.raw(&card);
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<main>\n    <div>\n        card\n    </div>\n    <div>\n        card\n    </div>\n</main>"
+//! );
+//! ```
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::{check, HTMLWriter, JSONWriter, TOMLWriter, WriterError, XMLWriter, YAMLWriter};
+
+/// A written part of a document, which is shared without a copy
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fragment(Arc<str>);
+
+impl Deref for Fragment {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Fragment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! capture {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Writes the fragment by the closure into a temporary writer with the options and the indent of
+                /// this one
+                pub fn capture<F>(&self, f: F) -> Fragment
+                where
+                    F: FnOnce(&mut $writer),
+                {
+                    check(self.try_capture(f))
+                }
+
+                /// Like ```capture()```, but returns the error instead of panicking, if the fragment has unclosed
+                /// elements
+                pub fn try_capture<F>(&self, f: F) -> Result<Fragment, WriterError>
+                where
+                    F: FnOnce(&mut $writer),
+                {
+                    let mut wr = self.next_document();
+                    f(&mut wr);
+                    if !wr.core.block_stack.is_empty() {
+                        return Err(WriterError::IllegalTransition {
+                            state: wr.core.state,
+                            operation: "capture",
+                            reason: "the fragment has unclosed elements",
+                        });
+                    }
+                    Ok(Fragment(Arc::from(wr.content)))
+                }
+            }
+        )*
+    };
+}
+
+capture!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter, TOMLWriter);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn capture_needs_closed_elements() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        assert!(wr.try_capture(|wr| wr.open_tag("b")).is_err());
+        let b = wr.capture(|wr| {
+            wr.open_tag("b");
+            wr.close_tag();
+        });
+        wr.raw(&b);
+        wr.close_tag();
+        assert_eq!(wr.content, "<a><b></b></a>");
+    }
+}
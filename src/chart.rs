@@ -0,0 +1,217 @@
+//! Simple charts as self-contained SVG.
+//!
+//! Reports often need a small chart, e.g. a trend as sparkline or a couple of values as bar chart. These
+//! helpers write such a chart with an SVGWriter and return the SVG-markup, which can be embedded inline into
+//! an HTML-document by ```HTMLWriter::embed_svg()```. The charts don't need any stylesheet or script, their numbers
+//! are written with the precision of the [`ChartOptions`].
+//!
+//! ```
+//! # use mllwriter::{bar_chart, sparkline, ChartOptions, HTMLWriter, MLLWriter};
+//! let trend = sparkline(&[1.0, 3.0, 2.0], &ChartOptions::sparkline());
+//! assert_eq!(
+//!     trend,
+//!     "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"20\" viewBox=\"0 0 100 20\">\
+//!      <polyline points=\"1,19 50,1 99,10\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"1\"/></svg>"
+//! );
+//!
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("p");
+//! wr.embed_svg(&bar_chart(&["a", "b"], &[2.0, 4.0], &ChartOptions::default()));
+//! wr.close_tag();
+//! ```
+
+use crate::svg::number as svg_number;
+use crate::{check, HTMLWriter, MLLWriter, SVGWriter};
+
+/// The options of a chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartOptions {
+    /// Width of the chart in pixels
+    pub width: f64,
+    /// Height of the chart in pixels
+    pub height: f64,
+    /// Color of the line or the bars
+    pub color: String,
+    /// Writes the values above the bars of a bar chart
+    pub show_values: bool,
+    /// Accessible title of the chart
+    pub title: Option<String>,
+    /// Number of decimals of the coordinates and the values
+    pub precision: usize,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            width: 300.0,
+            height: 150.0,
+            color: "steelblue".to_string(),
+            show_values: false,
+            title: None,
+            precision: 2,
+        }
+    }
+}
+
+impl ChartOptions {
+    /// Returns the options of a small sparkline, 100 x 20 pixels
+    pub fn sparkline() -> ChartOptions {
+        ChartOptions {
+            width: 100.0,
+            height: 20.0,
+            ..ChartOptions::default()
+        }
+    }
+}
+
+// Height of the area of the labels and values of a bar chart
+const LABEL_HEIGHT: f64 = 14.0;
+
+/// Returns a sparkline of the values as SVG. The values are scaled between their minimum and maximum.
+pub fn sparkline(values: &[f64], options: &ChartOptions) -> String {
+    let mut svg = open_svg(options);
+    if !values.is_empty() {
+        let (min, max) = range(values);
+        let pad = 1.0;
+        let step = if values.len() > 1 {
+            (options.width - 2.0 * pad) / (values.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let points: Vec<(f64, f64)> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                (
+                    pad + i as f64 * step,
+                    scale(*v, min, max, options.height - pad, pad),
+                )
+            })
+            .collect();
+        svg.polyline(&points);
+        svg.attr("fill", "none");
+        svg.attr("stroke", &options.color);
+        svg.attr("stroke-width", 1);
+    }
+    check(svg.finish())
+}
+
+/// Returns a bar chart of the values as SVG, with the labels below the bars. Negative values are drawn
+/// below the zero-line.
+pub fn bar_chart(labels: &[&str], values: &[f64], options: &ChartOptions) -> String {
+    let mut svg = open_svg(options);
+    if !values.is_empty() {
+        let (min, max) = range(values);
+        let (min, max) = (min.min(0.0), max.max(0.0));
+        // The values are written into the area above the bars
+        let top = if options.show_values {
+            LABEL_HEIGHT
+        } else {
+            0.0
+        };
+        let bottom = options.height - LABEL_HEIGHT;
+        let slot = options.width / values.len() as f64;
+        let zero = scale(0.0, min, max, bottom, top);
+        for (i, v) in values.iter().enumerate() {
+            let y = scale(*v, min, max, bottom, top);
+            let x = i as f64 * slot;
+            svg.rect(x + slot * 0.1, y.min(zero), slot * 0.8, (zero - y).abs());
+            svg.attr("fill", &options.color);
+            if options.show_values {
+                let value = svg_number(*v, options.precision);
+                svg_text(&mut svg, x + slot / 2.0, y.min(zero) - 3.0, &value);
+            }
+            if let Some(label) = labels.get(i) {
+                svg_text(&mut svg, x + slot / 2.0, options.height - 3.0, label);
+            }
+        }
+    }
+    check(svg.finish())
+}
+
+impl HTMLWriter {
+    /// Embeds the SVG-markup inline, e.g. a chart
+    pub fn embed_svg(&mut self, svg: &str) {
+        check(self.core.begin_markup("embed_svg"));
+        self.core.layout_node(&mut self.content);
+        self.content.push_str(svg);
+    }
+}
+
+// Returns the SVGWriter of the chart with the size and the coordinate system of the options, with the title if there
+// is one
+fn open_svg(options: &ChartOptions) -> SVGWriter {
+    let mut svg = SVGWriter::new(options.width, options.height);
+    svg.set_precision(options.precision);
+    svg.view_box(0.0, 0.0, options.width, options.height);
+    if let Some(title) = &options.title {
+        svg.open_tag("title");
+        svg.text(title);
+        svg.close_tag();
+    }
+    svg
+}
+
+// Writes a text centered at x
+fn svg_text(svg: &mut SVGWriter, x: f64, y: f64, text: &str) {
+    svg.open_tag("text");
+    svg.attr_number("x", x);
+    svg.attr_number("y", y);
+    svg.attr("font-size", 10);
+    svg.attr("text-anchor", "middle");
+    svg.text(text);
+    svg.close_tag();
+}
+
+// Returns the minimum and maximum of the values
+fn range(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        })
+}
+
+// Maps the value from min..max onto from..to, a range without extent maps onto the middle
+fn scale(value: f64, min: f64, max: f64, from: f64, to: f64) -> f64 {
+    if max > min {
+        from + (value - min) / (max - min) * (to - from)
+    } else {
+        (from + to) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_bars() {
+        let options = ChartOptions {
+            width: 100.0,
+            height: 64.0,
+            show_values: true,
+            title: Some("Sales & Costs".to_string()),
+            ..ChartOptions::default()
+        };
+        let svg = bar_chart(&["Q1", "Q2"], &[10.0, -5.0], &options);
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"64\" viewBox=\"0 0 100 64\">\
+             <title>Sales &amp; Costs</title>\
+             <rect x=\"5\" y=\"14\" width=\"40\" height=\"24\" fill=\"steelblue\"/>\
+             <text x=\"25\" y=\"11\" font-size=\"10\" text-anchor=\"middle\">10</text>\
+             <text x=\"25\" y=\"61\" font-size=\"10\" text-anchor=\"middle\">Q1</text>\
+             <rect x=\"55\" y=\"38\" width=\"40\" height=\"12\" fill=\"steelblue\"/>\
+             <text x=\"75\" y=\"35\" font-size=\"10\" text-anchor=\"middle\">-5</text>\
+             <text x=\"75\" y=\"61\" font-size=\"10\" text-anchor=\"middle\">Q2</text></svg>"
+        );
+
+        let options = ChartOptions {
+            precision: 3,
+            ..ChartOptions::sparkline()
+        };
+        let trend = sparkline(&[0.0, 1.0, 2.0, 3.0], &options);
+        assert!(trend.contains("points=\"1,19 33.667,13 "));
+    }
+}
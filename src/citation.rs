@@ -0,0 +1,244 @@
+//! Citations and the bibliography of academic-style documents.
+//!
+//! The references are registered by ```wr.add_reference()``` with a key. ```wr.cite()``` writes the numbered
+//! citation, which links to the entry of the bibliography, ```wr.cite_note()``` cites in a footnote instead, see
+//! ```wr.footnote()```. ```wr.render_bibliography()``` writes the cited references in the given style, with the
+//! anchor ids "ref-" and the key.
+//!
+//! ```
+//! # use mllwriter::{CitationStyle, HTMLWriter, MLLWriter, Reference};
+//! let mut wr = HTMLWriter::new();
+//! let mut reference = Reference::new(&["D. E. Knuth"], "Literate Programming", 1984);
+//! reference.set_container("The Computer Journal");
+//! wr.add_reference("knuth84", reference);
+//! wr.open_tag("p");
+//! wr.text("Programs are essays ");
+//! assert_eq!(wr.cite("knuth84"), "[1]");
+//! wr.close_tag();
+//! wr.render_bibliography(CitationStyle::Ieee);
+//! assert_eq!(
+//!     wr.content,
+//!     "<p>Programs are essays <a href=\"#ref-knuth84\" class=\"citation\">[1]</a></p>\
+//!      <ol class=\"bibliography\"><li id=\"ref-knuth84\">D. E. Knuth, \"Literate Programming,\" \
+//!      <i>The Computer Journal</i>, 1984.</li></ol>"
+//! );
+//! ```
+
+use crate::{check, escape_attr, escape_html_text, HTMLWriter, MLLWriter, WriterError};
+
+/// The styles of the bibliography
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CitationStyle {
+    /// Numbered in the order of the first citation, e.g. ```D. E. Knuth, "Title," <i>Journal</i>, 1984.```
+    Ieee,
+    /// Sorted by the authors, e.g. ```Knuth, D. E. (1984). Title. <i>Journal</i>.```
+    Apa,
+}
+
+/// A reference of the bibliography
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    authors: Vec<String>,
+    title: String,
+    year: u16,
+    container: Option<String>,
+    url: Option<String>,
+}
+
+impl Reference {
+    /// Returns the reference with the authors, as they are written in the bibliography
+    pub fn new(authors: &[&str], title: &str, year: u16) -> Reference {
+        Reference {
+            authors: authors.iter().map(|a| a.to_string()).collect(),
+            title: title.to_string(),
+            year,
+            container: None,
+            url: None,
+        }
+    }
+
+    /// Sets the journal, the proceedings or the publisher, which contains the work
+    pub fn set_container(&mut self, container: &str) {
+        self.container = Some(container.to_string());
+    }
+
+    /// Sets the URL, where the work is available
+    pub fn set_url(&mut self, url: &str) {
+        self.url = Some(url.to_string());
+    }
+
+    // The escaped entry of the bibliography in the style
+    fn entry(&self, style: CitationStyle) -> String {
+        let mut entry = match style {
+            CitationStyle::Ieee => format!(
+                "{}, \"{},\"",
+                escape_html_text(&self.authors.join(", ")),
+                escape_html_text(&self.title)
+            ),
+            CitationStyle::Apa => format!(
+                "{} ({}). {}.",
+                escape_html_text(&self.authors.join(" & ")),
+                self.year,
+                escape_html_text(&self.title)
+            ),
+        };
+        if let Some(container) = &self.container {
+            entry.push_str(&format!(" <i>{}</i>", escape_html_text(container)));
+            entry.push(if style == CitationStyle::Ieee {
+                ','
+            } else {
+                '.'
+            });
+        }
+        if style == CitationStyle::Ieee {
+            entry.push_str(&format!(" {}.", self.year));
+        }
+        if let Some(url) = &self.url {
+            entry.push_str(&format!(" {}", escape_html_text(url)));
+        }
+        entry
+    }
+
+    // The short form of the citation in a note, e.g. "Knuth (1984)"
+    fn short(&self) -> String {
+        let author = self.authors.first().map(String::as_str).unwrap_or("");
+        let author = author.split(',').next().unwrap_or(author);
+        if self.authors.len() > 1 {
+            format!("{} et al. ({})", author, self.year)
+        } else {
+            format!("{} ({})", author, self.year)
+        }
+    }
+}
+
+// The registered references with their key and the keys in the order of their first citation
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Citations {
+    references: Vec<(String, Reference)>,
+    cited: Vec<String>,
+}
+
+impl HTMLWriter {
+    /// Registers the reference with the key, a reference with the same key is replaced
+    pub fn add_reference(&mut self, key: &str, reference: Reference) {
+        let references = &mut self.citations.references;
+        match references.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = reference,
+            None => references.push((key.to_string(), reference)),
+        }
+    }
+
+    /// Writes the numbered citation of the reference, which links to the bibliography, and returns it, e.g. "[2]"
+    pub fn cite(&mut self, key: &str) -> String {
+        let number = self.cited_number(key, "cite");
+        let marker = format!("[{}]", number);
+        self.open_tag_w_property("a", "href", &format!("#ref-{}", key));
+        self.add_property("class", "citation");
+        self.text(&marker);
+        self.close_tag();
+        marker
+    }
+
+    /// Writes the citation of the reference as footnote and returns the marker of the footnote
+    pub fn cite_note(&mut self, key: &str) -> String {
+        self.cited_number(key, "cite_note");
+        let short = match self.reference(key) {
+            Some(reference) => reference.short(),
+            None => String::new(),
+        };
+        let href = format!("#ref-{}", escape_attr(key));
+        self.footnote_raw(&format!(
+            "<a{}>{}</a>",
+            self.core.property_string("href", &href),
+            escape_html_text(&short)
+        ))
+    }
+
+    /// Writes the cited references in the style, writes nothing if nothing was cited
+    pub fn render_bibliography(&mut self, style: CitationStyle) {
+        let mut entries: Vec<(String, Reference)> = self
+            .citations
+            .cited
+            .iter()
+            .filter_map(|key| self.reference(key).map(|r| (key.clone(), r.clone())))
+            .collect();
+        if entries.is_empty() {
+            return;
+        }
+        let tag = match style {
+            CitationStyle::Ieee => "ol",
+            CitationStyle::Apa => {
+                entries.sort_by(|(_, a), (_, b)| (&a.authors, a.year).cmp(&(&b.authors, b.year)));
+                "ul"
+            }
+        };
+        self.open_tag_w_property(tag, "class", "bibliography");
+        for (key, reference) in &entries {
+            self.open_tag_w_property("li", "id", &format!("ref-{}", key));
+            self.raw(&reference.entry(style));
+            self.close_tag();
+        }
+        self.close_tag();
+    }
+
+    // Returns the registered reference of the key
+    fn reference(&self, key: &str) -> Option<&Reference> {
+        self.citations
+            .references
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, r)| r)
+    }
+
+    // Returns the number of the reference in the order of the first citation, the key has to be registered
+    fn cited_number(&mut self, key: &str, operation: &'static str) -> usize {
+        if self.reference(key).is_none() {
+            check::<()>(Err(WriterError::UnknownReference {
+                key: key.to_string(),
+                operation,
+            }));
+        }
+        let cited = &mut self.citations.cited;
+        match cited.iter().position(|k| k == key) {
+            Some(index) => index + 1,
+            None => {
+                cited.push(key.to_string());
+                cited.len()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn citation_apa_and_notes() {
+        let mut wr = HTMLWriter::new();
+        wr.add_reference("b", Reference::new(&["Wirth, N."], "Algorithms", 1976));
+        let mut a = Reference::new(&["Dijkstra, E. W.", "Hoare, C. A. R."], "Structured", 1972);
+        a.set_url("https://example.com/s");
+        wr.add_reference("a", a);
+        assert_eq!(wr.cite("b"), "[1]");
+        assert_eq!(wr.cite_note("a"), "1");
+        assert_eq!(wr.cite("b"), "[1]");
+        wr.footnotes();
+        assert!(wr
+            .content
+            .contains("<li id=\"fn-1\"><a href=\"#ref-a\">Dijkstra et al. (1972)</a> "));
+        wr.content.clear();
+        wr.render_bibliography(CitationStyle::Apa);
+        assert_eq!(
+            wr.content,
+            "<ul class=\"bibliography\"><li id=\"ref-a\">Dijkstra, E. W. &amp; Hoare, C. A. R. (1972). Structured. \
+             https://example.com/s</li><li id=\"ref-b\">Wirth, N. (1976). Algorithms.</li></ul>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the reference 'missing' is not registered")]
+    fn citation_unknown_key() {
+        HTMLWriter::new().cite("missing");
+    }
+}
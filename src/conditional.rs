@@ -0,0 +1,102 @@
+//! Conditional comments of HTML, which are still needed for HTML-emails targeting Outlook.
+//!
+//! The content of a conditional comment is written by a child writer in a closure. The child takes over the
+//! options and the indent of its parent, and has to close all of its elements.
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, HTMLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.conditional_comment("mso", |child| {
+//!     child.open_tag("table");
+//!     child.close_tag();
+//! });
+//! wr.conditional_comment_revealed("!mso", |child| {
+//!     child.open_tag("div");
+//!     child.close_tag();
+//! });
+//! assert_eq!(
+//!     wr.content,
+//!     "<!--[if mso]><table></table><![endif]--><!--[if !mso]><!--><div></div><!--<![endif]-->"
+//! );
+//! ```
+
+use crate::{check, HTMLWriter, WriterError};
+
+impl HTMLWriter {
+    /// Writes a downlevel-hidden conditional comment, e.g. ```<!--[if mso]> ... <![endif]-->```. The content is only
+    /// seen by clients which match the condition.
+    pub fn conditional_comment<F>(&mut self, condition: &str, f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let open = format!("<!--[if {}]>", condition);
+        self.write_conditional("conditional_comment", &open, "<![endif]-->", f);
+    }
+
+    /// Writes a downlevel-revealed conditional comment, e.g. ```<!--[if !mso]><!--> ... <!--<![endif]-->```. The
+    /// content is seen by all clients except the ones which don't match the condition.
+    pub fn conditional_comment_revealed<F>(&mut self, condition: &str, f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let open = format!("<!--[if {}]><!-->", condition);
+        self.write_conditional("conditional_comment_revealed", &open, "<!--<![endif]-->", f);
+    }
+
+    fn write_conditional<F>(&mut self, operation: &'static str, open: &str, close: &str, f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let mut child = self.sub_writer();
+        f(&mut child);
+        if !child.core.block_stack.is_empty() {
+            check(Err(WriterError::IllegalTransition {
+                state: child.core.state,
+                operation,
+                reason: "the child writer has unclosed elements",
+            }))
+        }
+        check(self.core.begin_markup(operation));
+        self.core.layout_node(&mut self.content);
+        self.content.push_str(open);
+        if self.core.auto_layout && !child.content.is_empty() {
+            self.content.push_str(self.core.newline());
+            self.content.push_str(&self.core.indent);
+            self.content.push_str(&child.content);
+            self.content.push_str(self.core.newline());
+            self.content.push_str(&self.core.indent);
+        } else {
+            self.content.push_str(&child.content);
+        }
+        self.content.push_str(close);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HTMLWriter, MLLWriter};
+
+    #[test]
+    fn conditional_comment_auto_layout() {
+        let mut wr = HTMLWriter::new();
+        wr.core.set_auto_layout(true);
+        wr.open_tag("body");
+        wr.conditional_comment("mso", |child| {
+            child.open_tag("table");
+            child.single_tag("tr");
+            child.close_tag();
+        });
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<body>\n    <!--[if mso]>\n    <table>\n        <tr>\n    </table>\n    <![endif]-->\n</body>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the child writer has unclosed elements")]
+    fn conditional_comment_unbalanced() {
+        let mut wr = HTMLWriter::new();
+        wr.conditional_comment("mso", |child| child.open_tag("table"));
+    }
+}
@@ -0,0 +1,62 @@
+//! Scripts, which run only after the consent of the user.
+//!
+//! The scripts are written with the type "text/plain", so the browser doesn't execute them, and with the category
+//! of the consent in "data-category". Consent managers like CookieConsent activate the scripts of a category,
+//! when the user agrees to it. The category is e.g. "analytics" or "marketing".
+//!
+//! ```
+//! # use mllwriter::HTMLWriter;
+//! let mut wr = HTMLWriter::new();
+//! wr.consent_script("analytics", "https://stats.example.com/track.js");
+//! assert_eq!(
+//!     wr.content,
+//!     "<script type=\"text/plain\" data-category=\"analytics\" src=\"https://stats.example.com/track.js\"></script>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{HTMLWriter, MLLWriter, Properties};
+
+impl HTMLWriter {
+    /// Writes the external script gated by the category of the consent
+    pub fn consent_script(&mut self, category: &str, src: &str) {
+        self.open_consent_script(category, &[("src", src)]);
+        self.close_tag();
+    }
+
+    /// Writes the inline script gated by the category of the consent
+    pub fn consent_inline_script(&mut self, category: &str, code: &str) {
+        self.open_consent_script(category, &[]);
+        let code = self.escape_end_tag(code, "</script");
+        self.write_str(&code)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+    }
+
+    // Opens the gated script with the additional attributes
+    fn open_consent_script(&mut self, category: &str, attributes: &[(&str, &str)]) {
+        self.open_tag("script");
+        let mut properties = Properties::new("type", "text/plain");
+        properties.add("data-category", category);
+        for (name, value) in attributes {
+            properties.add(name, value);
+        }
+        self.add_properties(&properties);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consent_inline() {
+        let mut wr = HTMLWriter::new();
+        wr.consent_inline_script("marketing", "track(\"</script>\");");
+        assert_eq!(
+            wr.content,
+            "<script type=\"text/plain\" data-category=\"marketing\">track(\"<\\/script>\");</script>"
+        );
+    }
+}
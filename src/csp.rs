@@ -0,0 +1,84 @@
+//! The nonce of the Content-Security-Policy.
+//!
+//! A policy with ```script-src 'nonce-...'``` executes only the inline scripts, which carry the nonce of the
+//! response. When the nonce is set by ```wr.set_csp_nonce()```, every 'script'- and 'style'-element gets it, also
+//! the ones of the helpers like ```critical_css()``` and of child writers, e.g. of conditional comments or
+//! ```sub_writer()```. Nested JSON-documents are written by ```wr.json_ld()```, which escapes them for the
+//! script-element.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_csp_nonce(Some("r4nd0m"));
+//! wr.json_ld(|json| {
+//!     json.open_tag("");
+//!     json.add_property("@type", "\"Organization\"");
+//!     json.close_tag();
+//! });
+//! assert_eq!(
+//!     wr.content,
+//!     "<script nonce=\"r4nd0m\" type=\"application/ld+json\">{\n  \"@type\": \"Organization\"\n}</script>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{HTMLWriter, JSONWriter, MLLWriter};
+
+impl HTMLWriter {
+    /// Sets the nonce for the inline scripts and styles, ```None``` removes it
+    pub fn set_csp_nonce(&mut self, nonce: Option<&str>) {
+        self.nonce = nonce.map(str::to_string);
+    }
+
+    /// Returns the nonce, if set
+    pub fn csp_nonce(&self) -> Option<&str> {
+        self.nonce.as_deref()
+    }
+
+    /// Writes the JSON-document of the closure as JSON-LD, e.g. structured data of search engines
+    pub fn json_ld<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut JSONWriter),
+    {
+        let mut child = JSONWriter::new();
+        f(&mut child);
+        self.open_tag("script");
+        self.add_property("type", "application/ld+json");
+        let json = self.escape_end_tag(&child.content, "</");
+        self.write_str(&json)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+    }
+}
+
+// Returns the nonce, if the element needs it
+pub(crate) fn nonce_of(tag: &str, nonce: &Option<String>) -> Option<String> {
+    match (tag, nonce) {
+        ("script" | "style", Some(nonce)) => Some(nonce.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HTMLWriter, StylesheetLoading};
+
+    #[test]
+    fn csp_nonce_in_children() {
+        let mut wr = HTMLWriter::new();
+        wr.set_csp_nonce(Some("n"));
+        wr.conditional_comment("mso", |child| {
+            child.critical_css("p{}", "/a.css", StylesheetLoading::MediaSwap);
+        });
+        assert!(wr
+            .content
+            .starts_with("<!--[if mso]><style nonce=\"n\">p{}</style>"));
+        let mut child = wr.sub_writer();
+        child.consent_inline_script("analytics", "");
+        assert_eq!(
+            child.content,
+            "<script nonce=\"n\" type=\"text/plain\" data-category=\"analytics\"></script>"
+        );
+    }
+}
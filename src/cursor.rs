@@ -0,0 +1,181 @@
+//! Secondary cursors for writing several places of a document at the same time.
+//!
+//! ```wr.cursor()``` marks the current place and returns a [`Cursor`]. Each cursor has an own sub-document with its
+//! own block stack and the indent of its place: ```wr.write_at()``` writes into it by the closure, as often as
+//! needed, while the main document goes on. Elements may stay open between the calls, e.g. a list of errors, to
+//! which items are appended as they are discovered. ```wr.merge_cursors()``` replaces the marks by the
+//! sub-documents, when all their elements are closed. A cursor can't be written after the merge, and a document
+//! with cursors, which aren't merged, can't be finished. A [`SinkWriter`](crate::SinkWriter) keeps the content
+//! from the first mark on in its buffer until the cursors are merged.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("body");
+//! let errors = wr.cursor();
+//! wr.write_at(errors, |wr| wr.open_tag("ul"));
+//! for row in ["a", "", "c"] {
+//!     if row.is_empty() {
+//!         wr.write_at(errors, |wr| {
+//!             wr.open_tag("li");
+//!             wr.text("empty row");
+//!             wr.close_tag();
+//!         });
+//!     } else {
+//!         wr.open_tag("p");
+//!         wr.text(row);
+//!         wr.close_tag();
+//!     }
+//! }
+//! wr.write_at(errors, |wr| wr.close_tag());
+//! wr.close_tag();
+//! wr.merge_cursors().unwrap();
+//! assert_eq!(wr.content, "<body><ul><li>empty row</li></ul><p>a</p><p>c</p></body>");
+//! ```
+
+use crate::{check, HTMLWriter, WriterError};
+
+/// A place in the document, which is written by ```wr.write_at()```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    id: usize,
+}
+
+// The sub-documents of the cursors, which aren't merged yet, by their id
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cursors {
+    writers: Vec<HTMLWriter>,
+    // the number of merged cursors, their ids come before the ones of the writers
+    merged: usize,
+}
+
+// The start of the mark of the place of a cursor
+pub(crate) const MARK_START: &str = "<!--mllwriter:cursor-";
+
+impl HTMLWriter {
+    /// Marks the current place and returns its cursor, see ```write_at()```
+    pub fn cursor(&mut self) -> Cursor {
+        check(self.core.begin_markup("cursor"));
+        self.core.layout_node(&mut self.content);
+        let id = self.cursors.merged + self.cursors.writers.len();
+        self.content.push_str(&mark(id));
+        let sub = self.sub_writer();
+        self.cursors.writers.push(sub);
+        self.core.open_cursors += 1;
+        Cursor { id }
+    }
+
+    /// Writes into the sub-document of the cursor by the closure
+    pub fn write_at<F>(&mut self, cursor: Cursor, f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        check(self.try_write_at(cursor, f));
+    }
+
+    /// Like ```write_at()```, but returns the error instead of panicking, e.g. for a cursor, which is merged already
+    pub fn try_write_at<F>(&mut self, cursor: Cursor, f: F) -> Result<(), WriterError>
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let sub = cursor
+            .id
+            .checked_sub(self.cursors.merged)
+            .and_then(|i| self.cursors.writers.get_mut(i))
+            .ok_or(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "write_at",
+                reason: "the cursor is merged already",
+            })?;
+        f(sub);
+        Ok(())
+    }
+
+    /// Replaces the marks of the cursors by their sub-documents, which must not have unclosed elements
+    pub fn merge_cursors(&mut self) -> Result<(), WriterError> {
+        for sub in &mut self.cursors.writers {
+            sub.merge_cursors()?;
+            if !sub.core.block_stack.is_empty() {
+                return Err(WriterError::IllegalTransition {
+                    state: sub.core.state,
+                    operation: "merge_cursors",
+                    reason: "the sub-document of a cursor has unclosed elements",
+                });
+            }
+        }
+        let first = self.cursors.merged;
+        for (i, sub) in std::mem::take(&mut self.cursors.writers)
+            .into_iter()
+            .enumerate()
+        {
+            self.content = self.content.replacen(&mark(first + i), &sub.content, 1);
+            self.cursors.merged += 1;
+        }
+        self.core.open_cursors = 0;
+        self.core.thaw();
+        Ok(())
+    }
+}
+
+impl Cursors {
+    // Drops the sub-documents, their cursors count as merged
+    pub(crate) fn clear(&mut self) {
+        self.merged += self.writers.len();
+        self.writers.clear();
+    }
+}
+
+// The mark of the place of a cursor
+fn mark(id: usize) -> String {
+    format!("{}{}-->", MARK_START, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BufferedWriter, MLLWriter};
+
+    #[test]
+    fn cursors_need_closed_elements() {
+        let mut wr = HTMLWriter::new();
+        let toc = wr.cursor();
+        let inner = wr.cursor();
+        wr.write_at(toc, |wr| {
+            wr.open_tag("nav");
+            let nested = wr.cursor();
+            wr.write_at(nested, |wr| wr.single_tag("br"));
+        });
+        wr.write_at(inner, |wr| wr.text("2"));
+        assert!(wr.merge_cursors().is_err());
+        wr.write_at(toc, |wr| wr.close_tag());
+        wr.merge_cursors().unwrap();
+        assert_eq!(wr.content, "<nav><br></nav>2");
+        assert!(wr.try_write_at(toc, |wr| wr.text("3")).is_err());
+        let late = wr.cursor();
+        assert!(wr.clone().finish().is_err());
+        wr.write_at(late, |wr| wr.text("3"));
+        wr.merge_cursors().unwrap();
+        assert_eq!(wr.finish().unwrap(), "<nav><br></nav>23");
+    }
+
+    #[test]
+    fn cursors_stay_in_the_buffer_of_a_sink() {
+        let mut wr = HTMLWriter::with_sink(Vec::new());
+        wr.open_tag("p");
+        let note = wr.writer().cursor();
+        for text in ["a", "b"] {
+            wr.open_tag("b");
+            wr.text(text);
+            wr.close_tag();
+        }
+        assert_eq!(wr.sink().as_slice(), b"<p>");
+        wr.writer().write_at(note, |wr| wr.text("note"));
+        wr.close_tag();
+        wr.writer().merge_cursors().unwrap();
+        let sink = wr.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(sink).unwrap(),
+            "<p>note<b>a</b><b>b</b></p>"
+        );
+    }
+}
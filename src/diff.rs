@@ -0,0 +1,302 @@
+//! HTML views of the differences between two texts.
+//!
+//! ```HTMLWriter::diff_view()``` compares two texts line by line, ```HTMLWriter::unified_diff_view()``` takes an
+//! existing unified diff. Both write a 'table' with the class "diff", either inline (one column of text) or
+//! side by side (old and new text next to each other). The rows and cells carry the classes "add", "del",
+//! "ctx" and "hunk" and the line-numbers the class "ln", so a stylesheet can color them like a code-review.
+//!
+//! ```diff_view()``` computes the longest common subsequence of the lines with a table of n·m entries for
+//! texts of n and m lines, so it is meant for texts of some thousand lines, not for huge files.
+//!
+//! ```
+//! # use mllwriter::{DiffLayout, HTMLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.diff_view("a\nb\n", "a\nc\n", DiffLayout::Inline);
+//! assert_eq!(
+//!     wr.content,
+//!     "<table class=\"diff\"><tbody>\
+//!      <tr class=\"ctx\"><td class=\"ln\">1</td><td class=\"ln\">1</td><td> a</td></tr>\
+//!      <tr class=\"del\"><td class=\"ln\">2</td><td class=\"ln\"></td><td>-b</td></tr>\
+//!      <tr class=\"add\"><td class=\"ln\"></td><td class=\"ln\">2</td><td>+c</td></tr>\
+//!      </tbody></table>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{escape_html_text, HTMLWriter, MLLWriter};
+
+/// The layout of a diff view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLayout {
+    /// One column of text with the removed lines in front of the added lines
+    Inline,
+    /// The old text on the left and the new text on the right
+    SideBySide,
+}
+
+// The kind of a line of a diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Context,
+    Removed,
+    Added,
+    Hunk,
+}
+
+impl LineKind {
+    fn class(self) -> &'static str {
+        match self {
+            LineKind::Context => "ctx",
+            LineKind::Removed => "del",
+            LineKind::Added => "add",
+            LineKind::Hunk => "hunk",
+        }
+    }
+
+    fn marker(self) -> &'static str {
+        match self {
+            LineKind::Context => " ",
+            LineKind::Removed => "-",
+            LineKind::Added => "+",
+            LineKind::Hunk => "",
+        }
+    }
+}
+
+// One line of a diff with its line-numbers in the old and the new text
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiffLine<'a> {
+    kind: LineKind,
+    old: Option<usize>,
+    new: Option<usize>,
+    text: &'a str,
+}
+
+// Compares the lines by their longest common subsequence, the table needs O(n·m) memory
+fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // lcs[i][j] is the length of the common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push(line(LineKind::Context, Some(i + 1), Some(j + 1), a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(line(LineKind::Removed, Some(i + 1), None, a[i]));
+            i += 1;
+        } else {
+            lines.push(line(LineKind::Added, None, Some(j + 1), b[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+// Reads the lines of a unified diff. The file-headers are skipped, but only outside of the hunks: the
+// counts of the hunk-header tell how many lines belong to the hunk, so a removed line "-- x" is kept.
+fn parse_unified(diff: &str) -> Vec<DiffLine<'_>> {
+    let mut lines = Vec::new();
+    let (mut old, mut new) = (1, 1);
+    // the lines of the old and the new text, which are left in the current hunk
+    let (mut old_left, mut new_left): (usize, usize) = (0, 0);
+    for text in diff.lines() {
+        let in_hunk = old_left > 0 || new_left > 0;
+        if !in_hunk && (text.starts_with("--- ") || text.starts_with("+++ ")) {
+            continue;
+        }
+        if let Some(header) = text.strip_prefix("@@").filter(|_| !in_hunk) {
+            // "@@ -old,count +new,count @@", a missing count is 1
+            let range = |prefix: char| {
+                let range = header
+                    .split_whitespace()
+                    .find_map(|range| range.strip_prefix(prefix))?;
+                let mut parts = range.split(',');
+                let start = parts.next()?.parse().ok()?;
+                let count = parts.next().map_or(Some(1), |count| count.parse().ok())?;
+                Some((start, count))
+            };
+            (old, old_left) = range('-').unwrap_or((old, 0));
+            (new, new_left) = range('+').unwrap_or((new, 0));
+            lines.push(line(LineKind::Hunk, None, None, text));
+        } else if let Some(text) = text.strip_prefix('-') {
+            lines.push(line(LineKind::Removed, Some(old), None, text));
+            old += 1;
+            old_left = old_left.saturating_sub(1);
+        } else if let Some(text) = text.strip_prefix('+') {
+            lines.push(line(LineKind::Added, None, Some(new), text));
+            new += 1;
+            new_left = new_left.saturating_sub(1);
+        } else if !text.starts_with('\\') {
+            let text = text.strip_prefix(' ').unwrap_or(text);
+            lines.push(line(LineKind::Context, Some(old), Some(new), text));
+            old += 1;
+            new += 1;
+            old_left = old_left.saturating_sub(1);
+            new_left = new_left.saturating_sub(1);
+        }
+    }
+    lines
+}
+
+fn line(kind: LineKind, old: Option<usize>, new: Option<usize>, text: &str) -> DiffLine<'_> {
+    DiffLine {
+        kind,
+        old,
+        new,
+        text,
+    }
+}
+
+impl HTMLWriter {
+    /// Writes the differences of the lines of two texts as table
+    pub fn diff_view(&mut self, old: &str, new: &str, layout: DiffLayout) {
+        self.write_diff(&diff_lines(old, new), layout);
+    }
+
+    /// Writes a unified diff, e.g. the output of ```git diff```, as table
+    pub fn unified_diff_view(&mut self, diff: &str, layout: DiffLayout) {
+        self.write_diff(&parse_unified(diff), layout);
+    }
+
+    fn write_diff(&mut self, lines: &[DiffLine], layout: DiffLayout) {
+        self.open_tag("table");
+        self.add_property("class", "diff");
+        self.open_tag("tbody");
+        match layout {
+            DiffLayout::Inline => lines.iter().for_each(|l| self.write_inline_row(l)),
+            DiffLayout::SideBySide => self.write_side_by_side(lines),
+        }
+        self.close_tag();
+        self.close_tag();
+    }
+
+    fn write_inline_row(&mut self, line: &DiffLine) {
+        self.open_tag("tr");
+        self.add_property("class", line.kind.class());
+        if line.kind == LineKind::Hunk {
+            self.diff_cell(None, Some("3"), line.text);
+        } else {
+            self.diff_cell(Some("ln"), None, &number(line.old));
+            self.diff_cell(Some("ln"), None, &number(line.new));
+            self.diff_cell(None, None, &(line.kind.marker().to_string() + line.text));
+        }
+        self.close_tag();
+    }
+
+    // Removed and added lines in a row are written next to each other
+    fn write_side_by_side(&mut self, lines: &[DiffLine]) {
+        let mut i = 0;
+        while i < lines.len() {
+            let kind = lines[i].kind;
+            if kind == LineKind::Hunk || kind == LineKind::Context {
+                self.open_tag("tr");
+                self.add_property("class", kind.class());
+                if kind == LineKind::Hunk {
+                    self.diff_cell(None, Some("4"), lines[i].text);
+                } else {
+                    self.diff_cell(Some("ln"), None, &number(lines[i].old));
+                    self.diff_cell(None, None, lines[i].text);
+                    self.diff_cell(Some("ln"), None, &number(lines[i].new));
+                    self.diff_cell(None, None, lines[i].text);
+                }
+                self.close_tag();
+                i += 1;
+                continue;
+            }
+            let removed: Vec<&DiffLine> = lines[i..]
+                .iter()
+                .take_while(|l| l.kind == LineKind::Removed)
+                .collect();
+            let added: Vec<&DiffLine> = lines[i + removed.len()..]
+                .iter()
+                .take_while(|l| l.kind == LineKind::Added)
+                .collect();
+            for row in 0..removed.len().max(added.len()) {
+                self.open_tag("tr");
+                for side in [removed.get(row), added.get(row)] {
+                    match side {
+                        Some(l) => {
+                            self.diff_cell(Some("ln"), None, &number(l.old.or(l.new)));
+                            self.diff_cell(Some(l.kind.class()), None, l.text);
+                        }
+                        None => {
+                            self.diff_cell(Some("ln"), None, "");
+                            self.diff_cell(None, None, "");
+                        }
+                    }
+                }
+                self.close_tag();
+            }
+            i += removed.len() + added.len();
+        }
+    }
+
+    fn diff_cell(&mut self, class: Option<&str>, colspan: Option<&str>, text: &str) {
+        self.open_tag("td");
+        if let Some(class) = class {
+            self.add_property("class", class);
+        }
+        if let Some(colspan) = colspan {
+            self.add_property("colspan", colspan);
+        }
+        if !text.is_empty() {
+            self.write_str(&escape_html_text(text))
+                .expect("text inside of a cell is always allowed");
+        }
+        self.close_tag();
+    }
+}
+
+fn number(n: Option<usize>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_unified_side_by_side() {
+        let diff = "--- a.txt\n+++ b.txt\n@@ -3,2 +3,2 @@\n keep\n-old <1>\n+new\n";
+        let mut wr = HTMLWriter::new();
+        wr.unified_diff_view(diff, DiffLayout::SideBySide);
+        assert_eq!(
+            wr.content,
+            "<table class=\"diff\"><tbody>\
+             <tr class=\"hunk\"><td colspan=\"4\">@@ -3,2 +3,2 @@</td></tr>\
+             <tr class=\"ctx\"><td class=\"ln\">3</td><td>keep</td><td class=\"ln\">3</td><td>keep</td></tr>\
+             <tr><td class=\"ln\">4</td><td class=\"del\">old &lt;1&gt;</td><td class=\"ln\">4</td><td class=\"add\">new</td></tr>\
+             </tbody></table>"
+        );
+    }
+
+    #[test]
+    fn diff_unified_header_like_lines() {
+        // the removed line "-- x" and the added line "++ y" look like file-headers
+        let diff = "--- a.txt\n+++ b.txt\n@@ -1,2 +1,2 @@\n--- x\n+++ y\n keep\n";
+        let lines = parse_unified(diff);
+        assert_eq!(
+            lines,
+            vec![
+                line(LineKind::Hunk, None, None, "@@ -1,2 +1,2 @@"),
+                line(LineKind::Removed, Some(1), None, "-- x"),
+                line(LineKind::Added, None, Some(1), "++ y"),
+                line(LineKind::Context, Some(2), Some(2), "keep"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Entity definitions in the DTD internal subset of XML-documents.
+//!
+//! Entities are declared at the XMLWriter, written into the DOCTYPE with ```write_doctype()``` and referenced
+//! in the text by ```entity_ref()```. References to entities which weren't declared are rejected.
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! wr.declare_entity("company", "ACME Corp.");
+//! wr.declare_external_entity("legal", "legal.xml");
+//! wr.write_doctype("doc");
+//! wr.open_tag("doc");
+//! wr.entity_ref("company");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<!DOCTYPE doc [\n  <!ENTITY company \"ACME Corp.\">\n  <!ENTITY legal SYSTEM \"legal.xml\">\n]>\n<doc>&company;</doc>"
+//! );
+//! ```
+
+use crate::{check, tag, DocumentState, WriterError, XMLWriter};
+
+/// The entities which are predefined by XML and don't have to be declared
+pub const PREDEFINED_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+
+// The declared entities and whether the DOCTYPE was already written
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dtd {
+    // name and declaration body of each entity, e.g. ("company", "\"ACME Corp.\"")
+    entities: Vec<(String, String)>,
+    written: bool,
+}
+
+impl XMLWriter {
+    /// Declares an internal entity, which is replaced by value when the document is parsed. The value is the
+    /// replacement text as it is, e.g. markup or references; '&', '%' and '"' are written as character- or
+    /// entity-references into the declaration.
+    pub fn declare_entity(&mut self, name: &str, value: &str) {
+        check(self.try_declare_entity(name, value));
+    }
+
+    /// Like ```declare_entity()```, but returns the error instead of panicking, e.g. ```InvalidXmlName```
+    pub fn try_declare_entity(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        let value = value
+            .replace('&', "&#38;")
+            .replace('%', "&#37;")
+            .replace('"', "&quot;");
+        let body = "\"".to_string() + &value + "\"";
+        self.add_entity("declare_entity", name, body)
+    }
+
+    /// Declares an external entity, which is replaced by the content of the file given by system_id. The
+    /// system_id is quoted with '\'' if it contains '"'.
+    pub fn declare_external_entity(&mut self, name: &str, system_id: &str) {
+        check(self.try_declare_external_entity(name, system_id));
+    }
+
+    /// Like ```declare_external_entity()```, but returns the error instead of panicking, e.g.
+    /// ```InvalidSystemId``` for a system_id with both '"' and '\''
+    pub fn try_declare_external_entity(
+        &mut self,
+        name: &str,
+        system_id: &str,
+    ) -> Result<(), WriterError> {
+        let quote = match (system_id.contains('"'), system_id.contains('\'')) {
+            (false, _) => '"',
+            (true, false) => '\'',
+            (true, true) => return Err(WriterError::InvalidSystemId(system_id.to_string())),
+        };
+        let body = format!("SYSTEM {}{}{}", quote, system_id, quote);
+        self.add_entity("declare_external_entity", name, body)
+    }
+
+    fn add_entity(
+        &mut self,
+        operation: &'static str,
+        name: &str,
+        body: String,
+    ) -> Result<(), WriterError> {
+        if !tag::is_xml_name(name) {
+            return Err(WriterError::InvalidXmlName(name.to_string()));
+        }
+        if self.dtd.written {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation,
+                reason: "entities have to be declared before the DOCTYPE is written",
+            });
+        }
+        self.dtd.entities.retain(|(n, _)| n != name);
+        self.dtd.entities.push((name.to_string(), body));
+        Ok(())
+    }
+
+    /// Returns true if the entity is predefined or was declared
+    pub fn is_entity_declared(&self, name: &str) -> bool {
+        PREDEFINED_ENTITIES.contains(&name) || self.dtd.entities.iter().any(|(n, _)| n == name)
+    }
+
+    /// Writes the DOCTYPE with all declared entities in its internal subset. It has to be written in the prolog,
+    /// a line-feed is added after it.
+    pub fn write_doctype(&mut self, root: &str) {
+        if self.core.state != DocumentState::Prolog {
+            check(Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "write_doctype",
+                reason: "the DOCTYPE can only be written in the prolog",
+            }))
+        }
+        check(self.core.begin_markup("write_doctype"));
+        self.content.push_str("<!DOCTYPE ");
+        self.content.push_str(root);
+        if !self.dtd.entities.is_empty() {
+            self.content.push_str(" [");
+            for (name, body) in self.dtd.entities.iter() {
+                self.content.push_str(self.core.newline());
+                self.content.push_str(&self.core.indent_unit());
+                self.content.push_str("<!ENTITY ");
+                self.content.push_str(name);
+                self.content.push(' ');
+                self.content.push_str(body);
+                self.content.push('>');
+            }
+            self.content.push_str(self.core.newline());
+            self.content.push(']');
+        }
+        self.content.push('>');
+        self.content.push_str(self.core.newline());
+        self.dtd.written = true;
+    }
+
+    /// Writes a reference to an entity, e.g. ```&company;```. The entity has to be predefined or declared.
+    pub fn entity_ref(&mut self, name: &str) {
+        check(self.check_entity_refs(&("&".to_string() + name + ";")));
+        check(self.core.begin_text("entity_ref"));
+        self.content.push('&');
+        self.content.push_str(name);
+        self.content.push(';');
+    }
+
+    /// Checks all entity references in the given text, character references like ```&#169;``` are always valid. A
+    /// '&', which doesn't start a reference, is an ```UnescapedAmpersand```.
+    pub fn check_entity_refs(&self, text: &str) -> Result<(), WriterError> {
+        for part in text.split('&').skip(1) {
+            let name = match part.split_once(';') {
+                Some((name, _)) if is_reference_name(name) => name,
+                _ => {
+                    let context: String = part.chars().take(10).collect();
+                    return Err(WriterError::UnescapedAmpersand(format!(
+                        "&{}",
+                        context.trim_end()
+                    )));
+                }
+            };
+            if !name.starts_with('#') && !self.is_entity_declared(name) {
+                return Err(WriterError::UndeclaredEntity(name.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Returns true if the text between '&' and ';' is the name of an entity or a character reference
+fn is_reference_name(name: &str) -> bool {
+    let name = name.strip_prefix('#').unwrap_or(name);
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MLLWriter, WriterError, XMLWriter};
+
+    #[test]
+    fn entity_validation() {
+        let mut wr = XMLWriter::new();
+        wr.declare_entity("company", "ACME \"Corp\"");
+        wr.declare_entity("terms", "R&D 100%");
+        wr.declare_external_entity("legal", "say \"hi\".xml");
+        assert_eq!(
+            wr.try_declare_entity("a b", "x"),
+            Err(WriterError::InvalidXmlName("a b".to_string()))
+        );
+        assert_eq!(
+            wr.try_declare_external_entity("c", "\"'"),
+            Err(WriterError::InvalidSystemId("\"'".to_string()))
+        );
+        assert!(wr.check_entity_refs("&company; &amp; &#169;").is_ok());
+        assert_eq!(
+            wr.check_entity_refs("&product;"),
+            Err(WriterError::UndeclaredEntity("product".to_string()))
+        );
+        assert_eq!(
+            wr.check_entity_refs("A & B; &amp;"),
+            Err(WriterError::UnescapedAmpersand("& B;".to_string()))
+        );
+        assert_eq!(
+            wr.check_entity_refs("R&D"),
+            Err(WriterError::UnescapedAmpersand("&D".to_string()))
+        );
+        wr.write_doctype("doc");
+        assert!(wr
+            .content
+            .contains("<!ENTITY company \"ACME &quot;Corp&quot;\">"));
+        assert!(wr.content.contains("<!ENTITY terms \"R&#38;D 100&#37;\">"));
+        assert!(wr
+            .content
+            .contains("<!ENTITY legal SYSTEM 'say \"hi\".xml'>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "the entity 'product' is not declared")]
+    fn entity_undeclared_ref() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("doc");
+        wr.entity_ref("product");
+    }
+}
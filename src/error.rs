@@ -0,0 +1,264 @@
+//! Error types of the writers.
+
+use crate::DocumentState;
+
+/// Errors which are raised, when an operation cannot be performed by a writer in its current
+/// state, e.g. opening a second root element in a XML-document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriterError {
+    /// The operation is not allowed in the current state of the document
+    IllegalTransition {
+        /// The state of the document, when the operation was called
+        state: DocumentState,
+        /// The name of the rejected operation, e.g. "add_property"
+        operation: &'static str,
+        /// Describes why the operation was rejected
+        reason: &'static str,
+    },
+    /// An element is closed, but there is no open element
+    UnbalancedClose {
+        /// The state of the document, when the operation was called
+        state: DocumentState,
+        /// The name of the rejected operation, e.g. "close_tag"
+        operation: &'static str,
+    },
+    /// The operation is not supported by the writer-type, e.g. single_tag() in JSON
+    UnsupportedOperation {
+        /// The name of the operation
+        operation: &'static str,
+        /// Describes why the writer doesn't support it
+        reason: &'static str,
+    },
+    /// The name of a tag isn't allowed by the notation of the writer
+    InvalidTagName(String),
+    /// The name of an attribute isn't allowed by the notation of the writer, e.g. "data-User" in HTML
+    InvalidAttributeName(String),
+    /// An element was placed where the content-model of the language doesn't allow it
+    InvalidNesting {
+        /// The tag of the misplaced element
+        tag: String,
+        /// The path of the open elements, e.g. "html > body > p"
+        open_path: String,
+        /// Describes the violated rule
+        reason: &'static str,
+    },
+    /// A WriterProfile could not be loaded or stored, the message is given by the config-parser
+    InvalidProfile(String),
+    /// A referenced entity was neither predefined nor declared
+    UndeclaredEntity(String),
+    /// A '&' doesn't start a reference and has to be escaped as "&amp;", given with the text following it
+    UnescapedAmpersand(String),
+    /// An include could not be written or its file could not be expanded
+    IncludeFailed {
+        /// The reference of the include
+        href: String,
+        /// Describes why the include failed
+        message: String,
+    },
+    /// The data could not be encoded as QR code, the message is given by the encoder
+    QrCodeFailed(String),
+    /// A JSON-document could not be parsed
+    InvalidJson(String),
+    /// A JSON Pointer is malformed or doesn't point to a value
+    InvalidPointer(String),
+    /// A JSON Patch operation misses its value or source path, or has one it doesn't take
+    InvalidPatch(String),
+    /// A SARIF-result refers to an unknown rule, a rule is defined twice, or there is no run yet
+    InvalidSarif(String),
+    /// Elements cannot be inserted into the head of the page, because it has none
+    MissingHead(String),
+    /// An asset, e.g. a favicon, could not be generated
+    AssetFailed(String),
+    /// A citation refers to a reference, which isn't registered
+    UnknownReference {
+        /// The key of the reference
+        key: String,
+        /// The citing operation
+        operation: &'static str,
+    },
+    /// An abbreviation isn't in the glossary
+    UnknownAbbreviation(String),
+    /// The document was finished with unclosed elements, given by their path
+    UnclosedElements(String),
+    /// A well-known file, e.g. security.txt, misses a required field or has an invalid one
+    InvalidWellKnownFile {
+        /// The name of the file
+        file: &'static str,
+        /// Describes the violated rule
+        reason: &'static str,
+    },
+    /// A name isn't a valid XML-name
+    InvalidXmlName(String),
+    /// A void element of HTML was opened like an element with content
+    VoidElement(String),
+    /// The element isn't an element of HTML5
+    UnknownElement(String),
+    /// The void element doesn't take the attribute
+    InvalidAttribute {
+        /// The tag of the void element
+        tag: String,
+        /// The name of the attribute
+        name: String,
+    },
+    /// The markup isn't allowed in an AMP-document
+    AmpViolation {
+        /// The tag or attribute, which isn't allowed
+        name: String,
+        /// Describes the violated rule
+        reason: &'static str,
+    },
+    /// A document read by ```replay_html()``` or ```replay_xml()``` is malformed
+    InvalidMarkup(String),
+    /// The content could not be written into a file
+    WriteFailed {
+        /// The path of the file
+        path: String,
+        /// The message of the file system
+        message: String,
+    },
+    /// A typed value has no formatter in the registry and isn't a built-in type, see ```FormatterRegistry```
+    MissingFormatter {
+        /// The name of the type
+        type_name: &'static str,
+        /// The name of the operation, e.g. "add_typed_property"
+        operation: &'static str,
+    },
+    /// A generated document misses a field, which its format requires
+    MissingField {
+        /// The format of the document, e.g. "SPDX"
+        document: &'static str,
+        /// Describes the missing field and how to set it
+        field: &'static str,
+    },
+    /// The system-identifier of an external entity contains both '"' and '\'', so it can't be quoted
+    InvalidSystemId(String),
+}
+
+impl std::fmt::Display for WriterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            WriterError::IllegalTransition {
+                state,
+                operation,
+                reason,
+            } => write!(
+                f,
+                "illegal operation {}() in state {:?}: {}",
+                operation, state, reason
+            ),
+            WriterError::UnbalancedClose { state, operation } => write!(
+                f,
+                "unbalanced {}() in state {:?}: there is no open element to be closed",
+                operation, state
+            ),
+            WriterError::UnsupportedOperation { operation, reason } => {
+                write!(f, "unsupported operation {}(): {}", operation, reason)
+            }
+            WriterError::InvalidTagName(name) => write!(
+                f,
+                "invalid name '{}': only ASCII-lowercase letters and digits are allowed",
+                name
+            ),
+            WriterError::InvalidAttributeName(name) => write!(
+                f,
+                "invalid attribute name '{}': it isn't allowed by the notation of the writer",
+                name
+            ),
+            WriterError::InvalidNesting {
+                tag,
+                open_path,
+                reason,
+            } => write!(
+                f,
+                "invalid nesting of '{}' in '{}': {}",
+                tag, open_path, reason
+            ),
+            WriterError::InvalidProfile(msg) => write!(f, "invalid writer profile: {}", msg),
+            WriterError::UndeclaredEntity(name) => {
+                write!(f, "the entity '{}' is not declared", name)
+            }
+            WriterError::UnescapedAmpersand(text) => {
+                write!(f, "the ampersand of '{}' has to be escaped as '&amp;'", text)
+            }
+            WriterError::IncludeFailed { href, message } => {
+                write!(f, "include of '{}' failed: {}", href, message)
+            }
+            WriterError::QrCodeFailed(msg) => write!(f, "QR code failed: {}", msg),
+            WriterError::InvalidJson(msg) => write!(f, "invalid JSON: {}", msg),
+            WriterError::InvalidPointer(pointer) => {
+                write!(f, "the JSON Pointer '{}' doesn't point to a value", pointer)
+            }
+            WriterError::InvalidPatch(msg) => write!(f, "invalid JSON Patch: {}", msg),
+            WriterError::InvalidSarif(msg) => write!(f, "invalid SARIF: {}", msg),
+            WriterError::MissingHead(path) => write!(f, "the page '{}' has no head", path),
+            WriterError::AssetFailed(msg) => write!(f, "asset failed: {}", msg),
+            WriterError::UnknownReference { key, operation } => {
+                write!(
+                    f,
+                    "{}(): the reference '{}' is not registered",
+                    operation, key
+                )
+            }
+            WriterError::UnknownAbbreviation(abbreviation) => {
+                write!(
+                    f,
+                    "the abbreviation '{}' is not in the glossary",
+                    abbreviation
+                )
+            }
+            WriterError::UnclosedElements(open_path) => {
+                write!(
+                    f,
+                    "the document ends with unclosed elements: '{}'",
+                    open_path
+                )
+            }
+            WriterError::InvalidWellKnownFile { file, reason } => {
+                write!(f, "invalid {}: {}", file, reason)
+            }
+            WriterError::InvalidXmlName(name) => write!(
+                f,
+                "invalid XML-name '{}': it has to start with a letter, '_' or ':', and must not start with 'xml'",
+                name
+            ),
+            WriterError::VoidElement(tag) => write!(
+                f,
+                "'{}' is a void element without content or end-tag, use single_tag()",
+                tag
+            ),
+            WriterError::UnknownElement(tag) => {
+                write!(f, "'{}' isn't an element of HTML5", tag)
+            }
+            WriterError::InvalidAttribute { tag, name } => write!(
+                f,
+                "the attribute '{}' isn't allowed on the void element '{}'",
+                name, tag
+            ),
+            WriterError::AmpViolation { name, reason } => {
+                write!(f, "'{}' violates AMP: {}", name, reason)
+            }
+            WriterError::InvalidMarkup(msg) => write!(f, "invalid markup: {}", msg),
+            WriterError::WriteFailed { path, message } => {
+                write!(f, "writing '{}' failed: {}", path, message)
+            }
+            WriterError::MissingFormatter {
+                type_name,
+                operation,
+            } => write!(
+                f,
+                "{}(): the type '{}' has no registered formatter",
+                operation, type_name
+            ),
+            WriterError::MissingField { document, field } => {
+                write!(f, "the {}-document needs {}", document, field)
+            }
+            WriterError::InvalidSystemId(system_id) => write!(
+                f,
+                "invalid system-identifier '{}': it must not contain both '\"' and '\''",
+                system_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WriterError {}
@@ -0,0 +1,190 @@
+//! The escaping of the writers, for content written by hand.
+//!
+//! Content, which is pushed into ```wr.content``` or given to ```wr.raw()``` and the ```*_raw```-variants, isn't
+//! escaped by the writers. These functions escape it the same way as the writers do it for text and properties, so
+//! hand-written parts and written parts of a document can't differ. ```unescape()``` reverses the escaping, e.g. to
+//! read values back out of written content.
+//!
+//! Documents in a legacy encoding need ASCII only: ```wr.core.set_ascii_only()``` escapes the other characters of
+//! text, properties and JSON-strings as character references or ```\u```-escapes.
+//!
+//! ```
+//! # use mllwriter::escape::{escape_attr, escape_html_text, escape_json_string};
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("p");
+//! wr.raw(&format!("<a title=\"{}\">{}</a>", escape_attr("\"x\""), escape_html_text("1 < 2")));
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<p><a title=\"&quot;x&quot;\">1 &lt; 2</a></p>");
+//! assert_eq!(escape_json_string("a\"b\n"), "a\\\"b\\n");
+//! ```
+
+use crate::WriterCore;
+
+/// Escapes the characters of a text content, which would be taken as markup, like ```wr.text()``` in HTML and XML
+pub fn escape_html_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes the characters of an attribute-value in double or single quotes, like ```wr.add_property()```
+pub fn escape_attr(value: &str) -> String {
+    escape_html_text(value)
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escapes the characters of a JSON-string without the quotes, like the string-values of the JSONWriter
+pub fn escape_json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes the characters of XML, which are special in text and in attribute-values, so the result fits both
+pub fn escape_xml(text: &str) -> String {
+    escape_attr(text)
+}
+
+/// Escapes the characters outside of ASCII of escaped markup as character references, e.g. "&#xe9;" for 'é'
+pub fn escape_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            escaped.push_str(&format!("&#x{:x};", c as u32));
+        }
+    }
+    escaped
+}
+
+/// Escapes the characters outside of ASCII of an escaped JSON-string, the ones beyond the BMP as surrogate pairs
+pub fn escape_json_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut units = [0u16; 2];
+    for c in text.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            for unit in c.encode_utf16(&mut units) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+/// Reverses the escaping of text and attribute-values: the predefined entities, "&nbsp;" and numeric character
+/// references are replaced, other references are kept as they are
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        unescaped.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let reference = rest.find(';').map(|end| (&rest[1..end], end));
+        match reference.and_then(|(name, end)| Some((reference_char(name)?, end))) {
+            Some((c, end)) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+// Returns the character of the reference without '&' and ';', e.g. "amp" or "#x27"
+fn reference_char(name: &str) -> Option<char> {
+    let code = match name {
+        "amp" => return Some('&'),
+        "lt" => return Some('<'),
+        "gt" => return Some('>'),
+        "quot" => return Some('"'),
+        "apos" => return Some('\''),
+        "nbsp" => return Some('\u{a0}'),
+        _ => name.strip_prefix('#')?,
+    };
+    let value = match code.strip_prefix(['x', 'X']) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => code.parse().ok()?,
+    };
+    char::from_u32(value)
+}
+
+impl WriterCore {
+    /// Escapes the characters outside of ASCII, which are written from now on by text, properties and JSON-strings
+    pub fn set_ascii_only(&mut self, ascii_only: bool) {
+        self.ascii_only = ascii_only;
+    }
+
+    // Returns the escaped markup with ASCII only, if the writer is set to it
+    pub(crate) fn ascii_markup(&self, escaped: String) -> String {
+        if self.ascii_only {
+            escape_non_ascii(&escaped)
+        } else {
+            escaped
+        }
+    }
+
+    // Returns the escaped JSON-string with ASCII only, if the writer is set to it
+    pub(crate) fn ascii_json(&self, escaped: String) -> String {
+        if self.ascii_only {
+            escape_json_non_ascii(&escaped)
+        } else {
+            escaped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_control_characters() {
+        assert_eq!(escape_json_string("\u{1}\t\\"), "\\u0001\\t\\\\");
+        assert_eq!(escape_xml("<a b='c'>&"), "&lt;a b=&#39;c&#39;&gt;&amp;");
+    }
+
+    #[test]
+    fn unescape_references() {
+        assert_eq!(
+            unescape("&lt;a&gt; &amp;amp; &#39;&#x22;&nbsp;"),
+            "<a> &amp; '\"\u{a0}"
+        );
+        assert_eq!(
+            unescape("a & b &unknown; &#xd800; &"),
+            "a & b &unknown; &#xd800; &"
+        );
+        assert_eq!(unescape(&escape_attr("<\"'&>")), "<\"'&>");
+    }
+
+    #[test]
+    fn escape_ascii_only() {
+        assert_eq!(
+            escape_non_ascii("caf\u{e9} \u{1f600}"),
+            "caf&#xe9; &#x1f600;"
+        );
+        assert_eq!(
+            escape_json_non_ascii("\u{e9}\u{1f600}"),
+            "\\u00e9\\ud83d\\ude00"
+        );
+    }
+}
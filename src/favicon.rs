@@ -0,0 +1,165 @@
+//! The set of favicons of HTML-documents.
+//!
+//! ```wr.favicons()``` writes the links of the usual set of icons below a base path: the ICO-file for old
+//! browsers, PNGs in 16 and 32 pixels, the touch icon of Apple and the pinned-tab mask of Safari, together with
+//! the theme color. With the feature ```favicon``` the PNG- and ICO-files are resized from one source image into the
+//! output directory of a [`DocumentSet`], see ```generate_favicons()```. The mask is an SVG and isn't generated.
+//!
+//! ```
+//! # use mllwriter::HTMLWriter;
+//! let mut wr = HTMLWriter::new();
+//! wr.favicons("/icons", "#0a84ff");
+//! assert!(wr.content.starts_with(
+//!     "<link rel=\"icon\" href=\"/icons/favicon.ico\" sizes=\"48x48\">\
+//!      <link rel=\"icon\" type=\"image/png\" sizes=\"32x32\" href=\"/icons/favicon-32x32.png\">"
+//! ));
+//! ```
+//!
+//! [`DocumentSet`]: crate::DocumentSet
+
+use crate::HTMLWriter;
+
+/// The files of the PNG-icons with their size in pixels
+pub const FAVICON_PNGS: [(&str, u32); 3] = [
+    ("favicon-32x32.png", 32),
+    ("favicon-16x16.png", 16),
+    ("apple-touch-icon.png", 180),
+];
+
+/// The sizes in pixels of the images in the ICO-file
+pub const FAVICON_ICO_SIZES: [u32; 3] = [16, 32, 48];
+
+impl HTMLWriter {
+    /// Writes the links of the favicons below the base path, the theme color is also the color of the mask
+    pub fn favicons(&mut self, base_path: &str, theme_color: &str) {
+        let base = base_path.trim_end_matches('/');
+        self.add_void(
+            "link",
+            &[
+                ("rel", "icon"),
+                ("href", &format!("{}/favicon.ico", base)),
+                ("sizes", "48x48"),
+            ],
+        );
+        for (file, size) in FAVICON_PNGS {
+            let sizes = format!("{0}x{0}", size);
+            let href = format!("{}/{}", base, file);
+            if size == 180 {
+                self.add_void(
+                    "link",
+                    &[
+                        ("rel", "apple-touch-icon"),
+                        ("sizes", &sizes),
+                        ("href", &href),
+                    ],
+                );
+            } else {
+                self.add_void(
+                    "link",
+                    &[
+                        ("rel", "icon"),
+                        ("type", "image/png"),
+                        ("sizes", &sizes),
+                        ("href", &href),
+                    ],
+                );
+            }
+        }
+        self.add_void(
+            "link",
+            &[
+                ("rel", "mask-icon"),
+                ("href", &format!("{}/safari-pinned-tab.svg", base)),
+                ("color", theme_color),
+            ],
+        );
+        self.add_meta("theme-color", theme_color);
+    }
+}
+
+#[cfg(feature = "favicon")]
+impl crate::DocumentSet {
+    /// Resizes the source image (e.g. a PNG of 512 pixels) to the PNG- and ICO-files of the favicons, and writes
+    /// them into the directory below the output directory, e.g. "icons". Returns the paths of the written files.
+    pub fn generate_favicons(
+        &self,
+        source: &[u8],
+        directory: &str,
+    ) -> Result<Vec<std::path::PathBuf>, crate::WriterError> {
+        use image::codecs::ico::{IcoEncoder, IcoFrame};
+        use image::imageops::FilterType;
+        use image::ExtendedColorType;
+
+        let failed = |e: &dyn std::fmt::Display| crate::WriterError::AssetFailed(e.to_string());
+        let output = self
+            .output_dir()
+            .ok_or_else(|| failed(&"the DocumentSet has no output directory"))?
+            .join(directory);
+        std::fs::create_dir_all(&output).map_err(|e| failed(&e))?;
+        let image = image::load_from_memory(source).map_err(|e| failed(&e))?;
+        let mut written = Vec::new();
+        for (file, size) in FAVICON_PNGS {
+            let path = output.join(file);
+            image
+                .resize_exact(size, size, FilterType::Lanczos3)
+                .save(&path)
+                .map_err(|e| failed(&e))?;
+            written.push(path);
+        }
+        let resized: Vec<_> = FAVICON_ICO_SIZES
+            .iter()
+            .map(|size| {
+                image
+                    .resize_exact(*size, *size, FilterType::Lanczos3)
+                    .to_rgba8()
+            })
+            .collect();
+        let frames = resized
+            .iter()
+            .map(|r| IcoFrame::as_png(r, r.width(), r.height(), ExtendedColorType::Rgba8))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| failed(&e))?;
+        let path = output.join("favicon.ico");
+        let file = std::fs::File::create(&path).map_err(|e| failed(&e))?;
+        IcoEncoder::new(std::io::BufWriter::new(file))
+            .encode_images(&frames)
+            .map_err(|e| failed(&e))?;
+        written.push(path);
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn favicon_links() {
+        let mut wr = HTMLWriter::new();
+        wr.favicons("/", "#fff");
+        assert!(wr.content.contains(
+            "<link rel=\"apple-touch-icon\" sizes=\"180x180\" href=\"/apple-touch-icon.png\">\
+             <link rel=\"mask-icon\" href=\"/safari-pinned-tab.svg\" color=\"#fff\">\
+             <meta name=\"theme-color\" content=\"#fff\">"
+        ));
+    }
+
+    #[cfg(feature = "favicon")]
+    #[test]
+    fn favicon_assets() {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::new(64, 64)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let dir = std::env::temp_dir().join(format!("mllwriter-favicon-{}", std::process::id()));
+        let mut site = crate::DocumentSet::new("https://example.com");
+        assert!(site.generate_favicons(png.get_ref(), "icons").is_err());
+        site.set_output_dir(&dir);
+        let written = site.generate_favicons(png.get_ref(), "icons").unwrap();
+        assert_eq!(written.len(), 4);
+        let ico = std::fs::read(dir.join("icons/favicon.ico")).unwrap();
+        // reserved, type icon, number of images
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 3, 0]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+//! Writing the finished content into a file.
+//!
+//! ```wr.write_to_file()``` writes the content like ```finish()``` returns it, so the [`FinalFormat`] of the writer
+//! decides about the trailing line-feed, the [`LineEnding`] and a byte order mark. ```wr.write_to_file_atomic()```
+//! writes a temporary file in the same directory first and renames it, so readers of the file, e.g. a web server,
+//! never see a half-written document. Both fail without touching the file, if elements are unclosed.
+//!
+//! ```
+//! # use mllwriter::{FinalFormat, LineEnding, MLLWriter, XMLWriter};
+//! let path = std::env::temp_dir().join(format!("mllwriter-file-doc-{}.xml", std::process::id()));
+//! let mut wr = XMLWriter::new();
+//! wr.core.set_line_ending(LineEnding::CrLf);
+//! wr.core.set_final_format(FinalFormat::posix());
+//! wr.open_tag("a");
+//! wr.line_feed(1);
+//! wr.close_tag();
+//! wr.write_to_file_atomic(&path).unwrap();
+//! assert_eq!(std::fs::read_to_string(&path).unwrap(), "<a>\r\n</a>\r\n");
+//! # std::fs::remove_file(path).unwrap();
+//! ```
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::{HTMLWriter, JSONWriter, TOMLWriter, WriterError, XMLWriter, YAMLWriter};
+
+#[cfg(doc)]
+use crate::{FinalFormat, LineEnding};
+
+macro_rules! write_to_file {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Writes the finished content into the file, which is created or truncated
+                pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), WriterError> {
+                    let path = path.as_ref();
+                    let content = self.clone().finish()?;
+                    std::fs::write(path, content).map_err(|e| write_failed(path, &e))
+                }
+
+                /// Writes the finished content into a temporary file, which replaces the file by a rename
+                pub fn write_to_file_atomic(&self, path: impl AsRef<Path>) -> Result<(), WriterError> {
+                    let path = path.as_ref();
+                    let content = self.clone().finish()?;
+                    write_atomic(path, content.as_bytes())
+                }
+            }
+        )*
+    };
+}
+
+write_to_file!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter, TOMLWriter);
+
+// Writes the temporary file next to the file, so the rename doesn't cross file systems, and removes it on errors
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), WriterError> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| write_failed(path, &"the path has no file name"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = path.with_file_name(temp_name);
+    let written = std::fs::File::create(&temp).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&temp, path)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(write_failed(path, &e));
+    }
+    Ok(())
+}
+
+fn write_failed(path: &Path, e: &dyn std::fmt::Display) -> WriterError {
+    WriterError::WriteFailed {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn unfinished_documents_are_not_written() {
+        let dir = std::env::temp_dir().join(format!(
+            "mllwriter-unfinished-documents-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("doc.html");
+        let mut wr = HTMLWriter::new();
+        wr.open_tag("p");
+        assert!(wr.write_to_file_atomic(&path).is_err());
+        assert!(!path.exists());
+        wr.close_tag();
+        wr.write_to_file(&path).unwrap();
+        wr.write_to_file_atomic(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "<p></p>");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(matches!(
+            wr.write_to_file_atomic(dir.join("missing/doc.html")),
+            Err(WriterError::WriteFailed { .. })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
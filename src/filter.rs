@@ -0,0 +1,397 @@
+//! Output policies, which rewrite the operations on their way to a writer.
+//!
+//! The [`FilterWriter`] wraps a writer and passes each operation as [`WriterOp`] through its filters, in the order
+//! they were added. A filter returns the operations, which replace the one it got: none to drop it, the operation
+//! itself, changed ones or additional ones. So cross-cutting policies, e.g. ```loading="lazy"``` on every 'img',
+//! are set up once instead of being scattered through the generation code. The common policies have their own
+//! methods, raw markup isn't filtered. If a filter drops the opening of an element, its closing is dropped as well,
+//! so the content stays in the parent. An injected attribute is written, when the properties of the element are
+//! complete, and only if the element didn't get the attribute explicitly.
+//!
+//! ```
+//! # use mllwriter::{FilterWriter, HTMLWriter, MLLWriter, WriterOp};
+//! let mut wr = FilterWriter::new(HTMLWriter::new());
+//! wr.rename_tag("b", "strong");
+//! wr.deny_attributes(&["onclick"]);
+//! wr.inject_attribute("img", "loading", "lazy");
+//! wr.add_filter(|op| match op {
+//!     WriterOp::Text(text) => vec![WriterOp::Text(text.to_uppercase())],
+//!     op => vec![op],
+//! });
+//! wr.open_tag("b");
+//! wr.add_property("onclick", "steal()");
+//! wr.text("new");
+//! wr.close_tag();
+//! wr.single_tag_w_property("img", "src", "a.png");
+//! wr.single_tag_w_property("img", "loading", "eager");
+//! assert_eq!(
+//!     wr.into_inner().content,
+//!     "<strong>NEW</strong><img src=\"a.png\" loading=\"lazy\"><img loading=\"eager\">"
+//! );
+//! ```
+
+use crate::banner::BannerStyle;
+use crate::provenance::TimestampPolicy;
+use crate::{check, MLLWriter, Properties, WriterError, WriterOp, WriterProfile};
+
+// A filter maps an operation to the operations, which replace it
+type Filter = Box<dyn Fn(WriterOp) -> Vec<WriterOp> + Send + Sync>;
+
+// A step of the filtering, in the order they were added
+enum Stage {
+    Filter(Filter),
+    // The attribute for the elements with the tag, pending while the properties of such an element may follow
+    Inject {
+        tag: String,
+        name: String,
+        value: String,
+        pending: bool,
+    },
+}
+
+impl Stage {
+    // Returns the operations, which replace the operation
+    fn apply(&mut self, op: WriterOp) -> Vec<WriterOp> {
+        let (tag, name, value, pending) = match self {
+            Stage::Filter(filter) => return filter(op),
+            Stage::Inject {
+                tag,
+                name,
+                value,
+                pending,
+            } => (tag, name, value, pending),
+        };
+        if let WriterOp::AddProperty(property, _) = &op {
+            // The explicit attribute replaces the injected one
+            if property == name {
+                *pending = false;
+            }
+            return vec![op];
+        }
+        let mut ops = Vec::new();
+        if std::mem::take(pending) {
+            ops.push(WriterOp::AddProperty(name.clone(), value.clone()));
+        }
+        if let WriterOp::OpenTag(t) | WriterOp::SingleTag(t) = &op {
+            *pending = t == tag;
+        }
+        ops.push(op);
+        ops
+    }
+}
+
+/// A writer, which rewrites the operations by its filters before they reach the wrapped writer
+pub struct FilterWriter<M: MLLWriter> {
+    writer: M,
+    stages: Vec<Stage>,
+    // For each open element the number of elements, which its opening opened in the writer
+    opened: Vec<usize>,
+}
+
+impl<M: MLLWriter> FilterWriter<M> {
+    /// Wraps the writer, without any filter so far
+    pub fn new(writer: M) -> FilterWriter<M> {
+        FilterWriter {
+            writer,
+            stages: Vec::new(),
+            opened: Vec::new(),
+        }
+    }
+
+    /// Adds the filter behind the other ones
+    pub fn add_filter<F>(&mut self, filter: F)
+    where
+        F: Fn(WriterOp) -> Vec<WriterOp> + Send + Sync + 'static,
+    {
+        self.stages.push(Stage::Filter(Box::new(filter)));
+    }
+
+    /// Writes the elements with the tag by the other tag
+    pub fn rename_tag(&mut self, from: &str, to: &str) {
+        let (from, to) = (from.to_string(), to.to_string());
+        self.add_filter(move |op| match op {
+            WriterOp::OpenTag(tag) if tag == from => vec![WriterOp::OpenTag(to.clone())],
+            WriterOp::SingleTag(tag) if tag == from => vec![WriterOp::SingleTag(to.clone())],
+            op => vec![op],
+        });
+    }
+
+    /// Drops the properties with the names
+    pub fn deny_attributes(&mut self, names: &[&str]) {
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        self.add_filter(move |op| match op {
+            WriterOp::AddProperty(name, _) if names.contains(&name) => Vec::new(),
+            op => vec![op],
+        });
+    }
+
+    /// Adds the property to each element with the tag, unless the element gets the property explicitly
+    pub fn inject_attribute(&mut self, tag: &str, name: &str, value: &str) {
+        self.stages.push(Stage::Inject {
+            tag: tag.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            pending: false,
+        });
+    }
+
+    /// Gives access to the writer, e.g. for its own methods, which aren't filtered
+    pub fn writer(&mut self) -> &mut M {
+        check(self.flush());
+        &mut self.writer
+    }
+
+    /// Returns the writer
+    pub fn into_inner(mut self) -> M {
+        check(self.flush());
+        self.writer
+    }
+
+    // Passes the operation through the filters and applies the resulting ones, until one is rejected. The closing
+    // of an element, whose opening was dropped, is dropped as well.
+    fn run(&mut self, op: WriterOp) -> Result<(), WriterError> {
+        let open = matches!(op, WriterOp::OpenTag(_));
+        if op == WriterOp::CloseTag && self.opened.pop() == Some(0) {
+            return Ok(());
+        }
+        let ops = self.filter(0, vec![op]);
+        if open {
+            let opened = ops
+                .iter()
+                .filter(|op| matches!(op, WriterOp::OpenTag(_)))
+                .count();
+            let closed = ops.iter().filter(|op| **op == WriterOp::CloseTag).count();
+            self.opened.push(opened.saturating_sub(closed));
+        }
+        ops.into_iter().try_for_each(|op| self.forward(op))
+    }
+
+    // Passes the operations through the stages from the index on
+    fn filter(&mut self, from: usize, mut ops: Vec<WriterOp>) -> Vec<WriterOp> {
+        for stage in &mut self.stages[from..] {
+            ops = ops.into_iter().flat_map(|op| stage.apply(op)).collect();
+        }
+        ops
+    }
+
+    // Writes the pending injected attributes, before operations, which aren't filtered
+    fn flush(&mut self) -> Result<(), WriterError> {
+        for i in 0..self.stages.len() {
+            if let Stage::Inject {
+                name,
+                value,
+                pending: pending @ true,
+                ..
+            } = &mut self.stages[i]
+            {
+                *pending = false;
+                let op = WriterOp::AddProperty(name.clone(), value.clone());
+                let ops = self.filter(i + 1, vec![op]);
+                ops.into_iter().try_for_each(|op| self.forward(op))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Applies the operation to the writer
+    fn forward(&mut self, op: WriterOp) -> Result<(), WriterError> {
+        let wr = &mut self.writer;
+        match op {
+            WriterOp::OpenTag(tag) => wr.try_open_tag(&tag),
+            WriterOp::CloseTag => wr.try_close_tag(),
+            WriterOp::SingleTag(tag) => wr.try_single_tag(&tag),
+            WriterOp::AddProperty(name, value) => wr.try_add_property(&name, &value),
+            WriterOp::Text(text) => wr.try_text(&text),
+            WriterOp::AddComment(comment) => wr.try_add_comment(&comment),
+            WriterOp::LineFeed(n) => {
+                wr.line_feed(n);
+                Ok(())
+            }
+            WriterOp::BlankLine => {
+                wr.blank_line();
+                Ok(())
+            }
+            WriterOp::LineFeedInc => {
+                wr.line_feed_inc();
+                Ok(())
+            }
+            WriterOp::LineFeedDec => {
+                wr.line_feed_dec();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<M: MLLWriter> MLLWriter for FilterWriter<M> {
+    fn open_tag(&mut self, tag: &str) {
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.run(WriterOp::OpenTag(tag.to_string()))
+    }
+
+    fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.open_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn close_tag(&mut self) {
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        self.run(WriterOp::CloseTag)
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.run(WriterOp::SingleTag(tag.to_string()))
+    }
+
+    fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.single_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn add_property(&mut self, name: &str, value: &str) {
+        check(self.try_add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.run(WriterOp::AddProperty(name.to_string(), value.to_string()))
+    }
+
+    /// The properties are filtered one by one
+    fn add_properties(&mut self, properties: &Properties) {
+        for (name, value) in properties.iter() {
+            self.add_property(name, value);
+        }
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.run(WriterOp::AddComment(comment.to_string()))
+    }
+
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.run(WriterOp::Text(text.to_string()))
+    }
+
+    /// Raw markup isn't filtered
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.flush()?;
+        self.writer.try_raw(raw)
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        self.writer().file_banner(lines, style);
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.writer()
+            .stamp_provenance(tool, version, timestamp_policy);
+    }
+
+    fn line_feed(&mut self, n: usize) {
+        check(self.run(WriterOp::LineFeed(n)));
+    }
+
+    fn blank_line(&mut self) {
+        check(self.run(WriterOp::BlankLine));
+    }
+
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.writer().ensure_blank_lines(n);
+    }
+
+    fn line_feed_inc(&mut self) {
+        check(self.run(WriterOp::LineFeedInc));
+    }
+
+    fn line_feed_dec(&mut self) {
+        check(self.run(WriterOp::LineFeedDec));
+    }
+
+    fn inc_indent_step(&mut self) {
+        self.writer.inc_indent_step();
+    }
+
+    fn dec_indent_step(&mut self) {
+        self.writer.dec_indent_step();
+    }
+
+    fn set_indent_step(&mut self, indent_step: usize) {
+        self.writer.set_indent_step(indent_step);
+    }
+
+    fn set_indent_step_size(&mut self, indent_step_size: usize) {
+        self.writer.set_indent_step_size(indent_step_size);
+    }
+
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.writer.apply_profile(profile);
+    }
+
+    fn end_document(&mut self) {
+        self.writer().end_document();
+    }
+
+    /// Clears the writer, the filters stay
+    fn clear(&mut self) {
+        for stage in &mut self.stages {
+            if let Stage::Inject { pending, .. } = stage {
+                *pending = false;
+            }
+        }
+        self.opened.clear();
+        self.writer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XMLWriter;
+
+    #[test]
+    fn filters_in_order() {
+        let mut wr = FilterWriter::new(XMLWriter::new());
+        wr.inject_attribute("a", "id", "1");
+        wr.rename_tag("a", "b");
+        wr.inject_attribute("a", "id", "2");
+        wr.deny_attributes(&["x"]);
+        wr.open_tag("a");
+        wr.add_property("x", "1");
+        wr.line_feed(1);
+        wr.close_tag();
+        assert_eq!(wr.into_inner().content, "<b id=\"1\">\n</b>");
+
+        let mut wr = FilterWriter::new(XMLWriter::new());
+        wr.add_filter(|op| match op {
+            WriterOp::OpenTag(tag) if tag == "font" => Vec::new(),
+            op => vec![op],
+        });
+        wr.open_tag("div");
+        wr.open_tag("font");
+        wr.text("a");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(wr.into_inner().content, "<div>a</div>");
+    }
+}
@@ -0,0 +1,308 @@
+//! The end of writing, which hands back the content only if the document is complete.
+//!
+//! ```wr.finish()``` consumes the writer and returns its content, if all elements are closed, so a truncated
+//! document can't be shipped. In the JSONWriter all blocks and arrays have to be closed, the blocks of
+//! ```set_path()``` are closed before.
+//!
+//! The [`FinalFormat`] of the writer, set by ```wr.core.set_final_format()``` or a profile, is applied to the
+//! content by ```finish()```: the trailing whitespace of the lines can be stripped and the end of the content can
+//! get exactly one line-feed, like POSIX expects of text files, or none. With [`LineEnding::CrLf`], see
+//! ```wr.core.set_line_ending()```, also the line-feeds of text and raw markup are converted. The content can start
+//! with a byte order mark.
+//!
+//! ```
+//! # use mllwriter::{FinalFormat, HTMLWriter, MLLWriter, WriterError};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("html");
+//! wr.open_tag("body");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.clone().finish(),
+//!     Err(WriterError::UnclosedElements("html".to_string()))
+//! );
+//! wr.close_tag();
+//! assert_eq!(wr.finish().unwrap(), "<html><body></body></html>");
+//!
+//! let mut wr = HTMLWriter::new();
+//! wr.core.set_final_format(FinalFormat::posix());
+//! wr.open_tag("p");
+//! wr.line_feed_inc();
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(wr.finish().unwrap(), "<p>\n\n</p>\n");
+//! ```
+
+use crate::{
+    DocumentState, HTMLWriter, JSONWriter, LineEnding, TOMLWriter, WriterCore, WriterError,
+    XMLWriter, YAMLWriter,
+};
+
+macro_rules! finish {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Returns the content, if all elements are closed, otherwise the open elements as error
+                pub fn finish(self) -> Result<String, WriterError> {
+                    check_finished(&self.core)?;
+                    let mut content = self.content;
+                    self.core.final_format.apply(&mut content, self.core.line_ending);
+                    Ok(content)
+                }
+            }
+        )*
+    };
+}
+
+finish!(HTMLWriter, XMLWriter, YAMLWriter, TOMLWriter);
+
+impl JSONWriter {
+    /// Returns the content, if all blocks and arrays are closed, otherwise the open blocks as error
+    pub fn finish(mut self) -> Result<String, WriterError> {
+        self.close_paths();
+        self.check_open_blocks()?;
+        self.core
+            .final_format
+            .apply(&mut self.content, self.core.line_ending);
+        Ok(self.content)
+    }
+}
+
+impl JSONWriter {
+    // Returns the open blocks as error, the unnamed ones by their brackets, e.g. "{} > a > []"
+    pub(crate) fn check_open_blocks(&self) -> Result<(), WriterError> {
+        check_finished(&self.core).map_err(|e| match e {
+            WriterError::UnclosedElements(_) => {
+                let blocks: Vec<&str> = self
+                    .core
+                    .block_stack
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| match name.as_str() {
+                        "" if self.arrays.contains(&(i + 1)) => "[]",
+                        "" => "{}",
+                        name => name,
+                    })
+                    .collect();
+                WriterError::UnclosedElements(blocks.join(" > "))
+            }
+            e => e,
+        })
+    }
+}
+
+/// The line-feeds at the end of the content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TrailingNewline {
+    /// As written
+    #[default]
+    Keep,
+    /// No line-feed
+    None,
+    /// Exactly one line-feed, unless the content is empty
+    Single,
+}
+
+/// The formatting of the content applied by ```finish()```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FinalFormat {
+    /// The line-feeds at the end
+    pub trailing_newline: TrailingNewline,
+    /// Strips spaces and tabs at the end of each line, also inside of e.g. 'pre'-elements
+    pub trim_trailing_whitespace: bool,
+    /// Starts the content with the byte order mark of UTF-8, e.g. for spreadsheet tools
+    pub bom: bool,
+}
+
+impl FinalFormat {
+    /// Returns the format of POSIX text files: no trailing whitespace and one line-feed at the end
+    pub fn posix() -> FinalFormat {
+        FinalFormat {
+            trailing_newline: TrailingNewline::Single,
+            trim_trailing_whitespace: true,
+            ..FinalFormat::default()
+        }
+    }
+
+    /// Applies the format to the content, whose line-feeds are converted to the line ending, also the ones of text
+    /// and raw markup
+    pub fn apply(&self, content: &mut String, line_ending: LineEnding) {
+        let mut formatter = FinalFormatter::new(*self, line_ending);
+        let mut formatted = formatter.push(content);
+        formatted.push_str(&formatter.finish());
+        *content = formatted;
+    }
+}
+
+// Applies a FinalFormat to content, which is written in parts, e.g. into the sink of a SinkWriter. The whitespace
+// at the end of a part is held back, until the next part shows whether it ends a line or the content.
+#[derive(Debug, Clone)]
+pub(crate) struct FinalFormatter {
+    format: FinalFormat,
+    line_ending: LineEnding,
+    // the whitespace at the end of the parts so far
+    held: String,
+    // true if content was returned already, so the byte order mark was written
+    started: bool,
+}
+
+impl FinalFormatter {
+    pub(crate) fn new(format: FinalFormat, line_ending: LineEnding) -> FinalFormatter {
+        FinalFormatter {
+            format,
+            line_ending,
+            held: String::new(),
+            started: false,
+        }
+    }
+
+    // Returns the formatted content up to the whitespace at the end of the part
+    pub(crate) fn push(&mut self, part: &str) -> String {
+        self.held.push_str(part);
+        let end = self.held.trim_end_matches([' ', '\t', '\r', '\n']).len();
+        if end == 0 {
+            return String::new();
+        }
+        let rest = self.held.split_off(end);
+        let lines = std::mem::replace(&mut self.held, rest);
+        let trimmed = self.trim(&lines);
+        self.convert(trimmed)
+    }
+
+    // Returns the formatted end of the content
+    pub(crate) fn finish(&mut self) -> String {
+        let held = std::mem::take(&mut self.held);
+        let mut rest = self.trim(&held);
+        if self.format.trailing_newline != TrailingNewline::Keep {
+            rest.truncate(rest.trim_end_matches(['\n', '\r']).len());
+        }
+        if self.format.trailing_newline == TrailingNewline::Single
+            && (self.started || !rest.is_empty())
+        {
+            rest.push('\n');
+        }
+        self.convert(rest)
+    }
+
+    // Strips the whitespace at the end of the lines, also in front of the '\r' of a "\r\n"
+    fn trim(&self, text: &str) -> String {
+        if !self.format.trim_trailing_whitespace {
+            return text.to_string();
+        }
+        let trimmed: Vec<String> = text
+            .split('\n')
+            .map(|line| match line.strip_suffix('\r') {
+                Some(line) => line.trim_end_matches([' ', '\t']).to_string() + "\r",
+                None => line.trim_end_matches([' ', '\t']).to_string(),
+            })
+            .collect();
+        trimmed.join("\n")
+    }
+
+    // Converts the line-feeds to the line ending and starts the content with the byte order mark
+    fn convert(&mut self, mut text: String) -> String {
+        if self.line_ending == LineEnding::CrLf {
+            text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+        }
+        if self.format.bom && !self.started && !text.starts_with('\u{feff}') {
+            text.insert(0, '\u{feff}');
+        }
+        self.started = true;
+        text
+    }
+}
+
+impl WriterCore {
+    /// Sets the formatting of the content applied by ```finish()```
+    pub fn set_final_format(&mut self, final_format: FinalFormat) {
+        self.final_format = final_format;
+    }
+}
+
+// Checks that there are no unclosed elements, also if the document was ended already
+pub(crate) fn check_finished(core: &WriterCore) -> Result<(), WriterError> {
+    if core.open_cursors > 0 {
+        return Err(WriterError::IllegalTransition {
+            state: core.state,
+            operation: "finish",
+            reason: "the cursors aren't merged, see merge_cursors()",
+        });
+    }
+    if core.state == DocumentState::Finished || core.block_stack.is_empty() {
+        Ok(())
+    } else {
+        Err(WriterError::UnclosedElements(core.block_stack.join(" > ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn final_formats() {
+        let mut content = String::from("a  \n\tb\t\n\n");
+        FinalFormat {
+            trailing_newline: TrailingNewline::None,
+            trim_trailing_whitespace: false,
+            ..FinalFormat::default()
+        }
+        .apply(&mut content, LineEnding::Lf);
+        assert_eq!(content, "a  \n\tb\t");
+        FinalFormat::posix().apply(&mut content, LineEnding::Lf);
+        assert_eq!(content, "a\n\tb\n");
+        let mut content = String::new();
+        FinalFormat::posix().apply(&mut content, LineEnding::Lf);
+        assert_eq!(content, "");
+        let mut content = String::from("a\r\nb");
+        let bom = FinalFormat {
+            bom: true,
+            ..FinalFormat::posix()
+        };
+        bom.apply(&mut content, LineEnding::CrLf);
+        bom.apply(&mut content, LineEnding::CrLf);
+        assert_eq!(content, "\u{feff}a\r\nb\r\n");
+        let mut content = String::from("<p>a  \r\nb</p>");
+        FinalFormat::posix().apply(&mut content, LineEnding::Lf);
+        assert_eq!(content, "<p>a\r\nb</p>\n");
+        let mut wr = HTMLWriter::new();
+        wr.core.set_line_ending(LineEnding::CrLf);
+        wr.core.set_final_format(FinalFormat::posix());
+        wr.open_tag("p");
+        wr.text("a  ");
+        wr.line_feed(1);
+        wr.text("b");
+        wr.close_tag();
+        assert_eq!(wr.finish().unwrap(), "<p>a\r\nb</p>\r\n");
+    }
+
+    #[test]
+    fn finish_json() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.open_array("a");
+        assert_eq!(
+            wr.clone().finish(),
+            Err(WriterError::UnclosedElements("{} > a".to_string()))
+        );
+        wr.close_tag();
+        wr.set_path("b.c", "1");
+        assert_eq!(
+            wr.clone().finish(),
+            Err(WriterError::UnclosedElements("{}".to_string()))
+        );
+        wr.close_tag();
+        assert!(wr.finish().unwrap().ends_with("}\n}"));
+        let mut wr = JSONWriter::new();
+        wr.open_array("");
+        wr.open_tag("");
+        assert_eq!(
+            wr.finish(),
+            Err(WriterError::UnclosedElements("[] > {}".to_string()))
+        );
+    }
+}
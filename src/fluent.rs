@@ -0,0 +1,161 @@
+//! A fluent facade of the MLLWriter-trait for chained calls.
+//!
+//! ```wr.fluent()``` returns a [`Fluent`], whose methods return the facade again, so a template can be written
+//! as one chain. The methods panic like the ones of the writer.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.fluent()
+//!     .open_tag("div")
+//!     .add_property("id", "x")
+//!     .line_feed_inc()
+//!     .single_tag_w_property("img", "src", "a.png")
+//!     .line_feed_dec()
+//!     .close_tag();
+//! assert_eq!(wr.content, "<div id=\"x\">\n    <img src=\"a.png\">\n</div>");
+//! ```
+
+use crate::banner::BannerStyle;
+use crate::provenance::TimestampPolicy;
+use crate::{MLLWriter, Properties};
+
+/// Chains the calls of the writer, each method calls the method of the writer with the same name, see
+/// ```MLLWriter::fluent()```
+pub struct Fluent<'w, W: MLLWriter> {
+    writer: &'w mut W,
+}
+
+impl<'w, W: MLLWriter> Fluent<'w, W> {
+    pub(crate) fn new(writer: &'w mut W) -> Fluent<'w, W> {
+        Fluent { writer }
+    }
+
+    /// Returns the writer, e.g. for its own methods
+    pub fn writer(&mut self) -> &mut W {
+        self.writer
+    }
+
+    pub fn open_tag(&mut self, tag: &str) -> &mut Self {
+        self.writer.open_tag(tag);
+        self
+    }
+
+    pub fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) -> &mut Self {
+        self.writer.open_tag_w_property(tag, prop, value);
+        self
+    }
+
+    pub fn close_tag(&mut self) -> &mut Self {
+        self.writer.close_tag();
+        self
+    }
+
+    pub fn single_tag(&mut self, tag: &str) -> &mut Self {
+        self.writer.single_tag(tag);
+        self
+    }
+
+    pub fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) -> &mut Self {
+        self.writer.single_tag_w_property(tag, prop, value);
+        self
+    }
+
+    pub fn add_property(&mut self, name: &str, value: &str) -> &mut Self {
+        self.writer.add_property(name, value);
+        self
+    }
+
+    pub fn add_properties(&mut self, properties: &Properties) -> &mut Self {
+        self.writer.add_properties(properties);
+        self
+    }
+
+    pub fn add_comment(&mut self, comment: &str) -> &mut Self {
+        self.writer.add_comment(comment);
+        self
+    }
+
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.writer.text(text);
+        self
+    }
+
+    pub fn raw(&mut self, raw: &str) -> &mut Self {
+        self.writer.raw(raw);
+        self
+    }
+
+    pub fn file_banner(&mut self, lines: &[&str], style: BannerStyle) -> &mut Self {
+        self.writer.file_banner(lines, style);
+        self
+    }
+
+    pub fn stamp_provenance(
+        &mut self,
+        tool: &str,
+        version: &str,
+        timestamp_policy: TimestampPolicy,
+    ) -> &mut Self {
+        self.writer
+            .stamp_provenance(tool, version, timestamp_policy);
+        self
+    }
+
+    pub fn line_feed(&mut self, n: usize) -> &mut Self {
+        self.writer.line_feed(n);
+        self
+    }
+
+    pub fn blank_line(&mut self) -> &mut Self {
+        self.writer.blank_line();
+        self
+    }
+
+    pub fn ensure_blank_lines(&mut self, n: usize) -> &mut Self {
+        self.writer.ensure_blank_lines(n);
+        self
+    }
+
+    pub fn line_feed_inc(&mut self) -> &mut Self {
+        self.writer.line_feed_inc();
+        self
+    }
+
+    pub fn line_feed_dec(&mut self) -> &mut Self {
+        self.writer.line_feed_dec();
+        self
+    }
+
+    pub fn inc_indent_step(&mut self) -> &mut Self {
+        self.writer.inc_indent_step();
+        self
+    }
+
+    pub fn dec_indent_step(&mut self) -> &mut Self {
+        self.writer.dec_indent_step();
+        self
+    }
+
+    pub fn end_document(&mut self) -> &mut Self {
+        self.writer.end_document();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JSONWriter, MLLWriter};
+
+    #[test]
+    fn fluent_json() {
+        let mut wr = JSONWriter::new();
+        wr.fluent()
+            .open_tag("")
+            .add_property("a", "1")
+            .open_tag("b")
+            .close_tag()
+            .close_tag();
+        assert_eq!(wr.content, "{\n  \"a\": 1,\n  \"b\":\n  {\n  }\n}");
+    }
+}
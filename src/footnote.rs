@@ -0,0 +1,100 @@
+//! Footnotes and endnotes with back-links.
+//!
+//! ```wr.footnote()``` writes the superscript reference to the note and collects the note.
+//! ```wr.footnotes()``` writes the collected notes, e.g. at the end of a section or of the document, each note
+//! links back to its reference. The notes are numbered through the whole document.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("p");
+//! wr.text("Rust is fast");
+//! let marker = wr.footnote("Benchmarks & claims");
+//! assert_eq!(marker, "1");
+//! wr.close_tag();
+//! wr.footnotes();
+//! assert_eq!(
+//!     wr.content,
+//!     "<p>Rust is fast<sup id=\"fnref-1\"><a href=\"#fn-1\">1</a></sup></p>\
+//!      <ol class=\"footnotes\" start=\"1\"><li id=\"fn-1\">Benchmarks &amp; claims \
+//!      <a href=\"#fnref-1\">↩</a></li></ol>"
+//! );
+//! ```
+
+use crate::{escape_html_text, HTMLWriter, MLLWriter};
+
+// The number of the notes so far and the notes, which aren't written yet, with their number and markup
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Footnotes {
+    count: usize,
+    pending: Vec<(usize, String)>,
+}
+
+impl HTMLWriter {
+    /// Writes the reference to a new note with the text and returns its marker, e.g. "3"
+    pub fn footnote(&mut self, text: &str) -> String {
+        self.footnote_raw(&escape_html_text(text))
+    }
+
+    /// Writes the collected notes as ordered list, each with a back-link to its reference. Writes nothing, if there
+    /// are no notes.
+    pub fn footnotes(&mut self) {
+        let pending = std::mem::take(&mut self.footnotes.pending);
+        let Some((first, _)) = pending.first() else {
+            return;
+        };
+        self.open_tag("ol");
+        self.add_property("class", "footnotes");
+        self.add_property("start", &first.to_string());
+        for (number, note) in &pending {
+            self.open_tag_w_property("li", "id", &format!("fn-{}", number));
+            self.raw(note);
+            self.text(" ");
+            self.open_tag_w_property("a", "href", &format!("#fnref-{}", number));
+            self.text("↩");
+            self.close_tag();
+            self.close_tag();
+        }
+        self.close_tag();
+    }
+
+    // Writes the reference to a new note with the markup and returns its marker
+    pub(crate) fn footnote_raw(&mut self, note: &str) -> String {
+        self.footnotes.count += 1;
+        let marker = self.footnotes.count.to_string();
+        self.open_tag_w_property("sup", "id", &format!("fnref-{}", marker));
+        self.open_tag_w_property("a", "href", &format!("#fn-{}", marker));
+        self.text(&marker);
+        self.close_tag();
+        self.close_tag();
+        self.footnotes
+            .pending
+            .push((self.footnotes.count, note.to_string()));
+        marker
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footnotes_per_section() {
+        let mut wr = HTMLWriter::new();
+        wr.footnote("a");
+        wr.footnotes();
+        wr.content.clear();
+        assert_eq!(wr.footnote("b"), "2");
+        wr.footnote("c");
+        wr.footnotes();
+        assert!(wr
+            .content
+            .contains("<ol class=\"footnotes\" start=\"2\"><li id=\"fn-2\">b "));
+        assert!(wr
+            .content
+            .contains("<li id=\"fn-3\">c <a href=\"#fnref-3\">"));
+        wr.content.clear();
+        wr.footnotes();
+        assert!(wr.content.is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+//! A registry of value-formatters for the typed property APIs.
+//!
+//! Domain types, e.g. a money amount, a duration or a geo-position, should look the same in every document.
+//! Instead of formatting those values at each call site, a formatter is registered once, either for a Rust
+//! type or for a logical type name, and the typed property methods of the writers consult the registry.
+//! The values of domain types don't need a ```Display```-implementation, e.g. ```std::time::Duration```. Without
+//! a registered formatter, the built-in types (numbers, bool, char and strings) are written by their
+//! ```Display```-implementation, other types are a ```MissingFormatter```-error. One registry can be shared by
+//! many writers with ```wr.core.set_formatters()```.
+//!
+//! With the feature ```num-format``` a locale can be set on the registry, which is applied to the human-facing
+//! output of the HTMLWriter, see the module ```locale```.
+//!
+//! ```
+//! # use mllwriter::{FormatterRegistry, MLLWriter, XMLWriter};
+//! # use std::sync::Arc;
+//! struct Money(i64);
+//!
+//! let mut formatters = FormatterRegistry::new();
+//! formatters.register(|m: &Money| format!("{}.{:02} EUR", m.0 / 100, m.0 % 100));
+//! formatters.register_named("percent", |raw: &str| format!("{}%", raw));
+//!
+//! let mut wr = XMLWriter::new();
+//! wr.core.set_formatters(Arc::new(formatters));
+//! wr.open_tag("item");
+//! wr.add_typed_property("price", &Money(1250));
+//! wr.add_named_property("tax", "percent", "19");
+//! assert_eq!(wr.content, "<item price=\"12.50 EUR\" tax=\"19%\">");
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::escape::escape_json_string;
+#[cfg(feature = "num-format")]
+use crate::locale::{localize_date, localize_number, Locale};
+use crate::provenance::civil_date;
+use crate::{
+    check, escape_html_text, HTMLWriter, JSONWriter, MLLWriter, WriterCore, WriterError, XMLWriter,
+};
+
+/// Formats values of one type into their textual representation
+pub trait ValueFormatter<T: ?Sized>: Send + Sync {
+    /// Returns the text of the value
+    fn format(&self, value: &T) -> String;
+}
+
+impl<T: ?Sized, F: Fn(&T) -> String + Send + Sync> ValueFormatter<T> for F {
+    fn format(&self, value: &T) -> String {
+        self(value)
+    }
+}
+
+// A formatter with the type erased, it is only called with values of the registered type
+type AnyFormatter = Arc<dyn Fn(&dyn Any) -> Option<String> + Send + Sync>;
+
+/// The formatters registered by Rust type and by logical type name
+#[derive(Clone, Default)]
+pub struct FormatterRegistry {
+    by_type: HashMap<TypeId, AnyFormatter>,
+    by_name: HashMap<String, Arc<dyn ValueFormatter<str>>>,
+    // the locale of the human-facing output
+    #[cfg(feature = "num-format")]
+    pub(crate) locale: Option<Locale>,
+}
+
+impl FormatterRegistry {
+    /// Returns an empty registry
+    pub fn new() -> FormatterRegistry {
+        FormatterRegistry::default()
+    }
+
+    /// Registers the formatter for all values of type T, a previous formatter of T is replaced
+    pub fn register<T: Any, F: ValueFormatter<T> + 'static>(&mut self, formatter: F) {
+        let formatter: AnyFormatter =
+            Arc::new(move |value: &dyn Any| value.downcast_ref::<T>().map(|v| formatter.format(v)));
+        self.by_type.insert(TypeId::of::<T>(), formatter);
+    }
+
+    /// Registers the formatter for a logical type name, e.g. "percent" or "iso-date". It gets the raw text of
+    /// the value.
+    pub fn register_named<F: ValueFormatter<str> + 'static>(
+        &mut self,
+        type_name: &str,
+        formatter: F,
+    ) {
+        self.by_name
+            .insert(type_name.to_string(), Arc::new(formatter));
+    }
+
+    /// Returns true if a formatter is registered for type T
+    pub fn has_formatter<T: Any>(&self) -> bool {
+        self.by_type.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns true if a formatter is registered for the logical type name
+    pub fn has_named_formatter(&self, type_name: &str) -> bool {
+        self.by_name.contains_key(type_name)
+    }
+
+    /// Returns the formatted value, or None if no formatter is registered for type T
+    pub fn try_format<T: Any>(&self, value: &T) -> Option<String> {
+        self.by_type
+            .get(&TypeId::of::<T>())
+            .and_then(|formatter| formatter(value))
+    }
+
+    /// Returns the value formatted by the formatter of type T. Without a formatter, the built-in types (numbers,
+    /// bool, char and strings) are formatted by their ```Display```-implementation, other types give None.
+    pub fn format_value<T: Any>(&self, value: &T) -> Option<String> {
+        self.try_format(value).or_else(|| display_builtin(value))
+    }
+
+    /// Like ```format_value()```, but for human readers: numbers without a registered formatter get the separators
+    /// of the locale (feature ```num-format```)
+    pub fn format_human_value<T: Any>(&self, value: &T) -> Option<String> {
+        if let Some(formatted) = self.try_format(value) {
+            return Some(formatted);
+        }
+        #[cfg(feature = "num-format")]
+        if let Some(localized) = self
+            .locale
+            .and_then(|locale| localize_number(locale, value))
+        {
+            return Some(localized);
+        }
+        display_builtin(value)
+    }
+
+    /// Returns the date of the seconds since the unix epoch as "YYYY-MM-DD"
+    pub fn format_date(&self, secs: u64) -> String {
+        let (year, month, day) = civil_date(secs);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// Returns the date of the seconds since the unix epoch for human readers, in the format of the locale
+    /// (feature ```num-format```), otherwise it is the same as ```format_date()```
+    pub fn format_human_date(&self, secs: u64) -> String {
+        #[cfg(feature = "num-format")]
+        if let Some(locale) = self.locale {
+            return localize_date(locale, secs);
+        }
+        self.format_date(secs)
+    }
+
+    /// Returns the raw text formatted as the logical type, or the raw text itself if no formatter is registered
+    pub fn format_named(&self, type_name: &str, raw: &str) -> String {
+        match self.by_name.get(type_name) {
+            Some(formatter) => formatter.format(raw),
+            None => raw.to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for FormatterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.by_name.keys().collect();
+        names.sort();
+        let mut debug = f.debug_struct("FormatterRegistry");
+        debug.field("types", &self.by_type.len());
+        debug.field("names", &names);
+        #[cfg(feature = "num-format")]
+        debug.field("locale", &self.locale.map(|locale| locale.name()));
+        debug.finish()
+    }
+}
+
+impl WriterCore {
+    /// Sets the formatter registry consulted by the typed property methods
+    pub fn set_formatters(&mut self, formatters: Arc<FormatterRegistry>) {
+        self.formatters = formatters;
+    }
+
+    /// Returns the formatter registry of the writer
+    pub fn formatters(&self) -> &FormatterRegistry {
+        &self.formatters
+    }
+}
+
+// Returns true for the primitive number types
+pub(crate) fn is_number<T: Any>() -> bool {
+    [
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<i128>(),
+        TypeId::of::<isize>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<u128>(),
+        TypeId::of::<usize>(),
+        TypeId::of::<f32>(),
+        TypeId::of::<f64>(),
+    ]
+    .contains(&TypeId::of::<T>())
+}
+
+// Inserts the separator between the groups of digits, the last group has the size first, all others
+// the size next. A size of 0 means no grouping.
+pub(crate) fn group_digits(digits: &str, first: usize, next: usize, separator: &str) -> String {
+    let mut groups = Vec::new();
+    let mut rest = digits;
+    let mut size = first;
+    while size > 0 && rest.len() > size {
+        let (head, tail) = rest.split_at(rest.len() - size);
+        groups.push(tail);
+        rest = head;
+        size = next;
+    }
+    groups.push(rest);
+    groups.reverse();
+    groups.join(separator)
+}
+
+// Returns the Display-text of the built-in types: the numbers, bool, char and strings
+pub(crate) fn display_builtin<T: Any>(value: &T) -> Option<String> {
+    let value = value as &dyn Any;
+    macro_rules! display {
+        ($($builtin:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$builtin>() {
+                    return Some(value.to_string());
+                }
+            )*
+        };
+    }
+    display!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+    display!(bool, char, String, &'static str);
+    None
+}
+
+// Returns the error of a value, which neither has a formatter nor is a built-in type
+fn missing_formatter<T: Any>(operation: &'static str) -> WriterError {
+    WriterError::MissingFormatter {
+        type_name: std::any::type_name::<T>(),
+        operation,
+    }
+}
+
+// Returns true for values, which are written without quotes into JSON
+fn is_json_literal<T: Any>() -> bool {
+    is_number::<T>() || TypeId::of::<T>() == TypeId::of::<bool>()
+}
+
+// Returns true for NaN and the infinities, JSON has no literal for them
+fn is_non_finite<T: Any>(value: &T) -> bool {
+    let value = value as &dyn Any;
+    match (value.downcast_ref::<f64>(), value.downcast_ref::<f32>()) {
+        (Some(value), _) => !value.is_finite(),
+        (_, Some(value)) => !value.is_finite(),
+        _ => false,
+    }
+}
+
+// Returns the text as a quoted JSON-string
+pub(crate) fn json_string(text: &str) -> String {
+    format!("\"{}\"", escape_json_string(text))
+}
+
+impl HTMLWriter {
+    /// Adds a property with the value formatted for human readers by the registry of the writer
+    pub fn add_typed_property<T: Any>(&mut self, name: &str, value: &T) {
+        check(self.try_add_typed_property(name, value));
+    }
+
+    /// Like ```add_typed_property()```, but returns the error instead of panicking, e.g. ```MissingFormatter```
+    pub fn try_add_typed_property<T: Any>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), WriterError> {
+        let value = self.core.formatters.format_human_value(value);
+        let value = value.ok_or_else(|| missing_formatter::<T>("add_typed_property"))?;
+        self.try_add_property(name, &value)
+    }
+
+    /// Adds the value formatted for human readers by the registry of the writer as text
+    pub fn add_typed_text<T: Any>(&mut self, value: &T) {
+        check(self.try_add_typed_text(value));
+    }
+
+    /// Like ```add_typed_text()```, but returns the error instead of panicking, e.g. ```MissingFormatter```
+    pub fn try_add_typed_text<T: Any>(&mut self, value: &T) -> Result<(), WriterError> {
+        let value = self.core.formatters.format_human_value(value);
+        let value = value.ok_or_else(|| missing_formatter::<T>("add_typed_text"))?;
+        self.core.begin_text("add_typed_text")?;
+        self.content
+            .push_str(&self.core.ascii_markup(escape_html_text(&value)));
+        Ok(())
+    }
+
+    /// Adds a property with the date of the seconds since the unix epoch, formatted for human readers
+    pub fn add_date_property(&mut self, name: &str, secs: u64) {
+        let value = self.core.formatters.format_human_date(secs);
+        self.add_property(name, &value);
+    }
+
+    /// Adds a property with the raw value formatted as the logical type by the registry of the writer
+    pub fn add_named_property(&mut self, name: &str, type_name: &str, raw: &str) {
+        let value = self.core.formatters.format_named(type_name, raw);
+        self.add_property(name, &value);
+    }
+}
+
+impl XMLWriter {
+    /// Adds a property with the value formatted by the registry of the writer
+    pub fn add_typed_property<T: Any>(&mut self, name: &str, value: &T) {
+        check(self.try_add_typed_property(name, value));
+    }
+
+    /// Like ```add_typed_property()```, but returns the error instead of panicking, e.g. ```MissingFormatter```
+    pub fn try_add_typed_property<T: Any>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), WriterError> {
+        let value = self.core.formatters.format_value(value);
+        let value = value.ok_or_else(|| missing_formatter::<T>("add_typed_property"))?;
+        self.try_add_property(name, &value)
+    }
+
+    /// Adds a property with the date of the seconds since the unix epoch as "YYYY-MM-DD"
+    pub fn add_date_property(&mut self, name: &str, secs: u64) {
+        let value = self.core.formatters.format_date(secs);
+        self.add_property(name, &value);
+    }
+
+    /// Adds a property with the raw value formatted as the logical type by the registry of the writer
+    pub fn add_named_property(&mut self, name: &str, type_name: &str, raw: &str) {
+        let value = self.core.formatters.format_named(type_name, raw);
+        self.add_property(name, &value);
+    }
+}
+
+impl JSONWriter {
+    /// Adds a property with the value formatted by the registry of the writer. Formatted values are written
+    /// as JSON-strings, only numbers and booleans without a registered formatter are written as they are.
+    /// NaN and the infinities have no JSON-literal, they are written as ```null```.
+    pub fn add_typed_property<T: Any>(&mut self, name: &str, value: &T) {
+        check(self.try_add_typed_property(name, value));
+    }
+
+    /// Like ```add_typed_property()```, but returns the error instead of panicking, e.g. ```MissingFormatter```
+    pub fn try_add_typed_property<T: Any>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), WriterError> {
+        let value = match (
+            self.core.formatters.try_format(value),
+            display_builtin(value),
+        ) {
+            (Some(formatted), _) => self.string(&formatted),
+            (None, Some(_)) if is_non_finite(value) => "null".to_string(),
+            (None, Some(text)) if is_json_literal::<T>() => text,
+            (None, Some(text)) => self.string(&text),
+            (None, None) => return Err(missing_formatter::<T>("add_typed_property")),
+        };
+        self.try_add_property(name, &value)
+    }
+
+    /// Adds a property with the date of the seconds since the unix epoch as JSON-string "YYYY-MM-DD"
+    pub fn add_date_property(&mut self, name: &str, secs: u64) {
+        let value = self.string(&self.core.formatters.format_date(secs));
+        self.add_property(name, &value);
+    }
+
+    /// Adds a property with the raw value formatted as the logical type by the registry of the writer, it
+    /// is written as JSON-string
+    pub fn add_named_property(&mut self, name: &str, type_name: &str, raw: &str) {
+        let value = self.string(&self.core.formatters.format_named(type_name, raw));
+        self.add_property(name, &value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn json_typed_properties() {
+        let mut formatters = FormatterRegistry::new();
+        formatters.register(|d: &Duration| format!("{}s", d.as_secs()));
+        let mut wr = JSONWriter::new();
+        wr.core.set_formatters(Arc::new(formatters));
+        wr.open_tag("");
+        wr.add_typed_property("count", &3);
+        wr.add_typed_property("name", &"say \"hi\"");
+        wr.add_named_property("kind", "unknown", "raw");
+        // Duration has no Display-implementation, it is formatted by the registry
+        wr.add_typed_property("timeout", &Duration::from_secs(90));
+        wr.add_typed_property("ratio", &f64::NAN);
+        wr.add_typed_property("limit", &f32::INFINITY);
+        assert_eq!(
+            wr.try_add_typed_property("at", &std::time::Instant::now()),
+            Err(WriterError::MissingFormatter {
+                type_name: "std::time::Instant",
+                operation: "add_typed_property"
+            })
+        );
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            concat!(
+                "{\n  \"count\": 3,\n  \"name\": \"say \\\"hi\\\"\",\n  \"kind\": \"raw\",\n",
+                "  \"timeout\": \"90s\",\n  \"ratio\": null,\n  \"limit\": null\n}"
+            )
+        );
+    }
+}
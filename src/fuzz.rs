@@ -0,0 +1,302 @@
+//! Fuzzing of the writers by sequences of operations.
+//!
+//! A [`WriterOp`] is one call of the ```MLLWriter```-trait, ```apply()``` calls the ```try_```-variant, so a
+//! rejected operation returns its error instead of panicking. ```check_consistency()``` applies a sequence of
+//! operations and checks the invariants of the writer after each one: a rejected operation doesn't change the
+//! document, because all validation happens before the write, the block_stack follows the opened and closed
+//! elements, and a property is inserted into the last start-tag, or the one of the current element, without
+//! changing the content around it.
+//!
+//! With the feature "arbitrary" the operations implement ```Arbitrary``` for cargo-fuzz, with the feature
+//! "proptest" ```writer_ops()``` returns a strategy. Both prefer the names of [`FUZZ_NAMES`] and [`FUZZ_VALUES`], so the
+//! sequences get past the validation of names, but also try arbitrary strings.
+//!
+//! ```
+//! # use mllwriter::{check_consistency, HTMLWriter, WriterOp};
+//! let ops = [
+//!     WriterOp::OpenTag("div".to_string()),
+//!     WriterOp::AddProperty("class".to_string(), "a&b".to_string()),
+//!     WriterOp::SingleTag("br".to_string()),
+//!     WriterOp::AddProperty("id".to_string(), "x".to_string()),
+//!     WriterOp::LineFeed(1),
+//!     WriterOp::AddProperty("late".to_string(), "".to_string()),
+//!     WriterOp::CloseTag,
+//!     WriterOp::CloseTag,
+//! ];
+//! let mut wr = HTMLWriter::new();
+//! assert_eq!(check_consistency(&mut wr, &ops), Ok(()));
+//! assert_eq!(wr.content, "<div class=\"a&amp;b\" late=\"\"><br id=\"x\">\n</div>");
+//! ```
+
+use crate::{void, BufferedWriter, DocumentState, WriterError};
+
+/// The names of elements and properties, which the sequences of operations prefer
+pub const FUZZ_NAMES: [&str; 12] = [
+    "html", "body", "div", "p", "span", "a", "ul", "li", "br", "img", "id", "class",
+];
+
+/// The values of properties and comments, which the sequences of operations prefer
+pub const FUZZ_VALUES: [&str; 6] = ["", "a", "a b", "a&b", "\"q\"", "<x>"];
+
+/// An operation of the ```MLLWriter```-trait
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriterOp {
+    /// ```open_tag()```
+    OpenTag(String),
+    /// ```close_tag()```
+    CloseTag,
+    /// ```single_tag()```
+    SingleTag(String),
+    /// ```add_property()```
+    AddProperty(String, String),
+    /// ```text()```
+    Text(String),
+    /// ```add_comment()```
+    AddComment(String),
+    /// ```line_feed()```
+    LineFeed(usize),
+    /// ```blank_line()```
+    BlankLine,
+    /// ```line_feed_inc()```
+    LineFeedInc,
+    /// ```line_feed_dec()```
+    LineFeedDec,
+}
+
+impl WriterOp {
+    /// Applies the operation to the writer and returns the error, if it was rejected
+    pub fn apply<M: BufferedWriter>(&self, wr: &mut M) -> Result<(), WriterError> {
+        match self {
+            WriterOp::OpenTag(tag) => wr.try_open_tag(tag),
+            WriterOp::CloseTag => wr.try_close_tag(),
+            WriterOp::SingleTag(tag) => wr.try_single_tag(tag),
+            WriterOp::AddProperty(name, value) => wr.try_add_property(name, value),
+            WriterOp::Text(text) => wr.try_text(text),
+            WriterOp::AddComment(comment) => wr.try_add_comment(comment),
+            WriterOp::LineFeed(n) => {
+                wr.line_feed(*n);
+                Ok(())
+            }
+            WriterOp::BlankLine => {
+                wr.blank_line();
+                Ok(())
+            }
+            WriterOp::LineFeedInc => {
+                wr.line_feed_inc();
+                Ok(())
+            }
+            WriterOp::LineFeedDec => {
+                wr.line_feed_dec();
+                Ok(())
+            }
+        }
+    }
+}
+
+/// An invariant of the writer, which an operation violated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inconsistency {
+    /// The index of the operation in the sequence
+    pub step: usize,
+    /// The operation
+    pub op: WriterOp,
+    /// Describes the violated invariant
+    pub reason: &'static str,
+}
+
+impl std::fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "step {}: {:?}: {}", self.step, self.op, self.reason)
+    }
+}
+
+/// Applies the operations to the writer and returns the first violated invariant
+pub fn check_consistency<M: BufferedWriter>(
+    wr: &mut M,
+    ops: &[WriterOp],
+) -> Result<(), Inconsistency> {
+    for (step, op) in ops.iter().enumerate() {
+        let (content, core) = wr.buffer();
+        let (before, stack, state) = (content.clone(), core.block_stack.clone(), core.state);
+        let result = op.apply(wr);
+        check_op(wr, op, result.is_ok(), &before, &stack, state).map_err(|reason| {
+            Inconsistency {
+                step,
+                op: op.clone(),
+                reason,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+// Checks the invariants after the operation, with the content, the block_stack and the state before it
+fn check_op<M: BufferedWriter>(
+    wr: &mut M,
+    op: &WriterOp,
+    accepted: bool,
+    before: &str,
+    stack: &[String],
+    state: DocumentState,
+) -> Result<(), &'static str> {
+    let (content, core) = wr.buffer();
+    let mut expected = stack.to_vec();
+    if !accepted {
+        if content != before || core.block_stack != stack {
+            return Err("a rejected operation changed the document");
+        }
+        return Ok(());
+    }
+    match op {
+        WriterOp::OpenTag(tag) => expected.push(tag.clone()),
+        // The guard pops the closed element
+        WriterOp::CloseTag if expected.pop().is_none() => {
+            return Err("a close without an open element was accepted");
+        }
+        WriterOp::AddProperty(name, _) => match state {
+            DocumentState::InStartTag => {
+                // The end of the start-tag is removed and written again behind the property
+                let tag_end = void::last_tag_end(before);
+                let kept = &before[..before.len() - tag_end.len()];
+                if !content.starts_with(kept)
+                    || !content.ends_with(tag_end)
+                    || !content[kept.len()..]
+                        .trim_start()
+                        .starts_with(name.as_str())
+                {
+                    return Err("the property wasn't inserted into the last start-tag");
+                }
+            }
+            DocumentState::InElement => {
+                // A member is appended to its block, a property is spliced into the start-tag of the current
+                // element
+                let at = before
+                    .char_indices()
+                    .zip(content.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(before.len(), |((i, _), _)| i);
+                let spliced = content.len() > before.len()
+                    && content[at + content.len() - before.len()..] == before[at..]
+                    && content[at..].trim_start().starts_with(name.as_str());
+                if !content.starts_with(before.trim_end()) && !spliced {
+                    return Err("a member or property changed the content around it");
+                }
+            }
+            _ => return Err("a property without a start-tag or block was accepted"),
+        },
+        _ => (),
+    }
+    if core.block_stack != expected {
+        return Err("the block_stack doesn't follow the opened and closed elements");
+    }
+    match core.state {
+        DocumentState::InElement if core.block_stack.is_empty() => {
+            Err("the state is InElement without an open element")
+        }
+        DocumentState::Prolog | DocumentState::AfterRoot if !core.block_stack.is_empty() => {
+            Err("there are open elements outside of the root")
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for WriterOp {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=9)? {
+            0 => WriterOp::OpenTag(arbitrary_str(u, &FUZZ_NAMES)?),
+            1 => WriterOp::CloseTag,
+            2 => WriterOp::SingleTag(arbitrary_str(u, &FUZZ_NAMES)?),
+            3 => WriterOp::AddProperty(
+                arbitrary_str(u, &FUZZ_NAMES)?,
+                arbitrary_str(u, &FUZZ_VALUES)?,
+            ),
+            4 => WriterOp::AddComment(arbitrary_str(u, &FUZZ_VALUES)?),
+            5 => WriterOp::LineFeed(u.int_in_range(0..=2)?),
+            6 => WriterOp::BlankLine,
+            7 => WriterOp::LineFeedInc,
+            8 => WriterOp::LineFeedDec,
+            _ => WriterOp::Text(arbitrary_str(u, &FUZZ_VALUES)?),
+        })
+    }
+}
+
+// Returns a string of the pool, or an arbitrary one in one of eight cases
+#[cfg(feature = "arbitrary")]
+fn arbitrary_str(u: &mut arbitrary::Unstructured<'_>, pool: &[&str]) -> arbitrary::Result<String> {
+    if u.ratio(1, 8)? {
+        u.arbitrary()
+    } else {
+        Ok(u.choose(pool)?.to_string())
+    }
+}
+
+/// Returns a proptest-strategy of one operation
+#[cfg(feature = "proptest")]
+pub fn writer_op() -> impl proptest::strategy::Strategy<Value = WriterOp> {
+    use proptest::prelude::*;
+
+    fn pool_str(pool: &'static [&'static str]) -> impl Strategy<Value = String> {
+        prop_oneof![
+            7 => proptest::sample::select(pool).prop_map(str::to_string),
+            1 => any::<String>(),
+        ]
+    }
+    prop_oneof![
+        pool_str(&FUZZ_NAMES).prop_map(WriterOp::OpenTag),
+        Just(WriterOp::CloseTag),
+        pool_str(&FUZZ_NAMES).prop_map(WriterOp::SingleTag),
+        (pool_str(&FUZZ_NAMES), pool_str(&FUZZ_VALUES))
+            .prop_map(|(n, v)| WriterOp::AddProperty(n, v)),
+        pool_str(&FUZZ_VALUES).prop_map(WriterOp::AddComment),
+        (0..=2usize).prop_map(WriterOp::LineFeed),
+        Just(WriterOp::BlankLine),
+        Just(WriterOp::LineFeedInc),
+        Just(WriterOp::LineFeedDec),
+        pool_str(&FUZZ_VALUES).prop_map(WriterOp::Text),
+    ]
+}
+
+/// Returns a proptest-strategy of sequences of up to ```max_len``` operations
+#[cfg(feature = "proptest")]
+pub fn writer_ops(max_len: usize) -> impl proptest::strategy::Strategy<Value = Vec<WriterOp>> {
+    proptest::collection::vec(writer_op(), 0..=max_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XMLWriter;
+
+    #[test]
+    fn rejected_ops_keep_the_document() {
+        let ops = [
+            WriterOp::CloseTag,
+            WriterOp::OpenTag("Div".to_string()),
+            WriterOp::OpenTag("root".to_string()),
+            WriterOp::BlankLine,
+            WriterOp::AddProperty("id".to_string(), "late".to_string()),
+            WriterOp::CloseTag,
+            WriterOp::OpenTag("second".to_string()),
+            WriterOp::AddComment("end".to_string()),
+        ];
+        let mut wr = XMLWriter::new();
+        assert_eq!(check_consistency(&mut wr, &ops), Ok(()));
+        assert_eq!(wr.content, "<root id=\"late\">\n\n</root><!-- end -->");
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn html_invariants(ops in writer_ops(40)) {
+            let mut wr = crate::HTMLWriter::new();
+            proptest::prop_assert_eq!(check_consistency(&mut wr, &ops), Ok(()));
+        }
+
+        #[test]
+        fn json_invariants(ops in writer_ops(40)) {
+            let mut wr = crate::JSONWriter::new();
+            proptest::prop_assert_eq!(check_consistency(&mut wr, &ops), Ok(()));
+        }
+    }
+}
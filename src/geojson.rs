@@ -0,0 +1,181 @@
+//! GeoJSON (RFC 7946) documents.
+//!
+//! The [`GeoJsonBuilder`] writes a FeatureCollection with the JSONWriter, each feature with its [`Geometry`]
+//! and its properties. The coordinates are written as compact arrays with a configurable number of decimals
+//! (default 6, about 10 cm), trailing zeros are dropped. The rings of polygons are closed as needed.
+//!
+//! ```
+//! # use mllwriter::{GeoJsonBuilder, Geometry};
+//! let mut geo = GeoJsonBuilder::new();
+//! geo.set_precision(2);
+//! geo.add_feature(&Geometry::Point([8.541694, 47.376887]), &[("name", "\"Zurich\"")]);
+//! assert_eq!(
+//!     geo.finish(),
+//!     "{\n  \"type\": \"FeatureCollection\",\n  \"features\":\n  [\n    {\n      \"type\": \"Feature\",\n      \
+//!      \"geometry\":\n      {\n        \"type\": \"Point\",\n        \"coordinates\": [8.54, 47.38]\n      },\n      \
+//!      \"properties\":\n      {\n        \"name\": \"Zurich\"\n      }\n    }\n  ]\n}"
+//! );
+//! ```
+
+use crate::format::json_string;
+use crate::{JSONWriter, MLLWriter};
+
+/// A position as longitude and latitude
+pub type Position = [f64; 2];
+
+/// The geometries of GeoJSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// A single position
+    Point(Position),
+    /// A line through at least two positions
+    LineString(Vec<Position>),
+    /// An outer ring followed by the rings of the holes
+    Polygon(Vec<Vec<Position>>),
+}
+
+impl Geometry {
+    /// Returns the GeoJSON-type, e.g. "Point"
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Geometry::Point(_) => "Point",
+            Geometry::LineString(_) => "LineString",
+            Geometry::Polygon(_) => "Polygon",
+        }
+    }
+
+    /// Returns the coordinates as JSON-array with at most the given number of decimals
+    pub fn coordinates(&self, precision: usize) -> String {
+        match self {
+            Geometry::Point(position) => position_array(position, precision),
+            Geometry::LineString(line) => positions_array(line, precision),
+            Geometry::Polygon(rings) => {
+                let rings: Vec<String> = rings
+                    .iter()
+                    .map(|ring| positions_array(&closed_ring(ring), precision))
+                    .collect();
+                format!("[{}]", rings.join(", "))
+            }
+        }
+    }
+}
+
+/// Writes a FeatureCollection
+#[derive(Debug)]
+pub struct GeoJsonBuilder {
+    writer: JSONWriter,
+    precision: usize,
+}
+
+impl Default for GeoJsonBuilder {
+    fn default() -> Self {
+        GeoJsonBuilder::new()
+    }
+}
+
+impl GeoJsonBuilder {
+    /// Returns a builder with an empty FeatureCollection
+    pub fn new() -> GeoJsonBuilder {
+        let mut writer = JSONWriter::new();
+        writer.open_tag("");
+        writer.add_property("type", &json_string("FeatureCollection"));
+        writer.open_array("features");
+        GeoJsonBuilder {
+            writer,
+            precision: 6,
+        }
+    }
+
+    /// Sets the number of decimals of the coordinates of the following features
+    pub fn set_precision(&mut self, decimals: usize) {
+        self.precision = decimals;
+    }
+
+    /// Adds a feature with the geometry and the properties, the values of the properties are raw JSON
+    pub fn add_feature(&mut self, geometry: &Geometry, properties: &[(&str, &str)]) {
+        self.writer.open_tag("");
+        self.writer.add_property("type", &json_string("Feature"));
+        self.writer
+            .add_geometry("geometry", geometry, self.precision);
+        self.writer.open_tag("properties");
+        for (name, value) in properties {
+            self.writer.add_property(name, value);
+        }
+        self.writer.close_tag();
+        self.writer.close_tag();
+    }
+
+    /// Closes the FeatureCollection and returns the document
+    pub fn finish(mut self) -> String {
+        self.writer.close_tag();
+        self.writer.close_tag();
+        self.writer.content
+    }
+}
+
+impl JSONWriter {
+    /// Writes the geometry as named block with its type and coordinates
+    pub fn add_geometry(&mut self, name: &str, geometry: &Geometry, precision: usize) {
+        self.open_tag(name);
+        self.add_property("type", &json_string(geometry.type_name()));
+        self.add_property("coordinates", &geometry.coordinates(precision));
+        self.close_tag();
+    }
+}
+
+// Returns the coordinate with at most the given decimals, without trailing zeros
+fn coordinate(value: f64, precision: usize) -> String {
+    assert!(value.is_finite(), "a coordinate has to be finite");
+    let mut text = format!("{:.*}", precision, value);
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    if text == "-0" {
+        text.remove(0);
+    }
+    text
+}
+
+fn position_array(position: &Position, precision: usize) -> String {
+    format!(
+        "[{}, {}]",
+        coordinate(position[0], precision),
+        coordinate(position[1], precision)
+    )
+}
+
+fn positions_array(positions: &[Position], precision: usize) -> String {
+    let positions: Vec<String> = positions
+        .iter()
+        .map(|p| position_array(p, precision))
+        .collect();
+    format!("[{}]", positions.join(", "))
+}
+
+// Returns the ring with the first position repeated at the end, as GeoJSON requires it
+fn closed_ring(ring: &[Position]) -> Vec<Position> {
+    let mut ring = ring.to_vec();
+    if let (Some(first), Some(last)) = (ring.first(), ring.last()) {
+        if first != last {
+            ring.push(*first);
+        }
+    }
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geojson_polygon_closed() {
+        let polygon = Geometry::Polygon(vec![vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]]);
+        assert_eq!(polygon.coordinates(3), "[[[0, 0], [1, 0], [1, 1], [0, 0]]]");
+        assert_eq!(coordinate(-0.0001, 2), "0");
+        assert_eq!(coordinate(12.5, 0), "12");
+        assert_eq!(
+            GeoJsonBuilder::new().finish(),
+            "{\n  \"type\": \"FeatureCollection\",\n  \"features\":\n  [\n  ]\n}"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Abbreviations with their expansions and the glossary of a document.
+//!
+//! The [`Glossary`] is the registry of the expansions. It is set for each writer of a document set by
+//! ```wr.set_glossary()```, so the expansions stay consistent, and child writers take it over.
+//! ```wr.abbr()``` writes the abbreviation with its expansion as title, ```wr.render_glossary()``` writes the
+//! sorted glossary.
+//!
+//! ```
+//! # use mllwriter::{Glossary, HTMLWriter, MLLWriter};
+//! let mut glossary = Glossary::new();
+//! glossary.add("HTML", "HyperText Markup Language");
+//! let mut wr = HTMLWriter::new();
+//! wr.set_glossary(glossary);
+//! wr.open_tag("p");
+//! wr.abbr("HTML");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<p><abbr title=\"HyperText Markup Language\">HTML</abbr></p>");
+//! ```
+
+use crate::{check, HTMLWriter, MLLWriter, WriterError};
+
+/// The registry of abbreviations and their expansions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Glossary {
+    entries: Vec<(String, String)>,
+}
+
+impl Glossary {
+    pub fn new() -> Glossary {
+        Glossary::default()
+    }
+
+    /// Adds the abbreviation with its expansion, an abbreviation is only added once
+    pub fn add(&mut self, abbreviation: &str, expansion: &str) {
+        match self.entries.iter_mut().find(|(a, _)| a == abbreviation) {
+            Some(entry) => entry.1 = expansion.to_string(),
+            None => self
+                .entries
+                .push((abbreviation.to_string(), expansion.to_string())),
+        }
+    }
+
+    /// Returns the expansion of the abbreviation
+    pub fn expansion(&self, abbreviation: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(a, _)| a == abbreviation)
+            .map(|(_, e)| e.as_str())
+    }
+}
+
+impl HTMLWriter {
+    /// Sets the glossary of the writer
+    pub fn set_glossary(&mut self, glossary: Glossary) {
+        self.glossary = glossary;
+    }
+
+    /// Adds the abbreviation with its expansion to the glossary of the writer
+    pub fn add_abbreviation(&mut self, abbreviation: &str, expansion: &str) {
+        self.glossary.add(abbreviation, expansion);
+    }
+
+    /// Writes the abbreviation with its expansion as title, the abbreviation has to be in the glossary
+    pub fn abbr(&mut self, abbreviation: &str) {
+        let Some(expansion) = self.glossary.expansion(abbreviation).map(str::to_string) else {
+            return check(Err(WriterError::UnknownAbbreviation(
+                abbreviation.to_string(),
+            )));
+        };
+        self.open_tag_w_property("abbr", "title", &expansion);
+        self.text(abbreviation);
+        self.close_tag();
+    }
+
+    /// Writes the glossary as description list, sorted case-insensitive. Writes nothing, if the glossary is empty.
+    pub fn render_glossary(&mut self) {
+        let mut entries = self.glossary.entries.clone();
+        if entries.is_empty() {
+            return;
+        }
+        entries.sort_by(|(a, _), (b, _)| (a.to_lowercase(), a).cmp(&(b.to_lowercase(), b)));
+        self.open_tag_w_property("dl", "class", "glossary");
+        for (abbreviation, expansion) in &entries {
+            self.open_tag("dt");
+            self.open_tag("abbr");
+            self.text(abbreviation);
+            self.close_tag();
+            self.close_tag();
+            self.open_tag("dd");
+            self.text(expansion);
+            self.close_tag();
+        }
+        self.close_tag();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glossary_sorted() {
+        let mut wr = HTMLWriter::new();
+        wr.add_abbreviation("XML", "Extensible Markup Language");
+        wr.add_abbreviation("CSS", "Cascading Style Sheets");
+        let mut child = wr.sub_writer();
+        child.abbr("CSS");
+        assert!(child.content.contains("title=\"Cascading Style Sheets\""));
+        wr.render_glossary();
+        assert_eq!(
+            wr.content,
+            "<dl class=\"glossary\"><dt><abbr>CSS</abbr></dt><dd>Cascading Style Sheets</dd>\
+             <dt><abbr>XML</abbr></dt><dd>Extensible Markup Language</dd></dl>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the abbreviation 'SVG' is not in the glossary")]
+    fn glossary_unknown_abbreviation() {
+        HTMLWriter::new().abbr("SVG");
+    }
+}
@@ -0,0 +1,82 @@
+//! Elements, which are closed automatically at the end of their scope.
+//!
+//! ```wr.scoped_tag()``` opens the element and returns a [`TagGuard`], which closes it when dropped, also on an
+//! early return or by ```?```. The writer is used through the guard as long as it lives, so the nested content
+//! can't be written outside of the element. The element isn't closed while a panic unwinds.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, WriterError};
+//! fn items(wr: &mut HTMLWriter, items: &[&str]) -> Result<(), WriterError> {
+//!     let mut ul = wr.scoped_tag("ul");
+//!     for item in items {
+//!         let mut li = ul.scoped_tag("li");
+//!         li.try_add_property("title", item)?;
+//!     }
+//!     Ok(())
+//! }
+//! let mut wr = HTMLWriter::new();
+//! items(&mut wr, &["a", "b"]).unwrap();
+//! assert_eq!(wr.content, "<ul><li title=\"a\"></li><li title=\"b\"></li></ul>");
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use crate::MLLWriter;
+
+/// Closes its element when dropped, see ```MLLWriter::scoped_tag()```
+#[must_use = "the element is closed immediately, if the guard isn't kept"]
+pub struct TagGuard<'w, W: MLLWriter> {
+    writer: &'w mut W,
+}
+
+impl<'w, W: MLLWriter> TagGuard<'w, W> {
+    // Opens the element, the guard closes it
+    pub(crate) fn new(writer: &'w mut W, tag: &str) -> TagGuard<'w, W> {
+        writer.open_tag(tag);
+        TagGuard { writer }
+    }
+}
+
+impl<W: MLLWriter> Deref for TagGuard<'_, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        self.writer
+    }
+}
+
+impl<W: MLLWriter> DerefMut for TagGuard<'_, W> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.writer
+    }
+}
+
+impl<W: MLLWriter> Drop for TagGuard<'_, W> {
+    /// Closes the element, unless a panic unwinds
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.writer.close_tag();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JSONWriter, MLLWriter, XMLWriter};
+
+    #[test]
+    fn guards_of_all_writers() {
+        let mut wr = XMLWriter::new();
+        {
+            let mut a = wr.scoped_tag("a");
+            let _b = a.scoped_tag("b");
+        }
+        assert_eq!(wr.content, "<a><b></b></a>");
+        let mut wr = JSONWriter::new();
+        {
+            let mut root = wr.scoped_tag("");
+            root.add_property("x", "1");
+        }
+        assert_eq!(wr.content, "{\n  \"x\": 1\n}");
+    }
+}
@@ -0,0 +1,303 @@
+//! HTTP Archives (HAR 1.2) of captured network traffic.
+//!
+//! The [`HarBuilder`] writes the log with its creator and an entry per [`HarEntry`], each with the request, the
+//! response and the [`HarTimings`] in milliseconds. The query string of the request is taken from the URL, the
+//! total time of an entry is the sum of its timings. Unknown sizes are written as -1, as the format requires it.
+//!
+//! ```
+//! # use mllwriter::{HarBuilder, HarEntry, HarTimings};
+//! let mut har = HarBuilder::new("probe", "1.0");
+//! let timings = HarTimings { send: 1.0, wait: 40.5, receive: 2.0, ..HarTimings::default() };
+//! har.add_entry(
+//!     &HarEntry::new("2026-10-16T12:00:00.000Z", "GET", "https://example.com/?q=1", 200, "OK")
+//!         .with_response_header("Content-Type", "text/plain")
+//!         .with_content("text/plain", "hello")
+//!         .with_timings(timings),
+//! );
+//! let json = har.finish();
+//! assert!(json.starts_with("{\n  \"log\":\n  {\n    \"version\": \"1.2\",\n"));
+//! assert!(json.contains("\"time\": 43.5,"));
+//! ```
+
+use crate::format::json_string;
+use crate::svg::number;
+use crate::{JSONWriter, MLLWriter};
+
+/// The phases of an entry in milliseconds, optional phases didn't happen or are unknown
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HarTimings {
+    /// Waiting in the queue of the client
+    pub blocked: Option<f64>,
+    /// The DNS resolution
+    pub dns: Option<f64>,
+    /// Creating the connection, including the TLS handshake
+    pub connect: Option<f64>,
+    /// The TLS handshake
+    pub ssl: Option<f64>,
+    /// Sending the request
+    pub send: f64,
+    /// Waiting for the response
+    pub wait: f64,
+    /// Receiving the response
+    pub receive: f64,
+}
+
+impl HarTimings {
+    /// Returns the total time, the TLS handshake is already part of the connection
+    pub fn total(&self) -> f64 {
+        [self.blocked, self.dns, self.connect]
+            .iter()
+            .flatten()
+            .sum::<f64>()
+            + self.send
+            + self.wait
+            + self.receive
+    }
+}
+
+/// A request with its response
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarEntry {
+    /// The start of the request as ISO 8601 date-time
+    pub started: String,
+    /// The method, e.g. "GET"
+    pub method: String,
+    /// The absolute URL
+    pub url: String,
+    /// The protocol, e.g. "HTTP/1.1"
+    pub http_version: String,
+    /// The headers of the request as name-value-pairs
+    pub request_headers: Vec<(String, String)>,
+    /// The mime-type and the text of the posted data
+    pub request_body: Option<(String, String)>,
+    /// The status-code of the response
+    pub status: u16,
+    /// The status-text of the response, e.g. "OK"
+    pub status_text: String,
+    /// The headers of the response as name-value-pairs
+    pub response_headers: Vec<(String, String)>,
+    /// The mime-type and the text of the response content
+    pub content: Option<(String, String)>,
+    /// The phases of the entry
+    pub timings: HarTimings,
+}
+
+impl HarEntry {
+    /// Returns an entry with HTTP/1.1 and without headers, bodies and timings
+    pub fn new(started: &str, method: &str, url: &str, status: u16, status_text: &str) -> HarEntry {
+        HarEntry {
+            started: started.to_string(),
+            method: method.to_string(),
+            url: url.to_string(),
+            http_version: "HTTP/1.1".to_string(),
+            request_headers: Vec::new(),
+            request_body: None,
+            status,
+            status_text: status_text.to_string(),
+            response_headers: Vec::new(),
+            content: None,
+            timings: HarTimings::default(),
+        }
+    }
+
+    /// Returns the entry with an additional request header
+    pub fn with_request_header(mut self, name: &str, value: &str) -> HarEntry {
+        self.request_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the entry with an additional response header
+    pub fn with_response_header(mut self, name: &str, value: &str) -> HarEntry {
+        self.response_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the entry with the posted data of the request
+    pub fn with_request_body(mut self, mime_type: &str, text: &str) -> HarEntry {
+        self.request_body = Some((mime_type.to_string(), text.to_string()));
+        self
+    }
+
+    /// Returns the entry with the content of the response
+    pub fn with_content(mut self, mime_type: &str, text: &str) -> HarEntry {
+        self.content = Some((mime_type.to_string(), text.to_string()));
+        self
+    }
+
+    /// Returns the entry with the timings
+    pub fn with_timings(mut self, timings: HarTimings) -> HarEntry {
+        self.timings = timings;
+        self
+    }
+
+    // The name-value-pairs of the query string, without decoding
+    fn query_string(&self) -> Vec<(&str, &str)> {
+        let url = self.url.split('#').next().unwrap_or_default();
+        let Some((_, query)) = url.split_once('?') else {
+            return Vec::new();
+        };
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .collect()
+    }
+}
+
+/// Writes an HTTP Archive
+#[derive(Debug)]
+pub struct HarBuilder {
+    writer: JSONWriter,
+}
+
+impl HarBuilder {
+    /// Returns a builder with an empty log, created by the named tool
+    pub fn new(creator: &str, version: &str) -> HarBuilder {
+        let mut writer = JSONWriter::new();
+        writer.open_tag("");
+        writer.open_tag("log");
+        writer.add_property("version", &json_string("1.2"));
+        writer.open_tag("creator");
+        writer.add_property("name", &json_string(creator));
+        writer.add_property("version", &json_string(version));
+        writer.close_tag();
+        writer.open_array("entries");
+        HarBuilder { writer }
+    }
+
+    /// Adds the entry to the log
+    pub fn add_entry(&mut self, entry: &HarEntry) {
+        let wr = &mut self.writer;
+        wr.open_tag("");
+        wr.add_property("startedDateTime", &json_string(&entry.started));
+        wr.add_property("time", &milliseconds(entry.timings.total()));
+
+        wr.open_tag("request");
+        wr.add_property("method", &json_string(&entry.method));
+        wr.add_property("url", &json_string(&entry.url));
+        wr.add_property("httpVersion", &json_string(&entry.http_version));
+        wr.add_property("cookies", "[]");
+        add_pairs(wr, "headers", &entry.request_headers);
+        add_pairs(wr, "queryString", &entry.query_string());
+        if let Some((mime_type, text)) = &entry.request_body {
+            wr.open_tag("postData");
+            wr.add_property("mimeType", &json_string(mime_type));
+            wr.add_property("text", &json_string(text));
+            wr.close_tag();
+        }
+        wr.add_property("headersSize", "-1");
+        let body_size = entry.request_body.as_ref().map_or(0, |(_, t)| t.len());
+        wr.add_property("bodySize", &body_size.to_string());
+        wr.close_tag();
+
+        wr.open_tag("response");
+        wr.add_property("status", &entry.status.to_string());
+        wr.add_property("statusText", &json_string(&entry.status_text));
+        wr.add_property("httpVersion", &json_string(&entry.http_version));
+        wr.add_property("cookies", "[]");
+        add_pairs(wr, "headers", &entry.response_headers);
+        wr.open_tag("content");
+        match &entry.content {
+            Some((mime_type, text)) => {
+                wr.add_property("size", &text.len().to_string());
+                wr.add_property("mimeType", &json_string(mime_type));
+                wr.add_property("text", &json_string(text));
+            }
+            None => {
+                wr.add_property("size", "0");
+                wr.add_property("mimeType", &json_string(""));
+            }
+        }
+        wr.close_tag();
+        let location = entry
+            .response_headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("location"))
+            .map_or("", |(_, value)| value.as_str());
+        wr.add_property("redirectURL", &json_string(location));
+        wr.add_property("headersSize", "-1");
+        let body_size = entry.content.as_ref().map_or(0, |(_, t)| t.len());
+        wr.add_property("bodySize", &body_size.to_string());
+        wr.close_tag();
+
+        wr.open_tag("cache");
+        wr.close_tag();
+
+        let t = &entry.timings;
+        wr.open_tag("timings");
+        for (name, value) in [
+            ("blocked", t.blocked),
+            ("dns", t.dns),
+            ("connect", t.connect),
+        ] {
+            wr.add_property(name, &value.map_or("-1".to_string(), milliseconds));
+        }
+        wr.add_property("send", &milliseconds(t.send));
+        wr.add_property("wait", &milliseconds(t.wait));
+        wr.add_property("receive", &milliseconds(t.receive));
+        wr.add_property("ssl", &t.ssl.map_or("-1".to_string(), milliseconds));
+        wr.close_tag();
+        wr.close_tag();
+    }
+
+    /// Closes the log and returns the archive
+    pub fn finish(mut self) -> String {
+        for _ in 0..3 {
+            self.writer.close_tag();
+        }
+        self.writer.content
+    }
+}
+
+// Writes the name-value-pairs as array of objects
+fn add_pairs<N: AsRef<str>, V: AsRef<str>>(wr: &mut JSONWriter, name: &str, pairs: &[(N, V)]) {
+    if pairs.is_empty() {
+        wr.add_property(name, "[]");
+        return;
+    }
+    wr.open_array(name);
+    for (n, v) in pairs {
+        wr.open_tag("");
+        wr.add_property("name", &json_string(n.as_ref()));
+        wr.add_property("value", &json_string(v.as_ref()));
+        wr.close_tag();
+    }
+    wr.close_tag();
+}
+
+// Formats a time in milliseconds with at most 2 decimals
+fn milliseconds(value: f64) -> String {
+    number(value, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn har_query_and_timings() {
+        let entry = HarEntry::new("t", "GET", "http://a/p?x=1&flag#top", 301, "Moved")
+            .with_response_header("Location", "/q");
+        assert_eq!(entry.query_string(), [("x", "1"), ("flag", "")]);
+        let timings = HarTimings {
+            dns: Some(2.0),
+            connect: Some(10.0),
+            ssl: Some(6.0),
+            wait: 3.25,
+            ..HarTimings::default()
+        };
+        assert_eq!(timings.total(), 15.25);
+
+        let mut har = HarBuilder::new("t", "1");
+        har.add_entry(&entry.with_timings(timings));
+        let json = har.finish();
+        assert!(json.contains("\"redirectURL\": \"/q\","));
+        assert!(json.contains(
+            "\"timings\":\n        {\n          \"blocked\": -1,\n          \"dns\": 2,\n          \"connect\": 10,\n"
+        ));
+        assert!(json.ends_with("\"ssl\": 6\n        }\n      }\n    ]\n  }\n}"));
+        crate::json::parse(&json, crate::JsonDialect::Json).unwrap();
+    }
+}
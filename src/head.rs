@@ -0,0 +1,176 @@
+//! Elements of the head of HTML-documents.
+//!
+//! The values of the attributes are escaped, so URLs and texts can be passed as they are.
+//!
+//! ```wr.critical_css()``` implements the critical-CSS pattern: the styles of the first screen are written inline,
+//! the full stylesheet is loaded without blocking the rendering, with a 'noscript'-fallback.
+//!
+//! The resource hints ```preload()```, ```prefetch()```, ```preconnect()``` and ```dns_prefetch()``` set
+//! "crossorigin" where the browser fetches with CORS, e.g. for fonts. Otherwise the preloaded font isn't used
+//! and loaded a second time.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("head");
+//! wr.add_meta("description", "Q&A");
+//! wr.add_link("icon", "/favicon.ico");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<head><meta name=\"description\" content=\"Q&amp;A\"><link rel=\"icon\" href=\"/favicon.ico\"></head>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{HTMLWriter, MLLWriter, Properties};
+
+/// The ways to load a stylesheet without blocking the rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StylesheetLoading {
+    /// The stylesheet is loaded for the media "print" and switched to "all" when it is loaded
+    MediaSwap,
+    /// The stylesheet is preloaded and turned into a stylesheet when it is loaded
+    Preload,
+}
+
+impl HTMLWriter {
+    /// Writes a 'meta'-element with name and content
+    pub fn add_meta(&mut self, name: &str, content: &str) {
+        self.add_void("meta", &[("name", name), ("content", content)]);
+    }
+
+    /// Writes a 'link'-element with the relation and the URL
+    pub fn add_link(&mut self, rel: &str, href: &str) {
+        self.add_void("link", &[("rel", rel), ("href", href)]);
+    }
+
+    /// Writes the critical styles inline and loads the full stylesheet deferred, clients without scripts load it
+    /// by the 'noscript'-fallback
+    pub fn critical_css(&mut self, critical: &str, href: &str, loading: StylesheetLoading) {
+        self.open_tag("style");
+        let critical = self.escape_end_tag(critical, "</style");
+        self.write_str(&critical)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+        match loading {
+            StylesheetLoading::MediaSwap => self.add_void(
+                "link",
+                &[
+                    ("rel", "stylesheet"),
+                    ("href", href),
+                    ("media", "print"),
+                    ("onload", "this.media='all'"),
+                ],
+            ),
+            StylesheetLoading::Preload => self.add_void(
+                "link",
+                &[
+                    ("rel", "preload"),
+                    ("href", href),
+                    ("as", "style"),
+                    ("onload", "this.onload=null;this.rel='stylesheet'"),
+                ],
+            ),
+        }
+        self.open_tag("noscript");
+        self.add_link("stylesheet", href);
+        self.close_tag();
+    }
+
+    /// Preloads a resource of the current page, the destination is e.g. "font", "style", "script" or "image"
+    pub fn preload(&mut self, href: &str, as_: &str) {
+        if as_ == "font" || as_ == "fetch" {
+            self.add_void(
+                "link",
+                &[
+                    ("rel", "preload"),
+                    ("href", href),
+                    ("as", as_),
+                    ("crossorigin", "anonymous"),
+                ],
+            );
+        } else {
+            self.add_void("link", &[("rel", "preload"), ("href", href), ("as", as_)]);
+        }
+    }
+
+    /// Fetches a resource of a following page in idle time
+    pub fn prefetch(&mut self, href: &str) {
+        self.add_link("prefetch", href);
+    }
+
+    /// Opens the connection to the origin early
+    pub fn preconnect(&mut self, origin: &str) {
+        self.add_link("preconnect", origin);
+    }
+
+    /// Opens the connection to the origin early for requests with CORS, e.g. fonts
+    pub fn preconnect_cors(&mut self, origin: &str) {
+        self.add_void(
+            "link",
+            &[
+                ("rel", "preconnect"),
+                ("href", origin),
+                ("crossorigin", "anonymous"),
+            ],
+        );
+    }
+
+    /// Resolves the domain of the origin early
+    pub fn dns_prefetch(&mut self, origin: &str) {
+        self.add_link("dns-prefetch", origin);
+    }
+
+    // Writes a void element with the attributes, which may contain characters like '-' and get escaped values
+    pub(crate) fn add_void(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.single_tag(tag);
+        let properties: Properties = attributes.iter().copied().collect();
+        self.add_properties(&properties);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_critical_css() {
+        let mut wr = HTMLWriter::new();
+        wr.critical_css("h1{color:red}", "/all.css", StylesheetLoading::Preload);
+        assert_eq!(
+            wr.content,
+            "<style>h1{color:red}</style><link rel=\"preload\" href=\"/all.css\" as=\"style\" \
+             onload=\"this.onload=null;this.rel=&#39;stylesheet&#39;\"><noscript><link rel=\"stylesheet\" \
+             href=\"/all.css\"></noscript>"
+        );
+    }
+
+    #[test]
+    fn head_resource_hints() {
+        let mut wr = HTMLWriter::new();
+        wr.preload("/f.woff2", "font");
+        wr.preload("/a.js", "script");
+        wr.preconnect_cors("https://fonts.gstatic.com");
+        assert_eq!(
+            wr.content,
+            "<link rel=\"preload\" href=\"/f.woff2\" as=\"font\" crossorigin=\"anonymous\">\
+             <link rel=\"preload\" href=\"/a.js\" as=\"script\">\
+             <link rel=\"preconnect\" href=\"https://fonts.gstatic.com\" crossorigin=\"anonymous\">"
+        );
+    }
+
+    #[test]
+    fn head_void_attributes() {
+        let mut wr = HTMLWriter::new();
+        wr.add_void(
+            "meta",
+            &[("http-equiv", "refresh"), ("content", "0; url=\"/\"")],
+        );
+        assert_eq!(
+            wr.content,
+            "<meta http-equiv=\"refresh\" content=\"0; url=&quot;/&quot;\">"
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! The validation of HTML5 by the HTMLWriter.
+//!
+//! The [`ValidationLevel`] decides, how much of HTML the writer checks. By default only the notation of the names
+//! and the void elements are checked, the strict mode adds the basics of the content-model. ```Html5``` rejects
+//! in addition the elements, which HTML5 doesn't know, attributes, which a void element doesn't take, and block
+//! content inside of phrasing elements, e.g. a 'div' inside of a 'span'. Elements inside of inline 'svg' and
+//! 'math' aren't checked, neither are attributes of elements with content, which may take custom ones.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, ValidationLevel};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_validation_level(ValidationLevel::Html5);
+//! wr.open_tag("span");
+//! assert!(wr.try_open_tag("blink").is_err());
+//! assert!(wr.try_open_tag("div").is_err());
+//! wr.single_tag("img");
+//! wr.add_property("alt", "logo");
+//! assert!(wr.try_add_property("href", "/").is_err());
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<span><img alt=\"logo\"></span>");
+//! ```
+
+use crate::html_rules::{BLOCK_ELEMENTS, VOID_ELEMENTS};
+use crate::tag::HTML5_ELEMENTS;
+use crate::{HTMLWriter, WriterError};
+
+/// How much of HTML the HTMLWriter validates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidationLevel {
+    /// The notation of the names and the void elements
+    #[default]
+    Basic,
+    /// The basics of the content-model in addition, see ```set_strict()```
+    Strict,
+    /// The elements and the attributes of void elements of HTML5 in addition, and no block content inside of
+    /// phrasing elements
+    Html5,
+}
+
+// Elements, whose content is phrasing content only, so they can't take block elements
+const PHRASING_ELEMENTS: [&str; 29] = [
+    "abbr", "b", "bdi", "bdo", "cite", "code", "data", "dfn", "em", "h1", "h2", "h3", "h4", "h5",
+    "h6", "i", "kbd", "label", "mark", "pre", "q", "s", "samp", "small", "span", "strong", "sub",
+    "sup", "time",
+];
+
+// The attributes of all elements, besides the ones with the prefixes "data-", "aria-" and "on"
+const GLOBAL_ATTRIBUTES: [&str; 29] = [
+    "accesskey",
+    "autocapitalize",
+    "autofocus",
+    "class",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "hidden",
+    "id",
+    "inert",
+    "inputmode",
+    "is",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "lang",
+    "nonce",
+    "part",
+    "popover",
+    "role",
+    "slot",
+    "spellcheck",
+    "style",
+    "tabindex",
+    "title",
+    "translate",
+];
+
+// Returns the attributes of the void element
+fn void_attributes(tag: &str) -> &'static [&'static str] {
+    match tag {
+        "area" => &[
+            "alt",
+            "coords",
+            "download",
+            "href",
+            "hreflang",
+            "ping",
+            "referrerpolicy",
+            "rel",
+            "shape",
+            "target",
+        ],
+        "base" => &["href", "target"],
+        "col" => &["span"],
+        "embed" => &["height", "src", "type", "width"],
+        "img" => &[
+            "alt",
+            "crossorigin",
+            "decoding",
+            "fetchpriority",
+            "height",
+            "ismap",
+            "loading",
+            "referrerpolicy",
+            "sizes",
+            "src",
+            "srcset",
+            "usemap",
+            "width",
+        ],
+        "input" => &[
+            "accept",
+            "alt",
+            "autocomplete",
+            "checked",
+            "dirname",
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "height",
+            "list",
+            "max",
+            "maxlength",
+            "min",
+            "minlength",
+            "multiple",
+            "name",
+            "pattern",
+            "placeholder",
+            "popovertarget",
+            "popovertargetaction",
+            "readonly",
+            "required",
+            "size",
+            "src",
+            "step",
+            "type",
+            "value",
+            "width",
+        ],
+        "link" => &[
+            "as",
+            "blocking",
+            "color",
+            "crossorigin",
+            "disabled",
+            "fetchpriority",
+            "href",
+            "hreflang",
+            "imagesizes",
+            "imagesrcset",
+            "integrity",
+            "media",
+            "referrerpolicy",
+            "rel",
+            "sizes",
+            "type",
+        ],
+        // "property" of RDFa, e.g. for Open Graph
+        "meta" => &[
+            "charset",
+            "content",
+            "http-equiv",
+            "media",
+            "name",
+            "property",
+        ],
+        "source" => &["height", "media", "sizes", "src", "srcset", "type", "width"],
+        "track" => &["default", "kind", "label", "src", "srclang"],
+        _ => &[],
+    }
+}
+
+// Checks that HTML5 knows the element and that it may be placed inside of the open elements
+pub(crate) fn check_element(tag: &str, block_stack: &[String]) -> Result<(), WriterError> {
+    if block_stack
+        .iter()
+        .any(|open| open == "svg" || open == "math")
+    {
+        return Ok(());
+    }
+    if !HTML5_ELEMENTS.contains(&tag) {
+        return Err(WriterError::UnknownElement(tag.to_string()));
+    }
+    if BLOCK_ELEMENTS.contains(&tag)
+        && block_stack
+            .iter()
+            .any(|open| PHRASING_ELEMENTS.contains(&open.as_str()))
+    {
+        return Err(WriterError::InvalidNesting {
+            tag: tag.to_string(),
+            open_path: block_stack.join(" > "),
+            reason: "block elements are not allowed inside of phrasing elements, e.g. a 'span'",
+        });
+    }
+    Ok(())
+}
+
+// Checks that the void element takes the attribute
+fn check_void_attribute(tag: &str, name: &str) -> Result<(), WriterError> {
+    if GLOBAL_ATTRIBUTES.contains(&name)
+        || ["data-", "aria-", "on"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        || void_attributes(tag).contains(&name)
+    {
+        Ok(())
+    } else {
+        Err(WriterError::InvalidAttribute {
+            tag: tag.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl HTMLWriter {
+    /// Sets how much of HTML is validated, see [`ValidationLevel`]
+    pub fn set_validation_level(&mut self, validation: ValidationLevel) {
+        self.validation = validation;
+    }
+
+    // Checks the attribute of the last start-tag, if it is a void element
+    pub(crate) fn check_attribute(&self, name: &str) -> Result<(), WriterError> {
+        if self.validation < ValidationLevel::Html5
+            || self.core.state != crate::DocumentState::InStartTag
+        {
+            return Ok(());
+        }
+        let tag_start = self.content.rfind('<').map_or(0, |i| i + 1);
+        let tag = self.content[tag_start..]
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default();
+        if VOID_ELEMENTS.contains(&tag) {
+            check_void_attribute(tag, name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MLLWriter, Properties};
+
+    #[test]
+    #[should_panic(expected = "the attribute 'src' isn't allowed on the void element 'br'")]
+    fn html5_void_attributes() {
+        let mut wr = HTMLWriter::new();
+        wr.set_validation_level(ValidationLevel::Html5);
+        wr.open_tag("svg");
+        wr.open_tag("circle");
+        wr.close_tag();
+        wr.close_tag();
+        wr.single_tag("br");
+        wr.add_properties(&Properties::new("data-x", "1"));
+        wr.add_property("src", "a.png");
+    }
+}
@@ -0,0 +1,152 @@
+//! Content-model basics of HTML, which are checked by the HTMLWriter in strict mode.
+//!
+//! Browsers don't reject invalid nestings, they silently rearrange the DOM instead (e.g. a 'div' inside
+//! of a 'p' closes the paragraph). In strict mode the HTMLWriter reports those nestings as errors, together
+//! with the path of the currently open elements.
+//!
+//! Optionally the HTMLWriter emulates the forgiveness of the HTML-parser instead: opening e.g. a 'p' while
+//! another 'p' is open closes the previous one implicitly.
+
+use crate::WriterError;
+
+/// Elements which implicitly close an open 'p', so they can't be nested inside of a paragraph
+pub(crate) const BLOCK_ELEMENTS: [&str; 30] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// The void elements of HTML, which have no content and no end-tag
+pub(crate) const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const LIST_ELEMENTS: [&str; 3] = ["ul", "ol", "menu"];
+
+const TABLE_SECTIONS: [&str; 4] = ["table", "thead", "tbody", "tfoot"];
+
+// Checks that the element can be opened, i.e. isn't a void element
+pub(crate) fn check_not_void(tag: &str) -> Result<(), WriterError> {
+    if VOID_ELEMENTS.contains(&tag) {
+        Err(WriterError::VoidElement(tag.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+fn invalid(tag: &str, block_stack: &[String], reason: &'static str) -> WriterError {
+    WriterError::InvalidNesting {
+        tag: tag.to_string(),
+        open_path: block_stack.join(" > "),
+        reason,
+    }
+}
+
+/// Checks if the element given by tag may be placed inside of the currently open elements.
+/// ```body_cnt``` is the number of 'body'-elements written so far.
+pub(crate) fn check_nesting(
+    tag: &str,
+    block_stack: &[String],
+    body_cnt: usize,
+) -> Result<(), WriterError> {
+    let parent = block_stack.last().map(|s| s.as_str());
+    if BLOCK_ELEMENTS.contains(&tag) && block_stack.iter().any(|t| t == "p") {
+        return Err(invalid(
+            tag,
+            block_stack,
+            "block elements are not allowed inside of a 'p'",
+        ));
+    }
+    if tag == "li" && !parent.is_some_and(|p| LIST_ELEMENTS.contains(&p)) {
+        return Err(invalid(
+            tag,
+            block_stack,
+            "'li' is only allowed inside of 'ul', 'ol' or 'menu'",
+        ));
+    }
+    if tag == "tr" && !parent.is_some_and(|p| TABLE_SECTIONS.contains(&p)) {
+        return Err(invalid(
+            tag,
+            block_stack,
+            "'tr' is only allowed inside of 'table', 'thead', 'tbody' or 'tfoot'",
+        ));
+    }
+    if tag == "body" && body_cnt > 0 {
+        return Err(invalid(
+            tag,
+            block_stack,
+            "a document can only have one 'body'",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the whole document when it is ended: a document with an 'html'-element needs exactly one 'body'
+pub(crate) fn check_document(html_cnt: usize, body_cnt: usize) -> Result<(), WriterError> {
+    if html_cnt > 0 && body_cnt == 0 {
+        return Err(invalid("html", &[], "a document needs exactly one 'body'"));
+    }
+    Ok(())
+}
+
+/// Returns the number of open elements, which have to be closed before the element given by tag can be
+/// opened, like the HTML-parser does implicitly. The search for an element to be closed stops at the
+/// boundaries of its scope, e.g. an 'li' of an outer list is not closed by an 'li' of a nested list.
+pub(crate) fn implicit_closes(tag: &str, block_stack: &[String]) -> usize {
+    let (closes, boundaries): (&[&str], &[&str]) = match tag {
+        "li" => (&["li"], &["ul", "ol", "menu"]),
+        "dd" | "dt" => (&["dd", "dt"], &["dl"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "tr" => (&["tr"], &["table", "thead", "tbody", "tfoot"]),
+        _ => (&[], &[]),
+    };
+    let item = closes_in_scope(block_stack, closes, boundaries);
+    // An open 'p' is closed by block elements and by the items of lists
+    let paragraph = if BLOCK_ELEMENTS.contains(&tag) || matches!(tag, "li" | "dd" | "dt") {
+        closes_in_scope(block_stack, &["p"], &["button", "table", "td", "th", "li"])
+    } else {
+        0
+    };
+    item.max(paragraph)
+}
+
+// Returns the number of open elements up to the last one of closes, the search stops at the boundaries
+fn closes_in_scope(block_stack: &[String], closes: &[&str], boundaries: &[&str]) -> usize {
+    for (i, open) in block_stack.iter().rev().enumerate() {
+        if closes.contains(&open.as_str()) {
+            return i + 1;
+        }
+        if boundaries.contains(&open.as_str()) {
+            break;
+        }
+    }
+    0
+}
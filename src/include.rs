@@ -0,0 +1,265 @@
+//! Include-files for XML (XInclude) and HTML (server-side includes).
+//!
+//! The XMLWriter writes ```<xi:include>```-elements and declares the XInclude-namespace on its own, if no open
+//! element declared it before. The HTMLWriter writes ```<!--#include virtual="..." -->```-comments for server-side
+//! includes. Optionally both writers can expand the includes locally: the referenced files are inlined into the
+//! content and re-indented to the indent of the include.
+
+use std::path::{Path, PathBuf};
+
+use crate::escape::unescape;
+use crate::tag::xml_name;
+use crate::{
+    check, escape_attr, escape_html_text, push_property, HTMLWriter, MLLWriter, VoidStyle,
+    WriterError, XMLWriter,
+};
+
+/// The namespace of XInclude
+pub const XINCLUDE_NAMESPACE: &str = "http://www.w3.org/2001/XInclude";
+
+/// How the resource of an ```<xi:include>``` is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XIncludeParse {
+    /// The resource is included as XML (default of XInclude)
+    Xml,
+    /// The resource is included as text
+    Text,
+}
+
+// Maximum depth of nested includes, to detect cycles
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+impl XMLWriter {
+    /// Adds a namespace declaration ```xmlns:prefix="uri"``` to the last written start-tag. It is known to the
+    /// writer until the element gets closed.
+    pub fn add_namespace(&mut self, prefix: &str, uri: &str) {
+        check(self.core.begin_property("add_namespace"));
+        let name = format!("xmlns:{}", prefix);
+        push_property(&mut self.core, &mut self.content, &name, &escape_attr(uri));
+        self.namespaces
+            .push((prefix.to_string(), self.core.block_stack.len()));
+    }
+
+    /// Returns true if an open element declared the namespace-prefix
+    pub fn has_namespace(&self, prefix: &str) -> bool {
+        self.namespaces.iter().any(|(p, _)| p == prefix)
+    }
+
+    /// Writes an ```<xi:include href="..."/>```-element. If the XInclude-namespace isn't declared by an open element,
+    /// it is declared on the include itself.
+    pub fn xinclude(&mut self, href: &str, parse: XIncludeParse) {
+        // The include is read by an XML-parser, so it is self-closing in any void style
+        let void_style = std::mem::replace(&mut self.void_style, VoidStyle::Xml);
+        self.single_element(&xml_name("xi:include"));
+        self.void_style = void_style;
+        self.add_property("href", href);
+        if parse == XIncludeParse::Text {
+            self.add_property("parse", "text");
+        }
+        if !self.has_namespace("xi") {
+            self.add_property("xmlns:xi", XINCLUDE_NAMESPACE);
+        }
+        // No properties can be added to the include
+        self.core.settle();
+    }
+
+    /// Inlines all ```<xi:include>```-elements of the content with the referenced files. Relative references are
+    /// resolved against base_dir, the included files are expanded recursively.
+    pub fn expand_includes(&mut self, base_dir: &Path) -> Result<(), WriterError> {
+        self.content = expand(&self.content, base_dir, &XINCLUDE, 0)?;
+        self.core.thaw();
+        Ok(())
+    }
+}
+
+impl HTMLWriter {
+    /// Writes a server-side include, e.g. ```<!--#include virtual="/footer.html" -->```. The virtual path must
+    /// not contain '"' or "--", which would end the directive early.
+    pub fn ssi_include(&mut self, virtual_path: &str) {
+        check(self.try_ssi_include(virtual_path));
+    }
+
+    /// Like ```ssi_include()```, but returns the error instead of panicking, e.g. ```IncludeFailed``` for a
+    /// virtual path with '"' or "--"
+    pub fn try_ssi_include(&mut self, virtual_path: &str) -> Result<(), WriterError> {
+        if virtual_path.contains('"') || virtual_path.contains("--") {
+            return Err(WriterError::IncludeFailed {
+                href: virtual_path.to_string(),
+                message: "the virtual path must not contain '\"' or \"--\"".to_string(),
+            });
+        }
+        self.core.begin_markup("ssi_include")?;
+        self.core.layout_node(&mut self.content);
+        self.content.push_str("<!--#include virtual=\"");
+        self.content.push_str(virtual_path);
+        self.content.push_str("\" -->");
+        Ok(())
+    }
+
+    /// Inlines all server-side includes of the content with the referenced files. Paths are resolved against
+    /// base_dir (absolute virtual paths as well), the included files are expanded recursively.
+    pub fn expand_includes(&mut self, base_dir: &Path) -> Result<(), WriterError> {
+        self.content = expand(&self.content, base_dir, &SSI, 0)?;
+        self.core.thaw();
+        Ok(())
+    }
+}
+
+// The syntax of an include: its start, the name of the attribute holding the reference and its end
+struct IncludeSyntax {
+    start: &'static str,
+    attribute: &'static str,
+    end: &'static str,
+}
+
+const XINCLUDE: IncludeSyntax = IncludeSyntax {
+    start: "<xi:include ",
+    attribute: "href",
+    end: "/>",
+};
+
+const SSI: IncludeSyntax = IncludeSyntax {
+    start: "<!--#include ",
+    attribute: "virtual",
+    end: "-->",
+};
+
+fn include_error(href: &str, message: String) -> WriterError {
+    WriterError::IncludeFailed {
+        href: href.to_string(),
+        message,
+    }
+}
+
+// Replaces all includes in content by the referenced files
+fn expand(
+    content: &str,
+    base_dir: &Path,
+    syntax: &IncludeSyntax,
+    depth: usize,
+) -> Result<String, WriterError> {
+    let mut expanded = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(syntax.start) {
+        let len = rest[start..]
+            .find(syntax.end)
+            .map(|i| i + syntax.end.len())
+            .ok_or_else(|| include_error("", "the include is not terminated".to_string()))?;
+        let include = &rest[start..start + len];
+        let href = attribute_value(include, syntax.attribute)
+            .ok_or_else(|| include_error("", format!("'{}' has no reference", include)))?;
+        if depth >= MAX_INCLUDE_DEPTH {
+            return Err(include_error(
+                &href,
+                "the includes are nested too deep".to_string(),
+            ));
+        }
+        let path: PathBuf = base_dir.join(href.trim_start_matches('/'));
+        let file =
+            std::fs::read_to_string(&path).map_err(|e| include_error(&href, e.to_string()))?;
+        let dir = path.parent().unwrap_or(base_dir);
+        let mut inlined = if attribute_value(include, "parse").as_deref() == Some("text") {
+            escape_html_text(&file)
+        } else {
+            expand(strip_declaration(&file), dir, syntax, depth + 1)?
+        };
+        inlined.truncate(inlined.trim_end().len());
+        // Re-indent the inlined lines to the indent of the include
+        expanded.push_str(&rest[..start]);
+        let line_start = expanded.rfind('\n').map_or(0, |i| i + 1);
+        let indent = if expanded[line_start..].trim().is_empty() {
+            expanded[line_start..].to_string()
+        } else {
+            String::new()
+        };
+        expanded.push_str(&inlined.replace('\n', &("\n".to_string() + &indent)));
+        rest = &rest[start + len..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+// Returns the unescaped value of the attribute of the include, which is quoted by double or single quotes
+fn attribute_value(include: &str, name: &str) -> Option<String> {
+    let start = include.find(&format!(" {}=", name))? + name.len() + 2;
+    let quote = include[start..]
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\''))?;
+    include[start + 1..].split(quote).next().map(unescape)
+}
+
+// Removes the XML-declaration of an included file
+fn strip_declaration(file: &str) -> &str {
+    let file = file.trim_start_matches('\u{feff}');
+    if file.starts_with("<?xml") {
+        file.find("?>").map_or(file, |i| file[i + 2..].trim_start())
+    } else {
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn include_xinclude_namespace() {
+        let mut wr = XMLWriter::new();
+        wr.xinclude("a.xml", XIncludeParse::Xml);
+        assert_eq!(
+            wr.content,
+            "<xi:include href=\"a.xml\" xmlns:xi=\"http://www.w3.org/2001/XInclude\"/>"
+        );
+
+        wr.clear();
+        wr.open_tag("doc");
+        wr.add_namespace("xi", XINCLUDE_NAMESPACE);
+        wr.xinclude("notes.txt", XIncludeParse::Text);
+        wr.close_tag();
+        assert!(!wr.has_namespace("xi"));
+        assert_eq!(
+            wr.content,
+            "<doc xmlns:xi=\"http://www.w3.org/2001/XInclude\"><xi:include href=\"notes.txt\" parse=\"text\"/></doc>"
+        );
+    }
+
+    #[test]
+    fn include_expansion() {
+        let dir = std::env::temp_dir().join(format!(
+            "mllwriter-include-expansion-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("part.xml"),
+            "<?xml version=\"1.0\"?>\n<part>\n  <a/>\n</part>\n",
+        )
+        .unwrap();
+
+        let mut wr = XMLWriter::new();
+        wr.open_tag("doc");
+        wr.add_namespace("xi", XINCLUDE_NAMESPACE);
+        wr.line_feed_inc();
+        wr.xinclude("part.xml", XIncludeParse::Xml);
+        wr.line_feed_dec();
+        wr.close_tag();
+        wr.expand_includes(&dir).unwrap();
+        assert_eq!(
+            wr.content,
+            "<doc xmlns:xi=\"http://www.w3.org/2001/XInclude\">\n  <part>\n    <a/>\n  </part>\n</doc>"
+        );
+
+        let mut wr = HTMLWriter::new();
+        assert!(wr.try_ssi_include("/a\" -->").is_err());
+        assert!(wr.try_ssi_include("/a--b.html").is_err());
+        assert!(wr.content.is_empty());
+        wr.ssi_include("/missing.html");
+        assert!(matches!(
+            wr.expand_includes(&dir),
+            Err(WriterError::IncludeFailed { .. })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+//! The characters of the indent.
+//!
+//! The writers indent by spaces, the number per indent-step is set by ```wr.set_indent_step_size()```.
+//! ```wr.core.set_indent_char(IndentChar::Tabs)``` indents by one tab per step instead, e.g. for style guides with
+//! tabs, also the automatic indents of the auto-layout. YAML doesn't allow tabs in its indent, so the YAMLWriter
+//! keeps indenting its entries by spaces.
+//!
+//! ```
+//! # use mllwriter::{IndentChar, MLLWriter, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! wr.core.set_indent_char(IndentChar::Tabs);
+//! wr.open_tag("a");
+//! wr.line_feed_inc();
+//! wr.single_tag("b");
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<a>\n\t<b>\n</a>");
+//! ```
+
+use crate::WriterCore;
+
+/// The characters of an indent-step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum IndentChar {
+    /// The number of spaces
+    Spaces(usize),
+    /// One tab
+    Tabs,
+}
+
+impl WriterCore {
+    /// Sets the characters of an indent-step, the current indent keeps its depth
+    pub fn set_indent_char(&mut self, indent_char: IndentChar) {
+        let depth = self.indent_depth();
+        match indent_char {
+            IndentChar::Spaces(size) => {
+                self.indent_tabs = false;
+                self.indent_step_size = size;
+            }
+            IndentChar::Tabs => self.indent_tabs = true,
+        }
+        self.set_indent_step(depth);
+    }
+
+    /// Returns the characters of an indent-step
+    pub fn indent_char(&self) -> IndentChar {
+        if self.indent_tabs {
+            IndentChar::Tabs
+        } else {
+            IndentChar::Spaces(self.indent_step_size)
+        }
+    }
+
+    // The indent of one step
+    pub(crate) fn indent_unit(&self) -> String {
+        if self.indent_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.indent_step_size)
+        }
+    }
+
+    // The number of steps of the current indent
+    fn indent_depth(&self) -> usize {
+        match self.indent_unit().len() {
+            0 => 0,
+            step => self.indent.len() / step,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JSONWriter, MLLWriter, YAMLWriter};
+
+    #[test]
+    fn tabs_keep_the_depth() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.open_tag("a");
+        wr.core.set_indent_char(IndentChar::Tabs);
+        wr.add_property("b", "1");
+        wr.core.set_indent_char(IndentChar::Spaces(1));
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(wr.content, "{\n  \"a\":\n  {\n\t\t\"b\": 1\n }\n}");
+        let mut wr = YAMLWriter::new();
+        wr.core.set_indent_char(IndentChar::Tabs);
+        wr.open_tag("");
+        wr.open_tag("a");
+        wr.add_property("b", "1");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(wr.content, "a:\n  b: 1");
+    }
+}
@@ -0,0 +1,86 @@
+//! The index of a document with the places of its terms.
+//!
+//! ```wr.index_term()``` writes an anchor at the current place and collects the term. ```wr.render_index()```
+//! writes the alphabetized index, where each term links to its places, e.g. at the end of the document.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("p");
+//! wr.index_term("Writer");
+//! wr.text("A writer writes.");
+//! wr.index_term("Escaping");
+//! wr.close_tag();
+//! wr.render_index();
+//! assert_eq!(
+//!     wr.content,
+//!     "<p><a id=\"index-1\"></a>A writer writes.<a id=\"index-2\"></a></p><ul class=\"index\">\
+//!      <li>Escaping <a href=\"#index-2\">1</a></li><li>Writer <a href=\"#index-1\">1</a></li></ul>"
+//! );
+//! ```
+
+use crate::{HTMLWriter, MLLWriter};
+
+// The terms with the numbers of their anchors, in the order of their first place
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Index {
+    anchors: usize,
+    terms: Vec<(String, Vec<usize>)>,
+}
+
+impl HTMLWriter {
+    /// Writes an anchor of the term at the current place and collects it for the index
+    pub fn index_term(&mut self, term: &str) {
+        self.index.anchors += 1;
+        let anchor = self.index.anchors;
+        match self.index.terms.iter_mut().find(|(t, _)| t == term) {
+            Some((_, anchors)) => anchors.push(anchor),
+            None => self.index.terms.push((term.to_string(), vec![anchor])),
+        }
+        self.open_tag_w_property("a", "id", &format!("index-{}", anchor));
+        self.close_tag();
+    }
+
+    /// Writes the index of the collected terms, sorted case-insensitive, each with numbered links to its places.
+    /// Writes nothing, if there are no terms.
+    pub fn render_index(&mut self) {
+        let mut terms = self.index.terms.clone();
+        if terms.is_empty() {
+            return;
+        }
+        terms.sort_by(|(a, _), (b, _)| (a.to_lowercase(), a).cmp(&(b.to_lowercase(), b)));
+        self.open_tag_w_property("ul", "class", "index");
+        for (term, anchors) in &terms {
+            self.open_tag("li");
+            self.text(term);
+            for (i, anchor) in anchors.iter().enumerate() {
+                self.text(if i == 0 { " " } else { ", " });
+                self.open_tag_w_property("a", "href", &format!("#index-{}", anchor));
+                self.text(&(i + 1).to_string());
+                self.close_tag();
+            }
+            self.close_tag();
+        }
+        self.close_tag();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_sorted_with_places() {
+        let mut wr = HTMLWriter::new();
+        wr.index_term("beta");
+        wr.index_term("Alpha");
+        wr.index_term("beta");
+        wr.content.clear();
+        wr.render_index();
+        assert_eq!(
+            wr.content,
+            "<ul class=\"index\"><li>Alpha <a href=\"#index-2\">1</a></li>\
+             <li>beta <a href=\"#index-1\">1</a>, <a href=\"#index-3\">2</a></li></ul>"
+        );
+    }
+}
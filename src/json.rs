@@ -0,0 +1,389 @@
+//! A small JSON-parser for the helpers, which read JSON-documents, e.g. the JSON-viewer.
+//!
+//! The numbers are kept as text, so they are written back exactly as they were read. Comments ('//' and
+//! '/* */') are skipped like whitespace in the dialects with comments, JSONC and JSON5, plain JSON doesn't allow
+//! them. Other syntax of JSON5 isn't read.
+
+use std::ops::Range;
+
+use crate::{JsonDialect, WriterError};
+
+// A parsed JSON-value, the members of objects keep their order
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// Parses a complete JSON-document of the dialect
+pub(crate) fn parse(text: &str, dialect: JsonDialect) -> Result<Json, WriterError> {
+    let mut parser = Parser::new(text, dialect, None);
+    parser.document()
+}
+
+// Returns the byte-range of the value at the path of member-names and array-indices in the JSON-document of the
+// dialect, or None if there is no value at the path
+pub(crate) fn locate(
+    text: &str,
+    dialect: JsonDialect,
+    path: &[String],
+) -> Result<Option<Range<usize>>, WriterError> {
+    let mut parser = Parser::new(text, dialect, Some(path));
+    parser.document()?;
+    Ok(parser.found)
+}
+
+// Returns the reference-tokens of a JSON Pointer (RFC 6901), e.g. "/a~1b/0" as ["a/b", "0"]
+pub(crate) fn pointer_tokens(pointer: &str) -> Result<Vec<String>, WriterError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        return Err(WriterError::InvalidPointer(pointer.to_string()));
+    };
+    tokens
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::new();
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(WriterError::InvalidPointer(pointer.to_string())),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+// Maximal nesting of arrays and objects
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+    // comments are skipped like whitespace
+    comments: bool,
+    // the path of the current value and the path of the value to be located
+    path: Vec<String>,
+    target: Option<&'a [String]>,
+    found: Option<Range<usize>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str, dialect: JsonDialect, target: Option<&'a [String]>) -> Parser<'a> {
+        Parser {
+            text: text.as_bytes(),
+            pos: 0,
+            comments: dialect != JsonDialect::Json,
+            path: Vec::new(),
+            target,
+            found: None,
+        }
+    }
+
+    fn document(&mut self) -> Result<Json, WriterError> {
+        let value = self.value(0)?;
+        self.skip_whitespace();
+        if self.pos < self.text.len() {
+            return Err(self.error("unexpected content after the value"));
+        }
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> WriterError {
+        WriterError::InvalidJson(format!("{} at byte {}", message, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            while self.pos < self.text.len() && b" \t\r\n".contains(&self.text[self.pos]) {
+                self.pos += 1;
+            }
+            let rest = &self.text[self.pos..];
+            if !self.comments {
+                return;
+            } else if rest.starts_with(b"//") {
+                self.pos += rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+            } else if rest.starts_with(b"/*") {
+                self.pos += rest
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(rest.len(), |p| p + 2);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), WriterError> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, WriterError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = self.plain_value(depth)?;
+        if self.target == Some(self.path.as_slice()) {
+            self.found = Some(start..self.pos);
+        }
+        Ok(value)
+    }
+
+    fn plain_value(&mut self, depth: usize) -> Result<Json, WriterError> {
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, WriterError> {
+        if self.text[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Json, WriterError> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a member name"));
+            }
+            let name = self.string()?;
+            self.expect(b':')?;
+            self.path.push(name.clone());
+            let value = self.value(depth + 1)?;
+            self.path.pop();
+            members.push((name, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Json, WriterError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            self.path.push(items.len().to_string());
+            let value = self.value(depth + 1)?;
+            self.path.pop();
+            items.push(value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, WriterError> {
+        let start = self.pos;
+        let digits = |p: &mut Parser| {
+            let from = p.pos;
+            while p.peek().is_some_and(|b| b.is_ascii_digit()) {
+                p.pos += 1;
+            }
+            p.pos > from
+        };
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        if self.peek() == Some(b'0') {
+            self.pos += 1;
+        } else if !digits(self) {
+            return Err(self.error("invalid number"));
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !digits(self) {
+                return Err(self.error("invalid fraction"));
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                return Err(self.error("invalid exponent"));
+            }
+        }
+        let number = std::str::from_utf8(&self.text[start..self.pos]).expect("digits are ASCII");
+        Ok(Json::Number(number.to_string()))
+    }
+
+    fn string(&mut self) -> Result<String, WriterError> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let c = self.unicode_escape()?;
+                            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.pos += 1;
+                    bytes.push(escaped as u8);
+                }
+                Some(b) if b < 0x20 => return Err(self.error("control character in string")),
+                Some(b) => {
+                    bytes.push(b);
+                    self.pos += 1;
+                }
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    // Reads the 4 hex-digits after "\u", and the low surrogate of a pair
+    fn unicode_escape(&mut self) -> Result<char, WriterError> {
+        let high = self.hex4()?;
+        if (0xD800..0xDC00).contains(&high) {
+            if !self.text[self.pos..].starts_with(b"\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let c = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            return char::from_u32(c).ok_or_else(|| self.error("invalid unicode escape"));
+        }
+        char::from_u32(high).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, WriterError> {
+        let hex = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u32::from_str_radix(h, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_parse() {
+        let json = parse(
+            r#" {"a": [1, -2.5e3, true, null], "b\n": "ä😀"} "#,
+            JsonDialect::Json,
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number("1".to_string()),
+                        Json::Number("-2.5e3".to_string()),
+                        Json::Bool(true),
+                        Json::Null
+                    ])
+                ),
+                ("b\n".to_string(), Json::String("ä😀".to_string()))
+            ])
+        );
+        assert!(parse("[1,]", JsonDialect::Json).is_err());
+        assert!(parse("01", JsonDialect::Json).is_err());
+        assert!(parse("{} x", JsonDialect::Json).is_err());
+        assert!(parse("/* c */ {}", JsonDialect::Json).is_err());
+        assert!(parse("/* c */ {} // d", JsonDialect::Jsonc).is_ok());
+    }
+
+    #[test]
+    fn json_locate() {
+        let text = "// banner\n{\"a\": [1, {\"b/c\": true}]}";
+        let path = pointer_tokens("/a/1/b~1c").unwrap();
+        assert_eq!(path, ["a", "1", "b/c"]);
+        assert_eq!(
+            locate(text, JsonDialect::Jsonc, &path).unwrap(),
+            Some(28..32)
+        );
+        let path = pointer_tokens("/x").unwrap();
+        assert_eq!(locate(text, JsonDialect::Jsonc, &path).unwrap(), None);
+        assert!(pointer_tokens("a").is_err());
+    }
+}
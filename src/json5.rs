@@ -0,0 +1,170 @@
+//! The dialects JSONC and JSON5 of the JSONWriter, e.g. for config-files.
+//!
+//! ```wr.set_dialect()``` switches from plain JSON to a dialect. Both dialects write ```add_comment()``` as real
+//! comments instead of '_comment'-properties: '//'-comments on their own line in front of the next member, or
+//! '/* */'-comments if they span lines or the writer is minified. JSON5 also writes trailing commas, property names
+//! which are identifiers without quotes, and the strings of the writer, e.g. of ```text()```, in single quotes if
+//! the [`QuoteStyle`] is single. Rewrites of the content, e.g. ```edit()```, expect plain JSON.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, JsonDialect, MLLWriter, QuoteStyle};
+//! let mut wr = JSONWriter::new();
+//! wr.set_dialect(JsonDialect::Json5);
+//! wr.core.set_quote_style(QuoteStyle::Single);
+//! wr.open_tag("");
+//! wr.add_comment("the port of the server");
+//! wr.add_property("port", "8080");
+//! wr.open_array("hosts");
+//! wr.text("it's local");
+//! wr.close_tag();
+//! wr.add_property("user-name", "null");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "{\n  // the port of the server\n  port: 8080,\n  hosts:\n  [\n    'it\\'s local',\n  ],\n  \
+//!      'user-name': null,\n}"
+//! );
+//! ```
+
+use crate::escape::escape_json_string;
+use crate::{JSONWriter, QuoteStyle};
+
+/// The dialect of the content of a JSONWriter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum JsonDialect {
+    /// Plain JSON, comments are '_comment'-properties
+    #[default]
+    Json,
+    /// JSON with comments, e.g. of tsconfig.json
+    Jsonc,
+    /// JSON5 with comments, trailing commas, unquoted property names and single-quoted strings
+    Json5,
+}
+
+impl JSONWriter {
+    /// Sets the dialect of the content, before the first member is written
+    pub fn set_dialect(&mut self, dialect: JsonDialect) {
+        self.dialect = dialect;
+    }
+
+    /// Returns the dialect of the content
+    pub fn dialect(&self) -> JsonDialect {
+        self.dialect
+    }
+
+    // Returns the text as string in the quotes of the dialect
+    pub(crate) fn string(&self, text: &str) -> String {
+        if self.dialect == JsonDialect::Json5 && self.core.quote_style == QuoteStyle::Single {
+            let escaped = escape_json_string(text)
+                .replace("\\\"", "\"")
+                .replace('\'', "\\'");
+            format!("'{}'", self.core.ascii_json(escaped))
+        } else {
+            format!("\"{}\"", self.core.ascii_json(escape_json_string(text)))
+        }
+    }
+
+    // Returns the escaped property name with the quotes, which the dialect needs
+    pub(crate) fn key(&self, name: &str) -> String {
+        if self.dialect == JsonDialect::Json5 && is_identifier(name) {
+            name.to_string()
+        } else if self.dialect == JsonDialect::Json5 {
+            self.string(name)
+        } else {
+            format!("\"{}\"", self.core.ascii_json(escape_json_string(name)))
+        }
+    }
+
+    // Writes the comment, comments inside of a block wait for the place of the next member
+    pub(crate) fn write_comment(&mut self, comment: &str) {
+        let comment = self.comment(comment);
+        if self.core.block_stack.is_empty() {
+            let newline = self.core.newline();
+            if !self.content.is_empty() && !self.content.ends_with('\n') {
+                self.content.push_str(newline);
+            }
+            self.content.push_str(&comment);
+            self.content.push_str(newline);
+        } else {
+            self.comments.push(comment);
+        }
+    }
+
+    // Writes the waiting comments, each is followed by a line-feed
+    pub(crate) fn push_comments(&mut self) {
+        for comment in std::mem::take(&mut self.comments) {
+            self.content.push_str(&comment);
+            self.core.line_feed(&mut self.content, 1);
+        }
+    }
+
+    // Writes the waiting comments in front of the end of the block, and the trailing comma of JSON5
+    pub(crate) fn end_members(&mut self) {
+        let empty = self.content.ends_with('{') || self.content.ends_with('[');
+        if self.dialect == JsonDialect::Json5 && !empty && !self.core.minified {
+            self.content.push(',');
+        }
+        if self.comments.is_empty() {
+            return;
+        }
+        if empty {
+            self.core.line_feed_inc(&mut self.content);
+        } else {
+            self.core.line_feed(&mut self.content, 1);
+        }
+        let comments = std::mem::take(&mut self.comments);
+        for (i, comment) in comments.iter().enumerate() {
+            if i > 0 {
+                self.core.line_feed(&mut self.content, 1);
+            }
+            self.content.push_str(comment);
+        }
+    }
+
+    // A comment, which spans lines or is minified, is a '/* */'-comment
+    fn comment(&self, comment: &str) -> String {
+        if comment.contains('\n') || self.core.minified {
+            format!(
+                "/* {} */",
+                self.core.line_breaks(&comment.replace("*/", "* /"))
+            )
+        } else {
+            format!("// {}", comment)
+        }
+    }
+}
+
+// Returns true if the name is an ECMAScript identifier of ASCII characters
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn jsonc_comments() {
+        let mut wr = JSONWriter::new();
+        wr.set_dialect(JsonDialect::Jsonc);
+        wr.add_comment("config");
+        wr.open_tag("");
+        wr.add_property("a", "1");
+        wr.add_comment("b is\nunused");
+        wr.open_tag("b");
+        wr.add_comment("empty");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "// config\n{\n  \"a\": 1,\n  /* b is\nunused */\n  \"b\":\n  {\n    // empty\n  }\n}"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Typed values of the JSONWriter.
+//!
+//! ```wr.add_value()``` writes a [`JsonValue`] with the correct quoting and escaping, so strings don't have to be
+//! quoted by hand. Arrays and objects are written as nested blocks of the writer. Inside of an array the name is
+//! ignored, and ```wr.add_item_value()``` adds the value as item.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, JsonValue, MLLWriter};
+//! let mut wr = JSONWriter::new();
+//! wr.open_tag("");
+//! wr.add_value("name", "Muster \"Max\"".into());
+//! wr.add_value("age", 42.into());
+//! wr.add_value("tags", JsonValue::Array(vec!["a".into(), JsonValue::Null]));
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "{\n  \"name\": \"Muster \\\"Max\\\"\",\n  \"age\": 42,\n  \"tags\":\n  [\n    \"a\",\n    null\n  ]\n}"
+//! );
+//! ```
+
+use crate::format::json_string;
+use crate::{JSONWriter, MLLWriter};
+
+/// A typed JSON-value, the members of objects keep their order
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    /// Written as ```null```, if it isn't finite, as JSON has no NaN and no infinity
+    Float(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the scalar value as JSON-text, arrays and objects are written by the JSONWriter
+    pub fn to_json_scalar(&self) -> Option<String> {
+        match self {
+            JsonValue::Null => Some("null".to_string()),
+            JsonValue::Bool(b) => Some(b.to_string()),
+            JsonValue::Int(i) => Some(i.to_string()),
+            JsonValue::UInt(u) => Some(u.to_string()),
+            JsonValue::Float(f) if f.is_finite() => Some(f.to_string()),
+            JsonValue::Float(_) => Some("null".to_string()),
+            JsonValue::String(s) => Some(json_string(s)),
+            JsonValue::Array(_) | JsonValue::Object(_) => None,
+        }
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String(value.to_string())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(value: String) -> Self {
+        JsonValue::String(value)
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> Self {
+        JsonValue::Bool(value)
+    }
+}
+
+impl From<i32> for JsonValue {
+    fn from(value: i32) -> Self {
+        JsonValue::Int(value.into())
+    }
+}
+
+impl From<i64> for JsonValue {
+    fn from(value: i64) -> Self {
+        JsonValue::Int(value)
+    }
+}
+
+impl From<u64> for JsonValue {
+    fn from(value: u64) -> Self {
+        JsonValue::UInt(value)
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(value: f64) -> Self {
+        JsonValue::Float(value)
+    }
+}
+
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(JsonValue::Null, Into::into)
+    }
+}
+
+impl<T: Into<JsonValue>> From<Vec<T>> for JsonValue {
+    fn from(value: Vec<T>) -> Self {
+        JsonValue::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl JSONWriter {
+    /// Adds the property with the typed value, inside of an array the value is added as item
+    pub fn add_value(&mut self, name: &str, value: JsonValue) {
+        self.write_value(name, &value);
+    }
+
+    /// Adds the typed value as item to the current array
+    pub fn add_item_value(&mut self, value: JsonValue) {
+        self.write_value("", &value);
+    }
+
+    // Writes the value as property or as item, arrays and objects as nested blocks
+    fn write_value(&mut self, name: &str, value: &JsonValue) {
+        let name = if self.in_array() { "" } else { name };
+        match value {
+            JsonValue::Array(items) => {
+                self.open_array(name);
+                items.iter().for_each(|item| self.write_value("", item));
+                self.close_tag();
+            }
+            JsonValue::Object(members) => {
+                self.open_tag(name);
+                members
+                    .iter()
+                    .for_each(|(member, value)| self.write_value(member, value));
+                self.close_tag();
+            }
+            scalar => {
+                let text = scalar
+                    .to_json_scalar()
+                    .expect("arrays and objects are written as blocks");
+                if self.in_array() {
+                    self.add_item(&text);
+                } else {
+                    self.add_property(name, &text);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_value_nested() {
+        let mut wr = JSONWriter::new();
+        wr.add_value(
+            "",
+            JsonValue::Object(vec![
+                ("n".to_string(), f64::NAN.into()),
+                (
+                    "o".to_string(),
+                    JsonValue::Object(vec![("u".to_string(), u64::MAX.into())]),
+                ),
+                ("l".to_string(), vec![vec![1], vec![]].into()),
+                ("a\"b".to_string(), 1.into()),
+            ]),
+        );
+        let value = crate::json::parse(&wr.content, crate::JsonDialect::Json).unwrap();
+        assert_eq!(
+            value,
+            crate::json::Json::Object(vec![
+                ("n".to_string(), crate::json::Json::Null),
+                (
+                    "o".to_string(),
+                    crate::json::Json::Object(vec![(
+                        "u".to_string(),
+                        crate::json::Json::Number(u64::MAX.to_string())
+                    )])
+                ),
+                (
+                    "l".to_string(),
+                    crate::json::Json::Array(vec![
+                        crate::json::Json::Array(vec![crate::json::Json::Number("1".to_string())]),
+                        crate::json::Json::Array(vec![])
+                    ])
+                ),
+                (
+                    "a\"b".to_string(),
+                    crate::json::Json::Number("1".to_string())
+                ),
+            ])
+        );
+    }
+}
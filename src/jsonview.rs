@@ -0,0 +1,109 @@
+//! A collapsible tree-view of JSON-documents.
+//!
+//! ```HTMLWriter::json_viewer()``` parses a JSON-document and writes it as nested 'details'-elements, so
+//! objects and arrays can be collapsed without any script. Names and values carry the classes "key",
+//! "string", "number", "bool" and "null" for styling.
+//!
+//! ```
+//! # use mllwriter::HTMLWriter;
+//! let mut wr = HTMLWriter::new();
+//! wr.json_viewer(r#"{"id": 7, "tags": ["a"]}"#).unwrap();
+//! assert_eq!(
+//!     wr.content,
+//!     "<div class=\"json-viewer\"><details open=\"open\"><summary>{2}</summary><ul>\
+//!      <li><span class=\"key\">\"id\"</span>: <span class=\"number\">7</span></li>\
+//!      <li><details open=\"open\"><summary><span class=\"key\">\"tags\"</span>: [1]</summary><ul>\
+//!      <li><span class=\"string\">\"a\"</span></li></ul></details></li></ul></details></div>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::format::json_string;
+use crate::json::{parse, Json};
+use crate::{escape_html_text, HTMLWriter, JsonDialect, MLLWriter, WriterError};
+
+impl HTMLWriter {
+    /// Writes the JSON-document as collapsible tree, it fails if the document isn't valid JSON
+    pub fn json_viewer(&mut self, json: &str) -> Result<(), WriterError> {
+        let json = parse(json, JsonDialect::Json)?;
+        self.open_tag_w_property("div", "class", "json-viewer");
+        self.json_node(None, &json);
+        self.close_tag();
+        Ok(())
+    }
+
+    fn json_node(&mut self, key: Option<&str>, json: &Json) {
+        let (open, close, children): (&str, &str, Vec<(Option<&str>, &Json)>) = match json {
+            Json::Array(items) => ("[", "]", items.iter().map(|i| (None, i)).collect()),
+            Json::Object(members) => (
+                "{",
+                "}",
+                members.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+            ),
+            _ => {
+                self.json_key(key);
+                let (class, text) = match json {
+                    Json::Null => ("null", "null".to_string()),
+                    Json::Bool(b) => ("bool", b.to_string()),
+                    Json::Number(n) => ("number", n.clone()),
+                    Json::String(s) => ("string", json_string(s)),
+                    _ => unreachable!("containers are handled above"),
+                };
+                self.json_span(class, &text);
+                return;
+            }
+        };
+        self.open_tag_w_property("details", "open", "open");
+        self.open_tag("summary");
+        self.json_key(key);
+        self.json_text(&format!("{}{}{}", open, children.len(), close));
+        self.close_tag();
+        self.open_tag("ul");
+        for (key, child) in children {
+            self.open_tag("li");
+            self.json_node(key, child);
+            self.close_tag();
+        }
+        self.close_tag();
+        self.close_tag();
+    }
+
+    fn json_key(&mut self, key: Option<&str>) {
+        if let Some(key) = key {
+            self.json_span("key", &json_string(key));
+            self.json_text(": ");
+        }
+    }
+
+    fn json_span(&mut self, class: &str, text: &str) {
+        self.open_tag_w_property("span", "class", class);
+        self.json_text(text);
+        self.close_tag();
+    }
+
+    fn json_text(&mut self, text: &str) {
+        self.write_str(&escape_html_text(text))
+            .expect("text inside of an element is always allowed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_viewer_scalars() {
+        let mut wr = HTMLWriter::new();
+        wr.json_viewer("\"<b>\"").unwrap();
+        assert_eq!(
+            wr.content,
+            "<div class=\"json-viewer\"><span class=\"string\">\"&lt;b&gt;\"</span></div>"
+        );
+        assert!(wr.json_viewer("/*x*/ {}").is_err());
+        assert!(matches!(
+            wr.json_viewer("[1"),
+            Err(WriterError::InvalidJson(_))
+        ));
+    }
+}
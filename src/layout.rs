@@ -0,0 +1,258 @@
+//! The auto-layout of the markup-writers (HTMLWriter and XMLWriter).
+//!
+//! By default the writers don't add any line-feed on their own. With auto-layout enabled, every element and
+//! comment starts on a new line, and the children of an element are indented by one indent-step. The layout
+//! can be tuned per element by [`LayoutRules`]:
+//! - the children of **no-indent** elements are not indented, e.g. 'html' or 'tbody'
+//! - **inline** elements are written without any line-feed, including all of their children, e.g. 'span'
+//!
+//! The [`FormatMode`] of the writer is the policy on top: ```Compact``` writes no line-feeds on its own,
+//! ```Pretty``` enables the auto-layout with the rules of the writer, and ```Custom``` with own rules.
+//! ```Minified``` drops all line-feeds and indents, also the manual ones, and in JSON the spaces after the
+//! colons, so the same code writes readable and small documents.
+//!
+//! ```
+//! # use mllwriter::{FormatMode, HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_indent_step_size(2);
+//! wr.set_format_mode(FormatMode::Pretty);
+//! wr.open_tag("ul");
+//! wr.open_tag("li");
+//! wr.open_tag("em");
+//! wr.text("first");
+//! wr.close_tag();
+//! wr.close_tag();
+//! wr.single_tag("hr");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<ul>\n  <li><em>first</em></li>\n  <hr>\n</ul>");
+//! ```
+
+use crate::profile::HTML_INLINE;
+use crate::{HTMLWriter, JSONWriter, WriterCore, WriterProfile, XMLWriter};
+
+/// The formatting policies of the markup-writers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FormatMode {
+    /// No line-feeds and indents on their own, e.g. for manual ```line_feed_inc()```
+    #[default]
+    Compact,
+    /// Each element on its own line, indented by its depth. HTML keeps phrasing elements inline, e.g. 'em', and
+    /// doesn't indent the children of 'html'.
+    Pretty,
+    /// Like ```Pretty```, but with own rules
+    Custom(LayoutRules),
+    /// No line-feeds and indents at all, also not the manual ones
+    Minified,
+}
+
+impl HTMLWriter {
+    /// Sets the formatting policy
+    pub fn set_format_mode(&mut self, mode: FormatMode) {
+        let mut rules = LayoutRules::default();
+        rules.add_no_indent("html");
+        HTML_INLINE.iter().for_each(|tag| rules.add_inline(tag));
+        self.core.set_format_mode(mode, rules);
+    }
+}
+
+impl JSONWriter {
+    /// Sets the formatting policy, only ```Minified``` differs from the layout of the JSONWriter
+    pub fn set_format_mode(&mut self, mode: FormatMode) {
+        self.core.set_format_mode(mode, LayoutRules::default());
+        self.core.auto_layout = false;
+    }
+}
+
+impl XMLWriter {
+    /// Sets the formatting policy, in ```Pretty``` all elements are on their own lines
+    pub fn set_format_mode(&mut self, mode: FormatMode) {
+        self.core.set_format_mode(mode, LayoutRules::default());
+    }
+}
+
+/// Per-element overrides consulted by the auto-layout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutRules {
+    // elements whose children are not indented
+    pub(crate) no_indent: Vec<String>,
+    // elements which are always written inline
+    pub(crate) inline: Vec<String>,
+}
+
+impl LayoutRules {
+    /// Registers an element, whose children should not be indented
+    pub fn add_no_indent(&mut self, tag: &str) {
+        if !self.is_no_indent(tag) {
+            self.no_indent.push(tag.to_string());
+        }
+    }
+
+    /// Registers an element, which should always be written inline
+    pub fn add_inline(&mut self, tag: &str) {
+        if !self.is_inline(tag) {
+            self.inline.push(tag.to_string());
+        }
+    }
+
+    /// Returns true if the children of the element are not indented
+    pub fn is_no_indent(&self, tag: &str) -> bool {
+        self.no_indent.iter().any(|t| t == tag)
+    }
+
+    /// Returns true if the element is always written inline
+    pub fn is_inline(&self, tag: &str) -> bool {
+        self.inline.iter().any(|t| t == tag)
+    }
+}
+
+// The layout of an open element
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OpenLayout {
+    // the element is written inline (itself or one of its ancestors is an inline element)
+    inline: bool,
+    // the element has got children on their own lines
+    has_children: bool,
+}
+
+impl WriterCore {
+    /// Enables or disables the auto-layout, which replaces manual line-feeds
+    pub fn set_auto_layout(&mut self, auto_layout: bool) {
+        self.auto_layout = auto_layout;
+    }
+
+    // Enables the auto-layout with the rules of the mode, pretty_rules are the ones of the writer
+    fn set_format_mode(&mut self, mode: FormatMode, pretty_rules: LayoutRules) {
+        self.minified = mode == FormatMode::Minified;
+        (self.auto_layout, self.layout_rules) = match mode {
+            FormatMode::Compact | FormatMode::Minified => (false, LayoutRules::default()),
+            FormatMode::Pretty => (true, pretty_rules),
+            FormatMode::Custom(rules) => (true, rules),
+        };
+        // Padded columns would bring back the spaces
+        if self.minified {
+            self.align_properties = false;
+        }
+    }
+
+    /// Gives access to the per-element overrides of the auto-layout
+    pub fn layout_rules(&mut self) -> &mut LayoutRules {
+        &mut self.layout_rules
+    }
+
+    // Takes over the layout-options of a profile
+    pub(crate) fn apply_layout_profile(&mut self, profile: &WriterProfile) {
+        self.auto_layout = profile.auto_layout;
+        self.align_properties = profile.align_properties;
+        profile
+            .no_indent
+            .iter()
+            .for_each(|tag| self.layout_rules.add_no_indent(tag));
+        profile
+            .inline
+            .iter()
+            .for_each(|tag| self.layout_rules.add_inline(tag));
+        if profile.minify {
+            self.set_format_mode(FormatMode::Minified, LayoutRules::default());
+        } else {
+            self.minified = false;
+        }
+    }
+
+    fn parent_inline(&self) -> bool {
+        self.open_layout.last().is_some_and(|l| l.inline)
+    }
+
+    // Starts a new line for a node, which is not inline
+    fn layout_new_line(&mut self, content: &mut String) {
+        if let Some(parent) = self.open_layout.last_mut() {
+            parent.has_children = true;
+        }
+        // The content might have been flushed already, e.g. by a stream
+        if !content.is_empty() || self.flushed {
+            content.push_str(self.newline());
+            content.push_str(&self.indent);
+        }
+    }
+
+    // Marks the current element to have children on their own lines, e.g. when they were written before the
+    // writer-state was restored
+    pub(crate) fn layout_mark_children(&mut self) {
+        if let Some(layout) = self.open_layout.last_mut() {
+            layout.has_children = true;
+        }
+    }
+
+    // Called before a start-tag or single-tag gets written
+    pub(crate) fn layout_open(&mut self, content: &mut String, tag: &str, single: bool) {
+        if !self.auto_layout {
+            return;
+        }
+        let inline = self.parent_inline() || self.layout_rules.is_inline(tag);
+        if !inline {
+            self.layout_new_line(content);
+        }
+        if !single {
+            self.open_layout.push(OpenLayout {
+                inline,
+                has_children: false,
+            });
+            if !inline && !self.layout_rules.is_no_indent(tag) {
+                self.inc_indent_step();
+            }
+        }
+    }
+
+    // Called before a node without children gets written, e.g. a comment
+    pub(crate) fn layout_node(&mut self, content: &mut String) {
+        if self.auto_layout && !self.parent_inline() {
+            self.layout_new_line(content);
+        }
+    }
+
+    // Called before the end-tag of the element given by tag gets written
+    pub(crate) fn layout_close(&mut self, content: &mut String, tag: &str) {
+        if !self.auto_layout {
+            return;
+        }
+        if let Some(layout) = self.open_layout.pop() {
+            if !layout.inline {
+                if !self.layout_rules.is_no_indent(tag) {
+                    self.dec_indent_step();
+                }
+                if layout.has_children {
+                    content.push_str(self.newline());
+                    content.push_str(&self.indent);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn minified() {
+        let mut wr = JSONWriter::new();
+        wr.set_format_mode(FormatMode::Minified);
+        wr.open_tag("");
+        wr.add_property("a", "1");
+        wr.blank_line();
+        wr.open_array("b");
+        wr.add_item("true");
+        wr.add_item("null");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(wr.content, "{\"a\":1,\"b\":[true,null]}");
+        let mut wr = HTMLWriter::new();
+        wr.set_format_mode(FormatMode::Minified);
+        wr.open_tag("div");
+        wr.line_feed_inc();
+        wr.single_tag("br");
+        wr.line_feed_dec();
+        wr.close_tag();
+        assert_eq!(wr.content, "<div><br></div>");
+    }
+}
@@ -20,6 +20,105 @@
 //!
 //! There are different default indent-step-sizes, e.g. 4 whitespaces in the XMLWriter and HTMLWriter, and 2 for the JSONWriter.
 //!
+//! The [`YAMLWriter`] writes YAML-files, e.g. configs, by the same model as the JSONWriter, with sequences opened by
+//! ```open_list()```. The [`TOMLWriter`] writes tables with their dotted headers, e.g. Cargo-style configs.
+//!
+//! Every writer tracks the state of the document under edit (see [`DocumentState`]). Operations which would lead to
+//! a malformed document, e.g. a second root element in XML or a property after the root element was closed, are
+//! rejected with a descriptive [`WriterError`]-message. The methods panic with this message, their ```try_```-variants,
+//! e.g. ```try_close_tag()```, return the error instead, so callers can recover. ```wr.finish()``` hands back the
+//! content only if all elements are closed, ```wr.write_to_file_atomic()``` writes it into a file by a rename.
+//!
+//! For maximal safety the [`ElementBuilder`] offers a typestate layer on top of HTMLWriter and XMLWriter, so illegal
+//! call sequences don't even compile. The dynamic MLLWriter-trait stays the flexible path.
+//! Elements opened by ```wr.scoped_tag()``` are closed by a [`TagGuard`] at the end of their scope, also on early
+//! returns. The calls of a writer can be chained by its ```wr.fluent()```-facade.
+//!
+//! All options of the writers can be bundled into a named [`WriterProfile`], which can be shared as a TOML- or
+//! JSON-config (features ```toml``` and ```json```).
+//!
+//! HTMLWriter and XMLWriter can optionally do the line-feeds and indents on their own, when the auto-layout is enabled
+//! (```wr.core.set_auto_layout(true)```). The layout can be adapted per element by the [`LayoutRules`], or set as a
+//! policy by ```wr.set_format_mode()```, e.g. ```FormatMode::Pretty``` or ```FormatMode::Minified```, also for JSON.
+//! Properties of sibling entries can be aligned in columns (```wr.core.set_align_properties(true)```).
+//!
+//! Values of domain types can be formatted consistently by a [`FormatterRegistry`], which is consulted by the
+//! typed property methods, e.g. ```wr.add_typed_property("price", &amount)```. With the feature ```num-format```
+//! the human-facing output of the HTMLWriter gets locale-specific numbers and dates. Report tables with formatted
+//! number-cells are written by the [`TableBuilder`], simple charts as inline SVG by [`sparkline`] and [`bar_chart`],
+//! and with the feature ```qrcode``` QR codes by ```qr_code()```. Differences of texts are shown by
+//! ```wr.diff_view()```, log records as filterable table by ```wr.log_viewer()``` and JSON-documents as collapsible
+//! tree by ```wr.json_viewer()```. Deep JSON-structures can be written from flat input by dotted paths, see
+//! ```JSONWriter::set_path()```, and values of a written document can be replaced by JSON Pointer, see
+//! ```JSONWriter::edit()```. JSON Patch documents with correctly escaped pointers are written by [`JsonPatch`].
+//! The JSONWriter can record the structure of its document and derive a JSON Schema, see ```schema()```.
+//! Arrays are opened by ```JSONWriter::open_array()```, GeoJSON feature collections are written by the
+//! [`GeoJsonBuilder`]. Captured network traffic is written as HTTP Archive by the [`HarBuilder`].
+//! Software bills of materials are written as CycloneDX (JSON and XML) or SPDX by the [`SbomBuilder`].
+//! Findings of linters and scanners are written as SARIF-log by the [`SarifBuilder`].
+//! The head of HTML-documents gets 'meta'- and 'link'-elements by ```wr.add_meta()``` and ```wr.add_link()```,
+//! the metadata of installable web apps is written by the [`WebManifest`] and ```browserconfig_xml()```. Critical
+//! styles are inlined with a deferred full stylesheet by ```wr.critical_css()```, resource hints are written by
+//! e.g. ```wr.preload()``` and ```wr.preconnect()```, the links of the favicons by ```wr.favicons()```.
+//! The pages of a site are collected in a [`DocumentSet`], which inserts the canonical URL and the links of the
+//! feeds into each head, and cross-links the variants of localized pages by "hreflang". The crawl policy is
+//! written by ```wr.robots()```, or as default of all pages of the set.
+//! Broken internal links between the pages are found by ```site.check_links()```, the search index of the pages
+//! for client-side search is written by ```site.search_index()```. Moved pages are redirected by stub pages or the
+//! config of the server, see ```site.add_redirect()```. The well-known files ```security.txt``` and ```humans.txt```
+//! are written by [`SecurityTxt`] and [`HumansTxt`].
+//! Documents start with ```wr.declaration()``` of XML or ```wr.doctype()``` of HTML.
+//! Elements are opened by their typed names [`HtmlTag`] and [`XmlName`] with ```wr.open_element()```.
+//! Common attributes are typed by [`Attr`] for ```wr.add_attr()```, so typos in their names don't compile.
+//! Classes and styles are merged into the last start-tag by ```wr.add_class()``` and ```wr.add_style()```.
+//! Properties are spliced into the start-tag of the current element also after its content, e.g. a count of its items.
+//! Elements without content are written as HTML5 void elements, XHTML or self-closing XML, see [`VoidStyle`].
+//! In AMP-mode (```wr.set_amp(true)```) the HTMLWriter rejects the markup, which AMP doesn't allow.
+//! Report pages get print stylesheets and page breaks by ```wr.print_stylesheet()``` and ```wr.page_break()```,
+//! exact page layouts are written as XSL-FO by the [`XslFoBuilder`].
+//! Third-party scripts are gated by the consent of the user with ```wr.consent_script()```. Inline scripts and
+//! styles get the nonce of the Content-Security-Policy, see ```wr.set_csp_nonce()```, also in child writers.
+//! Nested documents are embedded into iframes by ```wr.iframe_srcdoc()```.
+//! The plain-text version of a written HTML-document, e.g. for multipart emails, is rendered by ```wr.plain_text()```.
+//! Plain text is wrapped at a width by ```reflow()```, with hanging indents for list items.
+//! Figures, tables and sections of reports are numbered by ```wr.figure()``` and its siblings, their lists are
+//! written by ```wr.list_of()```. ```wr.footnote()``` writes the reference to a note, ```wr.footnotes()``` the
+//! collected notes with back-links. References are cited by ```wr.cite()``` and listed by
+//! ```wr.render_bibliography()```. The terms of the index are marked by ```wr.index_term()``` and listed by
+//! ```wr.render_index()```. Abbreviations are expanded consistently from a ```Glossary``` by ```wr.abbr()```.
+//! JSON is also written as JSONC or JSON5 with real comments, see [`JsonDialect`].
+//! Typed values, e.g. strings, which need quotes, are written into JSON by ```wr.add_value()```.
+//! Large documents are written directly into a ```std::io::Write```-sink by a ```SinkWriter```, e.g.
+//! ```HTMLWriter::with_sink(file)```.
+//! Repeated parts, e.g. cards, are written once by ```wr.capture()``` and reused as [`Fragment`].
+//! Fragments are rendered once for their key and re-indented on reuse by ```wr.render_cached()``` and a
+//! [`FragmentCache`].
+//! Output policies, e.g. ```loading="lazy"``` on every 'img', rewrite the operations in a [`FilterWriter`].
+//! Two documents, e.g. pretty and minified HTML, are written in one pass by a [`TeeWriter`].
+//! A dry run by ```HTMLWriter::dry_run()``` validates the operations and counts the bytes, e.g. for a Content-Length.
+//! The values of properties are escaped in HTML and XML, ```wr.text()``` writes escaped text, ```wr.raw()``` and the
+//! ```*_raw```-variants write pre-escaped markup as it is. JSON and YAML write text as string-item of an array.
+//! Corrections of the HTMLWriter, e.g. implicit closes in lenient mode, are recorded as [`Warning`]s.
+//! Several places of a document are written at the same time by the secondary cursors of ```wr.cursor()```.
+//! The values of properties are written in double or single quotes, see [`QuoteStyle`].
+//! Line-feeds are written as '\n' or "\r\n", see [`LineEnding`], indents by spaces or tabs, see [`IndentChar`].
+//! Markup with placeholders is written by ```mll_write!()```, which escapes the values and follows the indent.
+//! Nested structures with ```@if``` and ```@for``` are written by the macros ```html!()```, ```xml!()``` and
+//! ```json!()```.
+//! Existing documents are reformatted, minified or converted by replaying them into a writer, see ```replay_html()```,
+//! also on the command line by the binary ```mllfmt``` of the feature "cli".
+//! [`Properties`] keep their order and have no duplicates, "class" and "style" are merged, ```props!{}``` builds them.
+//! Content written by hand is escaped the same way by the functions of the module [`escape`].
+//! Sequences of operations, e.g. from cargo-fuzz or proptest, are checked against the invariants of a writer by
+//! ```check_consistency()```.
+//! The content is handed over without a copy by ```wr.into_bytes()``` or ```wr.take_content()```, which starts a
+//! new document in the same writer.
+//! While writing, ```wr.content_so_far()``` borrows the content and ```wr.snapshot()``` returns a [`Snapshot`] for
+//! other threads, which shares the unchanged chunks of former snapshots.
+//! Graphics, e.g. plots, are written by the [`SVGWriter`] with typed shapes like ```svg.rect()```.
+//! Servers reuse writers and their buffers from a [`WriterPool`].
+//! The workloads of the benchmarks are the module ```bench``` with the feature "bench".
+//!
 //! More individual behavior of the given writer-types will be implemented in the future, when needed or requested.
 //!
 //! ## Examples
@@ -53,40 +152,281 @@
 //! wr.close_tag();
 //! ```
 
+use escape::{escape_attr, escape_html_text};
 use std::result::Result;
 
+mod align;
+mod amp;
+mod attr;
+mod banner;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod bytes;
+mod cache;
+mod capture;
+mod chart;
+mod citation;
+mod conditional;
+mod consent;
+mod csp;
+mod cursor;
+mod diff;
+mod entity;
+mod error;
+pub mod escape;
+mod favicon;
+mod file;
+mod filter;
+mod finish;
+mod fluent;
+mod footnote;
+mod format;
+mod fuzz;
+mod geojson;
+mod glossary;
+mod guard;
+mod har;
+mod head;
+mod html5;
+mod html_rules;
+mod include;
+mod indent;
+mod index;
+mod json;
+mod json5;
+mod json_value;
+mod jsonview;
+mod layout;
+mod line_ending;
+mod links;
+#[cfg(feature = "num-format")]
+mod locale;
+mod logview;
+mod macros;
+mod null;
+mod numbering;
+mod patch;
+mod path;
+mod plaintext;
+mod pointer;
+mod pool;
+mod print;
+mod profile;
+mod prolog;
+mod properties;
+mod provenance;
+#[cfg(feature = "qrcode")]
+mod qr;
+mod quote;
+mod redirect;
+mod reflow;
+mod replay;
+mod retroactive;
+mod robots;
+mod sarif;
+mod sbom;
+mod schema;
+mod search;
+mod shard;
+mod sink;
+mod site;
+mod snapshot;
+mod srcdoc;
+mod state;
+mod stream;
+mod svg;
+mod table;
+mod tag;
+mod tee;
+mod template;
+mod text;
+mod toml_writer;
+mod typestate;
+mod void;
+mod warning;
+mod webapp;
+mod wellknown;
+mod yaml;
+
+pub use attr::Attr;
+pub use banner::BannerStyle;
+pub use cache::FragmentCache;
+pub use capture::Fragment;
+pub use chart::{bar_chart, sparkline, ChartOptions};
+pub use citation::{CitationStyle, Reference};
+pub use cursor::Cursor;
+pub use diff::DiffLayout;
+pub use entity::PREDEFINED_ENTITIES;
+pub use error::WriterError;
+pub use favicon::{FAVICON_ICO_SIZES, FAVICON_PNGS};
+pub use filter::FilterWriter;
+pub use finish::{FinalFormat, TrailingNewline};
+pub use fluent::Fluent;
+pub use format::{FormatterRegistry, ValueFormatter};
+pub use fuzz::{check_consistency, Inconsistency, WriterOp, FUZZ_NAMES, FUZZ_VALUES};
+#[cfg(feature = "proptest")]
+pub use fuzz::{writer_op, writer_ops};
+pub use geojson::{GeoJsonBuilder, Geometry, Position};
+pub use glossary::Glossary;
+pub use guard::TagGuard;
+pub use har::{HarBuilder, HarEntry, HarTimings};
+pub use head::StylesheetLoading;
+pub use html5::ValidationLevel;
+pub use include::{XIncludeParse, XINCLUDE_NAMESPACE};
+pub use indent::IndentChar;
+pub use json5::JsonDialect;
+pub use json_value::JsonValue;
+pub use layout::{FormatMode, LayoutRules};
+pub use line_ending::LineEnding;
+pub use links::BrokenLink;
+#[cfg(feature = "num-format")]
+pub use locale::Locale;
+pub use logview::{log_ndjson, LogRecord};
+pub use null::{ByteCounter, NullWriter};
+pub use numbering::NumberedKind;
+pub use patch::{json_pointer, JsonPatch, PatchOp};
+pub use plaintext::html_to_text;
+pub use pool::{PooledWriter, WriterPool};
+pub use print::{PageBreak, XslFoBuilder, XSL_FO_NAMESPACE};
+pub use profile::WriterProfile;
+pub use properties::{Properties, Property};
+pub use provenance::TimestampPolicy;
+#[cfg(feature = "qrcode")]
+pub use qr::qr_code;
+pub use quote::QuoteStyle;
+pub use reflow::{reflow, wrap_words};
+pub use replay::{replay_html, replay_json, replay_json_as_yaml, replay_xml};
+pub use robots::RobotsPolicy;
+pub use sarif::{SarifBuilder, SarifLevel, SarifLocation, SARIF_SCHEMA};
+pub use sbom::{SbomBuilder, SbomComponent, CYCLONEDX_NAMESPACE};
+pub use schema::JSON_SCHEMA_DRAFT_07;
+pub use shard::{ManifestFormat, ShardInfo, ShardedExport};
+pub use sink::{BufferedWriter, SinkWriter};
+pub use site::{DocumentSet, FeedKind};
+pub use snapshot::Snapshot;
+pub use state::DocumentState;
+pub use stream::{Slot, XmlRecord, XmlRecordStream};
+pub use svg::{PathData, SVGWriter, SVG_NAMESPACE};
+pub use table::{Cell, CellFormat, TableBuilder};
+pub use tag::{HtmlTag, XmlName};
+pub use tee::TeeWriter;
+pub use toml_writer::{toml_array, toml_string, TOMLWriter};
+pub use typestate::{Closed, ElementBuilder, HasChildren, InStartTag};
+pub use void::VoidStyle;
+pub use warning::{Warning, WarningKind};
+pub use webapp::{browserconfig_xml, ManifestDisplay, WebManifest};
+pub use wellknown::{HumansTxt, SecurityTxt};
+pub use yaml::{yaml_string, YAMLWriter};
+
+#[doc(hidden)]
+pub use mllwriter_macros::mll_write as __mll_write;
+
 /// Trait MLLWriter (Markup-language-like Writer) describes a common behavior for all writer-types. Writer-types will
 /// be a version which prints a HTML-file, a XML-file or a JSON-file each. All those file-types have a structural-pattern
 /// in common, even when a JSON-file is no markup-file - that's why it is a markup-language-like writer.
+///
+/// Each operation, which can fail, comes in two flavors. The ```try_```-variant, e.g. ```try_close_tag()```, returns
+/// a [`WriterError`], e.g. ```UnbalancedClose```, ```UnsupportedOperation``` or ```InvalidTagName```, so library
+/// consumers can recover. The variant without prefix panics with the message of the error: it is the short form for
+/// generators with a fixed call sequence, and it keeps the existing callers compiling, which is why the trait isn't
+/// Result-only.
 pub trait MLLWriter {
     /// Method opens a new block, e.g. the 'div'-HTML-tag or '{'-block in JSON.
     fn open_tag(&mut self, tag: &str);
 
+    /// Like open_tag(), but returns the error instead of panicking, e.g. an invalid tag name
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError>;
+
     /// Combines open_tag() and add_property()
     fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str);
 
     /// Method closes the last opened block, e.g. '/div'-HTML-tag or '}'-block in JSON.
     fn close_tag(&mut self);
 
+    /// Method opens a new block like open_tag() and returns a guard, which closes it when dropped.
+    fn scoped_tag(&mut self, tag: &str) -> TagGuard<'_, Self>
+    where
+        Self: Sized,
+    {
+        TagGuard::new(self, tag)
+    }
+
+    /// Method runs the calls of a template, which ```mll_write!()``` compiled out of its markup.
+    fn write_template(
+        &mut self,
+        calls: impl FnOnce(&mut Self) -> Result<(), WriterError>,
+    ) -> Result<(), WriterError>
+    where
+        Self: Sized,
+    {
+        calls(self)
+    }
+
+    /// Method returns a fluent facade of the writer, whose methods can be chained.
+    fn fluent(&mut self) -> Fluent<'_, Self>
+    where
+        Self: Sized,
+    {
+        Fluent::new(self)
+    }
+
+    /// Like close_tag(), but returns the error instead of panicking, e.g. when no element is open
+    fn try_close_tag(&mut self) -> Result<(), WriterError>;
+
     /// Method prints a single-tag element into the content-string, e.g. 'img' in HTML, no use-case in JSON.
     fn single_tag(&mut self, tag: &str);
 
+    /// Like single_tag(), but returns the error instead of panicking, e.g. in the JSONWriter
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError>;
+
     /// Combines single_tag() and add_property()
     fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str);
 
     /// Method adds a single property-value-pair and pushes it onto the content-string retroactively.
     fn add_property(&mut self, name: &str, value: &str);
 
+    /// Like add_property(), but returns the error instead of panicking, e.g. when no start-tag is open
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError>;
+
     /// Method generates a property-string out of given properties and pushes it onto content-string retroactively.
-    /// It uses therefor the Property-struct definition to be able to accept an arbitrary number of properties.
-    fn add_properties(&mut self, properties: &Property);
+    /// It uses therefor the Properties-struct to be able to accept an arbitrary number of properties.
+    fn add_properties(&mut self, properties: &Properties);
 
     /// Method adds a single comment at current cursor position
     fn add_comment(&mut self, comment: &str);
 
+    /// Like add_comment(), but returns the error instead of panicking, e.g. in plain JSON outside of an object
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError>;
+
+    /// Method writes the text escaped as content of the current element
+    fn text(&mut self, text: &str);
+
+    /// Like text(), but returns the error instead of panicking, e.g. in the TOMLWriter
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError>;
+
+    /// Method writes the string as it is at current cursor position, e.g. markup or text which is escaped already
+    fn raw(&mut self, raw: &str);
+
+    /// Like raw(), but returns the error instead of panicking, e.g. when the document is finished
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError>;
+
+    /// Method adds a framed comment block at current cursor position, e.g. a provenance or license note at the
+    /// top of the file. The comment syntax is given by the writer-type.
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle);
+
+    /// Method adds a standardized "Generated by"-note with the tool, its version and optionally a timestamp.
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy);
+
     /// Method adds n line feed(s) to content string and writes the current indent
     fn line_feed(&mut self, n: usize);
 
+    /// Method adds a blank line, unless the content already ends with one
+    fn blank_line(&mut self);
+
+    /// Method makes sure, that there are at least n blank lines at current cursor position. Blank lines which
+    /// were already written are taken into account, so they don't stack up.
+    fn ensure_blank_lines(&mut self, n: usize);
+
     /// Method meaningful combines inc_indent_step() and line_feed()
     fn line_feed_inc(&mut self);
 
@@ -107,28 +447,14 @@ pub trait MLLWriter {
     /// called after started editing (content isn't empty anymore).
     fn set_indent_step_size(&mut self, indent_step_size: usize);
 
-    /// Method resets the writer to defaults and empties the content-string as well
-    fn clear(&mut self);
-}
-
-/// The Property struct simplifies to encapsule several properties, e.g. class="superhero" and style="width: auto". These can
-/// be passed to the Writer, which pushes it onto the content-string in the right way
-pub struct Property {
-    pub(crate) p: Vec<(String, String)>,
-}
+    /// Method applies all options of the given profile, which are meaningful for the writer-type.
+    fn apply_profile(&mut self, profile: &WriterProfile);
 
-impl Property {
-    /// A default new method with one first property pair to be passed
-    pub fn new(name: &str, value: &str) -> Property {
-        let mut p = Property { p: Vec::new() };
-        p.p.push((name.to_string(), value.to_string()));
-        p
-    }
+    /// Method ends the document. All elements have to be closed before, afterwards nothing can be written anymore.
+    fn end_document(&mut self);
 
-    /// Simple method to add other properties to the stack
-    pub fn add(&mut self, name: &str, value: &str) {
-        self.p.push((name.to_string(), value.to_string()));
-    }
+    /// Method resets the writer to defaults and empties the content-string as well
+    fn clear(&mut self);
 }
 
 /// All Writer-types have some similarities, e.g. adding a line-feed or increment and decrement
@@ -137,7 +463,8 @@ impl Property {
 /// - the **content**-String, which holds the markup-content under edit
 /// - the indent_step_size, as a number of whitespaces to be added at current line
 /// - the block_stack, for closing HTML-tags automatically without specifying again which one
-/// - other useful data for internal usage
+/// - other useful data for internal usage, e.g. the current state of the document
+///
 /// This struct is used as a composition in the WriterTypes: HTMLWriter, XMLWriter and JSONWriter
 #[derive(Debug, Clone)]
 pub struct WriterCore {
@@ -145,8 +472,43 @@ pub struct WriterCore {
     pub(crate) indent_step_size: usize,
     // holds the current indent as a string for quick adding into content
     pub(crate) indent: String,
+    // one tab per indent-step instead of indent_step_size spaces
+    pub(crate) indent_tabs: bool,
     // holds a stack with opened/unclosed block-tags
     pub(crate) block_stack: Vec<String>,
+    // the current state of the document under edit
+    pub(crate) state: DocumentState,
+    // documents with only one root element (XML, JSON) or with many (HTML-fragments)
+    pub(crate) single_root: bool,
+    // automatic line-feeds and indents for markup-writers
+    pub(crate) auto_layout: bool,
+    pub(crate) layout_rules: LayoutRules,
+    pub(crate) open_layout: Vec<layout::OpenLayout>,
+    // column-alignment of properties
+    pub(crate) align_properties: bool,
+    pub(crate) align_stack: Vec<align::AlignBlock>,
+    // the content has been handed out already (e.g. by a stream), so the document isn't empty
+    pub(crate) flushed: bool,
+    // no line-feeds and indents at all, see FormatMode::Minified
+    pub(crate) minified: bool,
+    // the content shared by snapshots
+    pub(crate) frozen: snapshot::Frozen,
+    // the quotes around the values of properties
+    pub(crate) quote_style: QuoteStyle,
+    // the characters of a line-feed
+    pub(crate) line_ending: LineEnding,
+    // the characters outside of ASCII are escaped, see escape
+    pub(crate) ascii_only: bool,
+    // the depth and the content-offset of the start-tags of the open elements, see retroactive
+    pub(crate) tag_starts: Vec<(usize, usize)>,
+    // the properties of start-tags, which a dry run has flushed already, they are only counted
+    pub(crate) spliced: String,
+    // the number of cursor-marks in the content, which aren't merged yet, see cursor
+    pub(crate) open_cursors: usize,
+    // the formatting of the content applied by finish()
+    pub(crate) final_format: FinalFormat,
+    // formatters consulted by the typed property methods
+    pub(crate) formatters: std::sync::Arc<FormatterRegistry>,
 }
 
 impl WriterCore {
@@ -155,23 +517,106 @@ impl WriterCore {
         WriterCore {
             indent_step_size,
             indent: String::new(),
+            indent_tabs: false,
             block_stack: Vec::new(),
+            state: DocumentState::Prolog,
+            single_root: true,
+            auto_layout: false,
+            layout_rules: LayoutRules::default(),
+            open_layout: Vec::new(),
+            align_properties: false,
+            align_stack: Vec::new(),
+            flushed: false,
+            minified: false,
+            frozen: snapshot::Frozen::default(),
+            quote_style: QuoteStyle::Double,
+            line_ending: LineEnding::Lf,
+            ascii_only: false,
+            tag_starts: Vec::new(),
+            spliced: String::new(),
+            open_cursors: 0,
+            final_format: FinalFormat::default(),
+            formatters: std::sync::Arc::default(),
         }
     }
 
     fn clear(&mut self, indent_step: usize) {
         self.indent_step_size = indent_step;
         self.indent.clear();
+        self.indent_tabs = false;
         self.block_stack.clear();
+        self.state = DocumentState::Prolog;
+        self.auto_layout = false;
+        self.layout_rules = LayoutRules::default();
+        self.open_layout.clear();
+        self.align_properties = false;
+        self.align_stack.clear();
+        self.flushed = false;
+        self.minified = false;
+        self.thaw();
+        self.spliced.clear();
+        self.open_cursors = 0;
+        self.final_format = FinalFormat::default();
+        self.quote_style = QuoteStyle::Double;
+        self.line_ending = LineEnding::Lf;
+        self.ascii_only = false;
+        self.formatters = std::sync::Arc::default();
+    }
+
+    // Returns a core for a child writer, with the options and the indent of this one but an empty document
+    pub(crate) fn sub_core(&self) -> WriterCore {
+        let mut core = self.clone();
+        core.block_stack.clear();
+        core.state = DocumentState::Prolog;
+        core.open_layout.clear();
+        core.align_stack.clear();
+        core.flushed = false;
+        core.open_cursors = 0;
+        core.thaw();
+        core
     }
 
     fn line_feed(&mut self, content: &mut String, n: usize) {
+        self.settle();
+        if self.minified {
+            return;
+        }
+        // A line, which stays empty, keeps no indent
+        if let Some(line) = content.rfind('\n') {
+            if n > 0 && content[line + 1..].chars().all(|c| c == ' ' || c == '\t') {
+                content.truncate(line + 1);
+            }
+        }
         for _i in 0..n {
-            content.push('\n');
+            content.push_str(self.newline());
         }
         content.push_str(&self.indent);
     }
 
+    fn ensure_blank_lines(&mut self, content: &mut String, n: usize) {
+        self.settle();
+        if content.is_empty() || self.minified {
+            return;
+        }
+        // Remove the indent of an empty last line and count the line-feeds the content ends with
+        let line_start = content.rfind('\n').map_or(0, |i| i + 1);
+        let line_feeds = if content[line_start..].trim().is_empty() {
+            content.truncate(line_start);
+            let trimmed = content.trim_end_matches(['\n', '\r']).len();
+            content[trimmed..].matches('\n').count()
+        } else {
+            0
+        };
+        // The auto-layout adds the line-feed of the next line on its own
+        let wanted = if self.auto_layout { n } else { n + 1 };
+        for _i in line_feeds..wanted {
+            content.push_str(self.newline());
+        }
+        if !self.auto_layout {
+            content.push_str(&self.indent);
+        }
+    }
+
     fn line_feed_inc(&mut self, content: &mut String) {
         self.inc_indent_step();
         self.line_feed(content, 1);
@@ -183,45 +628,276 @@ impl WriterCore {
     }
 
     fn inc_indent_step(&mut self) {
-        self.indent
-            .push_str(" ".repeat(self.indent_step_size).as_str());
+        self.indent.push_str(&self.indent_unit());
     }
 
     fn dec_indent_step(&mut self) {
         let len = self.indent.len();
-        if self.indent_step_size > len {
+        let step = self.indent_unit().len();
+        if step > len {
             self.indent = String::new();
         } else {
-            self.indent.truncate(len - self.indent_step_size);
+            self.indent.truncate(len - step);
         }
     }
 
     pub fn set_indent_step(&mut self, indent_step: usize) {
-        self.indent = " ".repeat(indent_step * self.indent_step_size);
+        self.indent = self.indent_unit().repeat(indent_step);
     }
 
     pub fn set_indent_step_size(&mut self, indent_step_size: usize) {
         self.indent_step_size = indent_step_size;
     }
+
+    /// Returns the path of the currently open elements, e.g. "html > body > p"
+    pub fn open_path(&self) -> String {
+        self.block_stack.join(" > ")
+    }
 }
 
 // ================================================================================================
 /// Implementation of the HTMLWriter for writing HTML-files. Default indent-step-size is 4. There is
 /// no auto-fill in any way. The user has to use ```line_feed()```, ```line_feed_inc()``` and ```line_feed_dec()```
 /// for line-feeds and to style his HTML-files in its own taste.
+///
+/// In strict mode (see ```set_strict()```) the basics of the HTML content-model are checked, e.g. no block elements
+/// inside of a 'p', 'li' only inside of lists and exactly one 'body'. ```ValidationLevel::Html5``` also rejects
+/// unknown elements, see ```set_validation_level()```.
+///
+/// With implicit closing (see ```set_implicit_close()```) the writer emulates the HTML-parser instead: opening a 'p'
+/// while another 'p' is open closes the previous one, the same for 'li', 'td' and 'tr'. With lenient attributes
+/// (see ```set_lenient_attributes()```) attributes with invalid names are dropped instead of rejected.
 #[derive(Debug, Clone)]
 pub struct HTMLWriter {
     /// Content held by the writer
     pub content: String,
     /// WriterCore in a composition
     pub core: WriterCore,
+    /// How much of HTML is validated, e.g. the nesting-rules in strict mode, internal
+    validation: ValidationLevel,
+    /// Close 'p', 'li', 'td' and 'tr' implicitly, internal
+    implicit_close: bool,
+    /// Drop attributes with invalid names instead of rejecting them, internal
+    lenient_attributes: bool,
+    /// Counters for 'html' and 'body' elements, internal
+    html_cnt: usize,
+    body_cnt: usize,
+    /// AMP-mode and if the AMP-boilerplate was written, internal
+    amp: bool,
+    amp_boilerplate: bool,
+    /// The nonce of the Content-Security-Policy for 'script' and 'style', internal
+    nonce: Option<String>,
+    /// The numbered figures, tables and sections, internal
+    numbering: numbering::Numbering,
+    /// The footnotes so far and the ones, which aren't written yet, internal
+    footnotes: footnote::Footnotes,
+    /// The registered and the cited references, internal
+    citations: citation::Citations,
+    /// The terms of the index with their places, internal
+    index: index::Index,
+    /// The abbreviations with their expansions, internal
+    glossary: Glossary,
+    /// The notation of elements without content, internal
+    void_style: VoidStyle,
+    /// The sub-documents of the secondary cursors, internal
+    cursors: cursor::Cursors,
+    /// The corrections so far, internal
+    warnings: Vec<Warning>,
 }
 
 impl HTMLWriter {
     pub fn new() -> HTMLWriter {
+        let mut core = WriterCore::new(4);
+        // HTML-fragments may have several top-level elements
+        core.single_root = false;
+        HTMLWriter {
+            content: String::new(),
+            core,
+            validation: ValidationLevel::Basic,
+            implicit_close: false,
+            lenient_attributes: false,
+            html_cnt: 0,
+            body_cnt: 0,
+            amp: false,
+            amp_boilerplate: false,
+            nonce: None,
+            numbering: numbering::Numbering::default(),
+            cursors: cursor::Cursors::default(),
+            warnings: Vec::new(),
+            footnotes: footnote::Footnotes::default(),
+            citations: citation::Citations::default(),
+            index: index::Index::default(),
+            glossary: Glossary::new(),
+            void_style: VoidStyle::default(),
+        }
+    }
+
+    /// Returns a new HTMLWriter with the profile taken from the environment, see ```WriterProfile::from_environment()```
+    pub fn from_environment() -> Result<HTMLWriter, WriterError> {
+        let mut wr = HTMLWriter::new();
+        wr.apply_profile(&WriterProfile::from_environment()?);
+        Ok(wr)
+    }
+
+    /// Enables or disables the strict mode, which validates the nesting of HTML-elements
+    pub fn set_strict(&mut self, strict: bool) {
+        self.validation = if strict {
+            ValidationLevel::Strict
+        } else {
+            ValidationLevel::Basic
+        };
+    }
+
+    /// Enables or disables the implicit closing of 'p', 'li', 'td' and 'tr' elements
+    pub fn set_implicit_close(&mut self, implicit_close: bool) {
+        self.implicit_close = implicit_close;
+    }
+
+    /// Enables or disables the lenient mode of attributes: an attribute with an invalid name is dropped with a
+    /// ```DroppedAttribute```-warning instead of being rejected with ```InvalidAttributeName```
+    pub fn set_lenient_attributes(&mut self, lenient_attributes: bool) {
+        self.lenient_attributes = lenient_attributes;
+    }
+
+    /// Returns a child writer for a nested document, e.g. the content of a conditional comment or an inline SVG.
+    /// The child takes over the options, the indent, the CSP nonce and the glossary of its parent.
+    pub fn sub_writer(&self) -> HTMLWriter {
         HTMLWriter {
             content: String::new(),
-            core: WriterCore::new(4),
+            core: self.core.sub_core(),
+            validation: self.validation,
+            implicit_close: self.implicit_close,
+            lenient_attributes: self.lenient_attributes,
+            html_cnt: 0,
+            body_cnt: 0,
+            amp: self.amp,
+            amp_boilerplate: false,
+            nonce: self.nonce.clone(),
+            numbering: numbering::Numbering::default(),
+            cursors: cursor::Cursors::default(),
+            warnings: Vec::new(),
+            footnotes: footnote::Footnotes::default(),
+            citations: citation::Citations::default(),
+            index: index::Index::default(),
+            glossary: self.glossary.clone(),
+            void_style: self.void_style,
+        }
+    }
+
+    // Writes the start-tag of the element, whose name is checked already
+    pub(crate) fn write_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        html_rules::check_not_void(tag)?;
+        let closes = self.check_nesting(tag)?;
+        self.core.check_begin_element("open_tag")?;
+        self.enter_element(tag, closes);
+        self.core.begin_element("open_tag")?;
+        self.core.layout_open(&mut self.content, tag, false);
+        self.core.align_entry(&self.content);
+        self.core.mark_tag_start(self.content.len());
+        self.content.push('<');
+        self.content.push_str(tag);
+        self.content.push('>');
+        self.core.block_stack.push(tag.to_string());
+        self.core.align_open_block();
+        if self.amp && tag == "html" {
+            self.add_property("amp", "");
+        }
+        if let Some(nonce) = csp::nonce_of(tag, &self.nonce) {
+            self.add_property("nonce", &nonce);
+        }
+        Ok(())
+    }
+
+    // Writes the element without content, whose name is checked already
+    pub(crate) fn write_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        let closes = self.check_nesting(tag)?;
+        self.core.check_begin_element("single_tag")?;
+        self.enter_element(tag, closes);
+        self.core.begin_element("single_tag")?;
+        self.core.layout_open(&mut self.content, tag, true);
+        self.core.align_entry(&self.content);
+        self.content.push('<');
+        self.content.push_str(tag);
+        self.content.push_str(self.void_style.tag_end());
+        Ok(())
+    }
+
+    // Adds the property with the value as it is to the last start-tag
+    pub(crate) fn write_property(
+        &mut self,
+        prop: &str,
+        value: &str,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        if let Err(e) = check_attribute_name(prop) {
+            if !self.lenient_attributes {
+                return Err(e);
+            }
+            // The lenient mode drops the attribute
+            self.core.begin_any_property(operation)?;
+            self.warn(WarningKind::DroppedAttribute, prop);
+            return Ok(());
+        }
+        if self.amp {
+            amp::check_attribute(prop)?;
+        }
+        self.check_attribute(prop)?;
+        self.core.begin_any_property(operation)?;
+        push_property(&mut self.core, &mut self.content, prop, value);
+        Ok(())
+    }
+
+    // Adds the properties to the last start-tag, the values are escaped if wanted
+    pub(crate) fn write_properties(
+        &mut self,
+        properties: &Properties,
+        escape: bool,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        for (name, _) in properties.iter() {
+            if self.amp {
+                amp::check_attribute(name)?;
+            }
+            self.check_attribute(name)?;
+        }
+        self.core.begin_any_property(operation)?;
+        push_properties(&mut self.core, &mut self.content, properties, escape);
+        Ok(())
+    }
+
+    // Validates the nesting of a new element in strict mode against the elements, which are still open after
+    // the implicit closes, and returns the number of those closes. Nothing is changed yet, so a rejected
+    // element leaves the document as it was.
+    fn check_nesting(&self, tag: &str) -> Result<usize, WriterError> {
+        let closes = if self.implicit_close {
+            html_rules::implicit_closes(tag, &self.core.block_stack)
+        } else {
+            0
+        };
+        let open = &self.core.block_stack[..self.core.block_stack.len() - closes];
+        if self.validation >= ValidationLevel::Strict {
+            html_rules::check_nesting(tag, open, self.body_cnt)?;
+        }
+        if self.validation == ValidationLevel::Html5 {
+            html5::check_element(tag, open)?;
+        }
+        if self.amp {
+            amp::check_tag(tag)?;
+        }
+        Ok(closes)
+    }
+
+    // Closes the elements implicitly and counts the structural elements, once the new element is validated
+    fn enter_element(&mut self, tag: &str, closes: usize) {
+        for _i in 0..closes {
+            let closed = self.core.block_stack.last().cloned().unwrap_or_default();
+            self.warn(WarningKind::ImplicitClose, &closed);
+            self.close_tag();
+        }
+        match tag {
+            "html" => self.html_cnt += 1,
+            "body" => self.body_cnt += 1,
+            _ => (),
         }
     }
 }
@@ -233,34 +909,52 @@ impl Default for HTMLWriter {
 }
 
 impl MLLWriter for HTMLWriter {
-    /// Accepts only ASCII-lowercase
+    /// Accepts only ASCII-lowercase letters and digits, an empty tag is an ```InvalidTagName```
     fn open_tag(&mut self, tag: &str) {
-        assert_html_notation(tag);
-        self.content.push('<');
-        self.content.push_str(tag);
-        self.content.push('>');
-        self.core.block_stack.push(tag.to_string());
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        check_html_notation(tag)?;
+        self.write_open_tag(tag)
     }
 
     fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
-        assert_html_notation(tag);
         self.open_tag(tag);
         self.add_property(prop, value);
     }
 
     fn close_tag(&mut self) {
-        let tag = self.core.block_stack.pop().unwrap();
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        if self.amp
+            && self
+                .core
+                .block_stack
+                .last()
+                .is_some_and(|tag| tag == "head")
+        {
+            amp::check_head(self.amp_boilerplate)?;
+        }
+        let tag = self.core.end_element("close_tag")?;
+        self.core.align_close_block(&mut self.content);
+        self.core.layout_close(&mut self.content, &tag);
         self.content.push_str("</");
         self.content.push_str(&tag);
         self.content.push('>');
+        Ok(())
     }
 
-    /// Accepts only ASCII-lowercase
+    /// Accepts only ASCII-lowercase letters and digits, an empty tag is an ```InvalidTagName```
     fn single_tag(&mut self, tag: &str) {
-        assert_html_notation(tag);
-        self.content.push('<');
-        self.content.push_str(tag);
-        self.content.push('>');
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        check_html_notation(tag)?;
+        self.write_single_tag(tag)
     }
 
     fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
@@ -268,41 +962,93 @@ impl MLLWriter for HTMLWriter {
         self.add_property(prop, value);
     }
 
-    /// Accepts only ASCII-lowercase for the name-attribute
+    /// Accepts ASCII-lowercase, digits and '-' for the name-attribute, e.g. "data-id"
     fn add_property(&mut self, prop: &str, value: &str) {
-        assert_html_notation(prop);
-        // First we remove the '>' of the last entry
-        self.content.pop();
-        // Then add the property-value-pair and close the tag again after insertion
-        self.content.push(' ');
-        self.content.push_str(prop);
-        self.content.push_str("=\"");
-        self.content.push_str(value);
-        self.content.push_str("\">");
+        check(self.try_add_property(prop, value));
     }
 
-    fn add_properties(&mut self, properties: &Property) {
-        // First we remove the '>' of the last entry
-        self.content.pop();
-        // Then, we add our property-string
-        properties.p.iter().for_each(|x| {
-            self.content
-                .push_str(&(" ".to_string() + &x.0 + "=\"" + &x.1 + "\""))
-        });
-        // Finally, we close the tag again
-        self.content.push('>');
+    /// The value is escaped, see ```add_property_raw()``` for pre-escaped values
+    fn try_add_property(&mut self, prop: &str, value: &str) -> Result<(), WriterError> {
+        let value = self.core.ascii_markup(escape_attr(value));
+        self.write_property(prop, &value, "add_property")
+    }
+
+    /// The values are escaped, see ```add_properties_raw()``` for pre-escaped values
+    fn add_properties(&mut self, properties: &Properties) {
+        check(self.write_properties(properties, true, "add_properties"));
     }
 
     fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("add_comment")?;
+        self.core.layout_node(&mut self.content);
         self.content.push_str("<!-- ");
-        self.content.push_str(comment);
+        let comment = self.core.line_breaks(comment);
+        self.content.push_str(&comment);
         self.content.push_str(" -->");
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.core.begin_text("text")?;
+        self.content
+            .push_str(&self.core.ascii_markup(escape_html_text(text)));
+        Ok(())
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("raw")?;
+        self.content.push_str(raw);
+        Ok(())
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        check(self.core.begin_markup("file_banner"));
+        self.core.layout_node(&mut self.content);
+        let indent = self.core.indent.clone();
+        let newline = self.core.newline();
+        banner::write_banner(
+            &mut self.content,
+            newline,
+            &indent,
+            lines,
+            style,
+            "<!-- ",
+            " -->",
+        );
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.add_comment(&provenance::provenance_comment(
+            tool,
+            version,
+            timestamp_policy,
+        ));
     }
 
     fn line_feed(&mut self, n: usize) {
         self.core.line_feed(&mut self.content, n);
     }
 
+    fn blank_line(&mut self) {
+        self.ensure_blank_lines(1);
+    }
+
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.core.ensure_blank_lines(&mut self.content, n);
+    }
+
     fn line_feed_inc(&mut self) {
         self.core.line_feed_inc(&mut self.content);
     }
@@ -327,9 +1073,43 @@ impl MLLWriter for HTMLWriter {
         self.core.set_indent_step_size(indent_step_size);
     }
 
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.core.apply_profile(profile);
+        self.core.apply_layout_profile(profile);
+        if let Some(void_style) = profile.void_style {
+            self.void_style = void_style;
+        }
+        self.set_strict(profile.strict);
+        self.implicit_close = profile.implicit_close;
+        self.lenient_attributes = profile.lenient_attributes;
+    }
+
+    fn end_document(&mut self) {
+        check(self.core.end_document("end_document"));
+        if self.validation >= ValidationLevel::Strict {
+            check(html_rules::check_document(self.html_cnt, self.body_cnt));
+        }
+    }
+
     fn clear(&mut self) {
         self.content.clear();
         self.core.clear(4);
+        self.validation = ValidationLevel::Basic;
+        self.implicit_close = false;
+        self.lenient_attributes = false;
+        self.html_cnt = 0;
+        self.body_cnt = 0;
+        self.amp = false;
+        self.amp_boilerplate = false;
+        self.nonce = None;
+        self.numbering = numbering::Numbering::default();
+        self.cursors.clear();
+        self.warnings.clear();
+        self.footnotes = footnote::Footnotes::default();
+        self.citations = citation::Citations::default();
+        self.index = index::Index::default();
+        self.glossary = Glossary::new();
+        self.void_style = VoidStyle::default();
     }
 }
 
@@ -347,16 +1127,28 @@ impl std::fmt::Display for HTMLWriter {
 }
 
 impl std::fmt::Write for HTMLWriter {
+    /// Markup (starting with '<') and whitespace can be written everywhere, other text only where
+    /// the document allows text content
     fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        let checked = if s.trim().is_empty() || s.trim_start().starts_with('<') {
+            self.core.begin_markup("write_str")
+        } else {
+            self.core.begin_text("write_str")
+        };
+        checked.map_err(|_| std::fmt::Error)?;
         self.content.write_str(s)
     }
 
     fn write_char(&mut self, c: char) -> Result<(), std::fmt::Error> {
-        self.content.write_char(c)
+        self.write_str(c.encode_utf8(&mut [0; 4]))
     }
 
     fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> Result<(), std::fmt::Error> {
-        self.content.write_fmt(args)
+        // Format the whole string first, otherwise a declaration would be checked in pieces
+        match args.as_str() {
+            Some(s) => self.write_str(s),
+            None => self.write_str(&args.to_string()),
+        }
     }
 }
 
@@ -370,6 +1162,12 @@ pub struct XMLWriter {
     pub content: String,
     /// WriterCore in a composition
     pub core: WriterCore,
+    /// Namespace-prefixes declared by open elements, with the depth of the element, internal
+    namespaces: Vec<(String, usize)>,
+    /// Entities of the DTD internal subset, internal
+    dtd: entity::Dtd,
+    /// The notation of elements without content, internal
+    void_style: VoidStyle,
 }
 
 impl XMLWriter {
@@ -377,7 +1175,72 @@ impl XMLWriter {
         XMLWriter {
             content: String::new(),
             core: WriterCore::new(2),
+            namespaces: Vec::new(),
+            dtd: entity::Dtd::default(),
+            void_style: VoidStyle::default(),
+        }
+    }
+
+    /// Returns a new XMLWriter with the profile taken from the environment, see ```WriterProfile::from_environment()```
+    pub fn from_environment() -> Result<XMLWriter, WriterError> {
+        let mut wr = XMLWriter::new();
+        wr.apply_profile(&WriterProfile::from_environment()?);
+        Ok(wr)
+    }
+
+    // Writes the start-tag of the element, whose name is checked already
+    pub(crate) fn write_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.core.begin_element("open_tag")?;
+        self.core.layout_open(&mut self.content, tag, false);
+        self.core.align_entry(&self.content);
+        self.core.mark_tag_start(self.content.len());
+        self.content.push('<');
+        self.content.push_str(tag);
+        self.content.push('>');
+        self.core.block_stack.push(tag.to_string());
+        self.core.align_open_block();
+        Ok(())
+    }
+
+    // Writes the element without content, whose name is checked already
+    pub(crate) fn write_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.core.begin_element("single_tag")?;
+        self.core.layout_open(&mut self.content, tag, true);
+        self.core.align_entry(&self.content);
+        self.content.push('<');
+        self.content.push_str(tag);
+        self.content.push_str(self.void_style.tag_end());
+        Ok(())
+    }
+
+    // Adds the property with the value as it is to the last start-tag
+    pub(crate) fn write_property(
+        &mut self,
+        name: &str,
+        value: &str,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        if !tag::is_xml_name(name) {
+            return Err(WriterError::InvalidXmlName(name.to_string()));
         }
+        self.core.begin_any_property(operation)?;
+        push_property(&mut self.core, &mut self.content, name, value);
+        Ok(())
+    }
+
+    // Adds the properties to the last start-tag, the values are escaped if wanted
+    pub(crate) fn write_properties(
+        &mut self,
+        properties: &Properties,
+        escape: bool,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        if let Some((name, _)) = properties.iter().find(|(name, _)| !tag::is_xml_name(name)) {
+            return Err(WriterError::InvalidAttributeName(name.to_string()));
+        }
+        self.core.begin_any_property(operation)?;
+        push_properties(&mut self.core, &mut self.content, properties, escape);
+        Ok(())
     }
 }
 
@@ -388,34 +1251,45 @@ impl Default for XMLWriter {
 }
 
 impl MLLWriter for XMLWriter {
-    /// Accepts only ASCII-lowercase for the name-attribute
+    /// Accepts only ASCII-lowercase letters and digits, an empty tag is an ```InvalidTagName```
     fn open_tag(&mut self, tag: &str) {
-        assert_html_notation(tag);
-        self.content.push('<');
-        self.content.push_str(tag);
-        self.content.push('>');
-        self.core.block_stack.push(tag.to_string());
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        check_html_notation(tag)?;
+        self.write_open_tag(tag)
     }
 
     fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
-        assert_html_notation(tag);
         self.open_tag(tag);
         self.add_property(prop, value);
     }
 
     fn close_tag(&mut self) {
-        let tag = self.core.block_stack.pop().unwrap();
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        let tag = self.core.end_element("close_tag")?;
+        let depth = self.core.block_stack.len();
+        self.namespaces.retain(|(_, d)| *d <= depth);
+        self.core.align_close_block(&mut self.content);
+        self.core.layout_close(&mut self.content, &tag);
         self.content.push_str("</");
         self.content.push_str(&tag);
         self.content.push('>');
+        Ok(())
     }
 
-    /// Accepts only ASCII-lowercase for the name-attribute
+    /// Accepts only ASCII-lowercase letters and digits, an empty tag is an ```InvalidTagName```
     fn single_tag(&mut self, tag: &str) {
-        assert_html_notation(tag);
-        self.content.push('<');
-        self.content.push_str(tag);
-        self.content.push('>');
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        check_html_notation(tag)?;
+        self.write_single_tag(tag)
     }
 
     fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
@@ -425,39 +1299,91 @@ impl MLLWriter for XMLWriter {
 
     /// Accepts only ASCII-lowercase for the name-attribute
     fn add_property(&mut self, name: &str, value: &str) {
-        assert_html_notation(name);
-        // First we remove the '>' of the last entry
-        self.content.pop();
-        // Then add the property-value-pair and close the tag again after insertion
-        self.content.push(' ');
-        self.content.push_str(name);
-        self.content.push_str("=\"");
-        self.content.push_str(value);
-        self.content.push_str("\">");
+        check(self.try_add_property(name, value));
+    }
+
+    /// The value is escaped, see ```add_property_raw()``` for pre-escaped values
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        let value = self.core.ascii_markup(escape_attr(value));
+        self.write_property(name, &value, "add_property")
     }
 
     fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("add_comment")?;
+        self.core.layout_node(&mut self.content);
         self.content.push_str("<!-- ");
-        self.content.push_str(comment);
+        let comment = self.core.line_breaks(comment);
+        self.content.push_str(&comment);
         self.content.push_str(" -->");
+        Ok(())
     }
 
-    fn add_properties(&mut self, properties: &Property) {
-        // First we remove the '>' of the last entry
-        self.content.pop();
-        // Then, we add our property-string
-        properties.p.iter().for_each(|x| {
-            self.content
-                .push_str(&(" ".to_string() + &x.0 + "=\"" + &x.1 + "\""))
-        });
-        // Finally, we close the tag again
-        self.content.push('>');
+    /// The values are escaped, see ```add_properties_raw()``` for pre-escaped values
+    fn add_properties(&mut self, properties: &Properties) {
+        check(self.write_properties(properties, true, "add_properties"));
+    }
+
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.core.begin_text("text")?;
+        self.content
+            .push_str(&self.core.ascii_markup(escape_html_text(text)));
+        Ok(())
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("raw")?;
+        self.content.push_str(raw);
+        Ok(())
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        check(self.core.begin_markup("file_banner"));
+        self.core.layout_node(&mut self.content);
+        let indent = self.core.indent.clone();
+        let newline = self.core.newline();
+        banner::write_banner(
+            &mut self.content,
+            newline,
+            &indent,
+            lines,
+            style,
+            "<!-- ",
+            " -->",
+        );
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.add_comment(&provenance::provenance_comment(
+            tool,
+            version,
+            timestamp_policy,
+        ));
     }
 
     fn line_feed(&mut self, n: usize) {
         self.core.line_feed(&mut self.content, n);
     }
 
+    fn blank_line(&mut self) {
+        self.ensure_blank_lines(1);
+    }
+
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.core.ensure_blank_lines(&mut self.content, n);
+    }
+
     fn line_feed_inc(&mut self) {
         self.core.line_feed_inc(&mut self.content);
     }
@@ -482,9 +1408,24 @@ impl MLLWriter for XMLWriter {
         self.core.set_indent_step_size(indent_step_size);
     }
 
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.core.apply_profile(profile);
+        self.core.apply_layout_profile(profile);
+        if let Some(void_style) = profile.void_style {
+            self.void_style = void_style;
+        }
+    }
+
+    fn end_document(&mut self) {
+        check(self.core.end_document("end_document"));
+    }
+
     fn clear(&mut self) {
         self.core.clear(2);
         self.content.clear();
+        self.namespaces.clear();
+        self.dtd = entity::Dtd::default();
+        self.void_style = VoidStyle::default();
     }
 }
 
@@ -502,16 +1443,28 @@ impl std::fmt::Display for XMLWriter {
 }
 
 impl std::fmt::Write for XMLWriter {
+    /// Markup (starting with '<') and whitespace can be written everywhere, other text only where
+    /// the document allows text content
     fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        let checked = if s.trim().is_empty() || s.trim_start().starts_with('<') {
+            self.core.begin_markup("write_str")
+        } else {
+            self.core.begin_text("write_str")
+        };
+        checked.map_err(|_| std::fmt::Error)?;
         self.content.write_str(s)
     }
 
     fn write_char(&mut self, c: char) -> Result<(), std::fmt::Error> {
-        self.content.write_char(c)
+        self.write_str(c.encode_utf8(&mut [0; 4]))
     }
 
     fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> Result<(), std::fmt::Error> {
-        self.content.write_fmt(args)
+        // Format the whole string first, otherwise a declaration would be checked in pieces
+        match args.as_str() {
+            Some(s) => self.write_str(s),
+            None => self.write_str(&args.to_string()),
+        }
     }
 }
 
@@ -528,6 +1481,18 @@ pub struct JSONWriter {
     pub core: WriterCore,
     /// Counter for comments, interal
     comment_cnt: usize,
+    /// Number of blank lines to be written before the next property or block, internal
+    blank_lines: usize,
+    /// Number of blocks opened implicitly by ```set_path()```, internal
+    path_blocks: usize,
+    /// Recorded structure for ```schema()```, if enabled, internal
+    schema: Option<schema::SchemaRecorder>,
+    /// Depths of the open blocks, which are arrays, internal
+    arrays: Vec<usize>,
+    /// Dialect of the content, see ```set_dialect()```, internal
+    dialect: JsonDialect,
+    /// Comments waiting for the place of the next member, internal
+    comments: Vec<String>,
 }
 
 impl Default for JSONWriter {
@@ -543,20 +1508,137 @@ impl JSONWriter {
             content: String::new(),
             core: WriterCore::new(2),
             comment_cnt: 0,
+            blank_lines: 0,
+            path_blocks: 0,
+            schema: None,
+            arrays: Vec::new(),
+            dialect: JsonDialect::Json,
+            comments: Vec::new(),
+        }
+    }
+
+    /// Returns a new JSONWriter with the profile taken from the environment, see ```WriterProfile::from_environment()```
+    pub fn from_environment() -> Result<JSONWriter, WriterError> {
+        let mut wr = JSONWriter::new();
+        wr.apply_profile(&WriterProfile::from_environment()?);
+        Ok(wr)
+    }
+
+    /// Opens an array, which is closed by ```close_tag()```. Like blocks, a nested array needs a property name,
+    /// but the items of an array have none.
+    pub fn open_array(&mut self, name: &str) {
+        check(self.try_open_array(name));
+    }
+
+    /// Like ```open_array()```, but returns the error instead of panicking
+    pub fn try_open_array(&mut self, name: &str) -> Result<(), WriterError> {
+        self.open_block(name, '[', "open_array")?;
+        self.arrays.push(self.core.block_stack.len());
+        if let Some(schema) = &mut self.schema {
+            schema.open_array(name);
         }
+        Ok(())
+    }
+
+    /// Adds an item to the current array, the value is written as it is, e.g. ```"\"text\""``` or ```5```
+    pub fn add_item(&mut self, value: &str) {
+        check(self.try_add_item(value));
+    }
+
+    /// Like ```add_item()```, but returns the error instead of panicking
+    pub fn try_add_item(&mut self, value: &str) -> Result<(), WriterError> {
+        self.write_item(value, "add_item")
+    }
+
+    // Writes the value as it is as item of the current array
+    fn write_item(&mut self, value: &str, operation: &'static str) -> Result<(), WriterError> {
+        self.close_paths();
+        self.core.begin_member(operation)?;
+        if !self.in_array() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation,
+                reason: "items can only be added to an array",
+            });
+        }
+        self.prepare_property_write();
+        self.content.push_str(value);
+        Ok(())
+    }
+
+    // Returns true if the current block is an array
+    fn in_array(&self) -> bool {
+        self.arrays.last() == Some(&self.core.block_stack.len())
+    }
+
+    // Opens a block or an array, the items of an array have no name
+    fn open_block(
+        &mut self,
+        tag: &str,
+        open: char,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        self.close_paths();
+        if self.core.state == DocumentState::Prolog && !tag.is_empty() {
+            return Err(illegal_json_tag(
+                self.core.state,
+                operation,
+                "the root block cannot have a name",
+            ));
+        }
+        if self.core.state == DocumentState::InElement && self.in_array() != tag.is_empty() {
+            let reason = if tag.is_empty() {
+                "a nested block needs a property name"
+            } else {
+                "the items of an array have no property name"
+            };
+            return Err(illegal_json_tag(self.core.state, operation, reason));
+        }
+        let root = self.core.state == DocumentState::Prolog;
+        self.core.begin_element(operation)?;
+        self.core.block_stack.push(tag.to_string());
+        self.core.align_open_block();
+        self.core.state = DocumentState::InElement;
+        // The root block follows the prolog (e.g. a banner) directly
+        if !root {
+            self.prepare_property_write();
+        }
+        if !tag.is_empty() {
+            let key = self.key(tag);
+            self.content.push_str(&key);
+            self.content.push(':');
+            if !self.core.minified {
+                self.content.push_str(self.core.newline());
+                self.content.push_str(&self.core.indent);
+            }
+        }
+        self.content.push(open);
+        Ok(())
     }
 
     // This method checks the current ending and does correct line-feed, ether with indent-increment or with comma
     fn prepare_property_write(&mut self) {
+        let blank_lines = self
+            .core
+            .newline()
+            .repeat(std::mem::take(&mut self.blank_lines));
         // Check the current ending
-        if self.content.ends_with('{') {
-            // if it is a '{' add a line-feed with indent-increment
+        if self.content.ends_with('{') || self.content.ends_with('[') {
+            // if it is a '{' or '[' add a line-feed with indent-increment
+            if !self.core.minified {
+                self.content.push_str(&blank_lines);
+            }
             self.line_feed_inc();
         } else if !self.content.is_empty() {
             // there must be at least one property, so separate them by a comma
-            self.content.push_str(",\n");
-            self.content.push_str(&self.core.indent);
+            self.content.push(',');
+            if !self.core.minified {
+                self.content.push_str(&blank_lines);
+                self.content.push_str(self.core.newline());
+                self.content.push_str(&self.core.indent);
+            }
         }
+        self.push_comments();
     }
 }
 
@@ -564,17 +1646,17 @@ impl JSONWriter {
 // writes only the '{' and nothing else. add_property() writes only the property. If a line feed or indent
 // is needed, the method checks the current ending and adds this task before adding the true task.
 impl MLLWriter for JSONWriter {
+    /// Inside of an array the block has no name
     fn open_tag(&mut self, tag: &str) {
-        self.prepare_property_write();
-        if !tag.is_empty() {
-            self.content.push('\"');
-            self.content.push_str(tag);
-            self.content.push_str("\":\n");
-            self.content.push_str(&self.core.indent);
-            self.content.push('{');
-        } else {
-            self.content.push('{');
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.open_block(tag, '{', "open_tag")?;
+        if let Some(schema) = &mut self.schema {
+            schema.open(tag);
         }
+        Ok(())
     }
 
     fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
@@ -582,13 +1664,44 @@ impl MLLWriter for JSONWriter {
         self.add_property(prop, value);
     }
 
+    /// Closes the current block or array
     fn close_tag(&mut self) {
-        self.core.line_feed_dec(&mut self.content);
-        self.content.push('}');
+        check(self.try_close_tag());
     }
 
-    fn single_tag(&mut self, _tag: &str) {
-        panic!("there is no single_element in the JSONWriter");
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        self.close_paths();
+        let close = if self.in_array() {
+            self.arrays.pop();
+            ']'
+        } else {
+            '}'
+        };
+        self.core.end_element("close_tag")?;
+        if let Some(schema) = &mut self.schema {
+            schema.close();
+        }
+        self.core.align_close_block(&mut self.content);
+        self.end_members();
+        // The indent of an empty block was never incremented
+        if self.content.ends_with('{') || self.content.ends_with('[') {
+            self.core.line_feed(&mut self.content, 1);
+        } else {
+            self.core.line_feed_dec(&mut self.content);
+        }
+        self.content.push(close);
+        Ok(())
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, _tag: &str) -> Result<(), WriterError> {
+        Err(WriterError::UnsupportedOperation {
+            operation: "single_tag",
+            reason: "there is no single_element in the JSONWriter",
+        })
     }
 
     fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
@@ -597,32 +1710,129 @@ impl MLLWriter for JSONWriter {
     }
 
     fn add_property(&mut self, name: &str, value: &str) {
+        check(self.try_add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.close_paths();
+        self.core.begin_member("add_property")?;
+        if self.in_array() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "add_property",
+                reason: "the items of an array have no property name, see add_item()",
+            });
+        }
+        if let Some(schema) = &mut self.schema {
+            schema.property(name, value);
+        }
         self.prepare_property_write();
-        self.content.push('\"');
-        self.content.push_str(name);
-        self.content.push_str("\": ");
+        self.core.align_entry(&self.content);
+        let key = self.key(name);
+        self.content.push_str(&key);
+        self.content.push(':');
+        self.core.align_split(self.content.len());
+        if !self.core.minified {
+            self.content.push(' ');
+        }
         self.content.push_str(value);
+        Ok(())
     }
 
-    fn add_properties(&mut self, properties: &Property) {
+    fn add_properties(&mut self, properties: &Properties) {
         properties
             .p
             .iter()
             .for_each(|x| self.add_property(&x.0, &x.1));
     }
 
+    /// Writes a '_comment'-property in JSON, and a real comment in JSONC and JSON5, see ```set_dialect()```
     fn add_comment(&mut self, comment: &str) {
-        // Increase the comment counter before, because we init it with zero
+        check(self.try_add_comment(comment));
+    }
+
+    /// In plain JSON only objects can have comments, because they are properties
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        if self.dialect != JsonDialect::Json {
+            self.core.begin_markup("add_comment")?;
+            self.write_comment(comment);
+            return Ok(());
+        }
+        if self.core.block_stack.is_empty() || self.in_array() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "add_comment",
+                reason: "a comment of plain JSON is a property, which needs an open object",
+            });
+        }
+        // The comments are numbered from one, the counter only counts the written ones
+        let prop = "_comment".to_string() + &(self.comment_cnt + 1).to_string();
+        let value = self.string(comment);
+        // Comments are no part of the schema
+        let schema = self.schema.take();
+        let result = self.try_add_property(&prop, &value);
+        self.schema = schema;
+        result?;
         self.comment_cnt += 1;
-        let prop = "_comment".to_string() + &self.comment_cnt.to_string();
-        let value = "\"".to_string() + comment + "\"";
-        self.add_property(&prop, &value);
+        Ok(())
+    }
+
+    /// Writes the text as string-item of the current array, outside of arrays text needs a property name
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.write_item(&self.string(text), "text")
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("raw")?;
+        self.content.push_str(raw);
+        Ok(())
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        if self.core.state != DocumentState::Prolog {
+            check(Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "file_banner",
+                reason: "a banner can only be written in front of the root block",
+            }))
+        }
+        let newline = self.core.newline();
+        banner::write_banner(&mut self.content, newline, "", lines, style, "// ", "");
+        self.content.push_str(newline);
+    }
+
+    /// Writes a '_meta'-block into the current block
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.open_tag("_meta");
+        self.add_property("generator", &self.string(tool));
+        self.add_property("version", &self.string(version));
+        if let Some(timestamp) = timestamp_policy.timestamp() {
+            self.add_property("timestamp", &self.string(&timestamp));
+        }
+        self.close_tag();
     }
 
     fn line_feed(&mut self, n: usize) {
         self.core.line_feed(&mut self.content, n);
     }
 
+    fn blank_line(&mut self) {
+        self.ensure_blank_lines(1);
+    }
+
+    /// The blank lines are written in front of the next property or block
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.blank_lines = self.blank_lines.max(n);
+    }
+
     fn line_feed_inc(&mut self) {
         self.core.line_feed_inc(&mut self.content);
     }
@@ -647,9 +1857,28 @@ impl MLLWriter for JSONWriter {
         self.core.set_indent_step_size(indent_step_size);
     }
 
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.core.apply_profile(profile);
+        self.set_format_mode(if profile.minify {
+            FormatMode::Minified
+        } else {
+            FormatMode::Compact
+        });
+    }
+
+    fn end_document(&mut self) {
+        check(self.core.end_document("end_document"));
+    }
+
     fn clear(&mut self) {
         self.core.clear(2);
         self.content.clear();
+        self.blank_lines = 0;
+        self.path_blocks = 0;
+        self.schema = None;
+        self.arrays.clear();
+        self.dialect = JsonDialect::Json;
+        self.comments.clear();
     }
 }
 
@@ -681,24 +1910,115 @@ impl std::fmt::Write for JSONWriter {
 }
 
 // ================================================================================================
-fn assert_html_notation(tag: &str) {
-    assert!(tag.chars().all(|c| c.is_ascii_alphanumeric()));
-    assert!(tag
-        .chars()
-        .filter(|c| c.is_ascii_alphabetic())
-        .all(|c| c.is_lowercase()));
+// Panics with the descriptive message of the error, if an operation was rejected by the state machine
+fn check<T>(result: Result<T, WriterError>) -> T {
+    result.unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn illegal_json_tag(
+    state: DocumentState,
+    operation: &'static str,
+    reason: &'static str,
+) -> WriterError {
+    WriterError::IllegalTransition {
+        state,
+        operation,
+        reason,
+    }
+}
+
+// Inserts the property-value-pair in front of the '>' of the last start-tag
+fn push_property(core: &mut WriterCore, content: &mut String, name: &str, value: &str) {
+    if core.state != DocumentState::InStartTag {
+        let property = core.property_string(name, value);
+        core.insert_into_open_tag(content, &property);
+        return;
+    }
+    // First we remove the '>' of the last entry, or '/>' of a self-closing one
+    let tag_end = void::last_tag_end(content);
+    content.truncate(content.len() - tag_end.len());
+    // Then add the property-value-pair and close the tag again after insertion
+    core.align_split(content.len());
+    content.push_str(&core.property_string(name, value));
+    content.push_str(tag_end);
+}
+
+// Inserts the properties in front of the '>' of the last start-tag
+fn push_properties(
+    core: &mut WriterCore,
+    content: &mut String,
+    properties: &Properties,
+    escape: bool,
+) {
+    if core.state != DocumentState::InStartTag {
+        let mut text = String::new();
+        for (name, value) in properties.iter() {
+            let value = if escape {
+                core.ascii_markup(escape_attr(value))
+            } else {
+                value.to_string()
+            };
+            text.push_str(&core.property_string(name, &value));
+        }
+        core.insert_into_open_tag(content, &text);
+        return;
+    }
+    // First we remove the '>' of the last entry, or '/>' of a self-closing one
+    let tag_end = void::last_tag_end(content);
+    content.truncate(content.len() - tag_end.len());
+    // Then, we add our property-string
+    for (name, value) in &properties.p {
+        core.align_split(content.len());
+        let value = if escape {
+            core.ascii_markup(escape_attr(value))
+        } else {
+            value.clone()
+        };
+        content.push_str(&core.property_string(name, &value));
+    }
+    // Finally, we close the tag again
+    content.push_str(tag_end);
+}
+
+// Checks that the name of a tag isn't empty and has only ASCII-lowercase letters and digits
+fn check_html_notation(name: &str) -> Result<(), WriterError> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        Ok(())
+    } else {
+        Err(WriterError::InvalidTagName(name.to_string()))
+    }
+}
+
+// Checks the name of an HTML-attribute: ASCII-lowercase letters, digits and inner '-', e.g. "data-id" or
+// "aria-label"
+pub(crate) fn check_attribute_name(name: &str) -> Result<(), WriterError> {
+    if name.starts_with(|c: char| c.is_ascii_lowercase())
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        Ok(())
+    } else {
+        Err(WriterError::InvalidAttributeName(name.to_string()))
+    }
 }
 
 // ================================================================================================
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fmt::Write;
 
     // ============================================================================================
-    // Tests for the WriterCore and the Property-struct
+    // Tests for the WriterCore and the Properties-struct
     #[test]
     fn property_basic() {
-        let mut prop = Property::new("class", "superhero");
+        let mut prop = Properties::new("class", "superhero");
         assert_eq!(prop.p[0], ("class".to_string(), "superhero".to_string()));
 
         prop.add("style", "width: auto");
@@ -781,7 +2101,7 @@ mod tests {
 
     #[test]
     fn html_property_string() {
-        let mut properties = Property::new("class", "container");
+        let mut properties = Properties::new("class", "container");
         properties.add("style", "width: auto");
         let mut wr = HTMLWriter::new();
         wr.single_tag("img");
@@ -797,6 +2117,188 @@ mod tests {
         assert_eq!(wr.content, "<img style=\"width: auto\">");
     }
 
+    #[test]
+    fn html_state_machine() {
+        let mut wr = HTMLWriter::new();
+        assert_eq!(wr.core.state(), DocumentState::Prolog);
+        wr.open_tag("p");
+        assert_eq!(wr.core.state(), DocumentState::InStartTag);
+        wr.close_tag();
+        assert_eq!(wr.core.state(), DocumentState::AfterRoot);
+        // HTML-fragments may have several top-level elements
+        wr.single_tag("br");
+        wr.end_document();
+        assert_eq!(wr.core.state(), DocumentState::Finished);
+        assert_eq!(wr.content, "<p></p><br>");
+    }
+
+    #[test]
+    #[should_panic(expected = "properties can only be added directly after a start-tag")]
+    fn html_property_after_line_feed() {
+        let mut wr = HTMLWriter::new();
+        // Aligned properties can't be spliced into the start-tag afterwards
+        wr.core.set_align_properties(true);
+        wr.open_tag("div");
+        wr.line_feed_inc();
+        wr.add_property("class", "container");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid nesting of 'div' in 'body > p'")]
+    fn html_strict_block_in_paragraph() {
+        let mut wr = HTMLWriter::new();
+        wr.set_strict(true);
+        wr.open_tag("body");
+        wr.open_tag("p");
+        wr.open_tag("div");
+    }
+
+    #[test]
+    fn html_strict_nesting() {
+        let mut wr = HTMLWriter::new();
+        wr.set_strict(true);
+        wr.open_tag("html");
+        wr.open_tag("body");
+        wr.open_tag("ul");
+        wr.open_tag("li");
+        assert_eq!(wr.core.open_path(), "html > body > ul > li");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            html_rules::check_nesting("li", &wr.core.block_stack, 1),
+            Err(WriterError::InvalidNesting {
+                tag: "li".to_string(),
+                open_path: "html > body".to_string(),
+                reason: "'li' is only allowed inside of 'ul', 'ol' or 'menu'",
+            })
+        );
+        assert!(html_rules::check_nesting("body", &[], 1).is_err());
+        wr.close_tag();
+        wr.close_tag();
+        wr.end_document();
+    }
+
+    #[test]
+    fn html_apply_profile() {
+        let mut wr = HTMLWriter::new();
+        wr.apply_profile(&WriterProfile::named("html5-strict").unwrap());
+        assert_eq!(wr.validation, ValidationLevel::Strict);
+        assert!(!wr.implicit_close);
+        assert!(WriterProfile::named("html4").is_none());
+        for name in WriterProfile::NAMES {
+            assert_eq!(WriterProfile::named(name).unwrap().name, name);
+        }
+    }
+
+    #[test]
+    fn html_auto_layout() {
+        let mut wr = HTMLWriter::new();
+        wr.core.set_auto_layout(true);
+        wr.core.layout_rules().add_no_indent("html");
+        wr.core.layout_rules().add_inline("b");
+        wr.open_tag("html");
+        wr.open_tag("body");
+        wr.add_comment("content");
+        wr.open_tag("p");
+        wr.write_str("Hello ").unwrap();
+        wr.open_tag("b");
+        wr.write_str("World").unwrap();
+        wr.close_tag();
+        wr.close_tag();
+        wr.single_tag("br");
+        wr.open_tag("div");
+        wr.close_tag();
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<html>\n<body>\n    <!-- content -->\n    <p>Hello <b>World</b></p>\n    <br>\n    <div></div>\n</body>\n</html>"
+        );
+    }
+
+    #[test]
+    fn html_blank_lines() {
+        let mut wr = HTMLWriter::new();
+        wr.open_tag("div");
+        wr.line_feed_inc();
+        wr.single_tag("br");
+        wr.blank_line();
+        wr.ensure_blank_lines(1);
+        wr.single_tag("hr");
+        wr.ensure_blank_lines(2);
+        wr.blank_line();
+        wr.line_feed_dec();
+        wr.close_tag();
+        assert_eq!(wr.content, "<div>\n    <br>\n\n    <hr>\n\n\n\n</div>");
+
+        wr.clear();
+        wr.core.set_auto_layout(true);
+        wr.open_tag("div");
+        wr.single_tag("br");
+        wr.blank_line();
+        wr.blank_line();
+        wr.single_tag("hr");
+        wr.close_tag();
+        assert_eq!(wr.content, "<div>\n    <br>\n\n    <hr>\n</div>");
+    }
+
+    #[test]
+    fn html_implicit_close() {
+        let mut wr = HTMLWriter::new();
+        wr.set_implicit_close(true);
+        wr.set_strict(true);
+        wr.open_tag("p");
+        wr.open_tag("b");
+        wr.open_tag("p");
+        wr.close_tag();
+        wr.open_tag("ul");
+        wr.open_tag("li");
+        wr.open_tag("ol");
+        wr.open_tag("li");
+        wr.close_tag();
+        wr.close_tag();
+        wr.open_tag("li");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<p><b></b></p><p></p><ul><li><ol><li></li></ol></li><li></li></ul>"
+        );
+        assert!(wr.core.block_stack.is_empty());
+
+        // An open 'p' is closed by the next item of a list
+        wr.clear();
+        wr.set_implicit_close(true);
+        wr.open_tag("ul");
+        wr.open_tag("li");
+        wr.open_tag("p");
+        wr.text("a");
+        wr.open_tag("li");
+        wr.close_tag();
+        wr.close_tag();
+        wr.open_tag("dl");
+        wr.open_tag("dt");
+        wr.open_tag("p");
+        wr.open_tag("dd");
+        wr.open_tag("dt");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<ul><li><p>a</p></li><li></li></ul><dl><dt><p></p></dt><dd></dd><dt></dt></dl>"
+        );
+
+        // A rejected element doesn't close the 'p' implicitly
+        wr.clear();
+        wr.set_implicit_close(true);
+        wr.open_tag("span");
+        wr.open_tag("p");
+        wr.set_validation_level(ValidationLevel::Html5);
+        assert!(wr.try_open_tag("div").is_err());
+        assert_eq!(wr.content, "<span><p>");
+        assert_eq!(wr.core.block_stack, vec!["span", "p"]);
+    }
+
     // ============================================================================================
     // Tests for the XMLWriter
     #[test]
@@ -854,7 +2356,7 @@ mod tests {
 
     #[test]
     fn xml_property_string() {
-        let mut properties = Property::new("class", "container");
+        let mut properties = Properties::new("class", "container");
         properties.add("style", "width: auto");
         let mut wr = XMLWriter::new();
         wr.single_tag("img");
@@ -870,6 +2372,45 @@ mod tests {
         assert_eq!(wr.content, "<img style=\"width: auto\">");
     }
 
+    #[test]
+    #[should_panic(expected = "a document can only have one root element")]
+    fn xml_two_root_elements() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("root");
+        wr.close_tag();
+        wr.open_tag("root");
+    }
+
+    #[test]
+    #[should_panic(expected = "the root element is already closed")]
+    fn xml_property_after_root_close() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("root");
+        wr.close_tag();
+        wr.add_property("id", "1");
+    }
+
+    #[test]
+    fn xml_text_in_prolog() {
+        let mut wr = XMLWriter::new();
+        let version = "1.0";
+        assert!(write!(wr, "<?xml version=\"{}\"?>", version).is_ok());
+        assert!(write!(wr, "some text").is_err());
+        wr.open_tag("root");
+        assert!(write!(wr, "some text").is_ok());
+        wr.close_tag();
+        assert_eq!(wr.content, "<?xml version=\"1.0\"?><root>some text</root>");
+
+        wr.end_document();
+        assert_eq!(
+            wr.core.end_element("close_tag"),
+            Err(WriterError::UnbalancedClose {
+                state: DocumentState::Finished,
+                operation: "close_tag",
+            })
+        );
+    }
+
     // ============================================================================================
     #[test]
     #[should_panic(expected = "there is no single_element in the JSONWriter")]
@@ -878,6 +2419,34 @@ mod tests {
         wr.single_tag("img");
     }
 
+    #[test]
+    fn fallible_operations() {
+        let mut wr = HTMLWriter::new();
+        assert!(matches!(
+            wr.try_close_tag(),
+            Err(WriterError::UnbalancedClose { .. })
+        ));
+        assert_eq!(
+            wr.try_open_tag("Div"),
+            Err(WriterError::InvalidTagName("Div".to_string()))
+        );
+        assert_eq!(
+            XMLWriter::new().try_single_tag(""),
+            Err(WriterError::InvalidTagName(String::new()))
+        );
+        assert!(wr.try_add_property("id", "a").is_err());
+        assert!(wr.try_open_tag("div").is_ok());
+        assert!(wr.try_close_tag().is_ok());
+        assert_eq!(wr.content, "<div></div>");
+        let mut wr = JSONWriter::new();
+        assert!(matches!(
+            wr.try_single_tag("img"),
+            Err(WriterError::UnsupportedOperation { .. })
+        ));
+        assert!(wr.try_open_tag("name").is_err());
+        assert!(wr.content.is_empty());
+    }
+
     #[test]
     fn json_dual_elements() {
         let mut wr = JSONWriter::new();
@@ -890,6 +2459,49 @@ mod tests {
         assert_eq!(wr.content, "{\n  \"Name\": \"Mustermann\"");
     }
 
+    #[test]
+    fn json_arrays() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.open_array("ids");
+        wr.add_item("1");
+        wr.open_tag("");
+        wr.add_property("a", "true");
+        wr.close_tag();
+        wr.close_tag();
+        wr.add_property("n", "0");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"ids\":\n  [\n    1,\n    {\n      \"a\": true\n    }\n  ],\n  \"n\": 0\n}"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn json_property_in_array() {
+        let mut wr = JSONWriter::new();
+        wr.open_array("");
+        wr.add_property("a", "1");
+    }
+
+    #[test]
+    fn json_comments() {
+        let mut wr = JSONWriter::new();
+        assert!(wr.try_add_comment("").is_err());
+        wr.open_tag("");
+        wr.add_comment("a \"b\"");
+        wr.open_array("c");
+        assert!(wr.try_add_comment("d").is_err());
+        wr.close_tag();
+        wr.add_comment("e");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"_comment1\": \"a \\\"b\\\"\",\n  \"c\":\n  [\n  ],\n  \"_comment2\": \"e\"\n}"
+        );
+    }
+
     #[test]
     fn json_mixed_entries() {
         let mut wr = JSONWriter::new();
@@ -908,7 +2520,7 @@ mod tests {
 
     #[test]
     fn json_property_string() {
-        let mut properties = Property::new("Name", "\"Eberhardt\"");
+        let mut properties = Properties::new("Name", "\"Eberhardt\"");
         properties.add("Alter", "35");
         let mut wr = JSONWriter::new();
         wr.open_tag("");
@@ -922,6 +2534,76 @@ mod tests {
         wr.clear();
         assert_eq!(wr.content, "");
     }
+
+    #[test]
+    fn json_blank_lines() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("Name", "\"Eberhardt\"");
+        wr.blank_line();
+        wr.blank_line();
+        wr.add_property("Alter", "35");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"Name\": \"Eberhardt\",\n\n  \"Alter\": 35\n}"
+        );
+    }
+
+    #[test]
+    fn json_align_properties() {
+        let mut wr = JSONWriter::new();
+        wr.core.set_align_properties(true);
+        wr.open_tag("");
+        wr.add_property("Name", "\"Eberhardt\"");
+        wr.open_tag("Daten");
+        wr.add_property("Geburtstag", "\"03.10.1985\"");
+        wr.add_property("Ort", "\"Ulm\"");
+        wr.close_tag();
+        wr.add_property("Alter", "35");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"Name\":  \"Eberhardt\",\n  \"Daten\":\n  {\n    \"Geburtstag\": \"03.10.1985\",\n    \"Ort\":        \"Ulm\"\n  },\n  \"Alter\": 35\n}"
+        );
+    }
+
+    #[test]
+    fn json_file_banner() {
+        let mut wr = JSONWriter::new();
+        wr.file_banner(&["Generated by mllwriter", "v0.1"], BannerStyle::Framed);
+        wr.open_tag("");
+        wr.add_property("Name", "\"Eberhardt\"");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "// ======================\n// Generated by mllwriter\n// v0.1\n// ======================\n{\n  \"Name\": \"Eberhardt\"\n}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no open block to add the property to")]
+    fn json_property_before_root() {
+        let mut wr = JSONWriter::new();
+        wr.add_property("Name", "\"Eberhardt\"");
+    }
+
+    #[test]
+    #[should_panic(expected = "the root block cannot have a name")]
+    fn json_named_root() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("Daten");
+    }
+
+    #[test]
+    #[should_panic(expected = "the root block is already closed")]
+    fn json_property_after_root_close() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.close_tag();
+        assert_eq!(wr.core.state(), DocumentState::AfterRoot);
+        wr.add_property("Name", "\"Eberhardt\"");
+    }
 }
 
 // ================================================================================================
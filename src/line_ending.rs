@@ -0,0 +1,84 @@
+//! The line-feeds written by the writers.
+//!
+//! The writers write '\n' by default. ```wr.core.set_line_ending()``` switches every line-feed of a writer to
+//! "\r\n", e.g. for files consumed by tools on Windows: the explicit and the automatic line-feeds, the separators
+//! of JSON, YAML and TOML, and the lines of banners and comments, also for content streamed into a sink. Text and
+//! raw markup are written as they are, ```finish()``` converts their line-feeds as well.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, LineEnding, MLLWriter};
+//! let mut wr = JSONWriter::new();
+//! wr.core.set_line_ending(LineEnding::CrLf);
+//! wr.open_tag("");
+//! wr.add_property("a", "1");
+//! wr.add_property("b", "2");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "{\r\n  \"a\": 1,\r\n  \"b\": 2\r\n}");
+//! ```
+
+use crate::WriterCore;
+
+/// The line-feeds of the content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LineEnding {
+    /// '\n', e.g. of Unix
+    #[default]
+    Lf,
+    /// "\r\n", e.g. of tools on Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the characters of the line-feed
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+impl WriterCore {
+    /// Sets the line-feeds written from now on
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    // The characters of a line-feed
+    pub(crate) fn newline(&self) -> &'static str {
+        self.line_ending.as_str()
+    }
+
+    // Returns the text, e.g. of a comment, with its line-feeds written as the ones of the writer
+    pub(crate) fn line_breaks(&self, text: &str) -> String {
+        text.replace("\r\n", "\n").replace('\n', self.newline())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HTMLWriter, MLLWriter};
+
+    #[test]
+    fn crlf_auto_layout() {
+        let mut wr = HTMLWriter::new();
+        wr.core.set_line_ending(LineEnding::CrLf);
+        wr.core.set_auto_layout(true);
+        wr.open_tag("ul");
+        wr.open_tag("li");
+        wr.close_tag();
+        wr.ensure_blank_lines(1);
+        wr.open_tag("li");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<ul>\r\n    <li></li>\r\n\r\n    <li></li>\r\n</ul>"
+        );
+        wr.add_comment("a\nb");
+        assert!(wr.content.ends_with("<!-- a\r\nb -->"));
+    }
+}
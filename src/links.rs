@@ -0,0 +1,277 @@
+//! The integrity of the internal links of a [`DocumentSet`](crate::DocumentSet).
+//!
+//! ```check_links()``` is the pass at the end of the generation. It checks each "href" and "src" of all pages,
+//! which is relative, absolute to the site or starts with the base URL: the target has to be a page, an asset
+//! (see ```add_asset()```) or a feed of the set, and a fragment has to be the id of an element of the target
+//! page. Links to other sites, e.g. "https://" or "mailto:", aren't checked.
+//!
+//! ```
+//! # use mllwriter::{DocumentSet, HTMLWriter, MLLWriter};
+//! let mut site = DocumentSet::new("https://example.com/");
+//! let mut page = HTMLWriter::new();
+//! page.open_tag_w_property("h1", "id", "top");
+//! page.close_tag();
+//! page.open_tag_w_property("a", "href", "#top");
+//! page.close_tag();
+//! page.open_tag_w_property("a", "href", "docs/intro.html");
+//! page.close_tag();
+//! site.add_page("index.html", page);
+//! let broken = site.check_links();
+//! assert_eq!(broken.len(), 1);
+//! assert_eq!(
+//!     broken[0].to_string(),
+//!     "index.html:1: href=\"docs/intro.html\": there is no such page or asset"
+//! );
+//! ```
+
+use crate::escape::unescape;
+use crate::retroactive::tag_end;
+use crate::DocumentSet;
+
+/// A link, which doesn't point at a page, an asset or an id of the set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The path of the page with the link
+    pub page: String,
+    /// The line of the link in the page, starting at 1
+    pub line: usize,
+    /// The attribute, "href" or "src"
+    pub attribute: String,
+    /// The value of the attribute
+    pub target: String,
+    /// Describes why the link is broken
+    pub reason: &'static str,
+}
+
+impl std::fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}:{}: {}=\"{}\": {}",
+            self.page, self.line, self.attribute, self.target, self.reason
+        )
+    }
+}
+
+impl DocumentSet {
+    /// Returns the broken internal links of all pages, in the order of the pages and their lines
+    pub fn check_links(&self) -> Vec<BrokenLink> {
+        let pages: Vec<(String, Vec<String>)> = self
+            .pages()
+            .map(|(path, wr)| (normalize(path), attribute_values(&wr.content, "id")))
+            .collect();
+        let files: Vec<String> = self.files().map(normalize).collect();
+        let mut broken = Vec::new();
+        for (path, wr) in self.pages() {
+            for attribute in ["href", "src"] {
+                for (offset, target) in attribute_positions(&wr.content, attribute) {
+                    let Some(reason) = self.check_link(path, &target, &pages, &files) else {
+                        continue;
+                    };
+                    broken.push(BrokenLink {
+                        page: path.to_string(),
+                        line: wr.content[..offset].matches('\n').count() + 1,
+                        attribute: attribute.to_string(),
+                        target,
+                        reason,
+                    });
+                }
+            }
+        }
+        broken.sort_by(|a, b| (&a.page, a.line).cmp(&(&b.page, b.line)));
+        broken
+    }
+
+    // Returns the reason, if the link of the page is broken
+    fn check_link(
+        &self,
+        page: &str,
+        target: &str,
+        pages: &[(String, Vec<String>)],
+        files: &[String],
+    ) -> Option<&'static str> {
+        let target = match target.strip_prefix(self.base_url()) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.to_string(),
+            _ if is_external(target) => return None,
+            _ => target.to_string(),
+        };
+        let (path, fragment) = match target.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (target.as_str(), None),
+        };
+        let path = path.split('?').next().unwrap_or_default();
+        let resolved = if path.is_empty() {
+            normalize(page)
+        } else {
+            resolve(page, path)
+        };
+        let ids = pages
+            .iter()
+            .find(|(p, _)| *p == resolved || *p == format!("{}/index.html", resolved))
+            .or_else(|| {
+                pages
+                    .iter()
+                    .find(|(p, _)| resolved.is_empty() && p == "index.html")
+            })
+            .map(|(_, ids)| ids);
+        match (ids, fragment) {
+            (Some(ids), Some(fragment)) if !fragment.is_empty() => {
+                if ids.iter().any(|id| id == fragment) {
+                    None
+                } else {
+                    Some("the page has no element with this id")
+                }
+            }
+            (Some(_), _) => None,
+            (None, _) if files.contains(&resolved) => None,
+            (None, _) => Some("there is no such page or asset"),
+        }
+    }
+}
+
+// Returns true for links with a scheme or to another host, e.g. "mailto:" or "//cdn.example.com"
+fn is_external(target: &str) -> bool {
+    if target.starts_with("//") {
+        return true;
+    }
+    match target.find(':') {
+        Some(colon) => !target[..colon].contains(['/', '?', '#']),
+        None => false,
+    }
+}
+
+// Returns the path without leading and trailing '/' and without the segments "." and ".."
+fn normalize(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+// Resolves the link relative to the directory of the page, links with a leading '/' are absolute
+fn resolve(page: &str, link: &str) -> String {
+    if link.starts_with('/') {
+        return normalize(link);
+    }
+    let directory = match page.rfind('/') {
+        Some(i) => &page[..i],
+        None => "",
+    };
+    normalize(&format!("{}/{}", directory, link))
+}
+
+// Returns the values of the attribute in the content
+fn attribute_values(content: &str, attribute: &str) -> Vec<String> {
+    attribute_positions(content, attribute)
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect()
+}
+
+// Returns the offsets and the unescaped values of the attribute in the start-tags of the content, the values can be
+// in double or single quotes, see QuoteStyle. Text and the values of other attributes are skipped.
+fn attribute_positions(content: &str, attribute: &str) -> Vec<(usize, String)> {
+    let mut positions = Vec::new();
+    for start in tag_starts(content) {
+        let tag = &content[start..start + tag_end(&content[start..])];
+        let mut rest = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        while let Some(found) = tag[rest..].find(|c: char| !c.is_whitespace() && c != '/') {
+            let name_start = rest + found;
+            let name_end = tag[name_start..]
+                .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/'))
+                .map_or(tag.len(), |end| name_start + end);
+            rest = name_end;
+            let Some(value_start) = tag[rest..].strip_prefix('=').map(|_| rest + 1) else {
+                continue;
+            };
+            let (value, value_end) = match tag[value_start..].chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = tag[value_start + 1..]
+                        .find(quote)
+                        .map_or(tag.len(), |end| value_start + 1 + end);
+                    (&tag[value_start + 1..end], (end + 1).min(tag.len()))
+                }
+                _ => {
+                    let end = tag[value_start..]
+                        .find(char::is_whitespace)
+                        .map_or(tag.len(), |end| value_start + end);
+                    (&tag[value_start..end], end)
+                }
+            };
+            if &tag[name_start..name_end] == attribute {
+                positions.push((start + name_start, unescape(value)));
+            }
+            rest = value_end;
+        }
+    }
+    positions
+}
+
+// Returns the offsets of the start-tags of the content, i.e. of each '<' followed by the letter of a tag
+fn tag_starts(content: &str) -> impl Iterator<Item = usize> + '_ {
+    content.match_indices('<').map(|(i, _)| i).filter(|i| {
+        content[i + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HTMLWriter, MLLWriter, QuoteStyle};
+
+    #[test]
+    fn links_across_pages() {
+        let mut site = DocumentSet::new("https://example.com");
+        site.add_asset("/img/logo.png");
+        let mut index = HTMLWriter::new();
+        index.core.set_auto_layout(true);
+        index.core.set_quote_style(QuoteStyle::Single);
+        for href in [
+            "docs/",
+            "docs/a.html#intro",
+            "https://example.com/docs/a.html#missing",
+            "mailto:a@example.com",
+            "https://other.org/x",
+        ] {
+            index.open_tag_w_property("a", "href", href);
+            index.close_tag();
+        }
+        index.single_tag_w_property("img", "src", "img/logo.png");
+        index.single_tag_w_property("img", "src", "img/none.png");
+        let mut docs = HTMLWriter::new();
+        docs.open_tag_w_property("a", "href", "../index.html");
+        docs.add_property("title", "see href='none.html'");
+        docs.text("see href=\"none.html\"");
+        docs.close_tag();
+        let mut a = HTMLWriter::new();
+        a.open_tag_w_property("section", "id", "intro");
+        a.close_tag();
+        site.add_page("index.html", index);
+        site.add_page("docs/index.html", docs);
+        site.add_page("docs/a.html", a);
+        let broken: Vec<(usize, &str)> = site
+            .check_links()
+            .iter()
+            .map(|b| (b.line, b.reason))
+            .collect();
+        assert_eq!(
+            broken,
+            vec![
+                (3, "the page has no element with this id"),
+                (7, "there is no such page or asset")
+            ]
+        );
+    }
+}
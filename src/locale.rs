@@ -0,0 +1,127 @@
+//! Locale-aware numbers and dates for human-facing output (feature ```num-format```).
+//!
+//! A locale set on the [`FormatterRegistry`] applies the decimal- and thousands-separators of the locale to
+//! numbers without a registered formatter, and the date format of the locale to dates. Only the human-facing
+//! methods of the HTMLWriter (```add_typed_property()```, ```add_typed_text()``` and ```add_date_property()```)
+//! use it. The XMLWriter and the JSONWriter stay locale-independent, so the same registry can be used for a
+//! report and its machine-readable export.
+//!
+//! ```
+//! # use mllwriter::{FormatterRegistry, HTMLWriter, Locale, MLLWriter, XMLWriter};
+//! # use std::sync::Arc;
+//! let mut formatters = FormatterRegistry::new();
+//! formatters.set_locale(Locale::de);
+//! let formatters = Arc::new(formatters);
+//!
+//! let mut html = HTMLWriter::new();
+//! html.core.set_formatters(formatters.clone());
+//! html.open_tag("td");
+//! html.add_typed_text(&1234567.5);
+//! html.close_tag();
+//! assert_eq!(html.content, "<td>1.234.567,5</td>");
+//!
+//! let mut xml = XMLWriter::new();
+//! xml.core.set_formatters(formatters);
+//! xml.single_tag("value");
+//! xml.add_typed_property("amount", &1234567.5);
+//! xml.add_date_property("date", 0);
+//! assert_eq!(xml.content, "<value amount=\"1234567.5\" date=\"1970-01-01\">");
+//! ```
+
+use num_format::Grouping;
+pub use num_format::Locale;
+
+use crate::format::{display_builtin, group_digits, is_number};
+use crate::provenance::civil_date;
+use crate::FormatterRegistry;
+use std::any::Any;
+
+impl FormatterRegistry {
+    /// Sets the locale for the human-facing output
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = Some(locale);
+    }
+
+    /// Returns the locale for the human-facing output, if one is set
+    pub fn locale(&self) -> Option<Locale> {
+        self.locale
+    }
+}
+
+// Returns the formatted value with the separators of the locale, or None if it isn't a number
+pub(crate) fn localize_number<T: Any>(locale: Locale, value: &T) -> Option<String> {
+    if !is_number::<T>() {
+        return None;
+    }
+    let text = display_builtin(value)?;
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text.as_str()),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    // inf and NaN are kept as they are
+    if !integer.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut localized = String::new();
+    if negative {
+        localized.push_str(locale.minus_sign());
+    }
+    let (first, next) = match locale.grouping() {
+        Grouping::Standard => (3, 3),
+        Grouping::Indian => (3, 2),
+        Grouping::Posix => (0, 0),
+    };
+    localized.push_str(&group_digits(integer, first, next, locale.separator()));
+    if let Some(fraction) = fraction {
+        localized.push_str(locale.decimal());
+        localized.push_str(fraction);
+    }
+    Some(localized)
+}
+
+// Formats the date in the typical short form of the locale, e.g. "31.12.2026" in German
+pub(crate) fn localize_date(locale: Locale, secs: u64) -> String {
+    let (year, month, day) = civil_date(secs);
+    let name = locale.name();
+    let language = name.split('-').next().unwrap_or(name);
+    match language {
+        "en" if name == "en" || name == "en-US" => format!("{}/{}/{}", month, day, year),
+        "en" | "fr" | "es" | "it" | "pt" | "el" | "ca" => {
+            format!("{:02}/{:02}/{}", day, month, year)
+        }
+        "de" | "ru" | "pl" | "cs" | "fi" | "nb" | "da" | "tr" | "uk" => {
+            format!("{:02}.{:02}.{}", day, month, year)
+        }
+        "nl" => format!("{:02}-{:02}-{}", day, month, year),
+        "ja" | "zh" => format!("{}/{:02}/{:02}", year, month, day),
+        _ => format!("{}-{:02}-{:02}", year, month, day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_numbers_and_dates() {
+        assert_eq!(
+            localize_number(Locale::en, &-1234567).unwrap(),
+            "-1,234,567"
+        );
+        assert_eq!(
+            localize_number(Locale::en_IN, &12345678).unwrap(),
+            "1,23,45,678"
+        );
+        assert_eq!(localize_number(Locale::de, &0.25).unwrap(), "0,25");
+        assert_eq!(localize_number(Locale::en, &f64::NAN), None);
+        assert_eq!(localize_number(Locale::en, &"1234"), None);
+        assert_eq!(localize_date(Locale::en, 1792152245), "10/16/2026");
+        assert_eq!(localize_date(Locale::en_GB, 1792152245), "16/10/2026");
+        assert_eq!(localize_date(Locale::de, 1792152245), "16.10.2026");
+        assert_eq!(localize_date(Locale::sv, 1792152245), "2026-10-16");
+    }
+}
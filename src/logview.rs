@@ -0,0 +1,201 @@
+//! Incident reports from structured log records.
+//!
+//! ```HTMLWriter::log_viewer()``` writes the records as a table, which can be filtered by level with a row of
+//! radio-buttons. The filter is pure CSS, so the report works without scripts. ```log_ndjson()``` writes the
+//! same records as NDJSON (one JSON-object per line) with the JSONWriter, as machine-readable companion.
+//!
+//! ```
+//! # use mllwriter::{log_ndjson, HTMLWriter, LogRecord};
+//! let records = vec![
+//!     LogRecord::new("INFO", "2026-10-16T12:00:00Z", "started"),
+//!     LogRecord::new("ERROR", "2026-10-16T12:00:05Z", "disk full").with_field("disk", "/dev/sda1"),
+//! ];
+//! let mut wr = HTMLWriter::new();
+//! wr.log_viewer(&records);
+//! assert_eq!(
+//!     log_ndjson(&records),
+//!     "{\"timestamp\":\"2026-10-16T12:00:00Z\",\"level\":\"INFO\",\"message\":\"started\"}\n\
+//!      {\"timestamp\":\"2026-10-16T12:00:05Z\",\"level\":\"ERROR\",\"message\":\"disk full\",\"disk\":\"/dev/sda1\"}\n"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::format::json_string;
+use crate::{escape_html_text, FormatMode, HTMLWriter, JSONWriter, MLLWriter};
+
+/// A structured log record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The level, e.g. "ERROR" or "info"
+    pub level: String,
+    /// The formatted timestamp
+    pub timestamp: String,
+    /// The message
+    pub message: String,
+    /// Additional fields as name-value-pairs
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    /// Returns a record without fields
+    pub fn new(level: &str, timestamp: &str, message: &str) -> LogRecord {
+        LogRecord {
+            level: level.to_string(),
+            timestamp: timestamp.to_string(),
+            message: message.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Returns the record with an additional field
+    pub fn with_field(mut self, name: &str, value: &str) -> LogRecord {
+        self.fields.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // The level in the notation of classes and ids, e.g. "error"
+    fn level_class(&self) -> String {
+        self.level
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+}
+
+/// Returns the records as NDJSON, each record as JSON-object on its own line
+pub fn log_ndjson<'a, I: IntoIterator<Item = &'a LogRecord>>(records: I) -> String {
+    let mut ndjson = String::new();
+    for record in records {
+        let mut wr = JSONWriter::new();
+        wr.set_format_mode(FormatMode::Minified);
+        wr.open_tag("");
+        wr.add_property("timestamp", &json_string(&record.timestamp));
+        wr.add_property("level", &json_string(&record.level));
+        wr.add_property("message", &json_string(&record.message));
+        for (name, value) in &record.fields {
+            wr.add_property(name, &json_string(value));
+        }
+        wr.close_tag();
+        ndjson.push_str(&wr.content);
+        ndjson.push('\n');
+    }
+    ndjson
+}
+
+impl HTMLWriter {
+    /// Writes the records as table with a filter by level
+    pub fn log_viewer<'a, I: IntoIterator<Item = &'a LogRecord>>(&mut self, records: I) {
+        let records: Vec<&LogRecord> = records.into_iter().collect();
+        let mut levels: Vec<String> = Vec::new();
+        for record in &records {
+            let level = record.level_class();
+            if !levels.contains(&level) {
+                levels.push(level);
+            }
+        }
+        self.open_tag_w_property("div", "class", "log-viewer");
+        self.log_filter("all", true);
+        for level in &levels {
+            self.log_filter(level, false);
+        }
+        // Each checked filter hides all rows of the other levels
+        let css: String = levels
+            .iter()
+            .map(|l| {
+                format!(
+                    "#log-{0}:checked ~ table tbody tr:not(.{0}) {{ display: none; }} ",
+                    l
+                )
+            })
+            .collect();
+        self.open_tag("style");
+        self.write_str(css.trim_end())
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+
+        self.open_tag_w_property("table", "class", "log");
+        self.open_tag("thead");
+        self.open_tag("tr");
+        for h in ["Time", "Level", "Message", "Fields"] {
+            self.log_cell("th", h);
+        }
+        self.close_tag();
+        self.close_tag();
+        self.open_tag("tbody");
+        for record in records {
+            self.open_tag_w_property("tr", "class", &record.level_class());
+            self.log_cell("td", &record.timestamp);
+            self.log_cell("td", &record.level);
+            self.log_cell("td", &record.message);
+            let fields: Vec<String> = record
+                .fields
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            self.log_cell("td", &fields.join(" "));
+            self.close_tag();
+        }
+        self.close_tag();
+        self.close_tag();
+        self.close_tag();
+    }
+
+    fn log_filter(&mut self, level: &str, checked: bool) {
+        let id = format!("log-{}", level);
+        self.single_tag_w_property("input", "type", "radio");
+        self.add_property("name", "level");
+        self.add_property("id", &id);
+        if checked {
+            self.add_property("checked", "checked");
+        }
+        self.open_tag_w_property("label", "for", &id);
+        self.write_str(level)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+    }
+
+    fn log_cell(&mut self, tag: &str, text: &str) {
+        self.open_tag(tag);
+        if !text.is_empty() {
+            self.write_str(&escape_html_text(text))
+                .expect("text inside of a cell is always allowed");
+        }
+        self.close_tag();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_ndjson_escapes() {
+        let records = [LogRecord::new("INFO", "t", "a\n  b").with_field("x\"y", "1")];
+        assert_eq!(
+            log_ndjson(&records),
+            "{\"timestamp\":\"t\",\"level\":\"INFO\",\"message\":\"a\\n  b\",\"x\\\"y\":\"1\"}\n"
+        );
+    }
+
+    #[test]
+    fn log_viewer_filter() {
+        let records = [
+            LogRecord::new("WARN", "t1", "a < b"),
+            LogRecord::new("warn", "t2", "again"),
+        ];
+        let mut wr = HTMLWriter::new();
+        wr.log_viewer(&records);
+        assert_eq!(
+            wr.content,
+            "<div class=\"log-viewer\">\
+             <input type=\"radio\" name=\"level\" id=\"log-all\" checked=\"checked\"><label for=\"log-all\">all</label>\
+             <input type=\"radio\" name=\"level\" id=\"log-warn\"><label for=\"log-warn\">warn</label>\
+             <style>#log-warn:checked ~ table tbody tr:not(.warn) { display: none; }</style>\
+             <table class=\"log\"><thead><tr><th>Time</th><th>Level</th><th>Message</th><th>Fields</th></tr></thead>\
+             <tbody><tr class=\"warn\"><td>t1</td><td>WARN</td><td>a &lt; b</td><td></td></tr>\
+             <tr class=\"warn\"><td>t2</td><td>warn</td><td>again</td><td></td></tr></tbody></table></div>"
+        );
+    }
+}
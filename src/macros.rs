@@ -0,0 +1,262 @@
+//! Declarative macros, which write nested structures by the calls of a writer.
+//!
+//! ```html!()``` and ```xml!()``` expand their nodes to ```open_tag()```, ```add_property()```, ```text()``` and
+//! ```close_tag()``` of the HTMLWriter or XMLWriter, so the content is checked and escaped like any other:
+//!
+//! * ```div { ... }``` writes an element with children, ```img;``` an element without content.
+//! * ```div.card.wide``` adds classes, ```label(for = id, "title" = 7)``` adds attributes, their names are
+//!   identifiers, also keywords, or string literals, the values are any ```Display```.
+//! * ```"text"``` and ```(expr)``` write escaped text, ```@raw(expr)``` pre-escaped markup.
+//! * ```@if (cond) { ... } else { ... }``` and ```@for (pattern in iter) { ... }``` are the control flow.
+//!
+//! ```json!()``` writes the root block of a JSONWriter: ```"name": value``` adds a member, ```{ ... }``` and
+//! ```[ ... ]``` are nested blocks and arrays, other values are converted into a ```JsonValue```. Members and
+//! items are separated by commas, ```@if``` and ```@for``` work like in markup.
+//!
+//! Like the writer methods, the macros panic on illegal operations. Very deep structures may need a higher
+//! ```#![recursion_limit]```.
+//!
+//! ```
+//! # use mllwriter::{html, json, HTMLWriter, JSONWriter};
+//! let items = ["a", "b & c"];
+//! let mut wr = HTMLWriter::new();
+//! html!(wr, {
+//!     ul.list(id = "items") {
+//!         @for (item in items) {
+//!             li { (item) }
+//!         }
+//!         @if (items.is_empty()) { li { "none" } }
+//!     }
+//!     hr;
+//! });
+//! assert_eq!(
+//!     wr.content,
+//!     "<ul class=\"list\" id=\"items\"><li>a</li><li>b &amp; c</li></ul><hr>"
+//! );
+//!
+//! let mut wr = JSONWriter::new();
+//! json!(wr, {
+//!     "name": "mllwriter",
+//!     "tags": [@for (tag in items) { tag }],
+//!     "meta": { "stable": true, "parent": null },
+//! });
+//! assert_eq!(
+//!     wr.content,
+//!     "{\n  \"name\": \"mllwriter\",\n  \"tags\":\n  [\n    \"a\",\n    \"b & c\"\n  ],\n  \"meta\":\n  {\n    \
+//!      \"stable\": true,\n    \"parent\": null\n  }\n}"
+//! );
+//! ```
+
+/// Writes the nodes by the HTMLWriter, see the module macros
+#[macro_export]
+macro_rules! html {
+    ($wr:expr, { $($nodes:tt)* }) => {{
+        let wr: &mut $crate::HTMLWriter = &mut $wr;
+        $crate::__mll_markup!(wr; $($nodes)*);
+    }};
+}
+
+/// Writes the nodes by the XMLWriter, see the module macros
+#[macro_export]
+macro_rules! xml {
+    ($wr:expr, { $($nodes:tt)* }) => {{
+        let wr: &mut $crate::XMLWriter = &mut $wr;
+        $crate::__mll_markup!(wr; $($nodes)*);
+    }};
+}
+
+/// Writes the members as root block by the JSONWriter, see the module macros
+#[macro_export]
+macro_rules! json {
+    ($wr:expr, { $($members:tt)* }) => {{
+        let wr: &mut $crate::JSONWriter = &mut $wr;
+        $crate::MLLWriter::open_tag(&mut *wr, "");
+        $crate::__mll_json!(@members wr; $($members)*);
+        $crate::MLLWriter::close_tag(&mut *wr);
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mll_markup {
+    ($wr:ident;) => {};
+    ($wr:ident; @if ($cond:expr) { $($then:tt)* } else { $($else:tt)* } $($rest:tt)*) => {
+        if $cond {
+            $crate::__mll_markup!($wr; $($then)*);
+        } else {
+            $crate::__mll_markup!($wr; $($else)*);
+        }
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; @if ($cond:expr) { $($then:tt)* } $($rest:tt)*) => {
+        if $cond {
+            $crate::__mll_markup!($wr; $($then)*);
+        }
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; @for ($pat:pat in $iter:expr) { $($body:tt)* } $($rest:tt)*) => {
+        for $pat in $iter {
+            $crate::__mll_markup!($wr; $($body)*);
+        }
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; @raw ($raw:expr) $($rest:tt)*) => {
+        $crate::MLLWriter::raw(&mut *$wr, &::std::string::ToString::to_string(&$raw));
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; $text:literal $($rest:tt)*) => {
+        $crate::MLLWriter::text(&mut *$wr, &::std::string::ToString::to_string(&$text));
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; ($text:expr) $($rest:tt)*) => {
+        $crate::MLLWriter::text(&mut *$wr, &::std::string::ToString::to_string(&$text));
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; $tag:ident $(. $class:ident)* $(($($attrs:tt)*))? { $($body:tt)* } $($rest:tt)*) => {
+        $crate::MLLWriter::open_tag(&mut *$wr, stringify!($tag));
+        $crate::__mll_markup!(@classes $wr; $($class)*);
+        $($crate::__mll_markup!(@attrs $wr; $($attrs)*);)?
+        $crate::__mll_markup!($wr; $($body)*);
+        $crate::MLLWriter::close_tag(&mut *$wr);
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    ($wr:ident; $tag:ident $(. $class:ident)* $(($($attrs:tt)*))? ; $($rest:tt)*) => {
+        $crate::MLLWriter::single_tag(&mut *$wr, stringify!($tag));
+        $crate::__mll_markup!(@classes $wr; $($class)*);
+        $($crate::__mll_markup!(@attrs $wr; $($attrs)*);)?
+        $crate::__mll_markup!($wr; $($rest)*);
+    };
+    (@classes $wr:ident;) => {};
+    (@classes $wr:ident; $($class:ident)+) => {
+        $crate::MLLWriter::add_property(&mut *$wr, "class", &[$(stringify!($class)),+].join(" "));
+    };
+    (@attrs $wr:ident;) => {};
+    (@attrs $wr:ident; $name:tt = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::MLLWriter::add_property(
+            &mut *$wr,
+            $crate::__mll_json!(@name $name),
+            &::std::string::ToString::to_string(&$value),
+        );
+        $crate::__mll_markup!(@attrs $wr; $($($rest)*)?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mll_json {
+    (@name $name:ident) => {
+        stringify!($name)
+    };
+    (@name $name:literal) => {
+        $name
+    };
+    (@members $wr:ident;) => {};
+    (@members $wr:ident; @if ($cond:expr) { $($then:tt)* } else { $($else:tt)* } $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::__mll_json!(@members $wr; $($then)*);
+        } else {
+            $crate::__mll_json!(@members $wr; $($else)*);
+        }
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; @if ($cond:expr) { $($then:tt)* } $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::__mll_json!(@members $wr; $($then)*);
+        }
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; @for ($pat:pat in $iter:expr) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        for $pat in $iter {
+            $crate::__mll_json!(@members $wr; $($body)*);
+        }
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; $name:tt : { $($members:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::MLLWriter::open_tag(&mut *$wr, $crate::__mll_json!(@name $name));
+        $crate::__mll_json!(@members $wr; $($members)*);
+        $crate::MLLWriter::close_tag(&mut *$wr);
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; $name:tt : [ $($items:tt)* ] $(, $($rest:tt)*)?) => {
+        $wr.open_array($crate::__mll_json!(@name $name));
+        $crate::__mll_json!(@items $wr; $($items)*);
+        $crate::MLLWriter::close_tag(&mut *$wr);
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; $name:tt : null $(, $($rest:tt)*)?) => {
+        $wr.add_value($crate::__mll_json!(@name $name), $crate::JsonValue::Null);
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@members $wr:ident; $name:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $wr.add_value($crate::__mll_json!(@name $name), $crate::JsonValue::from($value));
+        $crate::__mll_json!(@members $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident;) => {};
+    (@items $wr:ident; @if ($cond:expr) { $($then:tt)* } else { $($else:tt)* } $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::__mll_json!(@items $wr; $($then)*);
+        } else {
+            $crate::__mll_json!(@items $wr; $($else)*);
+        }
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; @if ($cond:expr) { $($then:tt)* } $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::__mll_json!(@items $wr; $($then)*);
+        }
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; @for ($pat:pat in $iter:expr) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        for $pat in $iter {
+            $crate::__mll_json!(@items $wr; $($body)*);
+        }
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; { $($members:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::MLLWriter::open_tag(&mut *$wr, "");
+        $crate::__mll_json!(@members $wr; $($members)*);
+        $crate::MLLWriter::close_tag(&mut *$wr);
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; [ $($items:tt)* ] $(, $($rest:tt)*)?) => {
+        $wr.open_array("");
+        $crate::__mll_json!(@items $wr; $($items)*);
+        $crate::MLLWriter::close_tag(&mut *$wr);
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; null $(, $($rest:tt)*)?) => {
+        $wr.add_item_value($crate::JsonValue::Null);
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+    (@items $wr:ident; $value:expr $(, $($rest:tt)*)?) => {
+        $wr.add_item_value($crate::JsonValue::from($value));
+        $crate::__mll_json!(@items $wr; $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MLLWriter, XMLWriter};
+
+    #[test]
+    fn xml_macro_control_flow() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("doc");
+        let points = [(1, "a"), (2, "b")];
+        xml!(wr, {
+            @for ((x, name) in points) {
+                @if (x > 1) {
+                    point(x = x, for = name, "y" = 'y');
+                } else {
+                    @raw("<!-- first -->")
+                }
+            }
+            value { 5 }
+        });
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<doc><!-- first --><point x=\"2\" for=\"b\" y=\"y\"><value>5</value></doc>"
+        );
+    }
+}
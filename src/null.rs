@@ -0,0 +1,93 @@
+//! Dry runs, which validate and count the bytes of a document without keeping it.
+//!
+//! A [`NullWriter`] is a ```SinkWriter``` into a [`ByteCounter`]: all operations are validated like in the real
+//! writer, but the content is dropped after each operation, only its length is counted. So the generation logic
+//! can be checked cheaply, or the Content-Length of a response computed before the real streaming pass. The
+//! length is the one of the finished document, i.e. with the [`FinalFormat`](crate::FinalFormat) applied.
+//!
+//! ```
+//! # use mllwriter::{BufferedWriter, HTMLWriter, MLLWriter};
+//! fn page<M: MLLWriter>(wr: &mut M) {
+//!     wr.open_tag("p");
+//!     wr.text("Größe");
+//!     wr.close_tag();
+//! }
+//! let mut dry = HTMLWriter::dry_run();
+//! page(&mut dry);
+//! assert_eq!(dry.content_length(), 14);
+//! let mut wr = HTMLWriter::new();
+//! page(&mut wr);
+//! assert_eq!(wr.content.len(), 14);
+//! ```
+
+use std::io::Write;
+
+use crate::{BufferedWriter, SinkWriter};
+
+/// A sink, which drops the bytes and counts them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteCounter {
+    bytes: usize,
+}
+
+impl ByteCounter {
+    /// Returns the number of bytes written so far
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer, which validates the operations and counts the bytes, but drops the content
+pub type NullWriter<M> = SinkWriter<M, ByteCounter>;
+
+impl<M: BufferedWriter> NullWriter<M> {
+    /// Returns the length of the document so far in bytes, as ```finish()``` of the wrapped writer would return it
+    pub fn content_length(&mut self) -> usize {
+        self.sink().bytes() + self.rest().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FinalFormat, HTMLWriter, JSONWriter, MLLWriter};
+
+    #[test]
+    fn dry_run_counts_the_finished_document() {
+        let mut dry = JSONWriter::dry_run();
+        dry.open_tag("");
+        dry.add_property("a", "1");
+        assert_eq!(dry.content_length(), "{\n  \"a\": 1".len());
+        dry.close_tag();
+        let mut dry = HTMLWriter::dry_run();
+        dry.writer().core.set_final_format(FinalFormat {
+            bom: true,
+            ..FinalFormat::posix()
+        });
+        dry.open_tag("p");
+        dry.text("a  ");
+        dry.line_feed(1);
+        dry.close_tag();
+        assert_eq!(dry.content_length(), "\u{feff}<p>a\n</p>\n".len());
+    }
+
+    #[test]
+    #[should_panic(expected = "unbalanced close_tag() in state AfterRoot")]
+    fn dry_run_validates() {
+        let mut dry = JSONWriter::dry_run();
+        dry.open_tag("");
+        dry.close_tag();
+        dry.close_tag();
+    }
+}
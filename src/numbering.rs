@@ -0,0 +1,201 @@
+//! Numbered figures, tables and sections with a list of them.
+//!
+//! ```wr.figure()```, ```wr.numbered_table()``` and ```wr.numbered_section()``` count their kind of element, and
+//! give it the number in its caption and an anchor id, e.g. "figure-2". The content is written by the closure.
+//!
+//! The list of a kind is usually placed in front of the elements, so it is written in two passes:
+//! ```wr.list_of()``` marks the place of the list, and ```wr.resolve_lists()``` replaces the marks by the lists,
+//! when all elements are written.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, NumberedKind};
+//! let mut wr = HTMLWriter::new();
+//! wr.list_of(NumberedKind::Figure);
+//! let id = wr.figure("Sales & costs", |wr| wr.single_tag_w_property("img", "src", "sales.png"));
+//! assert_eq!(id, "figure-1");
+//! wr.resolve_lists();
+//! assert_eq!(
+//!     wr.content,
+//!     "<ol class=\"list-of-figures\"><li><a href=\"#figure-1\">Figure 1: Sales &amp; costs</a></li></ol>\
+//!      <figure id=\"figure-1\"><img src=\"sales.png\"><figcaption>Figure 1: Sales &amp; costs</figcaption></figure>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{check, escape_html_text, HTMLWriter, MLLWriter};
+
+/// The kinds of numbered elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberedKind {
+    /// 'figure'-elements with a 'figcaption'
+    Figure,
+    /// 'table'-elements with a 'caption'
+    Table,
+    /// 'section'-elements with a heading
+    Section,
+}
+
+impl NumberedKind {
+    /// Returns the label in front of the number, e.g. "Figure"
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberedKind::Figure => "Figure",
+            NumberedKind::Table => "Table",
+            NumberedKind::Section => "Section",
+        }
+    }
+
+    // The prefix of the anchor ids and the name of the list
+    fn name(self) -> &'static str {
+        match self {
+            NumberedKind::Figure => "figure",
+            NumberedKind::Table => "table",
+            NumberedKind::Section => "section",
+        }
+    }
+
+    // The mark of the list, which is replaced by resolve_lists()
+    fn mark(self) -> String {
+        format!("<!--mllwriter:list-of-{}s-->", self.name())
+    }
+}
+
+// The numbered elements of a document with their kind, number and caption
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Numbering {
+    entries: Vec<(NumberedKind, usize, String)>,
+}
+
+impl Numbering {
+    // Counts the element and returns its number
+    fn next(&mut self, kind: NumberedKind, caption: &str) -> usize {
+        let number = self.entries.iter().filter(|(k, _, _)| *k == kind).count() + 1;
+        self.entries.push((kind, number, caption.to_string()));
+        number
+    }
+}
+
+impl HTMLWriter {
+    /// Writes a numbered figure with the content of the closure and returns its anchor id
+    pub fn figure<F>(&mut self, caption: &str, f: F) -> String
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let (id, caption) = self.number(NumberedKind::Figure, caption);
+        self.open_tag_w_property("figure", "id", &id);
+        f(self);
+        self.open_tag("figcaption");
+        self.write_str(&caption)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+        self.close_tag();
+        id
+    }
+
+    /// Writes a numbered table with its caption, the closure writes the rows, returns the anchor id
+    pub fn numbered_table<F>(&mut self, caption: &str, f: F) -> String
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let (id, caption) = self.number(NumberedKind::Table, caption);
+        self.open_tag_w_property("table", "id", &id);
+        self.open_tag("caption");
+        self.write_str(&caption)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+        f(self);
+        self.close_tag();
+        id
+    }
+
+    /// Writes a numbered section with a heading of the level, the closure writes the content, returns the anchor id
+    pub fn numbered_section<F>(&mut self, level: u8, caption: &str, f: F) -> String
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let (id, caption) = self.number(NumberedKind::Section, caption);
+        self.open_tag_w_property("section", "id", &id);
+        self.open_tag(&format!("h{}", level.clamp(1, 6)));
+        self.write_str(&caption)
+            .expect("text inside of an element is always allowed");
+        self.close_tag();
+        f(self);
+        self.close_tag();
+        id
+    }
+
+    /// Marks the place of the list of the kind, see ```resolve_lists()```
+    pub fn list_of(&mut self, kind: NumberedKind) {
+        check(self.core.begin_markup("list_of"));
+        self.core.layout_node(&mut self.content);
+        self.content.push_str(&kind.mark());
+    }
+
+    /// Replaces the marks of the lists by the lists of the numbered elements, which are written so far
+    pub fn resolve_lists(&mut self) {
+        for kind in [
+            NumberedKind::Figure,
+            NumberedKind::Table,
+            NumberedKind::Section,
+        ] {
+            if !self.content.contains(&kind.mark()) {
+                continue;
+            }
+            let class = format!("list-of-{}s", kind.name());
+            let mut list = format!("<ol{}>", self.core.property_string("class", &class));
+            for (_, number, caption) in self.numbering.entries.iter().filter(|(k, _, _)| *k == kind)
+            {
+                let href = format!("#{}-{}", kind.name(), number);
+                list.push_str(&format!(
+                    "<li><a{}>{}</a></li>",
+                    self.core.property_string("href", &href),
+                    caption_text(kind, *number, caption)
+                ));
+            }
+            list.push_str("</ol>");
+            self.content = self.content.replace(&kind.mark(), &list);
+            self.core.thaw();
+        }
+    }
+
+    // Counts the element and returns its anchor id and its escaped caption
+    fn number(&mut self, kind: NumberedKind, caption: &str) -> (String, String) {
+        let number = self.numbering.next(kind, caption);
+        (
+            format!("{}-{}", kind.name(), number),
+            caption_text(kind, number, caption),
+        )
+    }
+}
+
+// The escaped caption with the label and the number
+fn caption_text(kind: NumberedKind, number: usize, caption: &str) -> String {
+    format!("{} {}: {}", kind.label(), number, escape_html_text(caption))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbering_per_kind() {
+        let mut wr = HTMLWriter::new();
+        wr.numbered_section(2, "Intro", |wr| {
+            wr.figure("A", |_| ());
+            wr.numbered_table("T", |_| ());
+            wr.figure("B", |_| ());
+        });
+        wr.list_of(NumberedKind::Table);
+        wr.resolve_lists();
+        assert!(wr.content.starts_with(
+            "<section id=\"section-1\"><h2>Section 1: Intro</h2><figure id=\"figure-1\">"
+        ));
+        assert!(wr
+            .content
+            .contains("<figure id=\"figure-2\"><figcaption>Figure 2: B</figcaption>"));
+        assert!(wr.content.ends_with(
+            "<ol class=\"list-of-tables\"><li><a href=\"#table-1\">Table 1: T</a></li></ol>"
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7396) documents.
+//!
+//! A [`JsonPatch`] collects patch-operations and writes them as array of operation-objects with the
+//! JSONWriter. The paths are given as list of reference-tokens and escaped by ```json_pointer()```, so '~' and
+//! '/' in member-names can't corrupt the pointer. A merge patch is a plain object, in which ```null``` removes
+//! a member, see ```JSONWriter::add_merge_removal()```.
+//!
+//! An operation without the value or the source path it needs is rejected by the ```try_```-variants, and by a
+//! panic of the others.
+//!
+//! ```
+//! # use mllwriter::{JsonPatch, PatchOp};
+//! let mut patch = JsonPatch::new();
+//! patch.add_patch_op(PatchOp::Replace, &["servers", "a/b"], Some("\"on\""));
+//! patch.add_patch_op(PatchOp::Remove, &["tmp~1"], None);
+//! assert_eq!(
+//!     patch.to_json().unwrap(),
+//!     "[\n  {\n    \"op\": \"replace\",\n    \"path\": \"/servers/a~1b\",\n    \"value\": \"on\"\n  },\n  \
+//!      {\n    \"op\": \"remove\",\n    \"path\": \"/tmp~01\"\n  }\n]"
+//! );
+//! ```
+
+use crate::format::json_string;
+use crate::{check, JSONWriter, LineEnding, MLLWriter, WriterError};
+
+/// The operations of a JSON Patch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchOp {
+    /// Adds the value at the path
+    Add,
+    /// Removes the value at the path
+    Remove,
+    /// Replaces the value at the path
+    Replace,
+    /// Moves the value from another path to the path
+    Move,
+    /// Copies the value from another path to the path
+    Copy,
+    /// Tests the value at the path for equality
+    Test,
+}
+
+impl PatchOp {
+    /// Returns the name of the operation, e.g. "add"
+    pub fn name(self) -> &'static str {
+        match self {
+            PatchOp::Add => "add",
+            PatchOp::Remove => "remove",
+            PatchOp::Replace => "replace",
+            PatchOp::Move => "move",
+            PatchOp::Copy => "copy",
+            PatchOp::Test => "test",
+        }
+    }
+}
+
+/// Returns the JSON Pointer of the reference-tokens, with '~' escaped as "~0" and '/' as "~1"
+pub fn json_pointer(tokens: &[&str]) -> String {
+    tokens
+        .iter()
+        .map(|token| "/".to_string() + &token.replace('~', "~0").replace('/', "~1"))
+        .collect()
+}
+
+/// A list of JSON Patch operations
+#[derive(Debug, Clone, Default)]
+pub struct JsonPatch {
+    // the operations with the escaped pointers and the raw values
+    ops: Vec<Operation>,
+    line_ending: LineEnding,
+}
+
+#[derive(Debug, Clone)]
+struct Operation {
+    op: PatchOp,
+    from: Option<String>,
+    path: String,
+    value: Option<String>,
+}
+
+impl JsonPatch {
+    /// Returns an empty patch
+    pub fn new() -> JsonPatch {
+        JsonPatch::default()
+    }
+
+    /// Sets the line-feeds of the JSON-array
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Adds an operation at the path. The value is raw JSON and is needed by "add", "replace" and "test". "move"
+    /// and "copy" need a source, see ```add_patch_from()```.
+    pub fn add_patch_op(&mut self, op: PatchOp, path: &[&str], value: Option<&str>) {
+        check(self.try_add_patch_op(op, path, value));
+    }
+
+    /// Like ```add_patch_op()```, but returns the error instead of panicking
+    pub fn try_add_patch_op(
+        &mut self,
+        op: PatchOp,
+        path: &[&str],
+        value: Option<&str>,
+    ) -> Result<(), WriterError> {
+        if matches!(op, PatchOp::Move | PatchOp::Copy) {
+            return Err(patch_error(op, "needs a source path, see add_patch_from()"));
+        }
+        if value.is_none() && op != PatchOp::Remove {
+            return Err(patch_error(op, "needs a value"));
+        }
+        self.push_op(op, None, path, value);
+        Ok(())
+    }
+
+    /// Adds a "move" or "copy" operation from the source path to the path
+    pub fn add_patch_from(&mut self, op: PatchOp, from: &[&str], path: &[&str]) {
+        check(self.try_add_patch_from(op, from, path));
+    }
+
+    /// Like ```add_patch_from()```, but returns the error instead of panicking
+    pub fn try_add_patch_from(
+        &mut self,
+        op: PatchOp,
+        from: &[&str],
+        path: &[&str],
+    ) -> Result<(), WriterError> {
+        if !matches!(op, PatchOp::Move | PatchOp::Copy) {
+            return Err(patch_error(op, "has no source path, see add_patch_op()"));
+        }
+        self.push_op(op, Some(from), path, None);
+        Ok(())
+    }
+
+    /// Returns the number of operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns true if there are no operations
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the patch as JSON-array
+    pub fn to_json(&self) -> Result<String, WriterError> {
+        if self.ops.is_empty() {
+            return Ok("[]".to_string());
+        }
+        let mut wr = JSONWriter::new();
+        wr.core.set_line_ending(self.line_ending);
+        wr.try_open_array("")?;
+        for op in &self.ops {
+            wr.try_open_tag("")?;
+            wr.try_add_property("op", &json_string(op.op.name()))?;
+            if let Some(from) = &op.from {
+                wr.try_add_property("from", &json_string(from))?;
+            }
+            wr.try_add_property("path", &json_string(&op.path))?;
+            if let Some(value) = &op.value {
+                wr.try_add_property("value", value)?;
+            }
+            wr.try_close_tag()?;
+        }
+        wr.try_close_tag()?;
+        wr.finish()
+    }
+
+    fn push_op(&mut self, op: PatchOp, from: Option<&[&str]>, path: &[&str], value: Option<&str>) {
+        self.ops.push(Operation {
+            op,
+            from: from.map(json_pointer),
+            path: json_pointer(path),
+            value: value.map(str::to_string),
+        });
+    }
+}
+
+fn patch_error(op: PatchOp, reason: &str) -> WriterError {
+    WriterError::InvalidPatch(format!("the operation '{}' {}", op.name(), reason))
+}
+
+impl JSONWriter {
+    /// Writes a member with the value ```null```, which removes the member in a JSON Merge Patch
+    pub fn add_merge_removal(&mut self, name: &str) {
+        self.add_property(name, "null");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_move_and_merge() {
+        let mut patch = JsonPatch::new();
+        assert_eq!(patch.to_json().unwrap(), "[]");
+        patch.set_line_ending(LineEnding::CrLf);
+        patch.add_patch_from(PatchOp::Move, &["a"], &[]);
+        assert_eq!(
+            patch.to_json().unwrap(),
+            "[\r\n  {\r\n    \"op\": \"move\",\r\n    \"from\": \"/a\",\r\n    \"path\": \"\"\r\n  }\r\n]"
+        );
+        assert!(matches!(
+            patch.try_add_patch_op(PatchOp::Copy, &["b"], None),
+            Err(WriterError::InvalidPatch(_))
+        ));
+        assert_eq!(json_pointer(&["", "~/"]), "//~0~1");
+
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_merge_removal("debug");
+        wr.close_tag();
+        assert_eq!(wr.content, "{\n  \"debug\": null\n}");
+    }
+
+    #[test]
+    #[should_panic(expected = "the operation 'add' needs a value")]
+    fn patch_add_without_value() {
+        JsonPatch::new().add_patch_op(PatchOp::Add, &["a"], None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Nested properties of the JSONWriter by dotted paths.
+//!
+//! ```set_path("server.tls.cert", value)``` writes the property "cert" into the block "tls" of the block
+//! "server", relative to the current block. The blocks of the path are opened as needed and stay open for the
+//! next path with the same prefix, they are closed by the next call of any other method. Paths with a common
+//! prefix have to follow each other, ```set_paths()``` groups unordered input, e.g. from environment variables,
+//! by its prefixes first.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, MLLWriter};
+//! let mut wr = JSONWriter::new();
+//! wr.open_tag("");
+//! wr.set_paths([("server.tls.cert", "\"a.pem\""), ("debug", "true"), ("server.port", "443")]);
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "{\n  \"server\":\n  {\n    \"tls\":\n    {\n      \"cert\": \"a.pem\"\n    },\n    \"port\": 443\n  },\n  \"debug\": true\n}"
+//! );
+//! ```
+
+use crate::{JSONWriter, MLLWriter};
+
+// A node of the tree of paths, with the order of the first appearance
+enum PathNode<'a> {
+    Value(&'a str),
+    Block(Vec<(&'a str, PathNode<'a>)>),
+}
+
+impl JSONWriter {
+    /// Writes the value at the dotted path relative to the current block, the blocks of the path are opened as
+    /// needed
+    pub fn set_path(&mut self, path: &str, value: &str) {
+        let mut segments: Vec<&str> = path.split('.').collect();
+        let name = segments.pop().unwrap_or_default();
+        // The blocks of the previous path are closed only where the paths differ
+        let open = std::mem::take(&mut self.path_blocks);
+        let base = self.core.block_stack.len() - open;
+        let common = self.core.block_stack[base..]
+            .iter()
+            .zip(&segments)
+            .take_while(|(open, segment)| open == *segment)
+            .count();
+        for _ in common..open {
+            self.close_tag();
+        }
+        for segment in &segments[common..] {
+            self.open_tag(segment);
+        }
+        self.add_property(name, value);
+        self.path_blocks = segments.len();
+    }
+
+    /// Writes all values at their dotted paths, the paths are grouped by their prefixes, so each block is
+    /// written once
+    pub fn set_paths<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(&mut self, paths: I) {
+        let mut root = Vec::new();
+        for (path, value) in paths {
+            insert(&mut root, path.split('.').collect(), value);
+        }
+        let mut prefix = Vec::new();
+        self.write_path_nodes(&root, &mut prefix);
+    }
+
+    fn write_path_nodes<'a>(
+        &mut self,
+        nodes: &[(&'a str, PathNode<'a>)],
+        prefix: &mut Vec<&'a str>,
+    ) {
+        for (name, node) in nodes {
+            prefix.push(name);
+            match node {
+                PathNode::Value(value) => self.set_path(&prefix.join("."), value),
+                PathNode::Block(children) => self.write_path_nodes(children, prefix),
+            }
+            prefix.pop();
+        }
+    }
+
+    // Closes the blocks opened by set_path()
+    pub(crate) fn close_paths(&mut self) {
+        for _ in 0..std::mem::take(&mut self.path_blocks) {
+            self.close_tag();
+        }
+    }
+}
+
+// Inserts the value into the tree, a later value replaces an earlier one at the same path
+fn insert<'a>(
+    nodes: &mut Vec<(&'a str, PathNode<'a>)>,
+    mut segments: Vec<&'a str>,
+    value: &'a str,
+) {
+    let name = segments.remove(0);
+    let position = nodes.iter().position(|(n, _)| *n == name);
+    if segments.is_empty() {
+        match position {
+            Some(i) => nodes[i].1 = PathNode::Value(value),
+            None => nodes.push((name, PathNode::Value(value))),
+        }
+        return;
+    }
+    let i = match position {
+        Some(i) if matches!(nodes[i].1, PathNode::Block(_)) => i,
+        Some(i) => {
+            nodes[i].1 = PathNode::Block(Vec::new());
+            i
+        }
+        None => {
+            nodes.push((name, PathNode::Block(Vec::new())));
+            nodes.len() - 1
+        }
+    };
+    if let PathNode::Block(children) = &mut nodes[i].1 {
+        insert(children, segments, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_closed_by_other_methods() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.set_path("a.b", "1");
+        wr.set_path("a.c.d", "2");
+        wr.add_property("e", "3");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"a\":\n  {\n    \"b\": 1,\n    \"c\":\n    {\n      \"d\": 2\n    }\n  },\n  \"e\": 3\n}"
+        );
+    }
+}
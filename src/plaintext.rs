@@ -0,0 +1,423 @@
+//! A plain-text version of written HTML, e.g. for the text part of a multipart email.
+//!
+//! ```wr.plain_text()``` renders the content of the HTMLWriter: blocks are separated by blank lines, headings of
+//! the first two levels are underlined, list items get a bullet or their number, and tables are aligned in
+//! columns. Links are numbered like footnotes, their URLs are listed at the end. Heads, scripts and styles are
+//! left out.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! # use std::fmt::Write;
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("h1");
+//! write!(wr, "Order shipped").unwrap();
+//! wr.close_tag();
+//! wr.open_tag("p");
+//! write!(wr, "Track it ").unwrap();
+//! wr.open_tag_w_property("a", "href", "https://example.com/track");
+//! write!(wr, "online").unwrap();
+//! wr.close_tag();
+//! write!(wr, ".").unwrap();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.plain_text(),
+//!     "Order shipped\n=============\n\nTrack it online [1].\n\n[1] https://example.com/track\n"
+//! );
+//! ```
+
+use crate::html_rules::VOID_ELEMENTS;
+use crate::HTMLWriter;
+
+// The elements, which are separated by blank lines
+const BLOCKS: [&str; 14] = [
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "table",
+    "blockquote",
+    "pre",
+    "figure",
+    "hr",
+];
+
+// The elements, which start a new line
+const LINES: [&str; 12] = [
+    "div", "section", "article", "header", "footer", "nav", "main", "aside", "li", "tr", "dl", "dt",
+];
+
+// The elements, whose content is left out
+const SKIPPED: [&str; 6] = ["head", "script", "style", "template", "noscript", "title"];
+
+impl HTMLWriter {
+    /// Returns the content as plain text
+    pub fn plain_text(&self) -> String {
+        html_to_text(&self.content)
+    }
+}
+
+/// Returns the plain text of an HTML-document or -fragment
+pub fn html_to_text(html: &str) -> String {
+    let mut renderer = Renderer::default();
+    let mut rest = html;
+    while !rest.is_empty() {
+        match rest.find('<') {
+            Some(0) => rest = renderer.markup(rest),
+            Some(i) => {
+                renderer.text(&rest[..i]);
+                rest = &rest[i..];
+            }
+            None => {
+                renderer.text(rest);
+                rest = "";
+            }
+        }
+    }
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct Renderer {
+    out: String,
+    // URLs of the links, and the URL of the open link
+    links: Vec<String>,
+    href: Option<String>,
+    // The numbers of the items of the open lists, None for unordered lists
+    lists: Vec<Option<usize>>,
+    // The rows of the open table and the open cell
+    rows: Vec<Vec<String>>,
+    cell: Option<String>,
+    // The start of the open heading
+    heading: usize,
+    skip: usize,
+    pre: usize,
+}
+
+impl Renderer {
+    // Consumes the markup at the start of the rest and returns the rest behind it
+    fn markup<'a>(&mut self, rest: &'a str) -> &'a str {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            return comment.find("-->").map_or("", |i| &comment[i + 3..]);
+        }
+        let end = tag_end(rest);
+        let tag = &rest[1..end];
+        let rest = rest.get(end + 1..).unwrap_or_default();
+        if tag.starts_with('!') || tag.starts_with('?') {
+            return rest;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            self.end(&name.trim().to_ascii_lowercase());
+            return rest;
+        }
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        self.start(&name, &tag[name_end..]);
+        if tag.ends_with('/') || VOID_ELEMENTS.contains(&name.as_str()) {
+            self.end(&name);
+        }
+        rest
+    }
+
+    fn start(&mut self, name: &str, attributes: &str) {
+        if SKIPPED.contains(&name) {
+            self.skip += 1;
+        }
+        if self.skip > 0 {
+            return;
+        }
+        self.separate(name);
+        match name {
+            "h1" | "h2" => self.heading = self.out.len(),
+            "pre" => self.pre += 1,
+            "ul" => self.lists.push(None),
+            "ol" => self.lists.push(Some(0)),
+            "li" => {
+                let depth = self.lists.len().saturating_sub(1);
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", n)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&"  ".repeat(depth));
+                self.out.push_str(&bullet);
+            }
+            "table" => self.rows.clear(),
+            "tr" => self.rows.push(Vec::new()),
+            "td" | "th" => self.cell = Some(String::new()),
+            "a" => self.href = attribute(attributes, "href"),
+            "img" => {
+                if let Some(alt) = attribute(attributes, "alt").filter(|alt| !alt.is_empty()) {
+                    self.append(&format!("[{}]", alt));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if SKIPPED.contains(&name) {
+            self.skip = self.skip.saturating_sub(1);
+            return;
+        }
+        if self.skip > 0 {
+            return;
+        }
+        match name {
+            "br" => self.out.push('\n'),
+            "hr" => self.out.push_str("----"),
+            "h1" | "h2" => {
+                let underline = if name == "h1" { "=" } else { "-" };
+                let length = self.out[self.heading..].trim().chars().count();
+                self.out.push('\n');
+                self.out.push_str(&underline.repeat(length));
+            }
+            "pre" => self.pre = self.pre.saturating_sub(1),
+            "ul" | "ol" => {
+                self.lists.pop();
+            }
+            "td" | "th" => {
+                if let (Some(cell), Some(row)) = (self.cell.take(), self.rows.last_mut()) {
+                    row.push(cell.trim().to_string());
+                }
+            }
+            "table" => self.table(),
+            "a" => {
+                if let Some(href) = self
+                    .href
+                    .take()
+                    .filter(|h| !h.is_empty() && !h.starts_with('#'))
+                {
+                    self.links.push(href);
+                    self.append(&format!(" [{}]", self.links.len()));
+                }
+            }
+            _ => (),
+        }
+        self.separate(name);
+    }
+
+    // Separates blocks by a blank line and lines by a line-feed, nested lists are only lines
+    fn separate(&mut self, name: &str) {
+        let nested_list = matches!(name, "ul" | "ol") && !self.lists.is_empty();
+        if BLOCKS.contains(&name) && !nested_list {
+            self.blank_line();
+        } else if LINES.contains(&name) || nested_list {
+            self.new_line();
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip > 0 {
+            return;
+        }
+        let text = decode(text);
+        if self.pre > 0 {
+            self.append(&text);
+            return;
+        }
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let target = self.cell.as_ref().unwrap_or(&self.out);
+        let at_line_start = target.is_empty() || target.ends_with(char::is_whitespace);
+        let leading = text.starts_with(char::is_whitespace) && !at_line_start;
+        let trailing = text.ends_with(char::is_whitespace) && !collapsed.is_empty();
+        let mut words = String::new();
+        if leading {
+            words.push(' ');
+        }
+        words.push_str(&collapsed);
+        if trailing {
+            words.push(' ');
+        }
+        self.append(&words);
+    }
+
+    // Appends to the open cell or to the output
+    fn append(&mut self, text: &str) {
+        match &mut self.cell {
+            Some(cell) => cell.push_str(text),
+            None => self.out.push_str(text),
+        }
+    }
+
+    fn new_line(&mut self) {
+        trim_line_end(&mut self.out);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.new_line();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    // Writes the rows of the table with the columns aligned to their widest cell
+    fn table(&mut self) {
+        let rows = std::mem::take(&mut self.rows);
+        let mut widths = Vec::new();
+        for row in &rows {
+            for (i, cell) in row.iter().enumerate() {
+                let width = cell.chars().count();
+                match widths.get_mut(i) {
+                    Some(w) if *w < width => *w = width,
+                    Some(_) => (),
+                    None => widths.push(width),
+                }
+            }
+        }
+        for row in rows.iter().filter(|row| !row.is_empty()) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            self.out.push_str(line.trim_end());
+            self.out.push('\n');
+        }
+    }
+
+    fn finish(mut self) -> String {
+        let mut text = self.out.trim().to_string();
+        if !self.links.is_empty() {
+            text.push_str("\n\n");
+            for (i, href) in self.links.drain(..).enumerate() {
+                text.push_str(&format!("[{}] {}\n", i + 1, href));
+            }
+            text.pop();
+        }
+        text.push('\n');
+        text
+    }
+}
+
+fn trim_line_end(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed);
+}
+
+// Returns the index of the '>' closing the tag, quoted values may contain '>'
+pub(crate) fn tag_end(markup: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in markup.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return i,
+            _ => (),
+        }
+    }
+    markup.len()
+}
+
+// Returns the decoded value of the attribute
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    parse_attributes(attributes)
+        .into_iter()
+        .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+        .map(|(_, value)| decode(value))
+}
+
+// Returns the names and the values of the attributes as they are written, without their quotes
+pub(crate) fn parse_attributes(attributes: &str) -> Vec<(&str, &str)> {
+    let mut parsed = Vec::new();
+    let mut rest = attributes;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return parsed;
+        }
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let attribute = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                let (value, tail) = match value.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let end = value[1..].find(q).map_or(value.len(), |i| i + 1);
+                        (&value[1..end], value.get(end + 1..).unwrap_or_default())
+                    }
+                    _ => {
+                        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        (&value[..end], &value[end..])
+                    }
+                };
+                rest = tail;
+                value
+            }
+            None => "",
+        };
+        parsed.push((attribute, value));
+    }
+}
+
+// Decodes the character references
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let reference = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = reference.and_then(|r| match r {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => r
+                .strip_prefix("#x")
+                .or_else(|| r.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| r.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        });
+        match (reference, character) {
+            (Some(r), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[r.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_lists_and_tables() {
+        let html = "<head><title>x</title></head><ul><li>One</li><li>Two<ol><li>a &amp; b</li></ol></li></ul>\
+                    <table><tr><th>Item</th><th>Price</th></tr><tr><td>Tea</td><td>3.50</td></tr></table>\
+                    <p>Line<br>break <a href=\"#top\">top</a> <img src=\"x.png\" alt=\"logo\"></p>";
+        assert_eq!(
+            html_to_text(html),
+            "- One\n- Two\n  1. a & b\n\nItem  Price\nTea   3.50\n\nLine\nbreak top [logo]\n"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Late edits of the written content of the JSONWriter by JSON Pointer (RFC 6901).
+//!
+//! Sometimes a few values are only known after the document has been written, e.g. a checksum or a count.
+//! ```edit()``` locates the value by its JSON Pointer in the content and replaces it. A value of several lines
+//! is indented like the line of the replaced value.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, MLLWriter};
+//! let mut wr = JSONWriter::new();
+//! wr.open_tag("");
+//! wr.add_property("count", "0");
+//! wr.open_tag("items");
+//! wr.add_property("a/b", "null");
+//! wr.close_tag();
+//! wr.close_tag();
+//! wr.edit("/count", "2").unwrap();
+//! wr.edit("/items/a~1b", "{\n  \"x\": 1\n}").unwrap();
+//! assert_eq!(
+//!     wr.content,
+//!     "{\n  \"count\": 2,\n  \"items\":\n  {\n    \"a/b\": {\n      \"x\": 1\n    }\n  }\n}"
+//! );
+//! ```
+
+use crate::json::{locate, pointer_tokens};
+use crate::{JSONWriter, JsonDialect, WriterError};
+
+impl JSONWriter {
+    /// Replaces the value at the JSON Pointer in the written content. The content has to be a complete
+    /// document, the new value is inserted as it is.
+    pub fn edit(&mut self, pointer: &str, new_value: &str) -> Result<(), WriterError> {
+        let path = pointer_tokens(pointer)?;
+        // Banners are written as comments also into plain JSON
+        let range = locate(&self.content, JsonDialect::Jsonc, &path)?
+            .ok_or_else(|| WriterError::InvalidPointer(pointer.to_string()))?;
+        // The following lines of the value get the indent of the line of the replaced value
+        let line_start = self.content[..range.start].rfind('\n').map_or(0, |p| p + 1);
+        let indent: String = self.content[line_start..range.start]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let value = new_value.replace('\n', &("\n".to_string() + &indent));
+        self.content.replace_range(range, &value);
+        self.core.thaw();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn pointer_edit_errors() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("a", "1");
+        assert!(matches!(
+            wr.edit("/a", "2"),
+            Err(WriterError::InvalidJson(_))
+        ));
+        wr.close_tag();
+        assert_eq!(
+            wr.edit("/b", "2"),
+            Err(WriterError::InvalidPointer("/b".to_string()))
+        );
+        wr.edit("", "[]").unwrap();
+        assert_eq!(wr.content, "[]");
+    }
+}
@@ -0,0 +1,144 @@
+//! A pool of writers for servers, which write many documents.
+//!
+//! The [`WriterPool`] keeps idle writers with their buffers. ```pool.get()``` hands out a writer with the profile
+//! of the pool applied, a [`PooledWriter`], which returns it when dropped: the document is cleared, but the buffer
+//! keeps its capacity, so the next request doesn't allocate again. The pool can be shared between threads.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, WriterPool, WriterProfile};
+//! let pool: WriterPool<HTMLWriter> = WriterPool::new(WriterProfile::named("html5-pretty").unwrap(), 4, 1024);
+//! {
+//!     let mut wr = pool.get();
+//!     wr.open_tag("p");
+//!     wr.close_tag();
+//!     assert_eq!(wr.as_bytes(), b"<p></p>");
+//! }
+//! let wr = pool.get();
+//! assert!(wr.content.is_empty() && wr.content.capacity() >= 1024);
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use crate::{BufferedWriter, WriterProfile};
+
+/// Idle writers with their buffers, see ```get()```
+#[derive(Debug)]
+pub struct WriterPool<M: BufferedWriter + Default> {
+    profile: WriterProfile,
+    idle: Mutex<Vec<M>>,
+    // the number of idle writers, which are kept
+    size: usize,
+}
+
+impl<M: BufferedWriter + Default> WriterPool<M> {
+    /// Returns a pool of size writers with the profile, whose buffers have the capacity in bytes
+    pub fn new(profile: WriterProfile, size: usize, capacity: usize) -> WriterPool<M> {
+        let idle = (0..size)
+            .map(|_| {
+                let mut wr = M::default();
+                wr.apply_profile(&profile);
+                wr.buffer().0.reserve(capacity);
+                wr
+            })
+            .collect();
+        WriterPool {
+            profile,
+            idle: Mutex::new(idle),
+            size,
+        }
+    }
+
+    /// Returns an idle writer, or a new one if all are in use
+    pub fn get(&self) -> PooledWriter<'_, M> {
+        let writer = self.lock().pop().unwrap_or_else(|| {
+            let mut wr = M::default();
+            wr.apply_profile(&self.profile);
+            wr
+        });
+        PooledWriter {
+            writer: Some(writer),
+            pool: self,
+        }
+    }
+
+    /// Returns the number of idle writers
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    // Clears the writer and keeps it, if the pool isn't full
+    fn reclaim(&self, mut writer: M) {
+        writer.clear();
+        writer.apply_profile(&self.profile);
+        let mut idle = self.lock();
+        if idle.len() < self.size {
+            idle.push(writer);
+        }
+    }
+
+    // A panic of another thread doesn't make the idle writers invalid
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<M>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A writer of a [`WriterPool`], which goes back into the pool when dropped
+pub struct PooledWriter<'p, M: BufferedWriter + Default> {
+    writer: Option<M>,
+    pool: &'p WriterPool<M>,
+}
+
+impl<M: BufferedWriter + Default> PooledWriter<'_, M> {
+    /// Returns the writer, which doesn't go back into the pool
+    pub fn into_inner(mut self) -> M {
+        self.writer.take().expect("the writer is only taken once")
+    }
+}
+
+impl<M: BufferedWriter + Default> Deref for PooledWriter<'_, M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        self.writer.as_ref().expect("the writer is only taken once")
+    }
+}
+
+impl<M: BufferedWriter + Default> DerefMut for PooledWriter<'_, M> {
+    fn deref_mut(&mut self) -> &mut M {
+        self.writer.as_mut().expect("the writer is only taken once")
+    }
+}
+
+impl<M: BufferedWriter + Default> Drop for PooledWriter<'_, M> {
+    /// Returns the writer into the pool
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            self.pool.reclaim(writer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JSONWriter, MLLWriter};
+
+    #[test]
+    fn pool_reuses_writers() {
+        let pool: WriterPool<JSONWriter> = WriterPool::new(WriterProfile::default(), 1, 0);
+        let mut a = pool.get();
+        let b = pool.get();
+        assert_eq!(pool.idle(), 0);
+        a.open_tag("");
+        a.add_property("x", "1");
+        drop(a);
+        drop(b);
+        assert_eq!(pool.idle(), 1);
+        let mut c = pool.get();
+        c.open_tag("");
+        c.close_tag();
+        assert_eq!(c.into_inner().content, "{\n}");
+        assert_eq!(pool.idle(), 0);
+    }
+}
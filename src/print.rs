@@ -0,0 +1,217 @@
+//! Printing of report pages.
+//!
+//! ```wr.print_stylesheet()``` links a stylesheet for the media "print", ```wr.page_break()``` marks the last
+//! opened element with the class of a [`PageBreak`]. The rules of these classes are written by
+//! ```wr.page_break_styles()```, with the current and the legacy properties, so the breaks also work in older
+//! PDF-renderers.
+//!
+//! When the layout of the pages has to be exact, the [`XslFoBuilder`] writes an XSL-FO-document instead, which is
+//! rendered to PDF by an FO-processor like Apache FOP.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, PageBreak};
+//! let mut wr = HTMLWriter::new();
+//! wr.print_stylesheet("/print.css");
+//! wr.open_tag("section");
+//! wr.page_break(PageBreak::Before);
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<link rel=\"stylesheet\" href=\"/print.css\" media=\"print\">\
+//!      <section class=\"page-break-before\"></section>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{check, HTMLWriter, MLLWriter, VoidStyle, XMLWriter, XmlName};
+
+/// The namespace of XSL-FO
+pub const XSL_FO_NAMESPACE: &str = "http://www.w3.org/1999/XSL/Format";
+
+/// The page breaks of printed elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageBreak {
+    /// The element starts on a new page
+    Before,
+    /// The element is followed by a new page
+    After,
+    /// The element isn't split across pages, e.g. a table or a figure
+    Avoid,
+}
+
+impl PageBreak {
+    /// Returns the name of the class, e.g. "page-break-before"
+    pub fn class_name(self) -> &'static str {
+        match self {
+            PageBreak::Before => "page-break-before",
+            PageBreak::After => "page-break-after",
+            PageBreak::Avoid => "page-break-avoid",
+        }
+    }
+
+    // The rule of the class
+    fn css(self) -> &'static str {
+        match self {
+            PageBreak::Before => "{break-before:page;page-break-before:always}",
+            PageBreak::After => "{break-after:page;page-break-after:always}",
+            PageBreak::Avoid => "{break-inside:avoid;page-break-inside:avoid}",
+        }
+    }
+}
+
+impl HTMLWriter {
+    /// Writes the link of a stylesheet, which is only applied when printing
+    pub fn print_stylesheet(&mut self, href: &str) {
+        self.add_void(
+            "link",
+            &[("rel", "stylesheet"), ("href", href), ("media", "print")],
+        );
+    }
+
+    /// Marks the last opened element with the class of the page break, its other classes are kept
+    pub fn page_break(&mut self, page_break: PageBreak) {
+        self.add_class(page_break.class_name());
+    }
+
+    /// Writes the rules of the page break classes for the media "print"
+    pub fn page_break_styles(&mut self) {
+        self.open_tag_w_property("style", "media", "print");
+        for page_break in [PageBreak::Before, PageBreak::After, PageBreak::Avoid] {
+            write!(self, ".{}{}", page_break.class_name(), page_break.css())
+                .expect("text inside of an element is always allowed");
+        }
+        self.close_tag();
+    }
+}
+
+/// Writes an XSL-FO-document with one kind of page, the content flows through the region of the body
+#[derive(Debug)]
+pub struct XslFoBuilder {
+    writer: XMLWriter,
+    // the number of open formatting objects
+    depth: usize,
+}
+
+impl XslFoBuilder {
+    /// Returns a builder with an empty flow, the lengths have units, e.g. "210mm"
+    pub fn new(page_width: &str, page_height: &str, margin: &str) -> XslFoBuilder {
+        let mut builder = XslFoBuilder {
+            writer: XMLWriter::new(),
+            depth: 0,
+        };
+        builder.writer.core.set_auto_layout(true);
+        builder.writer.set_void_style(VoidStyle::Xml);
+        builder.writer.declaration("1.0", Some("utf-8"), None);
+        builder.open("root", &[("xmlns:fo", XSL_FO_NAMESPACE)]);
+        builder.open("layout-master-set", &[]);
+        builder.open(
+            "simple-page-master",
+            &[
+                ("master-name", "page"),
+                ("page-width", page_width),
+                ("page-height", page_height),
+                ("margin", margin),
+            ],
+        );
+        builder.empty("region-body", &[]);
+        builder.close();
+        builder.close();
+        builder.open("page-sequence", &[("master-reference", "page")]);
+        builder.open("flow", &[("flow-name", "xsl-region-body")]);
+        builder
+    }
+
+    /// Returns a builder for pages in A4 with margins of 20mm
+    pub fn a4() -> XslFoBuilder {
+        XslFoBuilder::new("210mm", "297mm", "20mm")
+    }
+
+    /// Opens a formatting object, the name is given without the prefix "fo:", e.g. "table"
+    pub fn open(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.writer.open_element(&fo_name(name));
+        self.add_attributes(attributes);
+        self.depth += 1;
+    }
+
+    /// Closes the last opened formatting object
+    pub fn close(&mut self) {
+        self.writer.close_tag();
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Writes a block of text
+    pub fn block(&mut self, text: &str, attributes: &[(&str, &str)]) {
+        self.open("block", attributes);
+        self.writer.text(text);
+        self.close();
+    }
+
+    /// Writes an empty formatting object, e.g. "external-graphic"
+    pub fn empty(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.writer.single_element(&fo_name(name));
+        self.add_attributes(attributes);
+    }
+
+    /// Starts a new page
+    pub fn page_break(&mut self) {
+        self.empty("block", &[("break-after", "page")]);
+    }
+
+    /// Closes the flow and returns the document
+    pub fn finish(mut self) -> String {
+        while self.depth > 0 {
+            self.close();
+        }
+        self.writer.content
+    }
+
+    fn add_attributes(&mut self, attributes: &[(&str, &str)]) {
+        for (name, value) in attributes {
+            self.writer.add_property(name, value);
+        }
+    }
+}
+
+// Returns the name of the formatting object with the prefix "fo:"
+fn fo_name(name: &str) -> XmlName {
+    check(XmlName::new(&format!("fo:{}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn print_page_break_styles() {
+        let mut wr = HTMLWriter::new();
+        wr.open_tag_w_property("table", "class", "report");
+        wr.page_break(PageBreak::Avoid);
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<table class=\"report page-break-avoid\"></table>"
+        );
+        let mut wr = HTMLWriter::new();
+        wr.page_break_styles();
+        assert!(wr.content.starts_with(
+            "<style media=\"print\">.page-break-before{break-before:page;page-break-before:always}"
+        ));
+    }
+
+    #[test]
+    fn print_xsl_fo() {
+        let mut fo = XslFoBuilder::a4();
+        fo.block("Q&A", &[("font-weight", "bold")]);
+        fo.page_break();
+        let document = fo.finish();
+        assert!(document.contains(
+            "<fo:simple-page-master master-name=\"page\" page-width=\"210mm\" page-height=\"297mm\" \
+             margin=\"20mm\">\n      <fo:region-body/>\n    </fo:simple-page-master>"
+        ));
+        assert!(document.ends_with(
+            "<fo:block font-weight=\"bold\">Q&amp;A</fo:block>\n      <fo:block break-after=\"page\"/>\n    \
+             </fo:flow>\n  </fo:page-sequence>\n</fo:root>"
+        ));
+    }
+}
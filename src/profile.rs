@@ -0,0 +1,364 @@
+//! Named behavior profiles ("dialects") of the writers.
+//!
+//! The set of options of the writers grows, and a whole team should produce the same formatting. A
+//! [`WriterProfile`] bundles all options, can be applied to any writer and, with the features ```toml```
+//! or ```json```, be loaded from a shared config-file. The built-in profiles, e.g. "html5-pretty", "xml-canonical"
+//! or "json-compact", are listed by ```WriterProfile::NAMES```.
+//!
+//! Deployed generators can opt in to take their profile from the environment instead, see
+//! ```WriterProfile::from_environment()``` and the ```from_environment()```-constructors of the writers.
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, HTMLWriter, WriterProfile};
+//! let profile = WriterProfile::named("html5-strict").unwrap();
+//! let mut wr = HTMLWriter::new();
+//! wr.apply_profile(&profile);
+//! ```
+
+use crate::{FinalFormat, IndentChar, LineEnding, QuoteStyle, VoidStyle, WriterCore, WriterError};
+
+/// Name of the config-file, which is looked up in the current working directory (feature ```toml```)
+pub const CONFIG_FILE: &str = "mllwriter.toml";
+
+/// A named bundle of writer-options. Options which don't apply to a writer-type are ignored by it, e.g.
+/// the JSONWriter ignores ```strict```.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct WriterProfile {
+    /// The name of the profile, e.g. "html5-strict"
+    pub name: String,
+    /// Number of whitespaces per indent-step, None keeps the default of the writer
+    pub indent_step_size: Option<usize>,
+    /// Indent by one tab per step instead of spaces, see ```IndentChar```
+    pub indent_tabs: bool,
+    /// Validate the nesting-rules of HTML
+    pub strict: bool,
+    /// Close 'p', 'li', 'td' and 'tr' implicitly like the HTML-parser does
+    pub implicit_close: bool,
+    /// Drop HTML-attributes with invalid names with a warning instead of rejecting them
+    pub lenient_attributes: bool,
+    /// Automatic line-feeds and indents of the markup-writers
+    pub auto_layout: bool,
+    /// Elements whose children are not indented by the auto-layout
+    pub no_indent: Vec<String>,
+    /// Elements which are always written inline by the auto-layout
+    pub inline: Vec<String>,
+    /// Pad the properties of a block, so they line up in columns
+    pub align_properties: bool,
+    /// The formatting of the content applied by ```finish()```
+    pub final_format: FinalFormat,
+    /// The line-feeds of the content
+    pub line_ending: LineEnding,
+    /// The notation of elements without content, None keeps the default of the writer, e.g. ```<br/>``` in XML
+    pub void_style: Option<VoidStyle>,
+    /// The quotes around the values of properties
+    pub quote_style: QuoteStyle,
+    /// Escape the characters outside of ASCII, see ```WriterCore::set_ascii_only()```
+    pub ascii_only: bool,
+    /// No line-feeds and indents at all in the markup-writers and the JSONWriter, see ```FormatMode::Minified```
+    pub minify: bool,
+}
+
+// Phrasing elements of HTML, which the "html5-pretty" profile writes inline
+pub(crate) const HTML_INLINE: [&str; 18] = [
+    "a", "abbr", "b", "br", "cite", "code", "em", "i", "kbd", "label", "mark", "q", "s", "small",
+    "span", "strong", "sub", "sup",
+];
+
+impl Default for WriterProfile {
+    fn default() -> Self {
+        WriterProfile {
+            name: String::from("default"),
+            indent_step_size: None,
+            indent_tabs: false,
+            strict: false,
+            implicit_close: false,
+            lenient_attributes: false,
+            auto_layout: false,
+            no_indent: Vec::new(),
+            inline: Vec::new(),
+            align_properties: false,
+            final_format: FinalFormat::default(),
+            line_ending: LineEnding::Lf,
+            void_style: None,
+            quote_style: QuoteStyle::Double,
+            ascii_only: false,
+            minify: false,
+        }
+    }
+}
+
+impl WriterProfile {
+    /// The names of all built-in profiles, which can be loaded by ```named()```
+    pub const NAMES: [&'static str; 9] = [
+        "default",
+        "html5-strict",
+        "html5-lenient",
+        "html5-pretty",
+        "xml",
+        "xml-pretty",
+        "xml-canonical",
+        "json",
+        "json-compact",
+    ];
+
+    /// Returns the built-in profile with the given name, or None if there is no such profile
+    pub fn named(name: &str) -> Option<WriterProfile> {
+        let mut profile = WriterProfile {
+            name: name.to_string(),
+            ..WriterProfile::default()
+        };
+        match name {
+            "default" => (),
+            "html5-strict" => profile.strict = true,
+            "html5-lenient" => {
+                profile.implicit_close = true;
+                profile.lenient_attributes = true;
+            }
+            "html5-pretty" => {
+                profile.auto_layout = true;
+                profile.no_indent = vec![String::from("html")];
+                profile.inline = HTML_INLINE.iter().map(|t| t.to_string()).collect();
+            }
+            "xml" | "xml-pretty" => {
+                profile.indent_step_size = Some(2);
+                profile.void_style = Some(VoidStyle::Xml);
+                profile.final_format = FinalFormat::posix();
+                profile.auto_layout = name == "xml-pretty";
+            }
+            // The same bytes for the same document, e.g. for signatures and diffs
+            "xml-canonical" => {
+                profile.void_style = Some(VoidStyle::Xml);
+                profile.minify = true;
+            }
+            "json" => {
+                profile.indent_step_size = Some(2);
+                profile.final_format = FinalFormat::posix();
+            }
+            "json-compact" => profile.minify = true,
+            _ => return None,
+        }
+        Some(profile)
+    }
+
+    /// Loads a profile from a TOML-config. Missing options are taken from the default profile.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(config: &str) -> Result<WriterProfile, WriterError> {
+        toml::from_str(config).map_err(|e| WriterError::InvalidProfile(e.to_string()))
+    }
+
+    /// Writes the profile as a TOML-config
+    #[cfg(feature = "toml")]
+    pub fn to_toml_string(&self) -> Result<String, WriterError> {
+        toml::to_string(self).map_err(|e| WriterError::InvalidProfile(e.to_string()))
+    }
+
+    /// Loads a profile from a JSON-config. Missing options are taken from the default profile.
+    #[cfg(feature = "json")]
+    pub fn from_json_str(config: &str) -> Result<WriterProfile, WriterError> {
+        serde_json::from_str(config).map_err(|e| WriterError::InvalidProfile(e.to_string()))
+    }
+
+    /// Writes the profile as a JSON-config
+    #[cfg(feature = "json")]
+    pub fn to_json_string(&self) -> Result<String, WriterError> {
+        serde_json::to_string_pretty(self).map_err(|e| WriterError::InvalidProfile(e.to_string()))
+    }
+}
+
+impl WriterProfile {
+    /// Loads the profile from the environment, which is meant for deployed generators:
+    /// 1. the base is the config-file given by ```MLLWRITER_CONFIG``` or [`CONFIG_FILE`] in the current working
+    ///    directory (only with feature ```toml```), if it doesn't exist the built-in profile named by
+    ///    ```MLLWRITER_PROFILE```, otherwise the default profile
+    /// 2. the options are overridden by ```MLLWRITER_INDENT_STEP_SIZE```, ```MLLWRITER_INDENT_TABS```,
+    ///    ```MLLWRITER_STRICT```, ```MLLWRITER_IMPLICIT_CLOSE``` and ```MLLWRITER_AUTO_LAYOUT```
+    ///
+    /// A config-file, which can't be read or parsed, is an error, as well as ```MLLWRITER_CONFIG``` without the
+    /// feature ```toml```. Only a missing [`CONFIG_FILE`] falls back to the built-in profiles.
+    pub fn from_environment() -> Result<WriterProfile, WriterError> {
+        let lookup = |key: &str| std::env::var(key).ok();
+        let file = read_config(lookup("MLLWRITER_CONFIG"))?;
+        WriterProfile::from_lookup(lookup, file)
+    }
+
+    // Builds the profile out of the config-file content and a lookup for environment variables
+    fn from_lookup<F>(lookup: F, file: Option<String>) -> Result<WriterProfile, WriterError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut profile = match (file, lookup("MLLWRITER_PROFILE")) {
+            #[cfg(feature = "toml")]
+            (Some(config), _) => WriterProfile::from_toml_str(&config)?,
+            (_, Some(name)) => WriterProfile::named(&name).ok_or_else(|| {
+                WriterError::InvalidProfile(format!("there is no built-in profile '{}'", name))
+            })?,
+            _ => WriterProfile::default(),
+        };
+        if let Some(value) = lookup("MLLWRITER_INDENT_STEP_SIZE") {
+            profile.indent_step_size = Some(parse_env("MLLWRITER_INDENT_STEP_SIZE", &value)?);
+        }
+        if let Some(value) = lookup("MLLWRITER_INDENT_TABS") {
+            profile.indent_tabs = parse_env_bool("MLLWRITER_INDENT_TABS", &value)?;
+        }
+        if let Some(value) = lookup("MLLWRITER_STRICT") {
+            profile.strict = parse_env_bool("MLLWRITER_STRICT", &value)?;
+        }
+        if let Some(value) = lookup("MLLWRITER_IMPLICIT_CLOSE") {
+            profile.implicit_close = parse_env_bool("MLLWRITER_IMPLICIT_CLOSE", &value)?;
+        }
+        if let Some(value) = lookup("MLLWRITER_AUTO_LAYOUT") {
+            profile.auto_layout = parse_env_bool("MLLWRITER_AUTO_LAYOUT", &value)?;
+        }
+        Ok(profile)
+    }
+}
+
+impl WriterCore {
+    // Applies the options of the profile, which all writers share
+    pub(crate) fn apply_profile(&mut self, profile: &WriterProfile) {
+        if let Some(size) = profile.indent_step_size {
+            self.set_indent_step_size(size);
+        }
+        self.set_indent_char(if profile.indent_tabs {
+            IndentChar::Tabs
+        } else {
+            IndentChar::Spaces(self.indent_step_size)
+        });
+        self.final_format = profile.final_format;
+        self.line_ending = profile.line_ending;
+        self.quote_style = profile.quote_style;
+        self.ascii_only = profile.ascii_only;
+    }
+}
+
+// Reads the config-file given by MLLWRITER_CONFIG or the default one, if it exists
+fn read_config(config: Option<String>) -> Result<Option<String>, WriterError> {
+    if !cfg!(feature = "toml") {
+        return match config {
+            Some(_) => Err(WriterError::InvalidProfile(
+                "MLLWRITER_CONFIG needs the feature toml".to_string(),
+            )),
+            None => Ok(None),
+        };
+    }
+    let explicit = config.is_some();
+    let path = config.unwrap_or_else(|| CONFIG_FILE.to_string());
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if !explicit && e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(WriterError::InvalidProfile(format!(
+            "can't read the config-file '{}': {}",
+            path, e
+        ))),
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, WriterError> {
+    value.trim().parse().map_err(|_| {
+        WriterError::InvalidProfile(format!("{} has an invalid value '{}'", key, value))
+    })
+}
+
+fn parse_env_bool(key: &str, value: &str) -> Result<bool, WriterError> {
+    match value.trim() {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        _ => parse_env(key, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WriterProfile;
+    use crate::{HTMLWriter, IndentChar, JSONWriter, LineEnding, MLLWriter, QuoteStyle, XMLWriter};
+
+    #[test]
+    fn profile_from_lookup() {
+        let env = |key: &str| match key {
+            "MLLWRITER_PROFILE" => Some("html5-lenient".to_string()),
+            "MLLWRITER_INDENT_STEP_SIZE" => Some("3".to_string()),
+            "MLLWRITER_STRICT" => Some("1".to_string()),
+            _ => None,
+        };
+        let profile = WriterProfile::from_lookup(env, None).unwrap();
+        assert_eq!(profile.name, "html5-lenient");
+        assert_eq!(profile.indent_step_size, Some(3));
+        assert!(profile.strict);
+        assert!(profile.implicit_close);
+
+        let env = |key: &str| (key == "MLLWRITER_STRICT").then(|| "yes".to_string());
+        assert!(WriterProfile::from_lookup(env, None).is_err());
+    }
+
+    #[test]
+    fn profile_options_apply() {
+        let mut wr = HTMLWriter::new();
+        wr.apply_profile(&WriterProfile::named("xml").unwrap());
+        wr.single_tag("br");
+        assert_eq!(wr.finish().unwrap(), "<br/>\n");
+
+        let mut wr = XMLWriter::new();
+        wr.apply_profile(&WriterProfile::named("xml-canonical").unwrap());
+        wr.open_tag("a");
+        wr.line_feed_inc();
+        wr.single_tag("b");
+        wr.close_tag();
+        assert_eq!(wr.finish().unwrap(), "<a><b/></a>");
+
+        let mut wr = JSONWriter::new();
+        wr.apply_profile(&WriterProfile::named("json-compact").unwrap());
+        wr.open_tag("");
+        wr.add_property("a", "1");
+        wr.close_tag();
+        assert_eq!(wr.finish().unwrap(), "{\"a\":1}");
+
+        let profile = WriterProfile {
+            line_ending: LineEnding::CrLf,
+            quote_style: QuoteStyle::Single,
+            ascii_only: true,
+            ..WriterProfile::default()
+        };
+        let mut wr = HTMLWriter::new();
+        wr.apply_profile(&profile);
+        wr.open_tag("p");
+        wr.add_property("title", "caf\u{e9}");
+        wr.line_feed_inc();
+        wr.text("\u{e9}");
+        wr.close_tag();
+        assert_eq!(wr.content, "<p title='caf&#xe9;'>\r\n    &#xe9;</p>");
+
+        let mut wr = XMLWriter::new();
+        wr.core.set_indent_char(IndentChar::Tabs);
+        wr.apply_profile(&WriterProfile::named("xml").unwrap());
+        assert_eq!(wr.core.indent_char(), IndentChar::Spaces(2));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn profile_config_errors() {
+        let missing = format!("mllwriter-missing-{}.toml", std::process::id());
+        assert!(super::read_config(Some(missing)).is_err());
+        let config = Some("strict = 5".to_string());
+        assert!(WriterProfile::from_lookup(|_| None, config).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn profile_toml_roundtrip() {
+        let profile = WriterProfile::from_toml_str("name = \"team\"\nstrict = true\n").unwrap();
+        assert!(profile.strict);
+        assert_eq!(profile.indent_step_size, None);
+        let back = WriterProfile::from_toml_str(&profile.to_toml_string().unwrap()).unwrap();
+        assert_eq!(profile, back);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn profile_json_roundtrip() {
+        let profile = WriterProfile::from_json_str("{\"indent_step_size\": 3}").unwrap();
+        assert_eq!(profile.indent_step_size, Some(3));
+        assert!(WriterProfile::from_json_str("{\"strict\": 5}").is_err());
+    }
+}
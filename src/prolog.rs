@@ -0,0 +1,107 @@
+//! The start of documents: the XML-declaration and the DOCTYPE of HTML.
+//!
+//! Both have to be written in the prolog, i.e. before the root element. The declaration of XML has to be the very
+//! first markup of the document.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! wr.declaration("1.0", Some("UTF-8"), Some(true));
+//! wr.open_tag("note");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><note></note>"
+//! );
+//!
+//! let mut wr = HTMLWriter::new();
+//! wr.doctype();
+//! wr.open_tag("html");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<!DOCTYPE html><html></html>");
+//! ```
+
+use crate::{check, DocumentState, HTMLWriter, WriterCore, WriterError, XMLWriter};
+
+impl XMLWriter {
+    /// Writes the XML-declaration with the version, e.g. "1.0", and optionally the encoding and whether the
+    /// document is standalone. It has to be the first markup of the document.
+    pub fn declaration(&mut self, version: &str, encoding: Option<&str>, standalone: Option<bool>) {
+        if !self.content.is_empty() {
+            check(Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "declaration",
+                reason: "the XML-declaration has to be the first markup of the document",
+            }))
+        }
+        check(begin_prolog(&mut self.core, "declaration"));
+        self.content.push_str("<?xml version=\"");
+        self.content.push_str(version);
+        self.content.push('"');
+        if let Some(encoding) = encoding {
+            self.content.push_str(" encoding=\"");
+            self.content.push_str(encoding);
+            self.content.push('"');
+        }
+        if let Some(standalone) = standalone {
+            self.content.push_str(" standalone=\"");
+            self.content.push_str(if standalone { "yes" } else { "no" });
+            self.content.push('"');
+        }
+        self.content.push_str("?>");
+    }
+}
+
+impl HTMLWriter {
+    /// Writes the DOCTYPE of HTML5, ```<!DOCTYPE html>```
+    pub fn doctype(&mut self) {
+        self.doctype_custom("html");
+    }
+
+    /// Writes a DOCTYPE with the given content, e.g. the public identifier of a legacy DOCTYPE
+    pub fn doctype_custom(&mut self, doctype: &str) {
+        check(begin_prolog(&mut self.core, "doctype"));
+        self.core.layout_node(&mut self.content);
+        self.content.push_str("<!DOCTYPE ");
+        self.content.push_str(doctype);
+        self.content.push('>');
+    }
+}
+
+// Checks that the markup is written in the prolog
+fn begin_prolog(core: &mut WriterCore, operation: &'static str) -> Result<(), WriterError> {
+    if core.state != DocumentState::Prolog {
+        return Err(WriterError::IllegalTransition {
+            state: core.state,
+            operation,
+            reason: "it can only be written in the prolog, before the root element",
+        });
+    }
+    core.begin_markup(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn prolog_order() {
+        let mut wr = HTMLWriter::new();
+        wr.doctype_custom("html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\"");
+        assert_eq!(
+            wr.content,
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\">"
+        );
+        wr.open_tag("html");
+        assert!(begin_prolog(&mut wr.core, "doctype").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "the XML-declaration has to be the first markup of the document")]
+    fn prolog_declaration_first() {
+        let mut wr = XMLWriter::new();
+        wr.add_comment("c");
+        wr.declaration("1.0", None, None);
+    }
+}
@@ -0,0 +1,140 @@
+//! The properties of a start-tag as ordered map.
+//!
+//! [`Properties`] keep the order, in which the names were added first. A name, which is added again, replaces the
+//! value in its place, so a tag never gets an attribute twice. The values of "class" and "style" are merged
+//! instead: the classes are joined by spaces, without duplicates, the declarations of the style by "; ". The
+//! ```props!{}```-macro builds the properties like a map literal.
+//!
+//! ```
+//! # use mllwriter::{props, HTMLWriter, MLLWriter, Properties};
+//! let mut properties = props! {"class" => "card", "id" => "a", "style" => "color: red;"};
+//! properties.add("id", "b");
+//! properties.add("class", "wide card");
+//! properties.add("style", "margin: 0");
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("div");
+//! wr.add_properties(&properties);
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<div class=\"card wide\" id=\"b\" style=\"color: red; margin: 0\"></div>");
+//! ```
+
+/// The Properties encapsule several properties, e.g. class="superhero" and style="width: auto". These can be
+/// passed to the Writer, which pushes them onto the content-string in the right way
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Properties {
+    pub(crate) p: Vec<(String, String)>,
+}
+
+/// The former name of [`Properties`]
+pub type Property = Properties;
+
+impl Properties {
+    /// A default new method with one first property pair to be passed
+    pub fn new(name: &str, value: &str) -> Properties {
+        let mut p = Properties::default();
+        p.add(name, value);
+        p
+    }
+
+    /// Adds the property, an existing one is replaced in its place, or merged for "class" and "style"
+    pub fn add(&mut self, name: &str, value: &str) {
+        match self.p.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => {
+                *existing = match name {
+                    "class" => merge_class(existing, value),
+                    "style" => merge_style(existing, value),
+                    _ => value.to_string(),
+                }
+            }
+            None => self.p.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the value of the property
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.p
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Removes the property and returns its value, the order of the others is kept
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let index = self.p.iter().position(|(n, _)| n == name)?;
+        Some(self.p.remove(index).1)
+    }
+
+    /// Returns the number of properties
+    pub fn len(&self) -> usize {
+        self.p.len()
+    }
+
+    /// Returns true if there are no properties
+    pub fn is_empty(&self) -> bool {
+        self.p.is_empty()
+    }
+
+    /// Returns the names and values in their order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.p.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+}
+
+impl<N: AsRef<str>, V: AsRef<str>> FromIterator<(N, V)> for Properties {
+    /// Adds the properties in their order, see ```add()```
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Properties {
+        let mut properties = Properties::default();
+        for (name, value) in iter {
+            properties.add(name.as_ref(), value.as_ref());
+        }
+        properties
+    }
+}
+
+/// Builds [`Properties`] from pairs of names and values, e.g. ```props! {"class" => "card", "id" => "a"}```
+#[macro_export]
+macro_rules! props {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        <$crate::Properties as ::std::iter::FromIterator<(&str, &str)>>::from_iter([$(($name, $value)),*])
+    };
+}
+
+// Adds the classes, which aren't in the list yet
+pub(crate) fn merge_class(classes: &str, added: &str) -> String {
+    let mut merged: Vec<&str> = classes.split_whitespace().collect();
+    for class in added.split_whitespace() {
+        if !merged.contains(&class) {
+            merged.push(class);
+        }
+    }
+    merged.join(" ")
+}
+
+// Appends the declarations, a later declaration of the same CSS-property wins by the cascade
+pub(crate) fn merge_style(style: &str, added: &str) -> String {
+    let style = style.trim().trim_end_matches(';');
+    let added = added.trim().trim_end_matches(';');
+    match (style.is_empty(), added.is_empty()) {
+        (true, _) => added.to_string(),
+        (_, true) => style.to_string(),
+        _ => format!("{}; {}", style, added),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_replace_in_place() {
+        let mut properties: Properties = [("a", "1"), ("b", "2"), ("a", "3")].into_iter().collect();
+        assert_eq!(
+            properties.iter().collect::<Vec<_>>(),
+            [("a", "3"), ("b", "2")]
+        );
+        assert_eq!(properties.remove("a"), Some("3".to_string()));
+        assert_eq!(properties.get("b"), Some("2"));
+        assert_eq!(props! {}.len(), 0);
+        assert_eq!(merge_style("", "color: red;"), "color: red");
+    }
+}
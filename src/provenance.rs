@@ -0,0 +1,110 @@
+//! "Generated by"-provenance metadata of a document.
+//!
+//! ```stamp_provenance()``` writes which tool in which version generated the document, as a comment in HTML and
+//! XML, and as a ```_meta```-block in JSON. The timestamp can be suppressed or taken from ```SOURCE_DATE_EPOCH```
+//! for reproducible builds.
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, HTMLWriter, TimestampPolicy};
+//! let mut wr = HTMLWriter::new();
+//! wr.stamp_provenance("docgen", "1.2.0", TimestampPolicy::Fixed(0));
+//! assert_eq!(wr.content, "<!-- Generated by docgen 1.2.0 at 1970-01-01T00:00:00Z -->");
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Decides about the timestamp in the provenance metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampPolicy {
+    /// No timestamp, the output is reproducible
+    Omit,
+    /// The current system time
+    Now,
+    /// The time given by the environment variable ```SOURCE_DATE_EPOCH``` (reproducible builds), if it isn't set
+    /// the timestamp is omitted
+    SourceDateEpoch,
+    /// A fixed time in seconds since the unix epoch
+    Fixed(u64),
+}
+
+impl TimestampPolicy {
+    /// Returns the timestamp as RFC 3339 string in UTC, or None if it is omitted
+    pub fn timestamp(&self) -> Option<String> {
+        let secs = match self {
+            TimestampPolicy::Omit => return None,
+            TimestampPolicy::Now => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            TimestampPolicy::SourceDateEpoch => std::env::var("SOURCE_DATE_EPOCH")
+                .ok()?
+                .trim()
+                .parse()
+                .ok()?,
+            TimestampPolicy::Fixed(secs) => *secs,
+        };
+        Some(format_rfc3339(secs))
+    }
+}
+
+// Returns year, month and day of the seconds since the unix epoch, by the days-to-civil algorithm
+pub(crate) fn civil_date(secs: u64) -> (i64, i64, i64) {
+    let days = (secs / 86400) as i64;
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+// Formats seconds since the unix epoch as "YYYY-MM-DDThh:mm:ssZ"
+pub(crate) fn format_rfc3339(secs: u64) -> String {
+    let (year, month, day) = civil_date(secs);
+    let rem = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// The standardized comment-text of the provenance for markup-writers
+pub(crate) fn provenance_comment(tool: &str, version: &str, policy: TimestampPolicy) -> String {
+    match policy.timestamp() {
+        Some(timestamp) => format!("Generated by {} {} at {}", tool, version, timestamp),
+        None => format!("Generated by {} {}", tool, version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JSONWriter, MLLWriter};
+
+    #[test]
+    fn provenance_timestamp() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_rfc3339(1792152245), "2026-10-16T12:04:05Z");
+        assert_eq!(TimestampPolicy::Omit.timestamp(), None);
+    }
+
+    #[test]
+    fn provenance_json_meta() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.stamp_provenance("docgen", "1.2.0", TimestampPolicy::Omit);
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"_meta\":\n  {\n    \"generator\": \"docgen\",\n    \"version\": \"1.2.0\"\n  }\n}"
+        );
+    }
+}
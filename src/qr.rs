@@ -0,0 +1,72 @@
+//! QR codes as inline SVG (feature ```qrcode```).
+//!
+//! ```qr_code()``` encodes the data and writes the modules as a single path into an SVG, including the
+//! quiet zone of 4 modules around the code. ```HTMLWriter::embed_qr_code()``` embeds it inline, e.g. into a
+//! ticket or an invoice.
+//!
+//! ```
+//! # use mllwriter::{qr_code, HTMLWriter, MLLWriter};
+//! let svg = qr_code("https://example.com", 4.0).unwrap();
+//! assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"132\" height=\"132\" viewBox=\"0 0 33 33\">"));
+//!
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("div");
+//! wr.embed_qr_code("INV-2026-0042", 2.0).unwrap();
+//! wr.close_tag();
+//! ```
+
+use qrcode::{Color, QrCode};
+
+use crate::{HTMLWriter, MLLWriter, SVGWriter, WriterError};
+
+// Number of light modules around the code
+const QUIET_ZONE: usize = 4;
+
+/// Returns the QR code of the data as SVG, each module is module_size pixels wide
+pub fn qr_code(data: &str, module_size: f64) -> Result<String, WriterError> {
+    let code = QrCode::new(data).map_err(|e| WriterError::QrCodeFailed(e.to_string()))?;
+    let width = code.width();
+    let size = (width + 2 * QUIET_ZONE) as f64;
+    let mut path = String::new();
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color == Color::Dark {
+            let (x, y) = (i % width + QUIET_ZONE, i / width + QUIET_ZONE);
+            path.push_str(&format!("M{} {}h1v1h-1z", x, y));
+        }
+    }
+    let pixels = size * module_size;
+    let mut svg = SVGWriter::new(pixels, pixels);
+    // The coordinates of the path are given in modules
+    svg.view_box(0.0, 0.0, size, size);
+    svg.single_tag("rect");
+    svg.attr("width", "100%");
+    svg.attr("height", "100%");
+    svg.attr("fill", "white");
+    svg.single_tag("path");
+    svg.attr("d", path);
+    svg.attr("fill", "black");
+    svg.attr("shape-rendering", "crispEdges");
+    svg.finish()
+}
+
+impl HTMLWriter {
+    /// Embeds the QR code of the data inline as SVG
+    pub fn embed_qr_code(&mut self, data: &str, module_size: f64) -> Result<(), WriterError> {
+        let svg = qr_code(data, module_size)?;
+        self.embed_svg(&svg);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qr_too_long() {
+        let err = qr_code(&"x".repeat(4000), 1.0).unwrap_err();
+        assert!(matches!(err, WriterError::QrCodeFailed(_)));
+        let svg = qr_code("A", 1.0).unwrap();
+        assert!(svg.ends_with("fill=\"black\" shape-rendering=\"crispEdges\"/></svg>"));
+    }
+}
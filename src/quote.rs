@@ -0,0 +1,72 @@
+//! The quotes of the values of properties.
+//!
+//! HTML and XML write the values of properties in double quotes by default. ```wr.core.set_quote_style()```
+//! switches to single quotes, e.g. for markup, which is embedded into a double-quoted string of another language.
+//! The values are escaped for both quotes, so switching never breaks a value. The JSONWriter writes its strings in
+//! single quotes in the dialect JSON5, see [`JsonDialect`](crate::JsonDialect).
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, QuoteStyle, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! wr.core.set_quote_style(QuoteStyle::Single);
+//! wr.open_tag("a");
+//! wr.add_property("title", "it's \"x\"");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<a title='it&#39;s &quot;x&quot;'></a>");
+//! ```
+
+use crate::WriterCore;
+
+/// The quotes around the values of properties
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum QuoteStyle {
+    /// "value"
+    #[default]
+    Double,
+    /// 'value'
+    Single,
+}
+
+impl QuoteStyle {
+    /// Returns the quote character
+    pub fn quote(self) -> char {
+        match self {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        }
+    }
+}
+
+impl WriterCore {
+    /// Sets the quotes around the values of properties
+    pub fn set_quote_style(&mut self, quote_style: QuoteStyle) {
+        self.quote_style = quote_style;
+    }
+
+    // Returns the property, as it is written into a start-tag, with a leading space
+    pub(crate) fn property_string(&self, name: &str, value: &str) -> String {
+        let quote = self.quote_style.quote();
+        format!(" {}={}{}{}", name, quote, value, quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HTMLWriter, MLLWriter};
+
+    #[test]
+    fn single_quotes_everywhere() {
+        let mut wr = HTMLWriter::new();
+        wr.core.set_quote_style(QuoteStyle::Single);
+        wr.open_tag("p");
+        wr.add_class("a");
+        wr.add_class("b");
+        wr.text("t");
+        wr.add_property("id", "x>");
+        wr.close_tag();
+        assert_eq!(wr.content, "<p class='a b' id='x&gt;'>t</p>");
+    }
+}
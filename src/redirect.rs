@@ -0,0 +1,241 @@
+//! Redirects of moved pages of a [`DocumentSet`](crate::DocumentSet), e.g. after a migration of the site.
+//!
+//! The redirects are registered by ```site.add_redirect()``` with the old and the new path, relative to the base
+//! URL like the pages, or an absolute URL as target. ```site.redirect_pages()``` returns the stub pages for static
+//! hosting, which redirect by "refresh" and name the target as canonical URL. The same redirects are written for
+//! the servers as ```_redirects```-file (Netlify, Cloudflare Pages), as map of nginx and as ```.htaccess``` of
+//! Apache, all permanent. Paths with spaces or other separators of these files are quoted for nginx and Apache,
+//! and percent-encoded in the ```_redirects```-file. The old paths stay valid targets of links, see
+//! ```check_links()```.
+//!
+//! ```
+//! # use mllwriter::DocumentSet;
+//! let mut site = DocumentSet::new("https://example.com/");
+//! site.add_redirect("old/about.html", "about/");
+//! assert_eq!(site.redirects_file(), "/old/about.html /about/ 301\n");
+//! assert_eq!(
+//!     site.redirect_pages()[0].1,
+//!     "<html><head><meta charset=\"utf-8\"><title>Redirect</title>\
+//!      <meta http-equiv=\"refresh\" content=\"0; url=https://example.com/about/\">\
+//!      <link rel=\"canonical\" href=\"https://example.com/about/\"><meta name=\"robots\" content=\"noindex, follow\">\
+//!      </head><body><a href=\"https://example.com/about/\">https://example.com/about/</a></body></html>"
+//! );
+//! ```
+
+use crate::{DocumentSet, HTMLWriter, MLLWriter, RobotsPolicy};
+
+impl DocumentSet {
+    /// Adds the redirect from the old path to the new path or an absolute URL
+    pub fn add_redirect(&mut self, old: &str, new: &str) {
+        self.redirects.push((old.to_string(), new.to_string()));
+    }
+
+    /// Returns the old paths and the stub pages, which redirect to the new URLs
+    pub fn redirect_pages(&self) -> Vec<(String, String)> {
+        self.redirects
+            .iter()
+            .map(|(old, new)| {
+                let target = self.redirect_url(new);
+                let mut wr = HTMLWriter::new();
+                wr.open_tag("html");
+                wr.open_tag("head");
+                wr.add_void("meta", &[("charset", "utf-8")]);
+                wr.open_tag("title");
+                wr.text("Redirect");
+                wr.close_tag();
+                wr.add_void(
+                    "meta",
+                    &[
+                        ("http-equiv", "refresh"),
+                        ("content", &format!("0; url={}", target)),
+                    ],
+                );
+                wr.add_link("canonical", &target);
+                wr.robots(&RobotsPolicy::noindex());
+                wr.close_tag();
+                wr.open_tag("body");
+                wr.open_tag_w_property("a", "href", &target);
+                wr.text(&target);
+                wr.close_tag();
+                wr.close_tag();
+                wr.close_tag();
+                (old.clone(), wr.content)
+            })
+            .collect()
+    }
+
+    /// Returns the ```_redirects```-file, one line per redirect, e.g. ```/old.html /new.html 301```
+    pub fn redirects_file(&self) -> String {
+        self.redirect_lines(|old, new| {
+            format!(
+                "{} {} 301\n",
+                encode_whitespace(old),
+                encode_whitespace(new)
+            )
+        })
+    }
+
+    /// Returns the map of nginx from the old path to the new one in the variable ```$redirect_uri```, which is used
+    /// by e.g. ```if ($redirect_uri) { return 301 $redirect_uri; }```
+    pub fn nginx_map(&self) -> String {
+        format!(
+            "map $uri $redirect_uri {{\n{}}}\n",
+            self.redirect_lines(|old, new| format!(
+                "    {} {};\n",
+                quote_token(old),
+                quote_token(new)
+            ))
+        )
+    }
+
+    /// Returns the ```.htaccess``` of Apache, one ```RedirectMatch``` per redirect. ```Redirect``` would match the
+    /// old path as prefix, i.e. its sub-paths too, so the old path is an anchored pattern like in the other files.
+    pub fn htaccess(&self) -> String {
+        self.redirect_lines(|old, new| {
+            format!(
+                "RedirectMatch 301 {} {}\n",
+                apache_token(&exact_pattern(old)),
+                apache_token(&literal_target(new))
+            )
+        })
+    }
+
+    // The lines of the redirects with the old path and the new path or URL
+    fn redirect_lines(&self, line: impl Fn(&str, &str) -> String) -> String {
+        self.redirects
+            .iter()
+            .map(|(old, new)| {
+                let new = if is_url(new) {
+                    new.clone()
+                } else {
+                    site_path(new)
+                };
+                line(&site_path(old), &new)
+            })
+            .collect()
+    }
+
+    // The absolute URL of the target
+    fn redirect_url(&self, new: &str) -> String {
+        if is_url(new) {
+            new.to_string()
+        } else {
+            self.url(new)
+        }
+    }
+}
+
+// Returns true for an absolute URL
+fn is_url(target: &str) -> bool {
+    target.contains("://")
+}
+
+// Returns the path with a leading '/'
+fn site_path(path: &str) -> String {
+    format!("/{}", path.trim_start_matches('/'))
+}
+
+// Returns the path in double quotes, if it has characters which end a token of nginx
+fn quote_token(path: &str) -> String {
+    let separator =
+        |c: char| c.is_whitespace() || matches!(c, ';' | '"' | '\'' | '{' | '}' | '#' | '\\');
+    if path.contains(separator) {
+        format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        path.to_string()
+    }
+}
+
+// Returns the token of Apache in double quotes, if it has whitespace, quotes or '#'. Apache reads the escapes
+// \\ and \", so only a backslash in front of those, or at the end of a quoted token, is doubled.
+fn apache_token(token: &str) -> String {
+    let quoted = token.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '#'));
+    let mut escaped = String::new();
+    let mut chars = token.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('\\' | '"')) => escaped.push_str("\\\\"),
+            ('\\', None) if quoted => escaped.push_str("\\\\"),
+            ('"', _) => escaped.push_str("\\\""),
+            (c, _) => escaped.push(c),
+        }
+    }
+    if quoted {
+        format!("\"{}\"", escaped)
+    } else {
+        escaped
+    }
+}
+
+// Returns the regular expression, which matches exactly the path
+fn exact_pattern(path: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in path.chars() {
+        if "\\.+*?()[]{}^$|".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('$');
+    pattern
+}
+
+// Returns the target of RedirectMatch with escaped '$' and '&', which Apache replaces by the matched groups
+fn literal_target(target: &str) -> String {
+    target.replace('$', "\\$").replace('&', "\\&")
+}
+
+// Returns the path with percent-encoded whitespace, which separates the fields of the _redirects-file
+fn encode_whitespace(path: &str) -> String {
+    path.chars()
+        .map(|c| match c {
+            c if c.is_whitespace() => c
+                .to_string()
+                .bytes()
+                .map(|b| format!("%{:02X}", b))
+                .collect(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirect_server_files() {
+        let mut site = DocumentSet::new("https://example.com");
+        site.add_redirect("/a.html", "b.html");
+        site.add_redirect("c/", "https://other.org/c");
+        assert_eq!(
+            site.nginx_map(),
+            "map $uri $redirect_uri {\n    /a.html /b.html;\n    /c/ https://other.org/c;\n}\n"
+        );
+        assert_eq!(
+            site.htaccess(),
+            "RedirectMatch 301 ^/a\\.html$ /b.html\nRedirectMatch 301 ^/c/$ https://other.org/c\n"
+        );
+        site.add_redirect("old page;1.html", "new \"page\".html");
+        assert!(site
+            .nginx_map()
+            .ends_with("    \"/old page;1.html\" \"/new \\\"page\\\".html\";\n}\n"));
+        assert!(site
+            .htaccess()
+            .ends_with("RedirectMatch 301 \"^/old page;1\\.html$\" \"/new \\\"page\\\".html\"\n"));
+        assert!(site
+            .redirects_file()
+            .ends_with("/old%20page;1.html /new%20\"page\".html 301\n"));
+        site.redirects.pop();
+        site.add_redirect("a(1).html", "b.html?x=1&y=$2");
+        assert!(site
+            .htaccess()
+            .ends_with("RedirectMatch 301 ^/a\\(1\\)\\.html$ /b.html?x=1\\&y=\\$2\n"));
+        site.redirects.pop();
+        let mut page = HTMLWriter::new();
+        page.open_tag_w_property("a", "href", "a.html");
+        page.close_tag();
+        site.add_page("index.html", page);
+        assert!(site.check_links().is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! Word-wrap and reflow of plain text, e.g. the plain-text version of a document or the lines of a banner.
+//!
+//! ```reflow()``` joins the lines of each paragraph and wraps them at the width. The items of lists (```- ```,
+//! ```* ```, ```1. ```, or footnotes like ```[1] ```) start a new line and are continued with a hanging indent. Lines
+//! are only broken between words, so URLs and inline code in backticks are never split, even when they are wider
+//! than the width. Paragraphs with columns or underlines, e.g. tables and headings, are kept as they are.
+//!
+//! ```
+//! # use mllwriter::reflow;
+//! let text = "Install the crate by `cargo add mllwriter` and read\nthe docs.\n\n- a list item, which is too long for one line";
+//! assert_eq!(
+//!     reflow(text, 24),
+//!     "Install the crate by\n`cargo add mllwriter`\nand read the docs.\n\n- a list item, which is\n  too long for one line"
+//! );
+//! ```
+
+use crate::HTMLWriter;
+
+impl HTMLWriter {
+    /// Returns the content as plain text with the paragraphs wrapped at the width, see ```plain_text()```
+    pub fn plain_text_w_width(&self, width: usize) -> String {
+        reflow(&self.plain_text(), width)
+    }
+}
+
+/// Returns the text with its paragraphs wrapped at the width
+pub fn reflow(text: &str, width: usize) -> String {
+    let mut lines = Vec::new();
+    let mut paragraph = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            reflow_paragraph(&paragraph, width, &mut lines);
+            paragraph.clear();
+            lines.push(String::new());
+        } else {
+            paragraph.push(line);
+        }
+    }
+    reflow_paragraph(&paragraph, width, &mut lines);
+    let mut reflowed = lines.join("\n");
+    if text.ends_with('\n') {
+        reflowed.push('\n');
+    }
+    reflowed
+}
+
+/// Returns the words wrapped at the width as lines, the first line starts with the first prefix and the others
+/// with the second, e.g. for a hanging indent
+pub fn wrap_words(text: &str, width: usize, first_prefix: &str, prefix: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = first_prefix.to_string();
+    let mut empty = true;
+    for word in words(text) {
+        if !empty && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::replace(&mut line, prefix.to_string()));
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(&word);
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+fn reflow_paragraph(paragraph: &[&str], width: usize, lines: &mut Vec<String>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    if paragraph.iter().any(|line| is_verbatim(line)) {
+        lines.extend(paragraph.iter().map(|line| line.to_string()));
+        return;
+    }
+    // The items of the paragraph with their first prefix, the prefix of the continuation lines and the text
+    let mut items: Vec<(String, String, String)> = Vec::new();
+    for line in paragraph {
+        let text = line.trim_start();
+        let indent = &line[..line.len() - text.len()];
+        match (list_marker(text), items.last_mut()) {
+            (None, Some(item)) => {
+                item.2.push(' ');
+                item.2.push_str(text.trim_end());
+            }
+            (marker, _) => {
+                let marker = marker.unwrap_or(0);
+                items.push((
+                    format!("{}{}", indent, &text[..marker]),
+                    " ".repeat(indent.chars().count() + marker),
+                    text[marker..].trim_end().to_string(),
+                ));
+            }
+        }
+    }
+    for (first_prefix, prefix, text) in items {
+        lines.extend(wrap_words(&text, width, &first_prefix, &prefix));
+    }
+}
+
+// Columns (two spaces inside of a line) and underlines are kept as they are
+fn is_verbatim(line: &str) -> bool {
+    let text = line.trim();
+    text.contains("  ")
+        || (text.len() >= 3 && (text.chars().all(|c| c == '=') || text.chars().all(|c| c == '-')))
+}
+
+// Returns the length of the marker of a list item, including the space
+fn list_marker(text: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "].iter().any(|m| text.starts_with(m)) {
+        return Some(2);
+    }
+    let (digits, close) = match text.strip_prefix('[') {
+        Some(rest) => (rest, "] "),
+        None => (text, ". "),
+    };
+    let n = digits.chars().take_while(char::is_ascii_digit).count();
+    (n > 0 && digits[n..].starts_with(close)).then(|| text.len() - digits.len() + n + 2)
+}
+
+// Splits the text into words, code in backticks is a single word
+fn words(text: &str) -> Vec<String> {
+    // An unpaired backtick doesn't start code
+    let code_spans = text.matches('`').count().is_multiple_of(2);
+    let mut in_code = false;
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c == '`' && code_spans {
+            in_code = !in_code;
+        }
+        if c.is_whitespace() && !in_code {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflow_lists_and_tables() {
+        let text = "Item  Price\nTea   3.50\n\n  1. first item of\na nested list\n[1] https://example.com/a/very/long/url";
+        assert_eq!(
+            reflow(text, 14),
+            "Item  Price\nTea   3.50\n\n  1. first\n     item of a\n     nested\n     list\n\
+             [1] https://example.com/a/very/long/url"
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Reading documents back into the writers, e.g. to reformat or convert them.
+//!
+//! ```replay_html()```, ```replay_xml()``` and ```replay_json()``` parse a document and replay it by the calls of
+//! a writer. The options of the writer decide about the output, e.g. ```set_format_mode(FormatMode::Pretty)``` for
+//! reformatting, ```Minified``` for minifying or the validation level of the HTMLWriter for validating. Text and
+//! attribute values are taken as they are escaped already, whitespace between elements is dropped, because the
+//! layout of the writer replaces it. The content of 'pre', 'script', 'style' and 'textarea' is kept, end-tags,
+//! which HTML allows to leave out, e.g. of 'li', are added. The JSON-functions read the document in the given
+//! dialect: comments are accepted in JSONC and JSON5 only, and they are dropped. ```replay_json_as_yaml()```
+//! converts JSON into YAML.
+//!
+//! ```
+//! # use mllwriter::{replay_html, FormatMode, HTMLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_format_mode(FormatMode::Pretty);
+//! replay_html("<div>\n<p>a &amp; <b>b</b></p><br></div>", &mut wr).unwrap();
+//! assert_eq!(wr.content, "<div>\n    <p>a &amp; <b>b</b></p><br>\n</div>");
+//! ```
+
+use crate::html_rules::{implicit_closes, VOID_ELEMENTS};
+use crate::json::{self, Json};
+use crate::plaintext::{parse_attributes, tag_end};
+use crate::tag::is_xml_name;
+use crate::yaml::yaml_string;
+use crate::{
+    push_property, HTMLWriter, HtmlTag, JSONWriter, JsonDialect, MLLWriter, VoidStyle, WriterError,
+    XMLWriter, XmlName, YAMLWriter,
+};
+
+/// Writes the HTML-document or -fragment by the writer
+pub fn replay_html(html: &str, wr: &mut HTMLWriter) -> Result<(), WriterError> {
+    replay_markup(html, wr)
+}
+
+/// Writes the XML-document by the writer, elements without content are self-closing
+pub fn replay_xml(xml: &str, wr: &mut XMLWriter) -> Result<(), WriterError> {
+    wr.set_void_style(VoidStyle::Xml);
+    replay_markup(xml, wr)
+}
+
+/// Writes the JSON-document of the dialect by the writer, in the dialect of the writer
+pub fn replay_json(
+    json: &str,
+    dialect: JsonDialect,
+    wr: &mut JSONWriter,
+) -> Result<(), WriterError> {
+    replay_tree(&json::parse(json, dialect)?, wr)
+}
+
+/// Writes the JSON-document of the dialect as YAML by the writer
+pub fn replay_json_as_yaml(
+    json: &str,
+    dialect: JsonDialect,
+    wr: &mut YAMLWriter,
+) -> Result<(), WriterError> {
+    replay_tree(&json::parse(json, dialect)?, wr)
+}
+
+// The markup-writers with the rules of their markup
+trait MarkupReplay: MLLWriter {
+    // The elements without content and end-tag
+    const VOID: &'static [&'static str];
+    // The elements, whose content is kept as it is
+    const RAW_TEXT: &'static [&'static str];
+    // The elements, whose end-tag may be left out
+    const OPTIONAL_END: &'static [&'static str];
+
+    // Returns the name of the element or attribute as the writer takes it
+    fn name(raw: &str) -> String;
+
+    fn open(&mut self, name: &str, single: bool) -> Result<(), WriterError>;
+
+    fn property_raw(&mut self, name: &str, value: &str) -> Result<(), WriterError>;
+
+    // Returns the number of open elements, which are closed implicitly by the start-tag
+    fn implied_closes(_name: &str, _open: &[String]) -> usize {
+        0
+    }
+}
+
+impl MarkupReplay for HTMLWriter {
+    const VOID: &'static [&'static str] = &VOID_ELEMENTS;
+    const RAW_TEXT: &'static [&'static str] = &["pre", "script", "style", "textarea"];
+    const OPTIONAL_END: &'static [&'static str] = &[
+        "body", "caption", "colgroup", "dd", "dt", "head", "html", "li", "optgroup", "option", "p",
+        "rp", "rt", "tbody", "td", "tfoot", "th", "thead", "tr",
+    ];
+
+    // HTML isn't case-sensitive
+    fn name(raw: &str) -> String {
+        raw.to_ascii_lowercase()
+    }
+
+    fn open(&mut self, name: &str, single: bool) -> Result<(), WriterError> {
+        if name.contains('-') {
+            self.try_open_element(HtmlTag::Custom(name.to_string()))
+        } else if single {
+            self.try_single_tag(name)
+        } else {
+            self.try_open_tag(name)
+        }
+    }
+
+    fn property_raw(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.write_property(name, value, "replay_html")
+    }
+
+    fn implied_closes(name: &str, open: &[String]) -> usize {
+        let (closes, boundaries): (&[&str], &[&str]) = match name {
+            "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+            "option" | "optgroup" => (&["option"], &["select", "datalist", "optgroup"]),
+            _ => return implicit_closes(name, open),
+        };
+        open.iter()
+            .rev()
+            .take_while(|open| !boundaries.contains(&open.as_str()))
+            .position(|open| closes.contains(&open.as_str()))
+            .map_or(0, |i| i + 1)
+    }
+}
+
+impl MarkupReplay for XMLWriter {
+    const VOID: &'static [&'static str] = &[];
+    const RAW_TEXT: &'static [&'static str] = &[];
+    const OPTIONAL_END: &'static [&'static str] = &[];
+
+    fn name(raw: &str) -> String {
+        raw.to_string()
+    }
+
+    fn open(&mut self, name: &str, single: bool) -> Result<(), WriterError> {
+        XmlName::new(name)?;
+        if single {
+            self.write_single_tag(name)
+        } else {
+            self.write_open_tag(name)
+        }
+    }
+
+    // The names of namespaces, e.g. "xmlns:svg", and "xml:lang" start with "xml"
+    fn property_raw(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        if !is_xml_name(name) {
+            return Err(WriterError::InvalidXmlName(name.to_string()));
+        }
+        self.core.begin_any_property("replay_xml")?;
+        push_property(&mut self.core, &mut self.content, name, value);
+        Ok(())
+    }
+}
+
+fn replay_markup<M: MarkupReplay>(markup: &str, wr: &mut M) -> Result<(), WriterError> {
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = markup;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            text(wr, rest)?;
+            break;
+        };
+        text(wr, &rest[..start])?;
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| invalid("a comment isn't closed"))?;
+            wr.add_comment(comment[..end].trim());
+            rest = &comment[end + 3..];
+            continue;
+        }
+        if rest.starts_with("<![CDATA[") {
+            let end = rest
+                .find("]]>")
+                .ok_or_else(|| invalid("a CDATA-section isn't closed"))?
+                + 3;
+            wr.try_raw(&rest[..end])?;
+            rest = &rest[end..];
+            continue;
+        }
+        let end = tag_end(rest);
+        if end == rest.len() {
+            return Err(invalid("a tag isn't closed by '>'"));
+        }
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            wr.try_raw(&format!("<{}>", tag))?;
+        } else if let Some(name) = tag.strip_prefix('/') {
+            let name = M::name(name.trim());
+            // The elements with optional end-tags in front of the element are closed with it
+            let implied = open
+                .iter()
+                .rev()
+                .take_while(|open| **open != name && M::OPTIONAL_END.contains(&open.as_str()))
+                .count();
+            match open.iter().rev().nth(implied) {
+                Some(found) if *found == name => {
+                    for _ in 0..=implied {
+                        open.pop();
+                        wr.try_close_tag()?;
+                    }
+                }
+                Some(found) => {
+                    return Err(invalid(&format!(
+                        "the end-tag '{}' doesn't match '{}'",
+                        name, found
+                    )))
+                }
+                None => return Err(invalid(&format!("the end-tag '{}' has no start-tag", name))),
+            }
+        } else {
+            let name_end = tag
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(tag.len());
+            let name = M::name(&tag[..name_end]);
+            for _ in 0..M::implied_closes(&name, &open) {
+                open.pop();
+                wr.try_close_tag()?;
+            }
+            let single = tag.ends_with('/') || M::VOID.contains(&name.as_str());
+            wr.open(&name, single)?;
+            for (attribute, value) in parse_attributes(&tag[name_end..]) {
+                wr.property_raw(&M::name(attribute), &requote(value))?;
+            }
+            if single {
+                continue;
+            }
+            if M::RAW_TEXT.contains(&name.as_str()) {
+                let end = rest
+                    .to_ascii_lowercase()
+                    .find(&format!("</{}", name))
+                    .ok_or_else(|| invalid(&format!("'{}' isn't closed", name)))?;
+                if end > 0 {
+                    wr.try_raw(&rest[..end])?;
+                }
+                rest = &rest[end..];
+            }
+            open.push(name);
+        }
+    }
+    while open
+        .last()
+        .is_some_and(|name| M::OPTIONAL_END.contains(&name.as_str()))
+    {
+        open.pop();
+        wr.try_close_tag()?;
+    }
+    match open.last() {
+        Some(name) => Err(invalid(&format!("'{}' isn't closed", name))),
+        None => Ok(()),
+    }
+}
+
+// Escapes the characters, which the source left unescaped, but which can't stand in a quoted value, e.g. a '"' in
+// a value in single quotes. The references of the source are kept, e.g. "&copy;".
+fn requote(value: &str) -> String {
+    let mut requoted = String::with_capacity(value.len());
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => requoted.push_str("&quot;"),
+            '\'' => requoted.push_str("&#39;"),
+            '<' => requoted.push_str("&lt;"),
+            '>' => requoted.push_str("&gt;"),
+            '&' if !is_reference(&value[i..]) => requoted.push_str("&amp;"),
+            c => requoted.push(c),
+        }
+    }
+    requoted
+}
+
+// Returns true if the text starts with a character reference, e.g. "&amp;" or "&#39;"
+fn is_reference(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let reference = &text[1..end];
+    match reference.strip_prefix('#') {
+        Some(number) => {
+            let hex = number.strip_prefix(['x', 'X']);
+            !number.is_empty()
+                && match hex {
+                    Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+                    None => number.chars().all(|c| c.is_ascii_digit()),
+                }
+        }
+        None => !reference.is_empty() && reference.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+// Writes the text, the whitespace of line-feeds at its ends is dropped
+fn text<M: MLLWriter>(wr: &mut M, text: &str) -> Result<(), WriterError> {
+    let mut text = text;
+    let start = text.trim_start();
+    if text[..text.len() - start.len()].contains('\n') {
+        text = start;
+    }
+    let end = text.trim_end();
+    if text[end.len()..].contains('\n') {
+        text = end;
+    }
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    wr.try_raw(text)
+}
+
+// The writers of trees of mappings and sequences
+trait TreeReplay: MLLWriter {
+    fn open_sequence(&mut self, name: &str) -> Result<(), WriterError>;
+
+    fn item(&mut self, value: &str) -> Result<(), WriterError>;
+
+    // Returns the string as value of the writer
+    fn string(&self, text: &str) -> String;
+}
+
+impl TreeReplay for JSONWriter {
+    fn open_sequence(&mut self, name: &str) -> Result<(), WriterError> {
+        self.try_open_array(name)
+    }
+
+    fn item(&mut self, value: &str) -> Result<(), WriterError> {
+        self.try_add_item(value)
+    }
+
+    fn string(&self, text: &str) -> String {
+        JSONWriter::string(self, text)
+    }
+}
+
+impl TreeReplay for YAMLWriter {
+    fn open_sequence(&mut self, name: &str) -> Result<(), WriterError> {
+        self.try_open_list(name)
+    }
+
+    fn item(&mut self, value: &str) -> Result<(), WriterError> {
+        self.try_add_item(value)
+    }
+
+    fn string(&self, text: &str) -> String {
+        yaml_string(text)
+    }
+}
+
+// Writes the root, which is an object or an array
+fn replay_tree<T: TreeReplay>(root: &Json, wr: &mut T) -> Result<(), WriterError> {
+    if !matches!(root, Json::Object(_) | Json::Array(_)) {
+        return Err(WriterError::InvalidJson(
+            "the root of the document isn't an object or an array".to_string(),
+        ));
+    }
+    tree_value(wr, None, root)
+}
+
+// Writes the value as member with the name, or as item of an array or the root if there is no name
+fn tree_value<T: TreeReplay>(
+    wr: &mut T,
+    name: Option<&str>,
+    value: &Json,
+) -> Result<(), WriterError> {
+    match value {
+        Json::Object(_) | Json::Array(_) if name == Some("") => Err(WriterError::InvalidJson(
+            "objects and arrays with an empty name can't be written".to_string(),
+        )),
+        Json::Object(members) => {
+            wr.try_open_tag(name.unwrap_or_default())?;
+            for (name, value) in members {
+                tree_value(wr, Some(name), value)?;
+            }
+            wr.try_close_tag()
+        }
+        Json::Array(items) => {
+            wr.open_sequence(name.unwrap_or_default())?;
+            for item in items {
+                tree_value(wr, None, item)?;
+            }
+            wr.try_close_tag()
+        }
+        scalar => {
+            let text = match scalar {
+                Json::Null => "null".to_string(),
+                Json::Bool(b) => b.to_string(),
+                Json::Number(n) => n.clone(),
+                Json::String(s) => wr.string(s),
+                Json::Object(_) | Json::Array(_) => unreachable!("blocks are written above"),
+            };
+            match name {
+                Some(name) => wr.try_add_property(name, &text),
+                None => wr.item(&text),
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> WriterError {
+    WriterError::InvalidMarkup(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FormatMode;
+
+    #[test]
+    fn replay_round_trips() {
+        let mut wr = XMLWriter::new();
+        wr.set_format_mode(FormatMode::Minified);
+        replay_xml(
+            "<?xml version=\"1.0\"?>\n<svg xmlns='http://www.w3.org/2000/svg' x='1 &lt; \"2\"'>\n  \
+             <linearGradient/><!-- c -->\n</svg>",
+            &mut wr,
+        )
+        .unwrap();
+        assert_eq!(
+            wr.content,
+            "<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\" x=\"1 &lt; &quot;2&quot;\">\
+             <linearGradient/><!-- c --></svg>"
+        );
+        assert!(replay_xml("<a><b></a>", &mut XMLWriter::new()).is_err());
+        let mut wr = HTMLWriter::new();
+        replay_html(
+            "<ul><li>a<li>b</ul><p>c &copy; d<pre> e</pre><DL><dt>f<dd>g</dl>",
+            &mut wr,
+        )
+        .unwrap();
+        assert_eq!(
+            wr.content,
+            "<ul><li>a</li><li>b</li></ul><p>c &copy; d</p><pre> e</pre><dl><dt>f</dt><dd>g</dd></dl>"
+        );
+        let mut wr = YAMLWriter::new();
+        let json = "{\"a\": [1, {\"b\": \"true\"}], \"c\": null}";
+        replay_json_as_yaml(json, JsonDialect::Json, &mut wr).unwrap();
+        assert_eq!(wr.content, "a:\n  - 1\n  - b: \"true\"\nc: null");
+        let mut wr = JSONWriter::new();
+        replay_json("[1, {\"\": 2}] // c", JsonDialect::Jsonc, &mut wr).unwrap();
+        assert_eq!(wr.content, "[\n  1,\n  {\n    \"\": 2\n  }\n]");
+        assert!(replay_json("{\"\": []}", JsonDialect::Json, &mut JSONWriter::new()).is_err());
+        assert!(replay_json("/* c */ {}", JsonDialect::Json, &mut JSONWriter::new()).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Properties of the current element after its start-tag was left.
+//!
+//! HTML and XML remember where the start-tags of the open elements begin. So ```add_property()``` and
+//! ```add_properties()``` also work after text, a line-feed or closed child elements: the properties are spliced
+//! into the start-tag of the current element, i.e. the last one, which is still open. This isn't possible with
+//! aligned properties, in a ```SinkWriter```, whose start-tags are written already, or after methods, which
+//! rewrite the content, e.g. ```resolve_lists()```. A dry run by ```dry_run()``` accepts the properties like the
+//! writer and counts them, also if the start-tag was flushed.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("ul");
+//! let mut items = 0;
+//! for fruit in ["apple", "pear"] {
+//!     wr.line_feed(1);
+//!     wr.open_tag("li");
+//!     wr.text(fruit);
+//!     wr.close_tag();
+//!     items += 1;
+//! }
+//! wr.add_property("title", &format!("{} items", items));
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<ul title=\"2 items\">\n<li>apple</li>\n<li>pear</li></ul>");
+//! ```
+
+use crate::snapshot::Frozen;
+use crate::{DocumentState, WriterCore, WriterError};
+
+// The offset of a start-tag, which a dry run has flushed already
+const FLUSHED: usize = usize::MAX;
+
+impl WriterCore {
+    // Remembers that the start-tag of the element, which is opened next, begins at the offset
+    pub(crate) fn mark_tag_start(&mut self, offset: usize) {
+        self.tag_starts.push((self.block_stack.len() + 1, offset));
+    }
+
+    // Forgets the start-tags of the closed elements
+    pub(crate) fn forget_closed_tags(&mut self) {
+        while self
+            .tag_starts
+            .last()
+            .is_some_and(|(depth, _)| *depth > self.block_stack.len())
+        {
+            self.tag_starts.pop();
+        }
+    }
+
+    // Moves the start-tags by the flushed content. The flushed ones are forgotten, but a dry run keeps them to
+    // count their properties.
+    pub(crate) fn flush_tag_starts(&mut self, flushed: usize, dry_run: bool) {
+        self.tag_starts.retain_mut(|(_, offset)| {
+            if *offset == FLUSHED {
+                true
+            } else if *offset >= flushed {
+                *offset -= flushed;
+                true
+            } else if dry_run {
+                *offset = FLUSHED;
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    // Like begin_property(), but also accepts the start-tag of the current element, if it is known
+    pub(crate) fn begin_any_property(
+        &mut self,
+        operation: &'static str,
+    ) -> Result<(), WriterError> {
+        if self.state == DocumentState::InElement && self.current_tag_start().is_some() {
+            return Ok(());
+        }
+        self.begin_property(operation)
+    }
+
+    // Inserts the text in front of the '>' of the start-tag of the current element
+    pub(crate) fn insert_into_open_tag(&mut self, content: &mut String, text: &str) {
+        let start = self
+            .current_tag_start()
+            .expect("the start-tag is checked by begin_any_property()");
+        if start == FLUSHED {
+            self.spliced.push_str(text);
+            return;
+        }
+        let end = start + tag_end(&content[start..]);
+        content.insert_str(end, text);
+        if end < self.frozen.len() {
+            self.frozen = Frozen::default();
+        }
+    }
+
+    // The offset of the start-tag of the current element
+    fn current_tag_start(&self) -> Option<usize> {
+        if self.align_properties {
+            return None;
+        }
+        match self.tag_starts.last() {
+            Some((depth, offset)) if *depth == self.block_stack.len() => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+// Returns the offset of the '>', which ends the start-tag, the values in quotes may contain a '>'
+pub(crate) fn tag_end(tag: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('>', None) => return i,
+            _ => (),
+        }
+    }
+    tag.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BufferedWriter, HTMLWriter, MLLWriter, XMLWriter};
+
+    #[test]
+    fn properties_of_the_current_element() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        wr.add_property_raw("x", "1>0");
+        wr.open_tag("b");
+        wr.close_tag();
+        wr.add_property("y", "2");
+        let snapshot = wr.snapshot();
+        wr.text("t");
+        wr.add_property("z", "3");
+        wr.close_tag();
+        assert_eq!(wr.content, "<a x=\"1>0\" y=\"2\" z=\"3\"><b></b>t</a>");
+        assert_eq!(snapshot.to_string(), "<a x=\"1>0\" y=\"2\"><b></b>");
+        assert_eq!(wr.snapshot().to_string(), wr.content);
+        let mut wr = XMLWriter::with_sink(Vec::new());
+        wr.open_tag("a");
+        wr.open_tag("b");
+        wr.close_tag();
+        wr.text("t");
+        assert!(wr.try_add_property("z", "3").is_err());
+        fn list<M: MLLWriter>(wr: &mut M) {
+            wr.open_tag("ul");
+            for item in ["a", "b"] {
+                wr.open_tag("li");
+                wr.add_property("class", "item");
+                wr.text(item);
+                wr.close_tag();
+            }
+            wr.add_property("title", "2 items");
+            wr.close_tag();
+        }
+        let mut wr = HTMLWriter::new();
+        list(&mut wr);
+        let mut dry = HTMLWriter::dry_run();
+        list(&mut dry);
+        assert_eq!(dry.content_length(), wr.content.len());
+    }
+}
@@ -0,0 +1,115 @@
+//! The crawl policy of HTML-documents.
+//!
+//! A [`RobotsPolicy`] is written as 'meta'-element "robots" by ```wr.robots()```. A [`DocumentSet`] inserts its
+//! default policy into every page, which doesn't state an own one, see ```DocumentSet::set_robots()```.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, RobotsPolicy};
+//! let mut wr = HTMLWriter::new();
+//! wr.robots(&RobotsPolicy::noindex().with_follow(false).with_directive("noarchive"));
+//! assert_eq!(wr.content, "<meta name=\"robots\" content=\"noindex, nofollow, noarchive\">");
+//! ```
+//!
+//! [`DocumentSet`]: crate::DocumentSet
+
+use crate::HTMLWriter;
+
+/// The directives for crawlers, by default pages are indexed and their links followed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RobotsPolicy {
+    index: bool,
+    follow: bool,
+    directives: Vec<String>,
+}
+
+impl Default for RobotsPolicy {
+    fn default() -> Self {
+        RobotsPolicy {
+            index: true,
+            follow: true,
+            directives: Vec::new(),
+        }
+    }
+}
+
+impl RobotsPolicy {
+    /// Returns the policy for pages, which must not be indexed
+    pub fn noindex() -> RobotsPolicy {
+        RobotsPolicy::default().with_index(false)
+    }
+
+    /// Returns the policy with indexing allowed or forbidden
+    pub fn with_index(mut self, index: bool) -> RobotsPolicy {
+        self.index = index;
+        self
+    }
+
+    /// Returns the policy with following the links allowed or forbidden
+    pub fn with_follow(mut self, follow: bool) -> RobotsPolicy {
+        self.follow = follow;
+        self
+    }
+
+    /// Returns the policy with an additional directive, e.g. "noarchive" or "max-snippet:50"
+    pub fn with_directive(mut self, directive: &str) -> RobotsPolicy {
+        self.directives.push(directive.to_string());
+        self
+    }
+
+    /// Returns the content of the 'meta'-element, e.g. "noindex, follow"
+    pub fn content(&self) -> String {
+        let mut directives = vec![
+            if self.index { "index" } else { "noindex" }.to_string(),
+            if self.follow { "follow" } else { "nofollow" }.to_string(),
+        ];
+        directives.extend(self.directives.iter().cloned());
+        directives.join(", ")
+    }
+}
+
+impl HTMLWriter {
+    /// Writes the policy as 'meta'-element into the head
+    pub fn robots(&mut self, policy: &RobotsPolicy) {
+        self.add_meta("robots", &policy.content());
+    }
+}
+
+// Returns true if the head of the document has an own policy, in double or single quotes
+pub(crate) fn has_robots(content: &str) -> bool {
+    let head = content
+        .find("</head>")
+        .map_or(content, |end| &content[..end]);
+    head.contains("<meta name=\"robots\"") || head.contains("<meta name='robots'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentSet, MLLWriter, QuoteStyle};
+
+    #[test]
+    fn robots_set_default() {
+        let mut own = HTMLWriter::new();
+        own.core.set_quote_style(QuoteStyle::Single);
+        own.open_tag("head");
+        own.robots(&RobotsPolicy::default());
+        own.close_tag();
+        let mut plain = HTMLWriter::new();
+        plain.open_tag("head");
+        plain.close_tag();
+        let mut site = DocumentSet::new("https://example.com");
+        site.set_canonical(false);
+        site.set_robots(Some(RobotsPolicy::noindex()));
+        site.add_page("own.html", own);
+        site.add_page("plain.html", plain);
+        let pages = site.render().unwrap();
+        assert_eq!(
+            pages[0].1,
+            "<head><meta name='robots' content='index, follow'></head>"
+        );
+        assert_eq!(
+            pages[1].1,
+            "<head><meta name=\"robots\" content=\"noindex, follow\"></head>"
+        );
+    }
+}
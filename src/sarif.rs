@@ -0,0 +1,369 @@
+//! Static analysis results in SARIF 2.1.0.
+//!
+//! The [`SarifBuilder`] collects runs of tools, each with its rules and its results, and writes the nested
+//! SARIF-log with the JSONWriter. A result refers to a rule of its run, the index of the rule is filled in, and the
+//! level of the rule is the default of its results. Locations are files with a region of lines and columns.
+//!
+//! ```
+//! # use mllwriter::{SarifBuilder, SarifLevel, SarifLocation};
+//! let mut sarif = SarifBuilder::new();
+//! sarif.add_run("lint", "0.3.0");
+//! sarif.add_rule("L001", "Line too long", SarifLevel::Warning);
+//! sarif.add_result("L001", "line has 120 characters", &[SarifLocation::new("src/main.rs", 7)]);
+//! let json = sarif.to_json();
+//! assert!(json.contains("\"ruleId\": \"L001\",\n          \"ruleIndex\": 0,\n          \"level\": \"warning\","));
+//! assert!(json.contains("\"region\":\n                {\n                  \"startLine\": 7\n"));
+//! ```
+
+use crate::format::json_string;
+use crate::{check, JSONWriter, MLLWriter, WriterError};
+
+/// The URI of the JSON Schema of SARIF 2.1.0
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The levels of results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SarifLevel {
+    /// Not a problem, e.g. a passed check
+    None,
+    /// A minor problem or an opportunity for improvement
+    Note,
+    /// A problem
+    Warning,
+    /// A serious problem
+    Error,
+}
+
+impl SarifLevel {
+    /// Returns the name of the level, e.g. "warning"
+    pub fn name(self) -> &'static str {
+        match self {
+            SarifLevel::None => "none",
+            SarifLevel::Note => "note",
+            SarifLevel::Warning => "warning",
+            SarifLevel::Error => "error",
+        }
+    }
+}
+
+/// A region in a file, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SarifLocation {
+    /// The URI of the file, relative paths are relative to the root of the repository
+    pub uri: String,
+    /// The first line
+    pub start_line: u32,
+    /// The first column
+    pub start_column: Option<u32>,
+    /// The last line
+    pub end_line: Option<u32>,
+    /// The column after the region
+    pub end_column: Option<u32>,
+}
+
+impl SarifLocation {
+    /// Returns the location of a line
+    pub fn new(uri: &str, start_line: u32) -> SarifLocation {
+        SarifLocation {
+            uri: uri.to_string(),
+            start_line,
+            start_column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    /// Returns the location starting at the column
+    pub fn with_column(mut self, start_column: u32) -> SarifLocation {
+        self.start_column = Some(start_column);
+        self
+    }
+
+    /// Returns the location ending before the column of the line
+    pub fn with_end(mut self, end_line: u32, end_column: u32) -> SarifLocation {
+        self.end_line = Some(end_line);
+        self.end_column = Some(end_column);
+        self
+    }
+}
+
+// A run of a tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SarifRun {
+    tool: String,
+    version: String,
+    // id, description and default level
+    rules: Vec<(String, String, SarifLevel)>,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SarifResult {
+    rule_index: usize,
+    level: SarifLevel,
+    message: String,
+    locations: Vec<SarifLocation>,
+}
+
+/// Collects the runs of tools and writes them as SARIF-log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SarifBuilder {
+    runs: Vec<SarifRun>,
+}
+
+impl SarifBuilder {
+    /// Returns a log without runs
+    pub fn new() -> SarifBuilder {
+        SarifBuilder::default()
+    }
+
+    /// Starts the run of the tool, the following rules and results belong to it
+    pub fn add_run(&mut self, tool: &str, version: &str) {
+        self.runs.push(SarifRun {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            rules: Vec::new(),
+            results: Vec::new(),
+        });
+    }
+
+    /// Adds a rule to the current run
+    pub fn add_rule(&mut self, id: &str, description: &str, level: SarifLevel) {
+        check(self.try_add_rule(id, description, level));
+    }
+
+    /// Like ```add_rule()```, but returns the error instead of panicking, e.g. ```InvalidSarif``` for a rule,
+    /// which is already defined
+    pub fn try_add_rule(
+        &mut self,
+        id: &str,
+        description: &str,
+        level: SarifLevel,
+    ) -> Result<(), WriterError> {
+        let run = self.current_run()?;
+        if run.rules.iter().any(|(rule, _, _)| rule == id) {
+            return Err(WriterError::InvalidSarif(format!(
+                "the rule '{}' is already defined",
+                id
+            )));
+        }
+        run.rules
+            .push((id.to_string(), description.to_string(), level));
+        Ok(())
+    }
+
+    /// Adds a result of the rule with the default level of the rule
+    pub fn add_result(&mut self, rule_id: &str, message: &str, locations: &[SarifLocation]) {
+        check(self.try_add_result(rule_id, message, locations));
+    }
+
+    /// Like ```add_result()```, but returns the error instead of panicking, e.g. ```InvalidSarif``` for an
+    /// unknown rule
+    pub fn try_add_result(
+        &mut self,
+        rule_id: &str,
+        message: &str,
+        locations: &[SarifLocation],
+    ) -> Result<(), WriterError> {
+        let level = self.rule(rule_id)?.1;
+        self.try_add_result_w_level(rule_id, level, message, locations)
+    }
+
+    /// Adds a result of the rule with another level than the default
+    pub fn add_result_w_level(
+        &mut self,
+        rule_id: &str,
+        level: SarifLevel,
+        message: &str,
+        locations: &[SarifLocation],
+    ) {
+        check(self.try_add_result_w_level(rule_id, level, message, locations));
+    }
+
+    /// Like ```add_result_w_level()```, but returns the error instead of panicking
+    pub fn try_add_result_w_level(
+        &mut self,
+        rule_id: &str,
+        level: SarifLevel,
+        message: &str,
+        locations: &[SarifLocation],
+    ) -> Result<(), WriterError> {
+        let rule_index = self.rule(rule_id)?.0;
+        self.current_run()?.results.push(SarifResult {
+            rule_index,
+            level,
+            message: message.to_string(),
+            locations: locations.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Returns the number of results of all runs
+    pub fn result_count(&self) -> usize {
+        self.runs.iter().map(|run| run.results.len()).sum()
+    }
+
+    /// Returns the SARIF-log
+    pub fn to_json(&self) -> String {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("$schema", &json_string(SARIF_SCHEMA));
+        wr.add_property("version", &json_string("2.1.0"));
+        if self.runs.is_empty() {
+            wr.add_property("runs", "[]");
+        } else {
+            wr.open_array("runs");
+            for run in &self.runs {
+                write_run(&mut wr, run);
+            }
+            wr.close_tag();
+        }
+        wr.close_tag();
+        wr.content
+    }
+
+    // Returns the run, which the rules and results are added to
+    fn current_run(&mut self) -> Result<&mut SarifRun, WriterError> {
+        self.runs.last_mut().ok_or_else(|| {
+            WriterError::InvalidSarif("rules and results need a run, see add_run()".to_string())
+        })
+    }
+
+    // Returns the index and the default level of the rule of the current run
+    fn rule(&mut self, rule_id: &str) -> Result<(usize, SarifLevel), WriterError> {
+        self.current_run()?
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, (id, _, _))| id == rule_id)
+            .map(|(i, (_, _, level))| (i, *level))
+            .ok_or_else(|| {
+                WriterError::InvalidSarif(format!(
+                    "the rule '{}' isn't defined, see add_rule()",
+                    rule_id
+                ))
+            })
+    }
+}
+
+fn write_run(wr: &mut JSONWriter, run: &SarifRun) {
+    wr.open_tag("");
+    wr.open_tag("tool");
+    wr.open_tag("driver");
+    wr.add_property("name", &json_string(&run.tool));
+    wr.add_property("version", &json_string(&run.version));
+    if !run.rules.is_empty() {
+        wr.open_array("rules");
+        for (id, description, level) in &run.rules {
+            wr.open_tag("");
+            wr.add_property("id", &json_string(id));
+            wr.open_tag("shortDescription");
+            wr.add_property("text", &json_string(description));
+            wr.close_tag();
+            wr.open_tag("defaultConfiguration");
+            wr.add_property("level", &json_string(level.name()));
+            wr.close_tag();
+            wr.close_tag();
+        }
+        wr.close_tag();
+    }
+    wr.close_tag();
+    wr.close_tag();
+    if run.results.is_empty() {
+        // An empty array states, that the tool found nothing
+        wr.add_property("results", "[]");
+    } else {
+        wr.open_array("results");
+        for result in &run.results {
+            write_result(wr, run, result);
+        }
+        wr.close_tag();
+    }
+    wr.close_tag();
+}
+
+fn write_result(wr: &mut JSONWriter, run: &SarifRun, result: &SarifResult) {
+    wr.open_tag("");
+    wr.add_property("ruleId", &json_string(&run.rules[result.rule_index].0));
+    wr.add_property("ruleIndex", &result.rule_index.to_string());
+    wr.add_property("level", &json_string(result.level.name()));
+    wr.open_tag("message");
+    wr.add_property("text", &json_string(&result.message));
+    wr.close_tag();
+    if !result.locations.is_empty() {
+        wr.open_array("locations");
+        for location in &result.locations {
+            wr.open_tag("");
+            wr.open_tag("physicalLocation");
+            wr.open_tag("artifactLocation");
+            wr.add_property("uri", &json_string(&location.uri));
+            wr.close_tag();
+            wr.open_tag("region");
+            wr.add_property("startLine", &location.start_line.to_string());
+            let optional = [
+                ("startColumn", location.start_column),
+                ("endLine", location.end_line),
+                ("endColumn", location.end_column),
+            ];
+            for (name, value) in optional {
+                if let Some(value) = value {
+                    wr.add_property(name, &value.to_string());
+                }
+            }
+            wr.close_tag();
+            wr.close_tag();
+            wr.close_tag();
+        }
+        wr.close_tag();
+    }
+    wr.close_tag();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_levels_and_regions() {
+        let mut sarif = SarifBuilder::new();
+        sarif.add_run("scan", "1");
+        sarif.add_rule("A", "a", SarifLevel::Note);
+        sarif.add_rule("B", "b", SarifLevel::Error);
+        sarif.add_result_w_level(
+            "B",
+            SarifLevel::Warning,
+            "m",
+            &[SarifLocation::new("x.rs", 2).with_column(3).with_end(2, 9)],
+        );
+        sarif.add_run("empty", "1");
+        assert_eq!(sarif.result_count(), 1);
+        let json = sarif.to_json();
+        assert!(json.contains("\"ruleIndex\": 1,\n          \"level\": \"warning\","));
+        assert!(json.contains(
+            "\"startLine\": 2,\n                  \"startColumn\": 3,\n                  \
+             \"endLine\": 2,\n                  \"endColumn\": 9\n"
+        ));
+        assert!(json.contains("\"results\": []"));
+        crate::json::parse(&json, crate::JsonDialect::Json).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid SARIF: the rule 'X' isn't defined, see add_rule()")]
+    fn sarif_unknown_rule() {
+        let mut sarif = SarifBuilder::new();
+        assert!(matches!(
+            sarif.try_add_rule("A", "a", SarifLevel::Note),
+            Err(WriterError::InvalidSarif(_))
+        ));
+        sarif.add_run("scan", "1");
+        sarif.add_rule("A", "a", SarifLevel::Note);
+        assert_eq!(
+            sarif.try_add_rule("A", "b", SarifLevel::Error),
+            Err(WriterError::InvalidSarif(
+                "the rule 'A' is already defined".to_string()
+            ))
+        );
+        assert!(sarif.try_add_result("X", "m", &[]).is_err());
+        sarif.add_result("X", "m", &[]);
+    }
+}
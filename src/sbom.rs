@@ -0,0 +1,389 @@
+//! Software bills of materials (SBOM) in CycloneDX 1.5 and SPDX 2.3.
+//!
+//! The [`SbomBuilder`] collects the [`SbomComponent`]s of a product and writes them as CycloneDX-document with
+//! the JSONWriter or the XMLWriter, both flavors by the same call path, so they always carry the same fields in
+//! the order of the schema. ```spdx_tag_value()``` writes the same components in the SPDX tag-value format,
+//! missing information is written as "NOASSERTION".
+//!
+//! ```
+//! # use mllwriter::{SbomBuilder, SbomComponent};
+//! let mut sbom = SbomBuilder::new("shop", "2.0.0");
+//! sbom.add_component(
+//!     SbomComponent::new("serde", "1.0.210")
+//!         .with_purl("pkg:cargo/serde@1.0.210")
+//!         .with_license("MIT OR Apache-2.0"),
+//! );
+//! let xml = sbom.cyclonedx_xml();
+//! assert!(xml.contains(
+//!     "<component type=\"library\" bom-ref=\"pkg:cargo/serde@1.0.210\">\n      <name>serde</name>"
+//! ));
+//! assert!(sbom.cyclonedx_json().contains("\"expression\": \"MIT OR Apache-2.0\""));
+//! ```
+
+use crate::format::json_string;
+use crate::tag::{add_attributes, xml_element, xml_name};
+use crate::{JSONWriter, MLLWriter, WriterError, XMLWriter};
+
+/// The namespace of CycloneDX 1.5 XML-documents
+pub const CYCLONEDX_NAMESPACE: &str = "http://cyclonedx.org/schema/bom/1.5";
+
+/// A component of the product, e.g. a library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomComponent {
+    /// The name
+    pub name: String,
+    /// The version
+    pub version: String,
+    /// The package-URL, e.g. "pkg:cargo/serde@1.0.210"
+    pub purl: Option<String>,
+    /// The name of the supplying organization
+    pub supplier: Option<String>,
+    /// The license as SPDX-expression, e.g. "MIT OR Apache-2.0"
+    pub license: Option<String>,
+    /// The hashes as pairs of the CycloneDX-algorithm, e.g. "SHA-256", and the hex-value
+    pub hashes: Vec<(String, String)>,
+}
+
+impl SbomComponent {
+    /// Returns a component with name and version only
+    pub fn new(name: &str, version: &str) -> SbomComponent {
+        SbomComponent {
+            name: name.to_string(),
+            version: version.to_string(),
+            purl: None,
+            supplier: None,
+            license: None,
+            hashes: Vec::new(),
+        }
+    }
+
+    /// Returns the component with the package-URL
+    pub fn with_purl(mut self, purl: &str) -> SbomComponent {
+        self.purl = Some(purl.to_string());
+        self
+    }
+
+    /// Returns the component with the supplier
+    pub fn with_supplier(mut self, supplier: &str) -> SbomComponent {
+        self.supplier = Some(supplier.to_string());
+        self
+    }
+
+    /// Returns the component with the license-expression
+    pub fn with_license(mut self, license: &str) -> SbomComponent {
+        self.license = Some(license.to_string());
+        self
+    }
+
+    /// Returns the component with an additional hash
+    pub fn with_hash(mut self, algorithm: &str, value: &str) -> SbomComponent {
+        self.hashes.push((algorithm.to_string(), value.to_string()));
+        self
+    }
+
+    // The SPDX-identifier of the package, which allows letters, digits, '.' and '-' only
+    fn spdx_id(&self) -> String {
+        let id: String = format!("{}-{}", self.name, self.version)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("SPDXRef-Package-{}", id)
+    }
+}
+
+/// Collects the components of a product and writes them as SBOM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomBuilder {
+    name: String,
+    version: String,
+    timestamp: Option<String>,
+    serial_number: Option<String>,
+    components: Vec<SbomComponent>,
+}
+
+impl SbomBuilder {
+    /// Returns a builder for the product with the name and version
+    pub fn new(name: &str, version: &str) -> SbomBuilder {
+        SbomBuilder {
+            name: name.to_string(),
+            version: version.to_string(),
+            timestamp: None,
+            serial_number: None,
+            components: Vec::new(),
+        }
+    }
+
+    /// Sets the creation time as ISO 8601 date-time, which is needed by SPDX
+    pub fn set_timestamp(&mut self, timestamp: &str) {
+        self.timestamp = Some(timestamp.to_string());
+    }
+
+    /// Sets the serial number of the CycloneDX-document, e.g. "urn:uuid:..."
+    pub fn set_serial_number(&mut self, serial_number: &str) {
+        self.serial_number = Some(serial_number.to_string());
+    }
+
+    /// Adds the component
+    pub fn add_component(&mut self, component: SbomComponent) {
+        self.components.push(component);
+    }
+
+    /// Returns the CycloneDX-document as JSON
+    pub fn cyclonedx_json(&self) -> String {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("bomFormat", &json_string("CycloneDX"));
+        wr.add_property("specVersion", &json_string("1.5"));
+        if let Some(serial_number) = &self.serial_number {
+            wr.add_property("serialNumber", &json_string(serial_number));
+        }
+        wr.add_property("version", "1");
+        let mut bom = Bom::Json(wr);
+        self.write_bom(&mut bom);
+        let Bom::Json(mut wr) = bom else {
+            unreachable!("the flavor doesn't change")
+        };
+        wr.close_tag();
+        wr.content
+    }
+
+    /// Returns the CycloneDX-document as XML
+    pub fn cyclonedx_xml(&self) -> String {
+        let mut wr = XMLWriter::new();
+        wr.core.set_indent_step_size(2);
+        wr.core.set_auto_layout(true);
+        wr.declaration("1.0", Some("UTF-8"), None);
+        let mut attributes = vec![("xmlns", CYCLONEDX_NAMESPACE.to_string())];
+        if let Some(serial_number) = &self.serial_number {
+            attributes.push(("serialNumber", serial_number.clone()));
+        }
+        attributes.push(("version", "1".to_string()));
+        let mut bom = Bom::Xml(wr);
+        bom.open("bom", &attributes);
+        self.write_bom(&mut bom);
+        bom.close();
+        let Bom::Xml(wr) = bom else {
+            unreachable!("the flavor doesn't change")
+        };
+        wr.content
+    }
+
+    /// Returns the SPDX-document in the tag-value format, the namespace is the unique URI of the document. SPDX
+    /// requires the creation time, so it is an error without ```set_timestamp()```.
+    pub fn spdx_tag_value(&self, namespace: &str) -> Result<String, WriterError> {
+        let timestamp = self.timestamp.as_deref().ok_or(WriterError::MissingField {
+            document: "SPDX",
+            field: "a timestamp, see set_timestamp()",
+        })?;
+        let mut spdx = String::new();
+        let mut tag = |tag: &str, value: &str| spdx_tag(&mut spdx, tag, value);
+        tag("SPDXVersion", "SPDX-2.3");
+        tag("DataLicense", "CC0-1.0");
+        tag("SPDXID", "SPDXRef-DOCUMENT");
+        tag("DocumentName", &format!("{}-{}", self.name, self.version));
+        tag("DocumentNamespace", namespace);
+        tag(
+            "Creator",
+            &format!("Tool: mllwriter-{}", env!("CARGO_PKG_VERSION")),
+        );
+        tag("Created", timestamp);
+        for component in &self.components {
+            tag("", "");
+            tag("PackageName", &component.name);
+            tag("SPDXID", &component.spdx_id());
+            tag("PackageVersion", &component.version);
+            let supplier = component.supplier.as_ref();
+            tag(
+                "PackageSupplier",
+                &supplier.map_or("NOASSERTION".to_string(), |s| {
+                    format!("Organization: {}", s)
+                }),
+            );
+            tag("PackageDownloadLocation", "NOASSERTION");
+            tag("FilesAnalyzed", "false");
+            for (algorithm, value) in &component.hashes {
+                tag(
+                    "PackageChecksum",
+                    &format!("{}: {}", algorithm.replace('-', ""), value),
+                );
+            }
+            tag("PackageLicenseConcluded", "NOASSERTION");
+            tag(
+                "PackageLicenseDeclared",
+                component.license.as_deref().unwrap_or("NOASSERTION"),
+            );
+            tag("PackageCopyrightText", "NOASSERTION");
+            if let Some(purl) = &component.purl {
+                tag("ExternalRef", &format!("PACKAGE-MANAGER purl {}", purl));
+            }
+        }
+        tag("", "");
+        for component in &self.components {
+            tag(
+                "Relationship",
+                &format!("SPDXRef-DOCUMENT DESCRIBES {}", component.spdx_id()),
+            );
+        }
+        Ok(spdx)
+    }
+
+    // Writes the metadata and the components in the order of the schema
+    fn write_bom(&self, bom: &mut Bom) {
+        bom.open("metadata", &[]);
+        if let Some(timestamp) = &self.timestamp {
+            bom.field("timestamp", timestamp);
+        }
+        bom.open("component", &[("type", "application".to_string())]);
+        bom.field("name", &self.name);
+        bom.field("version", &self.version);
+        bom.close();
+        bom.close();
+
+        bom.list("components");
+        for component in &self.components {
+            let mut attributes = vec![("type", "library".to_string())];
+            if let Some(purl) = &component.purl {
+                attributes.push(("bom-ref", purl.clone()));
+            }
+            bom.open("component", &attributes);
+            if let Some(supplier) = &component.supplier {
+                bom.open("supplier", &[]);
+                bom.field("name", supplier);
+                bom.close();
+            }
+            bom.field("name", &component.name);
+            bom.field("version", &component.version);
+            if !component.hashes.is_empty() {
+                bom.list("hashes");
+                for (algorithm, value) in &component.hashes {
+                    bom.item("hash", &[("alg", algorithm.clone())], "content", value);
+                }
+                bom.close();
+            }
+            if let Some(license) = &component.license {
+                bom.list("licenses");
+                bom.item("expression", &[], "expression", license);
+                bom.close();
+            }
+            if let Some(purl) = &component.purl {
+                bom.field("purl", purl);
+            }
+            bom.close();
+        }
+        bom.close();
+    }
+}
+
+// Writes the tag-value line, an empty tag writes the blank line between the sections. Values with line-feeds
+// are enclosed in a 'text'-element.
+fn spdx_tag(spdx: &mut String, tag: &str, value: &str) {
+    if tag.is_empty() {
+        spdx.push('\n');
+    } else if value.contains('\n') {
+        spdx.push_str(&format!("{}: <text>{}</text>\n", tag, value));
+    } else {
+        spdx.push_str(&format!("{}: {}\n", tag, value));
+    }
+}
+
+// The flavors of CycloneDX. In XML objects are elements with attributes, and lists are elements with the items
+// as children. In JSON the attributes are members, and lists are arrays.
+enum Bom {
+    Json(JSONWriter),
+    Xml(XMLWriter),
+}
+
+impl Bom {
+    // Opens an object, inside of a JSON-array without name
+    fn open(&mut self, name: &str, attributes: &[(&str, String)]) {
+        match self {
+            Bom::Json(wr) => {
+                wr.open_tag(if wr.in_array() { "" } else { name });
+                for (attribute, value) in attributes {
+                    wr.add_property(attribute, &json_string(value));
+                }
+            }
+            Bom::Xml(wr) => {
+                wr.open_element(&xml_name(name));
+                add_attributes(wr, attributes);
+            }
+        }
+    }
+
+    fn list(&mut self, name: &str) {
+        match self {
+            Bom::Json(wr) => wr.open_array(name),
+            Bom::Xml(_) => self.open(name, &[]),
+        }
+    }
+
+    fn close(&mut self) {
+        match self {
+            Bom::Json(wr) => wr.close_tag(),
+            Bom::Xml(wr) => wr.close_tag(),
+        }
+    }
+
+    fn field(&mut self, name: &str, value: &str) {
+        match self {
+            Bom::Json(wr) => wr.add_property(name, &json_string(value)),
+            Bom::Xml(wr) => xml_element(wr, name, &[], Some(value)),
+        }
+    }
+
+    // Adds an item of a list, the text of the XML-element is the named member in JSON
+    fn item(&mut self, tag: &str, attributes: &[(&str, String)], member: &str, text: &str) {
+        match self {
+            Bom::Json(_) => {
+                self.open(tag, attributes);
+                self.field(member, text);
+                self.close();
+            }
+            Bom::Xml(wr) => xml_element(wr, tag, attributes, Some(text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sbom_flavors() {
+        let mut sbom = SbomBuilder::new("app", "1");
+        sbom.set_timestamp("2026-10-16T12:00:00Z");
+        sbom.add_component(
+            SbomComponent::new("zlib", "1.3")
+                .with_supplier("Zlib")
+                .with_hash("SHA-256", "ab12"),
+        );
+        assert!(sbom.cyclonedx_json().contains(
+            "\"hashes\":\n      [\n        {\n          \"alg\": \"SHA-256\",\n          \"content\": \"ab12\"\n"
+        ));
+        assert!(sbom
+            .cyclonedx_xml()
+            .contains("<hashes>\n        <hash alg=\"SHA-256\">ab12</hash>\n      </hashes>"));
+        let spdx = sbom
+            .spdx_tag_value("https://example.com/spdx/app-1")
+            .unwrap();
+        assert!(spdx.contains(
+            "\n\nPackageName: zlib\nSPDXID: SPDXRef-Package-zlib-1.3\nPackageVersion: 1.3\n\
+             PackageSupplier: Organization: Zlib\n"
+        ));
+        assert!(spdx.contains("PackageChecksum: SHA256: ab12\n"));
+        assert!(spdx
+            .ends_with("\n\nRelationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-zlib-1.3\n"));
+
+        let sbom = SbomBuilder::new("app", "1");
+        assert!(matches!(
+            sbom.spdx_tag_value("https://example.com/spdx/app-1"),
+            Err(WriterError::MissingField { .. })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! A JSON Schema of the documents written by the JSONWriter.
+//!
+//! With ```set_record_schema(true)``` the JSONWriter records the blocks and the types of the properties it
+//! writes. ```schema()``` returns a draft-07 JSON Schema of the recorded structure afterwards. All recorded
+//! members are required, a member written with different types gets all of them.
+//!
+//! ```
+//! # use mllwriter::{JSONWriter, MLLWriter};
+//! let mut wr = JSONWriter::new();
+//! wr.set_record_schema(true);
+//! wr.open_tag("");
+//! wr.add_property("name", "\"Max\"");
+//! wr.open_tag("data");
+//! wr.add_property("kids", "2");
+//! wr.close_tag();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.schema().unwrap(),
+//!     "{\n  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n  \"type\": \"object\",\n  \
+//!      \"properties\":\n  {\n    \"name\":\n    {\n      \"type\": \"string\"\n    },\n    \
+//!      \"data\":\n    {\n      \"type\": \"object\",\n      \"properties\":\n      {\n        \
+//!      \"kids\":\n        {\n          \"type\": \"integer\"\n        }\n      },\n      \
+//!      \"required\": [\"kids\"]\n    }\n  },\n  \"required\": [\"name\", \"data\"]\n}"
+//! );
+//! ```
+
+use crate::format::json_string;
+use crate::{JSONWriter, MLLWriter};
+
+/// The URI of the JSON Schema draft-07 meta-schema
+pub const JSON_SCHEMA_DRAFT_07: &str = "http://json-schema.org/draft-07/schema#";
+
+// The recorded schema of a value
+#[derive(Debug, Clone, Default)]
+struct SchemaNode {
+    // the JSON-types of the value, e.g. "string", in the order of their appearance
+    types: Vec<&'static str>,
+    // the members of objects
+    properties: Vec<(String, SchemaNode)>,
+}
+
+impl SchemaNode {
+    fn add_type(&mut self, json_type: &'static str) {
+        if !self.types.contains(&json_type) {
+            self.types.push(json_type);
+        }
+    }
+
+    fn member(&mut self, name: &str) -> &mut SchemaNode {
+        let i = match self.properties.iter().position(|(n, _)| n == name) {
+            Some(i) => i,
+            None => {
+                self.properties
+                    .push((name.to_string(), SchemaNode::default()));
+                self.properties.len() - 1
+            }
+        };
+        &mut self.properties[i].1
+    }
+
+    fn write(&self, wr: &mut JSONWriter) {
+        let types: Vec<String> = self.types.iter().map(|t| json_string(t)).collect();
+        match types.len() {
+            0 => {}
+            1 => wr.add_property("type", &types[0]),
+            _ => wr.add_property("type", &format!("[{}]", types.join(", "))),
+        }
+        if self.properties.is_empty() {
+            return;
+        }
+        wr.open_tag("properties");
+        for (name, node) in &self.properties {
+            wr.open_tag(name);
+            node.write(wr);
+            wr.close_tag();
+        }
+        wr.close_tag();
+        let required: Vec<String> = self
+            .properties
+            .iter()
+            .map(|(n, _)| json_string(n))
+            .collect();
+        wr.add_property("required", &format!("[{}]", required.join(", ")));
+    }
+}
+
+// Records the structure of the written document
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SchemaRecorder {
+    root: SchemaNode,
+    // the names of the open blocks below the root
+    path: Vec<String>,
+    depth: usize,
+    // number of open blocks since the first open array, the items of arrays aren't recorded
+    suspended: usize,
+}
+
+impl SchemaRecorder {
+    fn current(&mut self) -> &mut SchemaNode {
+        let mut node = &mut self.root;
+        for name in &self.path {
+            node = node.member(name);
+        }
+        node
+    }
+
+    pub(crate) fn open(&mut self, tag: &str) {
+        if self.suspended > 0 {
+            self.suspended += 1;
+            return;
+        }
+        if self.depth > 0 {
+            self.path.push(tag.to_string());
+        }
+        self.depth += 1;
+        self.current().add_type("object");
+    }
+
+    pub(crate) fn open_array(&mut self, name: &str) {
+        if self.suspended == 0 {
+            match self.depth {
+                0 => self.root.add_type("array"),
+                _ => self.current().member(name).add_type("array"),
+            }
+        }
+        self.suspended += 1;
+    }
+
+    pub(crate) fn close(&mut self) {
+        if self.suspended > 0 {
+            self.suspended -= 1;
+            return;
+        }
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth > 0 {
+            self.path.pop();
+        }
+    }
+
+    pub(crate) fn property(&mut self, name: &str, value: &str) {
+        if self.suspended > 0 {
+            return;
+        }
+        self.current().member(name).add_type(json_type(value));
+    }
+}
+
+// Returns the JSON-type of a raw value
+fn json_type(value: &str) -> &'static str {
+    let value = value.trim();
+    match value.as_bytes().first() {
+        Some(b'"') => "string",
+        Some(b'{') => "object",
+        Some(b'[') => "array",
+        _ if value == "true" || value == "false" => "boolean",
+        _ if value == "null" => "null",
+        _ if value.parse::<i64>().is_ok() || value.parse::<u64>().is_ok() => "integer",
+        _ => "number",
+    }
+}
+
+impl JSONWriter {
+    /// Enables or disables recording the structure of the document for ```schema()```
+    pub fn set_record_schema(&mut self, record: bool) {
+        self.schema = record.then(SchemaRecorder::default);
+    }
+
+    /// Returns the draft-07 JSON Schema of the recorded document, or None if the recording isn't enabled
+    pub fn schema(&self) -> Option<String> {
+        let recorder = self.schema.as_ref()?;
+        let mut wr = JSONWriter::new();
+        wr.set_indent_step_size(self.core.indent_step_size);
+        wr.open_tag("");
+        wr.add_property("$schema", &json_string(JSON_SCHEMA_DRAFT_07));
+        recorder.root.write(&mut wr);
+        wr.close_tag();
+        Some(wr.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_mixed_types() {
+        let mut wr = JSONWriter::new();
+        assert_eq!(wr.schema(), None);
+        wr.set_record_schema(true);
+        wr.open_tag("");
+        wr.add_comment("not recorded");
+        wr.add_property("v", "1.5");
+        wr.close_tag();
+        let mut rec = wr.schema.clone().unwrap();
+        assert_eq!(rec.root.properties.len(), 1);
+        rec.open("");
+        rec.property("v", "null");
+        rec.close();
+        assert_eq!(rec.root.properties[0].1.types, ["number", "null"]);
+        assert_eq!(json_type("[1]"), "array");
+        assert_eq!(json_type("-3"), "integer");
+    }
+}
@@ -0,0 +1,169 @@
+//! The search index of a [`DocumentSet`](crate::DocumentSet) for client-side search.
+//!
+//! ```site.search_index()``` collects the title, the headings and the visible text of each page and writes them
+//! as JSON-array of documents, which can be added to a Lunr- or Elasticlunr-index as they are, with the path as
+//! reference. Heads, scripts and styles aren't indexed, the title is taken from the 'title'-element or the first
+//! 'h1'.
+//!
+//! ```
+//! # use mllwriter::{DocumentSet, HTMLWriter, MLLWriter};
+//! let mut site = DocumentSet::new("https://example.com/");
+//! let mut page = HTMLWriter::new();
+//! page.open_tag("h1");
+//! page.text("Install");
+//! page.close_tag();
+//! page.open_tag("p");
+//! page.text("Run cargo add.");
+//! page.close_tag();
+//! site.add_page("docs/install.html", page);
+//! assert_eq!(
+//!     site.search_index(),
+//!     "[\n  {\n    \"id\": \"docs/install.html\",\n    \"url\": \"https://example.com/docs/install.html\",\n    \
+//!      \"title\": \"Install\",\n    \"headings\":\n    [\n      \"Install\"\n    ],\n    \
+//!      \"body\": \"Install Run cargo add.\"\n  }\n]"
+//! );
+//! ```
+
+use crate::escape::unescape;
+use crate::{DocumentSet, JSONWriter, JsonValue, MLLWriter};
+
+// The elements, whose content isn't visible
+const HIDDEN: [&str; 5] = ["head", "script", "style", "template", "noscript"];
+
+// The elements within a line of text, which don't separate words
+const INLINE: [&str; 14] = [
+    "a", "abbr", "b", "cite", "code", "em", "i", "mark", "q", "small", "span", "strong", "sub",
+    "sup",
+];
+
+const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+
+impl DocumentSet {
+    /// Returns the search index of all pages as JSON-array of documents with the fields "id", "url", "title",
+    /// "headings" and "body"
+    pub fn search_index(&self) -> String {
+        let mut wr = JSONWriter::new();
+        wr.open_array("");
+        for (path, page) in self.pages() {
+            let document = IndexedPage::of(&page.content);
+            let title = document
+                .title
+                .or_else(|| document.headings.first().map(|(_, text)| text.clone()))
+                .unwrap_or_default();
+            wr.add_item_value(JsonValue::Object(vec![
+                ("id".to_string(), path.into()),
+                ("url".to_string(), self.url(path).into()),
+                ("title".to_string(), title.into()),
+                (
+                    "headings".to_string(),
+                    document
+                        .headings
+                        .into_iter()
+                        .map(|(_, text)| text)
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+                ("body".to_string(), document.body.into()),
+            ]));
+        }
+        wr.close_tag();
+        wr.content
+    }
+}
+
+// The indexed parts of a page, the headings with their level
+#[derive(Debug, Default, PartialEq)]
+struct IndexedPage {
+    title: Option<String>,
+    headings: Vec<(u8, String)>,
+    body: String,
+}
+
+impl IndexedPage {
+    // Collects the parts of the HTML-document
+    fn of(html: &str) -> IndexedPage {
+        let mut page = IndexedPage::default();
+        // the open elements, which are collected: the title or a heading
+        let mut open: Option<(String, String)> = None;
+        let mut hidden: Option<String> = None;
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            page.text(&rest[..start], &mut open, hidden.is_some());
+            let end = rest[start..]
+                .find('>')
+                .map_or(rest.len(), |i| start + i + 1);
+            let tag = &rest[start + 1..end.saturating_sub(1).max(start + 1)];
+            rest = &rest[end..];
+            let closing = tag.starts_with('/');
+            let name: String = tag
+                .trim_start_matches('/')
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect();
+            if closing && open.as_ref().is_some_and(|(o, _)| *o == name) {
+                let (name, text) = open.take().unwrap_or_default();
+                let text = collapse(&text);
+                if name == "title" {
+                    page.title = Some(text);
+                } else {
+                    page.headings.push((name.as_bytes()[1] - b'0', text));
+                }
+            } else if !closing
+                && (name == "title" || hidden.is_none() && HEADINGS.contains(&name.as_str()))
+            {
+                open = Some((name, String::new()));
+            } else if let Some(h) = &hidden {
+                if closing && *h == name {
+                    hidden = None;
+                }
+            } else if !closing && HIDDEN.contains(&name.as_str()) {
+                hidden = Some(name);
+            } else if !INLINE.contains(&name.as_str()) {
+                page.body.push(' ');
+            }
+        }
+        page.text(rest, &mut open, hidden.is_some());
+        page.body = collapse(&page.body);
+        page
+    }
+
+    // Adds the text to the body and to the open title or heading
+    fn text(&mut self, text: &str, open: &mut Option<(String, String)>, hidden: bool) {
+        let text = unescape(text);
+        if let Some((name, collected)) = open {
+            collected.push_str(&text);
+            if name == "title" {
+                return;
+            }
+        }
+        if !hidden {
+            self.body.push_str(&text);
+        }
+    }
+}
+
+// Returns the text with each run of whitespace replaced by one space
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_parts_of_page() {
+        let page = IndexedPage::of(
+            "<html><head><title>A &amp; B</title><style>p{}</style></head><body>\
+             <h2 id=\"x\">Sub<em>title</em></h2><script>var a;</script><p>one<br>two</p></body></html>",
+        );
+        assert_eq!(
+            page,
+            IndexedPage {
+                title: Some("A & B".to_string()),
+                headings: vec![(2, "Subtitle".to_string())],
+                body: "Subtitle one two".to_string(),
+            }
+        );
+    }
+}
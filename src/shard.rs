@@ -0,0 +1,231 @@
+//! Parallel export of a record stream into several shard-files, linked by a manifest document.
+//!
+//! The [`ShardedExport`] distributes the records round-robin over N worker threads. Each worker streams its
+//! records into its own file with its own [`XmlRecordStream`]. When all records are written, a manifest is
+//! written next to the shards, as XML (like a sitemap-index) or as JSON. The shards and the manifest are written
+//! with the line-feeds given by ```set_line_ending()```.
+//!
+//! ```no_run
+//! # use mllwriter::{ManifestFormat, ShardedExport};
+//! let records = (0..1000).map(|i| vec![("id", i.to_string())]);
+//! let export = ShardedExport::new("export", "rows", "row", 4);
+//! let shards = export.run(records, ManifestFormat::Xml).unwrap();
+//! assert_eq!(shards.len(), 4);
+//! ```
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::format::json_string;
+use crate::{JSONWriter, LineEnding, MLLWriter, XMLWriter, XmlRecord, XmlRecordStream};
+
+/// The format of the manifest, which links the shards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// A sitemap-index-like XML-document
+    Xml,
+    /// A JSON-document
+    Json,
+}
+
+/// Describes one written shard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardInfo {
+    /// The path of the shard-file
+    pub path: PathBuf,
+    /// The number of records in the shard
+    pub count: usize,
+}
+
+/// Exports a record stream into shards, see the module documentation
+#[derive(Debug, Clone)]
+pub struct ShardedExport {
+    dir: PathBuf,
+    root: String,
+    record_tag: String,
+    shards: usize,
+    line_ending: LineEnding,
+}
+
+// Number of records, which can be queued for a worker
+const QUEUE_SIZE: usize = 256;
+
+impl ShardedExport {
+    /// Creates an export into dir with the given number of shards (at least one). The shards are named
+    /// ```<root>-0000.xml```, ```<root>-0001.xml``` and so on, the manifest ```<root>-manifest.xml``` or ```.json```.
+    pub fn new<P: AsRef<Path>>(
+        dir: P,
+        root: &str,
+        record_tag: &str,
+        shards: usize,
+    ) -> ShardedExport {
+        ShardedExport {
+            dir: dir.as_ref().to_path_buf(),
+            root: root.to_string(),
+            record_tag: record_tag.to_string(),
+            shards: shards.max(1),
+            line_ending: LineEnding::default(),
+        }
+    }
+
+    /// Sets the line-feeds of the shards and the manifest
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Returns the path of the manifest in the given format
+    pub fn manifest_path(&self, format: ManifestFormat) -> PathBuf {
+        let extension = match format {
+            ManifestFormat::Xml => "xml",
+            ManifestFormat::Json => "json",
+        };
+        self.dir
+            .join(format!("{}-manifest.{}", self.root, extension))
+    }
+
+    fn shard_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}-{:04}.xml", self.root, index))
+    }
+
+    /// Writes all records into the shards and the manifest, returns the written shards
+    pub fn run<I>(&self, records: I, format: ManifestFormat) -> std::io::Result<Vec<ShardInfo>>
+    where
+        I: IntoIterator,
+        I::Item: XmlRecord + Send + 'static,
+    {
+        std::fs::create_dir_all(&self.dir)?;
+        let mut senders = Vec::new();
+        let mut workers = Vec::new();
+        for index in 0..self.shards {
+            let (sender, receiver) = mpsc::sync_channel::<I::Item>(QUEUE_SIZE);
+            let path = self.shard_path(index);
+            let root = self.root.clone();
+            let record_tag = self.record_tag.clone();
+            let line_ending = self.line_ending;
+            senders.push(sender);
+            workers.push(thread::spawn(move || -> std::io::Result<ShardInfo> {
+                let file = BufWriter::new(File::create(&path)?);
+                let mut stream = XmlRecordStream::new(file, &root, &record_tag)?;
+                stream.writer().core.set_line_ending(line_ending);
+                for record in receiver {
+                    stream.write_record(&record)?;
+                }
+                let count = stream.count();
+                stream.finish()?;
+                Ok(ShardInfo { path, count })
+            }));
+        }
+        // A failed worker drops its receiver, the error is reported when joining it
+        for (i, record) in records.into_iter().enumerate() {
+            if senders[i % self.shards].send(record).is_err() {
+                break;
+            }
+        }
+        drop(senders);
+        let mut shards = Vec::new();
+        for worker in workers {
+            let shard = worker
+                .join()
+                .map_err(|_| std::io::Error::other("a shard worker panicked"))??;
+            shards.push(shard);
+        }
+        std::fs::write(self.manifest_path(format), self.manifest(&shards, format))?;
+        Ok(shards)
+    }
+
+    // Writes the manifest document linking the shards by their file names
+    fn manifest(&self, shards: &[ShardInfo], format: ManifestFormat) -> String {
+        let file_name = |s: &ShardInfo| {
+            s.path
+                .file_name()
+                .map_or(String::new(), |n| n.to_string_lossy().to_string())
+        };
+        match format {
+            ManifestFormat::Xml => {
+                let mut wr = XMLWriter::new();
+                wr.core.set_auto_layout(true);
+                wr.core.set_line_ending(self.line_ending);
+                wr.open_tag_w_property("manifest", "root", &self.root);
+                for shard in shards {
+                    wr.open_tag("shard");
+                    wr.add_property("records", &shard.count.to_string());
+                    wr.open_tag("loc");
+                    wr.text(&file_name(shard));
+                    wr.close_tag();
+                    wr.close_tag();
+                }
+                wr.close_tag();
+                let newline = wr.core.newline();
+                wr.content + newline
+            }
+            ManifestFormat::Json => {
+                let mut wr = JSONWriter::new();
+                wr.core.set_line_ending(self.line_ending);
+                wr.open_tag("");
+                wr.add_property("root", &json_string(&self.root));
+                wr.open_tag("shards");
+                for shard in shards {
+                    wr.open_tag(&file_name(shard));
+                    wr.add_property("records", &shard.count.to_string());
+                    wr.close_tag();
+                }
+                wr.close_tag();
+                wr.close_tag();
+                let newline = wr.core.newline();
+                wr.content + newline
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_export_with_manifest() {
+        let dir =
+            std::env::temp_dir().join(format!("mllwriter-shard-export-{}", std::process::id()));
+        let export = ShardedExport::new(&dir, "rows", "row", 3);
+        let records = (0..10).map(|i| vec![("id", i.to_string())]);
+        let shards = export.run(records, ManifestFormat::Json).unwrap();
+        assert_eq!(
+            shards.iter().map(|s| s.count).collect::<Vec<_>>(),
+            vec![4, 3, 3]
+        );
+        let first = std::fs::read_to_string(&shards[0].path).unwrap();
+        assert!(
+            first.starts_with("<rows>\n  <row>\n    <id>0</id>\n  </row>\n  <row>\n    <id>3</id>")
+        );
+        let manifest = std::fs::read_to_string(export.manifest_path(ManifestFormat::Json)).unwrap();
+        assert!(manifest.contains("\"rows-0002.xml\":\n    {\n      \"records\": 3\n    }"));
+
+        let records = (0..2).map(|i| vec![("id", i.to_string())]);
+        export.run(records, ManifestFormat::Xml).unwrap();
+        let manifest = std::fs::read_to_string(export.manifest_path(ManifestFormat::Xml)).unwrap();
+        assert!(
+            manifest.contains("<shard records=\"0\">\n    <loc>rows-0002.xml</loc>\n  </shard>")
+        );
+
+        let mut export = ShardedExport::new(&dir, "rows", "row", 1);
+        export.set_line_ending(LineEnding::CrLf);
+        let records = (0..1).map(|i| vec![("id", i.to_string())]);
+        let shards = export.run(records, ManifestFormat::Json).unwrap();
+        let shard = std::fs::read_to_string(&shards[0].path).unwrap();
+        assert_eq!(
+            shard,
+            "<rows>\r\n  <row>\r\n    <id>0</id>\r\n  </row>\r\n</rows>\r\n"
+        );
+        let manifest = std::fs::read_to_string(export.manifest_path(ManifestFormat::Json)).unwrap();
+        assert!(manifest.ends_with("\r\n}\r\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let export = ShardedExport::new(&dir, "a\"b", "row", 1);
+        assert!(export
+            .manifest(&[], ManifestFormat::Json)
+            .contains("\"root\": \"a\\\"b\""));
+    }
+}
@@ -0,0 +1,410 @@
+//! Writing directly into a ```std::io::Write```-sink.
+//!
+//! The [`SinkWriter`] wraps a writer and moves its content into the sink after each operation, so large
+//! documents aren't accumulated in memory. Only the tail of the content, which the next operation may still
+//! change, e.g. the last start-tag for its properties, stays in the buffer. While properties are aligned, the
+//! content is kept until the block is closed. Features, which change the content afterwards, e.g.
+//! ```resolve_lists()```, don't work with a sink.
+//!
+//! Writing into the sink can fail, the first error is kept and returned by ```finish()```, which also writes
+//! the rest of the content. Like the ```finish()``` of the writers, it returns an error if elements aren't closed
+//! and applies the [`FinalFormat`](crate::FinalFormat) of the writer, its whitespace at the end of the flushed
+//! parts is held back until it is known whether it ends a line.
+//!
+//! ```
+//! # use mllwriter::{BufferedWriter, HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::with_sink(Vec::new());
+//! wr.open_tag("ul");
+//! for i in 0..3 {
+//!     wr.open_tag("li");
+//!     wr.add_property("value", &i.to_string());
+//!     wr.close_tag();
+//! }
+//! wr.close_tag();
+//! let sink = wr.finish().unwrap();
+//! assert_eq!(
+//!     String::from_utf8(sink).unwrap(),
+//!     "<ul><li value=\"0\"></li><li value=\"1\"></li><li value=\"2\"></li></ul>"
+//! );
+//! ```
+
+use std::io::Write;
+
+use crate::banner::BannerStyle;
+use crate::finish::{check_finished, FinalFormatter};
+use crate::null::{ByteCounter, NullWriter};
+use crate::provenance::TimestampPolicy;
+use crate::snapshot::{self, Frozen};
+use crate::{
+    HTMLWriter, JSONWriter, MLLWriter, Properties, TOMLWriter, WriterCore, WriterError,
+    WriterProfile, XMLWriter, YAMLWriter,
+};
+
+/// The writers, which buffer their content in a ```String```, i.e. all writers of the crate
+pub trait BufferedWriter: MLLWriter {
+    /// Returns the content and the core of the writer
+    fn buffer(&mut self) -> (&mut String, &mut WriterCore);
+
+    /// Returns a writer, which writes into the sink
+    fn with_sink<W: Write>(sink: W) -> SinkWriter<Self, W>
+    where
+        Self: Default,
+    {
+        SinkWriter::new(Self::default(), sink)
+    }
+
+    /// Returns a writer for a dry run, which validates and counts the bytes, but drops the content
+    fn dry_run() -> NullWriter<Self>
+    where
+        Self: Default,
+    {
+        SinkWriter {
+            dry_run: true,
+            ..SinkWriter::new(Self::default(), ByteCounter::default())
+        }
+    }
+
+    /// Closes the blocks, which the writer opened by itself, before the content is finished, e.g. the blocks of
+    /// ```set_path()``` in the JSONWriter
+    fn close_own_blocks(&mut self) {}
+
+    /// Returns the open elements as error, unless the document is complete
+    fn check_finished(&mut self) -> Result<(), WriterError> {
+        check_finished(self.buffer().1)
+    }
+}
+
+macro_rules! buffered_writer {
+    ($($writer:ty),*) => {
+        $(
+            impl BufferedWriter for $writer {
+                fn buffer(&mut self) -> (&mut String, &mut WriterCore) {
+                    (&mut self.content, &mut self.core)
+                }
+            }
+        )*
+    };
+}
+
+buffered_writer!(HTMLWriter, XMLWriter, YAMLWriter, TOMLWriter);
+
+impl BufferedWriter for JSONWriter {
+    fn buffer(&mut self) -> (&mut String, &mut WriterCore) {
+        (&mut self.content, &mut self.core)
+    }
+
+    fn close_own_blocks(&mut self) {
+        self.close_paths();
+    }
+
+    fn check_finished(&mut self) -> Result<(), WriterError> {
+        self.check_open_blocks()
+    }
+}
+
+/// A writer, which moves its content into a sink
+pub struct SinkWriter<M: BufferedWriter, W: Write> {
+    writer: M,
+    sink: W,
+    // the first error of the sink
+    error: Option<std::io::Error>,
+    // applies the final format of the writer, it is taken at the first flush
+    formatter: Option<FinalFormatter>,
+    // the sink only counts the bytes, so properties of flushed start-tags are accepted
+    dry_run: bool,
+}
+
+impl<M: BufferedWriter, W: Write> SinkWriter<M, W> {
+    /// Wraps the writer, its content so far is written with the next operation
+    pub fn new(writer: M, sink: W) -> SinkWriter<M, W> {
+        SinkWriter {
+            writer,
+            sink,
+            error: None,
+            formatter: None,
+            dry_run: false,
+        }
+    }
+
+    /// Gives access to the writer, e.g. for its own methods. Its content is moved by the next ```flush()```.
+    pub fn writer(&mut self) -> &mut M {
+        &mut self.writer
+    }
+
+    /// Returns the sink, e.g. to check what was written so far
+    pub fn sink(&self) -> &W {
+        &self.sink
+    }
+
+    /// Moves the content into the sink, except of the tail which the next operation may still change
+    pub fn flush(&mut self) {
+        let (content, core) = self.writer.buffer();
+        let cut = snapshot::stable_len(content, core);
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self
+            .sink
+            .write_all(std::mem::take(&mut core.spliced).as_bytes())
+        {
+            self.error = Some(e);
+            return;
+        }
+        if cut == 0 {
+            return;
+        }
+        let formatter = self
+            .formatter
+            .get_or_insert_with(|| FinalFormatter::new(core.final_format, core.line_ending));
+        let part = formatter.push(&content[..cut]);
+        if let Err(e) = self.sink.write_all(part.as_bytes()) {
+            self.error = Some(e);
+            return;
+        }
+        content.drain(..cut);
+        core.flushed = true;
+        core.frozen = Frozen::default();
+        core.flush_tag_starts(cut, self.dry_run);
+    }
+
+    /// Writes the rest of the content and returns the sink, if all elements are closed. Otherwise the open elements
+    /// or the first error of the sink are returned.
+    pub fn finish(mut self) -> Result<W, WriterError> {
+        self.writer.close_own_blocks();
+        self.flush();
+        if let Some(e) = self.error.take() {
+            return Err(sink_failed(&e));
+        }
+        self.writer.check_finished()?;
+        let rest = self.rest();
+        self.sink
+            .write_all(rest.as_bytes())
+            .and_then(|_| self.sink.flush())
+            .map_err(|e| sink_failed(&e))?;
+        Ok(self.sink)
+    }
+
+    // Returns the formatted rest of the content, as finish() writes it
+    pub(crate) fn rest(&mut self) -> String {
+        let (content, core) = self.writer.buffer();
+        let mut formatter = self
+            .formatter
+            .clone()
+            .unwrap_or_else(|| FinalFormatter::new(core.final_format, core.line_ending));
+        let mut rest = formatter.push(content);
+        rest.push_str(&formatter.finish());
+        rest
+    }
+
+    // Runs the operation on the writer and flushes afterwards
+    fn run<T>(&mut self, operation: impl FnOnce(&mut M) -> T) -> T {
+        let result = operation(&mut self.writer);
+        self.flush();
+        result
+    }
+}
+
+impl<M: BufferedWriter, W: Write> MLLWriter for SinkWriter<M, W> {
+    fn open_tag(&mut self, tag: &str) {
+        self.run(|wr| wr.open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_open_tag(tag))
+    }
+
+    fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.run(|wr| wr.open_tag_w_property(tag, prop, value));
+    }
+
+    fn close_tag(&mut self) {
+        self.run(|wr| wr.close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_close_tag())
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        self.run(|wr| wr.single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_single_tag(tag))
+    }
+
+    fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.run(|wr| wr.single_tag_w_property(tag, prop, value));
+    }
+
+    fn add_property(&mut self, name: &str, value: &str) {
+        self.run(|wr| wr.add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_add_property(name, value))
+    }
+
+    fn add_properties(&mut self, properties: &Properties) {
+        self.run(|wr| wr.add_properties(properties));
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        self.run(|wr| wr.add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_add_comment(comment))
+    }
+
+    fn text(&mut self, text: &str) {
+        self.run(|wr| wr.text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_text(text))
+    }
+
+    fn raw(&mut self, raw: &str) {
+        self.run(|wr| wr.raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.run(|wr| wr.try_raw(raw))
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        self.run(|wr| wr.file_banner(lines, style));
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.run(|wr| wr.stamp_provenance(tool, version, timestamp_policy));
+    }
+
+    fn line_feed(&mut self, n: usize) {
+        self.run(|wr| wr.line_feed(n));
+    }
+
+    fn blank_line(&mut self) {
+        self.run(|wr| wr.blank_line());
+    }
+
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.run(|wr| wr.ensure_blank_lines(n));
+    }
+
+    fn line_feed_inc(&mut self) {
+        self.run(|wr| wr.line_feed_inc());
+    }
+
+    fn line_feed_dec(&mut self) {
+        self.run(|wr| wr.line_feed_dec());
+    }
+
+    fn inc_indent_step(&mut self) {
+        self.writer.inc_indent_step();
+    }
+
+    fn dec_indent_step(&mut self) {
+        self.writer.dec_indent_step();
+    }
+
+    fn set_indent_step(&mut self, indent_step: usize) {
+        self.writer.set_indent_step(indent_step);
+    }
+
+    fn set_indent_step_size(&mut self, indent_step_size: usize) {
+        self.writer.set_indent_step_size(indent_step_size);
+    }
+
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.writer.apply_profile(profile);
+    }
+
+    fn end_document(&mut self) {
+        self.run(|wr| wr.end_document());
+    }
+
+    /// Clears the writer, the content in the sink stays
+    fn clear(&mut self) {
+        self.writer.clear();
+    }
+}
+
+fn sink_failed(e: &std::io::Error) -> WriterError {
+    WriterError::WriteFailed {
+        path: "sink".to_string(),
+        message: e.to_string(),
+    }
+}
+
+impl<M: BufferedWriter, W: Write> std::fmt::Write for SinkWriter<M, W> {
+    /// Writes the text as it is, like the writer
+    fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        let (content, _) = self.writer.buffer();
+        content.push_str(s);
+        self.flush();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FinalFormat;
+
+    #[test]
+    fn sink_same_as_buffered() {
+        fn write<M: MLLWriter>(wr: &mut M) {
+            wr.open_tag("");
+            wr.add_property("a", "1");
+            wr.blank_line();
+            wr.open_tag("b");
+            wr.add_property("c", "\"x\"");
+            wr.close_tag();
+            wr.close_tag();
+        }
+        let mut buffered = JSONWriter::new();
+        write(&mut buffered);
+        let mut streamed = JSONWriter::with_sink(Vec::new());
+        write(&mut streamed);
+        assert!(streamed.writer().content.len() < 3);
+        let sink = streamed.finish().unwrap();
+        assert_eq!(String::from_utf8(sink).unwrap(), buffered.content);
+
+        fn pre<M: MLLWriter>(wr: &mut M) {
+            wr.open_tag("pre");
+            wr.text("a  ");
+            wr.open_tag("b");
+            wr.close_tag();
+            wr.raw("  \n\n");
+            wr.close_tag();
+            wr.line_feed(2);
+        }
+        let final_format = FinalFormat {
+            bom: true,
+            ..FinalFormat::posix()
+        };
+        let mut buffered = HTMLWriter::new();
+        buffered.core.set_final_format(final_format);
+        pre(&mut buffered);
+        let mut streamed = HTMLWriter::with_sink(Vec::new());
+        streamed.writer().core.set_final_format(final_format);
+        pre(&mut streamed);
+        let sink = streamed.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(sink).unwrap(),
+            "\u{feff}<pre>a  <b></b>\n\n</pre>\n"
+        );
+        assert_eq!(
+            buffered.finish().unwrap(),
+            "\u{feff}<pre>a  <b></b>\n\n</pre>\n"
+        );
+        let mut unclosed = HTMLWriter::with_sink(Vec::new());
+        unclosed.open_tag("p");
+        assert_eq!(
+            unclosed.finish(),
+            Err(WriterError::UnclosedElements("p".to_string()))
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Sets of HTML-documents, which are published together, e.g. a generated site.
+//!
+//! The pages of a [`DocumentSet`] are written completely by their own HTMLWriter. When the set is rendered, the
+//! elements which depend on the whole set are inserted into the head of each page: the default crawl policy, the
+//! canonical URL of the page and the auto-discovery links of all feeds. So they stay consistent, even if the pages
+//! are written by different parts of the generator. Localized pages are cross-linked with all their variants by
+//! "hreflang", including the variant of the default locale as "x-default".
+//!
+//! ```
+//! # use mllwriter::{DocumentSet, FeedKind, HTMLWriter, MLLWriter};
+//! let mut site = DocumentSet::new("https://example.com/");
+//! site.add_feed(FeedKind::Atom, "/feed.xml", "News");
+//! let mut page = HTMLWriter::new();
+//! page.open_tag("html");
+//! page.open_tag("head");
+//! page.close_tag();
+//! page.close_tag();
+//! site.add_page("blog/index.html", page);
+//! assert_eq!(
+//!     site.render().unwrap()[0].1,
+//!     "<html><head><link rel=\"canonical\" href=\"https://example.com/blog/index.html\">\
+//!      <link rel=\"alternate\" type=\"application/atom+xml\" title=\"News\" href=\"/feed.xml\"></head></html>"
+//! );
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::robots::has_robots;
+use crate::{HTMLWriter, RobotsPolicy, WriterCore, WriterError};
+
+/// The formats of feeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedKind {
+    /// RSS 2.0
+    Rss,
+    /// Atom 1.0
+    Atom,
+}
+
+impl FeedKind {
+    /// Returns the mime-type of the format, e.g. "application/rss+xml"
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            FeedKind::Rss => "application/rss+xml",
+            FeedKind::Atom => "application/atom+xml",
+        }
+    }
+}
+
+/// The pages of a site with the elements, which are shared by all heads
+#[derive(Debug)]
+pub struct DocumentSet {
+    base_url: String,
+    canonical: bool,
+    robots: Option<RobotsPolicy>,
+    // format, URL and title
+    feeds: Vec<(FeedKind, String, String)>,
+    pages: Vec<Page>,
+    default_locale: Option<String>,
+    output_dir: Option<PathBuf>,
+    // the paths of files, which aren't pages, e.g. images
+    assets: Vec<String>,
+    // the old and the new path of moved pages
+    pub(crate) redirects: Vec<(String, String)>,
+}
+
+// A page of the set, a localized page has the key of the page it translates and its locale
+#[derive(Debug)]
+struct Page {
+    path: String,
+    writer: HTMLWriter,
+    variant: Option<(String, String)>,
+}
+
+impl DocumentSet {
+    /// Returns an empty set, which is published at the base URL
+    pub fn new(base_url: &str) -> DocumentSet {
+        DocumentSet {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            canonical: true,
+            robots: None,
+            feeds: Vec::new(),
+            pages: Vec::new(),
+            default_locale: None,
+            output_dir: None,
+            assets: Vec::new(),
+            redirects: Vec::new(),
+        }
+    }
+
+    /// Sets the directory, into which the generated assets are written, e.g. the favicons
+    pub fn set_output_dir<P: AsRef<Path>>(&mut self, output_dir: P) {
+        self.output_dir = Some(output_dir.as_ref().to_path_buf());
+    }
+
+    /// Returns the output directory, if set
+    pub fn output_dir(&self) -> Option<&Path> {
+        self.output_dir.as_deref()
+    }
+
+    /// Enables or disables the canonical links, which are enabled by default
+    pub fn set_canonical(&mut self, canonical: bool) {
+        self.canonical = canonical;
+    }
+
+    /// Sets the default crawl policy, which is inserted into every page without an own policy
+    pub fn set_robots(&mut self, robots: Option<RobotsPolicy>) {
+        self.robots = robots;
+    }
+
+    /// Adds a feed, which is linked from every page
+    pub fn add_feed(&mut self, kind: FeedKind, href: &str, title: &str) {
+        self.feeds.push((kind, href.to_string(), title.to_string()));
+    }
+
+    /// Adds the page at the path relative to the base URL, the page has to be written completely
+    pub fn add_page(&mut self, path: &str, page: HTMLWriter) {
+        self.pages.push(Page {
+            path: path.to_string(),
+            writer: page,
+            variant: None,
+        });
+    }
+
+    /// Adds the page in the locale, e.g. "de-CH". All pages with the same key are variants of each other and are
+    /// cross-linked by "hreflang".
+    pub fn add_localized_page(&mut self, key: &str, locale: &str, path: &str, page: HTMLWriter) {
+        self.pages.push(Page {
+            path: path.to_string(),
+            writer: page,
+            variant: Some((key.to_string(), locale.to_string())),
+        });
+    }
+
+    /// Adds the path of a file, which is published with the pages, e.g. an image or a stylesheet
+    pub fn add_asset(&mut self, path: &str) {
+        self.assets.push(path.to_string());
+    }
+
+    /// Sets the locale, whose variant is linked as "x-default" for all other locales
+    pub fn set_default_locale(&mut self, locale: &str) {
+        self.default_locale = Some(locale.to_string());
+    }
+
+    /// Returns the absolute URL of the path
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// Returns the paths and the contents of all pages with the shared elements in their heads. It fails if a page
+    /// has no head.
+    pub fn render(&self) -> Result<Vec<(String, String)>, WriterError> {
+        self.pages
+            .iter()
+            .map(|page| {
+                let mut content = page.writer.content.clone();
+                let elements = self.head_elements(page);
+                if insert_into_head(&mut content, &elements, &page.writer.core) {
+                    Ok((page.path.clone(), content))
+                } else {
+                    Err(WriterError::MissingHead(page.path.clone()))
+                }
+            })
+            .collect()
+    }
+
+    // The paths and the writers of the pages
+    pub(crate) fn pages(&self) -> impl Iterator<Item = (&str, &HTMLWriter)> {
+        self.pages
+            .iter()
+            .map(|page| (page.path.as_str(), &page.writer))
+    }
+
+    // The paths of the files, which aren't pages: the assets, the feeds and the stubs of the redirects
+    pub(crate) fn files(&self) -> impl Iterator<Item = &str> {
+        self.assets
+            .iter()
+            .map(String::as_str)
+            .chain(self.feeds.iter().map(|(_, href, _)| href.as_str()))
+            .chain(self.redirects.iter().map(|(old, _)| old.as_str()))
+    }
+
+    // The base URL without the trailing '/'
+    pub(crate) fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // The elements of the head of the page, each written on its own
+    fn head_elements(&self, page: &Page) -> Vec<String> {
+        let mut elements = Vec::new();
+        let own_robots = has_robots(&page.writer.content);
+        if let Some(robots) = self.robots.as_ref().filter(|_| !own_robots) {
+            let mut wr = HTMLWriter::new();
+            wr.robots(robots);
+            elements.push(wr.content);
+        }
+        if self.canonical {
+            let mut wr = HTMLWriter::new();
+            wr.add_link("canonical", &self.url(&page.path));
+            elements.push(wr.content);
+        }
+        if let Some((key, _)) = &page.variant {
+            elements.extend(self.hreflang_links(key));
+        }
+        for (kind, href, title) in &self.feeds {
+            let mut wr = HTMLWriter::new();
+            wr.add_void(
+                "link",
+                &[
+                    ("rel", "alternate"),
+                    ("type", kind.mime_type()),
+                    ("title", title),
+                    ("href", href),
+                ],
+            );
+            elements.push(wr.content);
+        }
+        elements
+    }
+
+    // The links to all variants of the page including itself, and to the variant of the default locale
+    fn hreflang_links(&self, key: &str) -> Vec<String> {
+        let variants: Vec<(&str, &str)> = self
+            .pages
+            .iter()
+            .filter_map(|page| match &page.variant {
+                Some((k, locale)) if k == key => Some((locale.as_str(), page.path.as_str())),
+                _ => None,
+            })
+            .collect();
+        let default = self
+            .default_locale
+            .as_deref()
+            .and_then(|default| variants.iter().find(|(locale, _)| *locale == default))
+            .map(|(_, path)| ("x-default", *path));
+        variants
+            .iter()
+            .chain(default.iter())
+            .map(|(locale, path)| {
+                let mut wr = HTMLWriter::new();
+                wr.add_void(
+                    "link",
+                    &[
+                        ("rel", "alternate"),
+                        ("hreflang", locale),
+                        ("href", &self.url(path)),
+                    ],
+                );
+                wr.content
+            })
+            .collect()
+    }
+}
+
+// Inserts the elements at the end of the head. If the end-tag is on its own line, each element gets its own line
+// with the indent of the children, in the indent-characters and line-feeds of the page.
+fn insert_into_head(content: &mut String, elements: &[String], core: &WriterCore) -> bool {
+    let Some(end) = content.find("</head>") else {
+        return false;
+    };
+    let (at, separator) = match content[..end].rfind('\n') {
+        Some(line)
+            if content[line + 1..end]
+                .chars()
+                .all(|c| c == ' ' || c == '\t') =>
+        {
+            (
+                content[..line].strip_suffix('\r').map_or(line, str::len),
+                format!(
+                    "{}{}{}",
+                    core.newline(),
+                    &content[line + 1..end],
+                    core.indent_unit()
+                ),
+            )
+        }
+        _ => (end, String::new()),
+    };
+    let inserted: String = elements
+        .iter()
+        .map(|element| separator.clone() + element)
+        .collect();
+    content.insert_str(at, &inserted);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IndentChar, LineEnding, MLLWriter};
+
+    #[test]
+    fn site_head_layout() {
+        let mut page = HTMLWriter::new();
+        page.core.set_auto_layout(true);
+        page.open_tag("html");
+        page.open_tag("head");
+        page.open_tag("title");
+        page.close_tag();
+        page.close_tag();
+        page.close_tag();
+        let mut site = DocumentSet::new("https://example.com");
+        site.add_feed(FeedKind::Rss, "/rss.xml", "A & B");
+        site.set_canonical(false);
+        site.add_page("/", page);
+        site.add_page("empty.html", HTMLWriter::new());
+        assert_eq!(
+            site.render(),
+            Err(WriterError::MissingHead("empty.html".to_string()))
+        );
+        site.pages.pop();
+        assert_eq!(
+            site.render().unwrap()[0].1,
+            "<html>\n    <head>\n        <title></title>\n        <link rel=\"alternate\" \
+             type=\"application/rss+xml\" title=\"A &amp; B\" href=\"/rss.xml\">\n    </head>\n</html>"
+        );
+        assert_eq!(site.url("/"), "https://example.com/");
+
+        let mut page = HTMLWriter::new();
+        page.core.set_indent_char(IndentChar::Tabs);
+        page.core.set_line_ending(LineEnding::CrLf);
+        page.open_tag("head");
+        page.line_feed_inc();
+        page.single_tag("meta");
+        page.line_feed_dec();
+        page.close_tag();
+        let mut site = DocumentSet::new("https://example.com");
+        site.add_feed(FeedKind::Atom, "/atom.xml", "A");
+        site.set_canonical(false);
+        site.add_page("/", page);
+        assert_eq!(
+            site.render().unwrap()[0].1,
+            "<head>\r\n\t<meta>\r\n\t<link rel=\"alternate\" type=\"application/atom+xml\" title=\"A\" \
+             href=\"/atom.xml\">\r\n</head>"
+        );
+    }
+
+    #[test]
+    fn site_hreflang() {
+        let page = || {
+            let mut wr = HTMLWriter::new();
+            wr.open_tag("head");
+            wr.close_tag();
+            wr
+        };
+        let mut site = DocumentSet::new("https://example.com");
+        site.set_canonical(false);
+        site.set_default_locale("en");
+        site.add_localized_page("about", "en", "about.html", page());
+        site.add_localized_page("about", "de", "de/about.html", page());
+        site.add_localized_page("news", "de", "de/news.html", page());
+        let pages = site.render().unwrap();
+        assert_eq!(
+            pages[1].1,
+            "<head><link rel=\"alternate\" hreflang=\"en\" href=\"https://example.com/about.html\">\
+             <link rel=\"alternate\" hreflang=\"de\" href=\"https://example.com/de/about.html\">\
+             <link rel=\"alternate\" hreflang=\"x-default\" href=\"https://example.com/about.html\"></head>"
+        );
+        assert_eq!(
+            pages[2].1,
+            "<head><link rel=\"alternate\" hreflang=\"de\" href=\"https://example.com/de/news.html\"></head>"
+        );
+    }
+}
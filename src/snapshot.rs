@@ -0,0 +1,165 @@
+//! Read access to the content, while the document is still written.
+//!
+//! ```wr.content_so_far()``` borrows the content. A [`Snapshot`] is an owned, immutable view, which can be sent to
+//! other threads, e.g. for a validator or a hash, while the writer goes on. The content in front of the last
+//! start-tag doesn't change anymore, so it is frozen into shared chunks: each ```wr.snapshot()``` copies only the
+//! content since the previous one, the older chunks are shared.
+//!
+//! Methods, which rewrite the content afterwards, e.g. ```resolve_lists()``` or ```expand_includes()```, freeze it
+//! anew. Changes of the public ```content```-field by hand aren't noticed, except if it gets shorter.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("ul");
+//! wr.open_tag("li");
+//! let snapshot = wr.snapshot();
+//! let check = std::thread::spawn(move || snapshot.to_string());
+//! wr.add_property("class", "first");
+//! wr.close_tag();
+//! assert_eq!(check.join().unwrap(), "<ul><li>");
+//! assert_eq!(wr.content_so_far(), "<ul><li class=\"first\"></li>");
+//! assert_eq!(
+//!     wr.snapshot().chunks().collect::<Vec<_>>(),
+//!     ["<ul>", "<li class=\"first\">", "</li>"]
+//! );
+//! ```
+
+use std::sync::Arc;
+
+use crate::cursor;
+use crate::{HTMLWriter, JSONWriter, TOMLWriter, WriterCore, XMLWriter, YAMLWriter};
+
+/// An immutable view of the content at the time it was taken
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    chunks: Vec<Arc<str>>,
+    len: usize,
+}
+
+impl Snapshot {
+    /// Returns the length of the content in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the content was empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the chunks of the content in their order, e.g. for a hash
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().map(|chunk| &**chunk)
+    }
+}
+
+impl std::fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+// The frozen chunks of the content of a writer
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Frozen {
+    chunks: Vec<Arc<str>>,
+    len: usize,
+}
+
+macro_rules! snapshot {
+    ($($writer:ty),*) => {
+        $(
+            impl $writer {
+                /// Returns the content written so far
+                pub fn content_so_far(&self) -> &str {
+                    &self.content
+                }
+
+                /// Returns an immutable view of the content, which shares the unchanged chunks of former views
+                pub fn snapshot(&mut self) -> Snapshot {
+                    take_snapshot(&self.content, &mut self.core)
+                }
+            }
+        )*
+    };
+}
+
+snapshot!(HTMLWriter, XMLWriter, JSONWriter, YAMLWriter, TOMLWriter);
+
+impl Frozen {
+    // The length of the frozen content
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl WriterCore {
+    // Drops the frozen chunks and the places of the start-tags, because the content was rewritten
+    pub(crate) fn thaw(&mut self) {
+        self.frozen = Frozen::default();
+        self.tag_starts.clear();
+    }
+}
+
+// Freezes the stable content since the last snapshot and adds the tail, which may still change, as own chunk
+fn take_snapshot(content: &str, core: &mut WriterCore) -> Snapshot {
+    if content.len() < core.frozen.len {
+        core.thaw();
+    }
+    let stable = stable_len(content, core);
+    if stable > core.frozen.len {
+        let chunk = Arc::from(&content[core.frozen.len..stable]);
+        core.frozen.chunks.push(chunk);
+        core.frozen.len = stable;
+    }
+    let mut chunks = core.frozen.chunks.clone();
+    if content.len() > core.frozen.len {
+        chunks.push(Arc::from(&content[core.frozen.len..]));
+    }
+    Snapshot {
+        chunks,
+        len: content.len(),
+    }
+}
+
+// Returns the length of the content, which the next operations won't change anymore: all in front of the last
+// start-tag, which gets the properties, and of the last character, which decides about separators and line-feeds.
+// While properties are aligned, the whole block may still change, and the marks of cursors are replaced by the merge.
+pub(crate) fn stable_len(content: &str, core: &WriterCore) -> usize {
+    if !core.align_stack.is_empty() {
+        return 0;
+    }
+    let last_tag = if core.open_cursors > 0 {
+        content.find(cursor::MARK_START)
+    } else {
+        content.rfind('<')
+    }
+    .unwrap_or(content.len());
+    let last_char = content
+        .trim_end()
+        .char_indices()
+        .last()
+        .map_or(0, |(i, _)| i);
+    last_tag.min(last_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MLLWriter;
+
+    #[test]
+    fn snapshots_follow_rewrites() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("a", "1");
+        wr.add_property("b", "2");
+        assert_eq!(wr.snapshot().to_string(), "{\n  \"a\": 1,\n  \"b\": 2");
+        wr.close_tag();
+        wr.edit("/a", "3").unwrap();
+        let snapshot = wr.snapshot();
+        assert_eq!(snapshot.to_string(), wr.content);
+        assert_eq!(snapshot.len(), wr.content.len());
+    }
+}
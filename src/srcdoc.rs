@@ -0,0 +1,72 @@
+//! Nested documents in the 'srcdoc'-attribute of iframes.
+//!
+//! The nested document is written by a child writer in a closure, see ```sub_writer()```, and is escaped as a
+//! whole for the attribute. Text of the nested document is so escaped twice, e.g. "&" is written as "&amp;" by the
+//! child and as "&amp;amp;" into the attribute, which the browser decodes once before it parses the document.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! # use std::fmt::Write;
+//! let mut wr = HTMLWriter::new();
+//! wr.iframe_srcdoc_w_properties(&[("sandbox", "")], |inner| {
+//!     inner.open_tag_w_property("p", "class", "note");
+//!     write!(inner, "Fish &amp; Chips").unwrap();
+//!     inner.close_tag();
+//! });
+//! assert_eq!(
+//!     wr.content,
+//!     "<iframe sandbox=\"\" srcdoc=\"&lt;p class=&quot;note&quot;&gt;Fish &amp;amp; Chips&lt;/p&gt;\"></iframe>"
+//! );
+//! ```
+
+use crate::{check, HTMLWriter, MLLWriter, Properties, WriterError};
+
+impl HTMLWriter {
+    /// Writes an iframe with the document of the closure
+    pub fn iframe_srcdoc<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        self.iframe_srcdoc_w_properties(&[], f);
+    }
+
+    /// Writes an iframe with further attributes, e.g. "sandbox" or "title", and the document of the closure
+    pub fn iframe_srcdoc_w_properties<F>(&mut self, attributes: &[(&str, &str)], f: F)
+    where
+        F: FnOnce(&mut HTMLWriter),
+    {
+        let mut inner = self.sub_writer();
+        // The nested document starts without the indent of the iframe
+        inner.core.set_indent_step(0);
+        f(&mut inner);
+        if !inner.core.block_stack.is_empty() {
+            check(Err(WriterError::IllegalTransition {
+                state: inner.core.state,
+                operation: "iframe_srcdoc",
+                reason: "the nested document has unclosed elements",
+            }))
+        }
+        self.open_tag("iframe");
+        let mut properties: Properties = attributes.iter().copied().collect();
+        properties.add("srcdoc", &inner.content);
+        self.add_properties(&properties);
+        self.close_tag();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srcdoc_nonce_and_quotes() {
+        let mut wr = HTMLWriter::new();
+        wr.set_csp_nonce(Some("n"));
+        wr.iframe_srcdoc(|inner| inner.consent_inline_script("analytics", "a(\"x\")"));
+        assert_eq!(
+            wr.content,
+            "<iframe srcdoc=\"&lt;script nonce=&quot;n&quot; type=&quot;text/plain&quot; \
+             data-category=&quot;analytics&quot;&gt;a(&quot;x&quot;)&lt;/script&gt;\"></iframe>"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! The document state machine shared by all writers.
+//!
+//! Every writer tracks in which state the document under edit currently is. Operations which would
+//! produce a malformed document (a second root element, a property after the root was closed, text in
+//! the prolog, ...) are rejected with a descriptive [`WriterError`].
+
+use crate::{WriterCore, WriterError};
+
+/// The states a document passes while it is getting written. XMLWriter walks through all of them,
+/// JSONWriter never enters ```InStartTag``` (a block has no start-tag, properties are members of the
+/// block) and HTMLWriter may leave ```AfterRoot``` again, because HTML-fragments can have several
+/// top-level elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentState {
+    /// Nothing but whitespace, comments or declarations have been written so far
+    Prolog,
+    /// A start-tag has just been written, properties can still be appended to it
+    InStartTag,
+    /// There is at least one open element (or JSON-block)
+    InElement,
+    /// The root element has been closed
+    AfterRoot,
+    /// The document was ended explicitly, nothing can be written anymore
+    Finished,
+}
+
+fn illegal(state: DocumentState, operation: &'static str, reason: &'static str) -> WriterError {
+    WriterError::IllegalTransition {
+        state,
+        operation,
+        reason,
+    }
+}
+
+impl WriterCore {
+    /// Returns the current state of the document under edit
+    pub fn state(&self) -> DocumentState {
+        self.state
+    }
+
+    // The state after a start-tag has been left, depending on the elements still open
+    fn state_after_element(&self) -> DocumentState {
+        if self.block_stack.is_empty() {
+            DocumentState::AfterRoot
+        } else {
+            DocumentState::InElement
+        }
+    }
+
+    // Leaves a pending start-tag, because anything else than a property gets written now
+    pub(crate) fn settle(&mut self) {
+        if self.state == DocumentState::InStartTag {
+            self.state = self.state_after_element();
+        }
+    }
+
+    // Checks if a new element (open or single) may be started, without changing the state
+    pub(crate) fn check_begin_element(&self, operation: &'static str) -> Result<(), WriterError> {
+        let state = match self.state {
+            DocumentState::InStartTag => self.state_after_element(),
+            state => state,
+        };
+        match state {
+            DocumentState::Finished => Err(illegal(
+                state,
+                operation,
+                "the document is already finished",
+            )),
+            DocumentState::AfterRoot if self.single_root => Err(illegal(
+                state,
+                operation,
+                "a document can only have one root element",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Checks if a new element (open or single) may be started and enters InStartTag
+    pub(crate) fn begin_element(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        self.check_begin_element(operation)?;
+        self.state = DocumentState::InStartTag;
+        Ok(())
+    }
+
+    // Checks if a property can be appended to the last written start-tag
+    pub(crate) fn begin_property(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        match self.state {
+            DocumentState::InStartTag => Ok(()),
+            DocumentState::AfterRoot => Err(illegal(
+                self.state,
+                operation,
+                "the root element is already closed",
+            )),
+            _ => Err(illegal(
+                self.state,
+                operation,
+                "properties can only be added directly after a start-tag",
+            )),
+        }
+    }
+
+    // Checks if a property can be written as a member of the current (JSON-)block
+    pub(crate) fn begin_member(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        match self.state {
+            DocumentState::InElement => Ok(()),
+            DocumentState::Prolog => Err(illegal(
+                self.state,
+                operation,
+                "there is no open block to add the property to",
+            )),
+            DocumentState::AfterRoot => Err(illegal(
+                self.state,
+                operation,
+                "the root block is already closed",
+            )),
+            _ => Err(illegal(
+                self.state,
+                operation,
+                "the document is already finished",
+            )),
+        }
+    }
+
+    // Pops the last opened element from the block_stack and returns its tag
+    pub(crate) fn end_element(&mut self, operation: &'static str) -> Result<String, WriterError> {
+        self.settle();
+        match self.block_stack.pop() {
+            Some(tag) => {
+                self.forget_closed_tags();
+                self.state = self.state_after_element();
+                Ok(tag)
+            }
+            None => Err(WriterError::UnbalancedClose {
+                state: self.state,
+                operation,
+            }),
+        }
+    }
+
+    // Checks if markup which is no element (comments, declarations, ...) can be written
+    pub(crate) fn begin_markup(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        self.settle();
+        if self.state == DocumentState::Finished {
+            return Err(illegal(
+                self.state,
+                operation,
+                "the document is already finished",
+            ));
+        }
+        Ok(())
+    }
+
+    // Checks if text content can be written, which is only possible inside of the root element of
+    // single-rooted documents
+    pub(crate) fn begin_text(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        self.begin_markup(operation)?;
+        match self.state {
+            DocumentState::Prolog if self.single_root => Err(illegal(
+                self.state,
+                operation,
+                "text is not allowed in the prolog",
+            )),
+            DocumentState::AfterRoot if self.single_root => Err(illegal(
+                self.state,
+                operation,
+                "text is not allowed after the root element",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Ends the document, no operation except clear() is allowed afterwards
+    pub(crate) fn end_document(&mut self, operation: &'static str) -> Result<(), WriterError> {
+        self.begin_markup(operation)?;
+        if !self.block_stack.is_empty() {
+            return Err(illegal(
+                self.state,
+                operation,
+                "there are still unclosed elements",
+            ));
+        }
+        self.state = DocumentState::Finished;
+        Ok(())
+    }
+}
@@ -0,0 +1,565 @@
+//! Streaming export of records into a XML-document.
+//!
+//! The [`XmlRecordStream`] writes ```<root><record>…</record>…</root>``` into any ```std::io::Write```-sink. Every
+//! record is flushed to the sink as soon as it is written, so the memory stays constant no matter how many
+//! records are exported. The records are written by the [`XmlRecord`]-trait, which is implemented for lists and
+//! maps of field-value-pairs. Each field becomes a child element of the record.
+//!
+//! Exports into files can be resumed after an interruption (see ```XmlRecordStream::resume()```): the partially
+//! written file is reopened, a broken last record is cut off, and the export continues after the last complete
+//! record.
+//!
+//! A summary of the export (the number of records and the names of their fields) can be registered as trailer,
+//! which is written in front of the end-tag of the root. If the summary is needed at the top of the document,
+//! a fixed-width slot can be reserved in seekable sinks, which is filled when the values are known. The records
+//! are only streamed as XML, ```summary_json()``` returns the same summary as JSON-object, e.g. as "summary" of
+//! a JSON-manifest next to the export.
+//!
+//! ```
+//! # use mllwriter::XmlRecordStream;
+//! let mut stream = XmlRecordStream::new(Vec::new(), "customers", "customer").unwrap();
+//! stream.write_all(vec![[("name", "Muster"), ("city", "Ulm")]]).unwrap();
+//! let sink = stream.finish().unwrap();
+//! assert_eq!(
+//!     String::from_utf8(sink).unwrap(),
+//!     "<customers>\n  <customer>\n    <name>Muster</name>\n    <city>Ulm</city>\n  </customer>\n</customers>\n"
+//! );
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::format::json_string;
+use crate::{FormatMode, JSONWriter, MLLWriter, XMLWriter};
+
+/// A record which can be written as the children of a record element. The field names have to be valid tag
+/// names of the XMLWriter.
+pub trait XmlRecord {
+    /// Writes the fields of the record into the writer, the record element is already open
+    fn write_xml(&self, wr: &mut XMLWriter);
+
+    /// Returns the names of the fields, which are collected for the schema summary of the export
+    fn field_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// Writes one field as element with escaped text content
+fn write_field(wr: &mut XMLWriter, name: &str, value: &str) {
+    wr.open_tag(name);
+    wr.text(value);
+    wr.close_tag();
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> XmlRecord for [(K, V)] {
+    fn write_xml(&self, wr: &mut XMLWriter) {
+        self.iter()
+            .for_each(|(k, v)| write_field(wr, k.as_ref(), v.as_ref()));
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.iter().map(|(k, _)| k.as_ref().to_string()).collect()
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>, const N: usize> XmlRecord for [(K, V); N] {
+    fn write_xml(&self, wr: &mut XMLWriter) {
+        self.as_slice().write_xml(wr);
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.as_slice().field_names()
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> XmlRecord for Vec<(K, V)> {
+    fn write_xml(&self, wr: &mut XMLWriter) {
+        self.as_slice().write_xml(wr);
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.as_slice().field_names()
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> XmlRecord for BTreeMap<K, V> {
+    fn write_xml(&self, wr: &mut XMLWriter) {
+        self.iter()
+            .for_each(|(k, v)| write_field(wr, k.as_ref(), v.as_ref()));
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.keys().map(|k| k.as_ref().to_string()).collect()
+    }
+}
+
+impl<R: XmlRecord + ?Sized> XmlRecord for &R {
+    fn write_xml(&self, wr: &mut XMLWriter) {
+        (**self).write_xml(wr);
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        (**self).field_names()
+    }
+}
+
+/// A fixed-width slot in the output of a stream, which is filled later
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    // byte-offset of the slot in the sink
+    offset: u64,
+    width: usize,
+}
+
+/// Streams records as XML-document into a sink, with constant memory. The document is written with the
+/// auto-layout of the XMLWriter.
+pub struct XmlRecordStream<W: Write> {
+    sink: W,
+    writer: XMLWriter,
+    record_tag: String,
+    count: usize,
+    // the names of all fields written so far
+    fields: BTreeSet<String>,
+    // the tag of the summary trailer, if registered
+    trailer: Option<String>,
+    // number of bytes written into the sink
+    written: u64,
+}
+
+impl<W: Write> XmlRecordStream<W> {
+    /// Starts the document by writing the start-tag of the root element
+    pub fn new(sink: W, root: &str, record_tag: &str) -> std::io::Result<XmlRecordStream<W>> {
+        let mut writer = XMLWriter::new();
+        writer.core.set_auto_layout(true);
+        writer.open_tag(root);
+        let mut stream = XmlRecordStream {
+            sink,
+            writer,
+            record_tag: record_tag.to_string(),
+            count: 0,
+            fields: BTreeSet::new(),
+            trailer: None,
+            written: 0,
+        };
+        stream.flush()?;
+        Ok(stream)
+    }
+
+    /// Gives access to the writer, e.g. to change the indent-step-size before the first record
+    pub fn writer(&mut self) -> &mut XMLWriter {
+        &mut self.writer
+    }
+
+    /// Returns the number of records written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the names of all fields written so far
+    pub fn fields(&self) -> &BTreeSet<String> {
+        &self.fields
+    }
+
+    /// Registers a summary trailer with the given tag, which is written in front of the end-tag of the root
+    pub fn set_trailer(&mut self, tag: &str) {
+        self.trailer = Some(tag.to_string());
+    }
+
+    /// Returns the summary of the records written so far as single element, e.g.
+    /// ```<summary recordCount="2" fields="id name"/>```
+    pub fn summary_element(&self, tag: &str) -> String {
+        let core = &self.writer.core;
+        let mut summary = format!(
+            "<{}{}",
+            tag,
+            core.property_string("recordCount", &self.count.to_string())
+        );
+        if !self.fields.is_empty() {
+            let fields: Vec<&str> = self.fields.iter().map(|f| f.as_str()).collect();
+            summary.push_str(&core.property_string("fields", &fields.join(" ")));
+        }
+        summary.push_str("/>");
+        summary
+    }
+
+    /// Returns the summary of the records written so far as JSON-object, e.g.
+    /// ```{"recordCount":2,"fields":["id","name"]}```
+    pub fn summary_json(&self) -> String {
+        let mut wr = JSONWriter::new();
+        wr.set_format_mode(FormatMode::Minified);
+        wr.open_tag("");
+        wr.add_property("recordCount", &self.count.to_string());
+        wr.open_array("fields");
+        for field in &self.fields {
+            wr.add_item(&json_string(field));
+        }
+        wr.close_tag();
+        wr.close_tag();
+        wr.content
+    }
+
+    /// Writes one record and flushes it to the sink
+    pub fn write_record<R: XmlRecord + ?Sized>(&mut self, record: &R) -> std::io::Result<()> {
+        self.fields.extend(record.field_names());
+        self.writer.open_tag(&self.record_tag);
+        record.write_xml(&mut self.writer);
+        self.writer.close_tag();
+        self.count += 1;
+        self.flush()
+    }
+
+    /// Writes all records of the iterator
+    pub fn write_all<I>(&mut self, records: I) -> std::io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: XmlRecord,
+    {
+        records
+            .into_iter()
+            .try_for_each(|record| self.write_record(&record))
+    }
+
+    /// Closes the root element and returns the sink
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if let Some(tag) = self.trailer.take() {
+            let summary = self.summary_element(&tag);
+            self.write_raw_line(&summary);
+        }
+        self.writer.close_tag();
+        let newline = self.writer.core.newline();
+        self.writer.content.push_str(newline);
+        self.flush()?;
+        Ok(self.sink)
+    }
+
+    // Writes a line of markup at the current indent
+    fn write_raw_line(&mut self, markup: &str) {
+        self.writer.core.layout_mark_children();
+        let newline = self.writer.core.newline();
+        self.writer.content.push_str(newline);
+        self.writer.content.push_str(&self.writer.core.indent);
+        self.writer.content.push_str(markup);
+    }
+
+    // Moves the content of the writer into the sink
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.sink.write_all(self.writer.content.as_bytes())?;
+        self.written += self.writer.content.len() as u64;
+        self.writer.content.clear();
+        self.writer.core.flushed = true;
+        self.sink.flush()
+    }
+}
+
+impl<W: Write + Seek> XmlRecordStream<W> {
+    /// Reserves a slot of width bytes on a new line at the current position, e.g. for a summary in front of
+    /// the records. The slot is written with whitespace, until it is filled.
+    pub fn reserve_slot(&mut self, width: usize) -> std::io::Result<Slot> {
+        self.write_raw_line("");
+        self.flush()?;
+        let slot = Slot {
+            offset: self.written,
+            width,
+        };
+        self.writer.content.push_str(&" ".repeat(width));
+        self.flush()?;
+        Ok(slot)
+    }
+
+    /// Fills a reserved slot, the text is padded with whitespace to the width of the slot. It fails, if the
+    /// text is wider than the slot.
+    pub fn fill_slot(&mut self, slot: &Slot, text: &str) -> std::io::Result<()> {
+        if text.len() > slot.width {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("'{}' doesn't fit into a slot of {} bytes", text, slot.width),
+            ));
+        }
+        self.sink.seek(SeekFrom::Start(slot.offset))?;
+        self.sink.write_all(text.as_bytes())?;
+        self.sink
+            .write_all(" ".repeat(slot.width - text.len()).as_bytes())?;
+        self.sink.seek(SeekFrom::Start(self.written))?;
+        Ok(())
+    }
+}
+
+impl XmlRecordStream<File> {
+    /// Reopens a partially written export file and continues it. The tail of the file is repaired: everything
+    /// behind the last complete record (a broken record or the end-tag of the root) is cut off. The number of
+    /// records found in the file is taken over by ```count()```, the names of the child elements of those records
+    /// by ```fields()```.
+    pub fn resume<P: AsRef<Path>>(
+        path: P,
+        root: &str,
+        record_tag: &str,
+    ) -> std::io::Result<XmlRecordStream<File>> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let (root_end, self_closing) = find_start_tag_end(&mut file, root)?;
+        let end_tag = "</".to_string() + record_tag + ">";
+        let (count, last_end) = scan(&mut file, end_tag.as_bytes())?;
+        let fields = scan_fields(&mut file, last_end.unwrap_or(0), record_tag)?;
+        match last_end {
+            Some(last_end) => file.set_len(last_end)?,
+            // An empty root like <rows/> is reopened as start-tag
+            None if self_closing => {
+                file.set_len(root_end - 2)?;
+                file.seek(SeekFrom::End(0))?;
+                file.write_all(b">")?;
+            }
+            None => file.set_len(root_end)?,
+        }
+        file.seek(SeekFrom::End(0))?;
+
+        // Restore the writer-state, as if the root element and the records were written by it
+        let mut writer = XMLWriter::new();
+        writer.core.set_auto_layout(true);
+        writer.open_tag(root);
+        writer.content.clear();
+        writer.core.settle();
+        writer.core.flushed = true;
+        if count > 0 {
+            writer.core.layout_mark_children();
+        }
+        let written = file.stream_position()?;
+        Ok(XmlRecordStream {
+            sink: file,
+            writer,
+            record_tag: record_tag.to_string(),
+            count,
+            fields,
+            trailer: None,
+            written,
+        })
+    }
+
+    /// Makes sure, that all records written so far are persisted on the disk
+    pub fn checkpoint(&mut self) -> std::io::Result<()> {
+        self.sink.sync_data()
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+// Returns the offset behind the start-tag of the root element and whether it is self-closing. The head of the
+// file is read chunk by chunk, until the start-tag is complete.
+fn find_start_tag_end(file: &mut File, root: &str) -> std::io::Result<(u64, bool)> {
+    let start_tag = ("<".to_string() + root).into_bytes();
+    let mut head = Vec::new();
+    let mut chunk = vec![0u8; 64 * 1024];
+    file.seek(SeekFrom::Start(0))?;
+    loop {
+        let n = file.read(&mut chunk)?;
+        head.extend_from_slice(&chunk[..n]);
+        // The name of the root is followed by whitespace, '/' or '>'
+        let start = (0..head.len().saturating_sub(start_tag.len())).find(|&i| {
+            head[i..].starts_with(&start_tag)
+                && matches!(
+                    head[i + start_tag.len()],
+                    b'>' | b'/' | b' ' | b'\t' | b'\n' | b'\r'
+                )
+        });
+        let end = start.and_then(|start| {
+            let end = start + head[start..].iter().position(|&b| b == b'>')?;
+            Some(((end + 1) as u64, head[end - 1] == b'/'))
+        });
+        match (start, end, n) {
+            (_, Some(end), _) => return Ok(end),
+            (None, _, 0) => return Err(invalid_data("the root element was not found")),
+            (Some(_), _, 0) => {
+                return Err(invalid_data("the start-tag of the root element is broken"))
+            }
+            _ => (),
+        }
+    }
+}
+
+// Collects the names of the child elements of the records in front of the offset end, i.e. the fields of the
+// complete records. The file is read through a buffer, comments and CDATA-sections are skipped.
+fn scan_fields(file: &mut File, end: u64, record_tag: &str) -> std::io::Result<BTreeSet<String>> {
+    let mut fields = BTreeSet::new();
+    // the depth inside of the current record, 0 outside of the records
+    let mut depth = 0usize;
+    let mut tag: Option<Vec<u8>> = None;
+    file.seek(SeekFrom::Start(0))?;
+    for byte in BufReader::new(Read::by_ref(file).take(end)).bytes() {
+        let byte = byte?;
+        let Some(text) = tag.as_mut() else {
+            tag = (byte == b'<').then(Vec::new);
+            continue;
+        };
+        let unfinished = (text.starts_with(b"!--") && !text.ends_with(b"--"))
+            || (text.starts_with(b"![CDATA[") && !text.ends_with(b"]]"));
+        if byte != b'>' || unfinished {
+            text.push(byte);
+            continue;
+        }
+        let text = tag.take().unwrap_or_default();
+        let name: Vec<u8> = text
+            .iter()
+            .copied()
+            .take_while(|b| !b.is_ascii_whitespace() && *b != b'/')
+            .collect();
+        let self_closing = text.ends_with(b"/");
+        match text.first() {
+            Some(b'!' | b'?') | None => (),
+            Some(b'/') => depth = depth.saturating_sub(1),
+            _ if depth == 0 => {
+                if name == record_tag.as_bytes() && !self_closing {
+                    depth = 1;
+                }
+            }
+            _ => {
+                if depth == 1 {
+                    fields.insert(String::from_utf8_lossy(&name).into_owned());
+                }
+                if !self_closing {
+                    depth += 1;
+                }
+            }
+        }
+    }
+    Ok(fields)
+}
+
+// Counts the occurrences of pattern in the file chunk by chunk, and returns the offset behind the last one
+fn scan(file: &mut File, pattern: &[u8]) -> std::io::Result<(usize, Option<u64>)> {
+    let mut count = 0;
+    let mut last_end = None;
+    // Bytes of the previous chunk, an occurrence may be split between two chunks
+    let mut window: Vec<u8> = Vec::new();
+    let mut window_offset = 0u64;
+    let mut chunk = vec![0u8; 64 * 1024];
+    file.seek(SeekFrom::Start(0))?;
+    loop {
+        let n = file.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        window.extend_from_slice(&chunk[..n]);
+        let mut i = 0;
+        while i + pattern.len() <= window.len() {
+            if &window[i..i + pattern.len()] == pattern {
+                count += 1;
+                last_end = Some(window_offset + (i + pattern.len()) as u64);
+                i += pattern.len();
+            } else {
+                i += 1;
+            }
+        }
+        // Keep the bytes, which could be the beginning of a split occurrence
+        let keep = i.min(window.len());
+        window_offset += keep as u64;
+        window.drain(..keep);
+    }
+    Ok((count, last_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_records() {
+        let mut stream = XmlRecordStream::new(Vec::new(), "rows", "row").unwrap();
+        let mut record = BTreeMap::new();
+        record.insert("id", "1");
+        record.insert("text", "a < b & c");
+        stream.write_record(&record).unwrap();
+        stream.write_record(&vec![("id", "2")]).unwrap();
+        assert_eq!(stream.count(), 2);
+        // Everything written so far is in the sink already
+        assert!(stream.writer().content.is_empty());
+        let sink = stream.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(sink).unwrap(),
+            "<rows>\n  <row>\n    <id>1</id>\n    <text>a &lt; b &amp; c</text>\n  </row>\n  <row>\n    <id>2</id>\n  </row>\n</rows>\n"
+        );
+    }
+
+    #[test]
+    fn stream_summary() {
+        let mut stream =
+            XmlRecordStream::new(std::io::Cursor::new(Vec::new()), "rows", "row").unwrap();
+        let slot = stream.reserve_slot(50).unwrap();
+        stream.set_trailer("summary");
+        stream.write_record(&[("id", "1"), ("name", "a")]).unwrap();
+        stream.write_record(&[("id", "2")]).unwrap();
+        let summary = stream.summary_element("summary");
+        stream.fill_slot(&slot, &summary).unwrap();
+        assert_eq!(
+            stream.summary_json(),
+            "{\"recordCount\":2,\"fields\":[\"id\",\"name\"]}"
+        );
+        assert!(stream.fill_slot(&slot, &" ".repeat(51)).is_err());
+        let sink = stream.finish().unwrap().into_inner();
+        let expected = "<rows>\n  <summary recordCount=\"2\" fields=\"id name\"/>       \n  <row>\n    <id>1</id>\n    <name>a</name>\n  </row>\n  <row>\n    <id>2</id>\n  </row>\n  <summary recordCount=\"2\" fields=\"id name\"/>\n</rows>\n";
+        assert_eq!(String::from_utf8(sink).unwrap(), expected);
+    }
+
+    #[test]
+    fn stream_resume() {
+        let path = std::env::temp_dir().join(format!(
+            "mllwriter-stream-resume-{}.xml",
+            std::process::id()
+        ));
+        let file = File::create(&path).unwrap();
+        let mut stream = XmlRecordStream::new(file, "rows", "row").unwrap();
+        stream.write_record(&[("id", "1")]).unwrap();
+        drop(stream);
+        // An interrupted record at the tail of the file
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\n  <row>\n    <id>2</i").unwrap();
+        drop(file);
+
+        let mut stream = XmlRecordStream::resume(&path, "rows", "row").unwrap();
+        assert_eq!(stream.count(), 1);
+        assert_eq!(
+            stream.summary_element("summary"),
+            "<summary recordCount=\"1\" fields=\"id\"/>"
+        );
+        stream.write_record(&[("id", "2")]).unwrap();
+        stream.checkpoint().unwrap();
+        stream.finish().unwrap();
+        let expected = "<rows>\n  <row>\n    <id>1</id>\n  </row>\n  <row>\n    <id>2</id>\n  </row>\n</rows>\n";
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+
+        // A finished export is reopened behind its last record
+        let mut stream = XmlRecordStream::resume(&path, "rows", "row").unwrap();
+        assert_eq!(stream.count(), 2);
+        stream.write_record(&[("id", "3")]).unwrap();
+        stream.finish().unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("<id>2</id>\n  </row>\n  <row>\n    <id>3</id>\n  </row>\n</rows>\n"));
+
+        // An empty export continues behind the start-tag of the root
+        std::fs::write(&path, "<rows>\n  <ro").unwrap();
+        let stream = XmlRecordStream::resume(&path, "rows", "row").unwrap();
+        assert_eq!(stream.count(), 0);
+        stream.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "<rows></rows>\n");
+
+        // The root after a long prolog, with a line-feed behind its name, and an empty self-closing root
+        let prolog = format!("<!-- {} -->\n", "x".repeat(70 * 1024));
+        std::fs::write(&path, prolog.clone() + "<rows\n  a=\"1\">\n</rows>").unwrap();
+        let stream = XmlRecordStream::resume(&path, "rows", "row").unwrap();
+        stream.finish().unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("<rows\n  a=\"1\"></rows>\n"));
+        std::fs::write(&path, "<rows/>").unwrap();
+        let mut stream = XmlRecordStream::resume(&path, "rows", "row").unwrap();
+        stream.write_record(&[("id", "1")]).unwrap();
+        stream.finish().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "<rows>\n  <row>\n    <id>1</id>\n  </row>\n</rows>\n"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,298 @@
+//! The SVGWriter for graphics, e.g. plots, with typed shapes.
+//!
+//! The [`SVGWriter`] wraps an XMLWriter, whose methods stay available for anything not covered, e.g.
+//! ```open_tag("g")``` or ```add_comment()```. The shapes take their coordinates as numbers, which are written with
+//! at most 2 decimals by default, see ```set_precision()```. Like properties, ```attr()``` adds an attribute to the
+//! last shape, also the ones of SVG with mixed case or a '-', e.g. "stroke-width". Paths are built by a
+//! [`PathData`].
+//!
+//! ```
+//! # use mllwriter::{MLLWriter, PathData, SVGWriter};
+//! let mut svg = SVGWriter::new(100.0, 50.0);
+//! svg.view_box(0.0, 0.0, 10.0, 5.0);
+//! svg.rect(0.0, 0.0, 10.0, 5.0);
+//! svg.attr("fill", "white");
+//! svg.open_tag("g");
+//! svg.circle(2.5, 2.5, 1.0 / 3.0);
+//! svg.path(&PathData::new().move_to(0.0, 5.0).line_to(10.0, 0.0));
+//! svg.attr("stroke-width", 0.5);
+//! svg.set_precision(4);
+//! svg.circle(0.0, 0.0, 1.0 / 3.0);
+//! svg.close_tag();
+//! assert_eq!(
+//!     svg.finish().unwrap(),
+//!     "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 10 5\">\
+//!      <rect x=\"0\" y=\"0\" width=\"10\" height=\"5\" fill=\"white\"/><g><circle cx=\"2.5\" cy=\"2.5\" r=\"0.33\"/>\
+//!      <path d=\"M0 5 L10 0\" stroke-width=\"0.5\"/><circle cx=\"0\" cy=\"0\" r=\"0.3333\"/></g></svg>"
+//! );
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use crate::tag::{add_attributes, xml_name};
+use crate::{
+    check, escape_attr, push_property, MLLWriter, VoidStyle, WriterError, XMLWriter, XmlName,
+};
+
+/// The namespace of SVG
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+// The number of decimals of the coordinates, unless set otherwise
+const DEFAULT_PRECISION: usize = 2;
+
+/// The SVGWriter, which wraps an XMLWriter
+#[derive(Debug, Clone)]
+pub struct SVGWriter {
+    xml: XMLWriter,
+    precision: usize,
+}
+
+impl SVGWriter {
+    /// Returns a writer, which has opened the root-element with the size in pixels
+    pub fn new(width: f64, height: f64) -> SVGWriter {
+        let mut xml = XMLWriter::new();
+        // SVG is XML, its empty elements have to be self-closing
+        xml.set_void_style(VoidStyle::Xml);
+        xml.open_element(&xml_name("svg"));
+        add_attributes(
+            &mut xml,
+            &[
+                ("xmlns", SVG_NAMESPACE.to_string()),
+                ("width", number(width, DEFAULT_PRECISION)),
+                ("height", number(height, DEFAULT_PRECISION)),
+            ],
+        );
+        SVGWriter {
+            xml,
+            precision: DEFAULT_PRECISION,
+        }
+    }
+
+    /// Sets the number of decimals of the following coordinates, trailing zeros are dropped
+    pub fn set_precision(&mut self, decimals: usize) {
+        self.precision = decimals;
+    }
+
+    /// Sets the coordinate system of the root-element, directly after ```new()```
+    pub fn view_box(&mut self, min_x: f64, min_y: f64, width: f64, height: f64) {
+        let view_box = [min_x, min_y, width, height]
+            .map(|value| self.number(value))
+            .join(" ");
+        self.attr("viewBox", view_box);
+    }
+
+    /// Adds the attribute with the number to the last shape or start-tag, written with the precision of the writer
+    pub fn attr_number(&mut self, name: &str, value: f64) {
+        let value = self.number(value);
+        self.attr(name, value);
+    }
+
+    /// Adds the attribute to the last shape or start-tag, the value is escaped
+    pub fn attr(&mut self, name: &str, value: impl std::fmt::Display) {
+        check(self.try_attr(name, value));
+    }
+
+    /// Like ```attr()```, but returns the error instead of panicking
+    pub fn try_attr(
+        &mut self,
+        name: &str,
+        value: impl std::fmt::Display,
+    ) -> Result<(), WriterError> {
+        XmlName::new(name)?;
+        self.xml.core.begin_property("attr")?;
+        let value = escape_attr(&value.to_string());
+        push_property(&mut self.xml.core, &mut self.xml.content, name, &value);
+        Ok(())
+    }
+
+    /// Writes a rectangle
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.shape(
+            "rect",
+            &[("x", x), ("y", y), ("width", width), ("height", height)],
+        );
+    }
+
+    /// Writes a circle around the center
+    pub fn circle(&mut self, cx: f64, cy: f64, r: f64) {
+        self.shape("circle", &[("cx", cx), ("cy", cy), ("r", r)]);
+    }
+
+    /// Writes an ellipse around the center
+    pub fn ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64) {
+        self.shape("ellipse", &[("cx", cx), ("cy", cy), ("rx", rx), ("ry", ry)]);
+    }
+
+    /// Writes a line between the points
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.shape("line", &[("x1", x1), ("y1", y1), ("x2", x2), ("y2", y2)]);
+    }
+
+    /// Writes an open line through the points
+    pub fn polyline(&mut self, points: &[(f64, f64)]) {
+        self.points_shape("polyline", points);
+    }
+
+    /// Writes a closed shape through the points
+    pub fn polygon(&mut self, points: &[(f64, f64)]) {
+        self.points_shape("polygon", points);
+    }
+
+    /// Writes the path
+    pub fn path(&mut self, d: &PathData) {
+        let d = format!("{:.*}", self.precision, d);
+        self.element("path", &[("d", d)]);
+    }
+
+    /// Writes the text, x and y are the start of its baseline
+    pub fn text_at(&mut self, x: f64, y: f64, text: &str) {
+        self.xml.open_element(&xml_name("text"));
+        self.attr_number("x", x);
+        self.attr_number("y", y);
+        self.xml.text(text);
+        self.xml.close_tag();
+    }
+
+    /// Closes the root-element and returns the content, if all other elements are closed
+    pub fn finish(mut self) -> Result<String, WriterError> {
+        if self.xml.core.block_stack.len() == 1 {
+            self.xml.try_close_tag()?;
+        }
+        self.xml.finish()
+    }
+
+    /// Returns the XMLWriter
+    pub fn into_inner(self) -> XMLWriter {
+        self.xml
+    }
+
+    // Writes a shape with the numbers as attributes
+    fn shape(&mut self, tag: &'static str, attributes: &[(&str, f64)]) {
+        let attributes: Vec<(&str, String)> = attributes
+            .iter()
+            .map(|(name, value)| (*name, self.number(*value)))
+            .collect();
+        self.element(tag, &attributes);
+    }
+
+    fn points_shape(&mut self, tag: &'static str, points: &[(f64, f64)]) {
+        let points: Vec<String> = points
+            .iter()
+            .map(|(x, y)| format!("{},{}", self.number(*x), self.number(*y)))
+            .collect();
+        self.element(tag, &[("points", points.join(" "))]);
+    }
+
+    fn number(&self, value: f64) -> String {
+        number(value, self.precision)
+    }
+
+    // Writes the shape as element without content, further attributes can be added by attr()
+    fn element(&mut self, tag: &'static str, attributes: &[(&str, String)]) {
+        self.xml.single_element(&xml_name(tag));
+        add_attributes(&mut self.xml, attributes);
+    }
+}
+
+impl Deref for SVGWriter {
+    type Target = XMLWriter;
+
+    fn deref(&self) -> &XMLWriter {
+        &self.xml
+    }
+}
+
+impl DerefMut for SVGWriter {
+    fn deref_mut(&mut self) -> &mut XMLWriter {
+        &mut self.xml
+    }
+}
+
+/// The commands of a path with absolute coordinates. They are formatted with at most 2 decimals, or with the
+/// precision of the format, e.g. ```format!("{:.4}", path)```.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathData {
+    commands: Vec<(char, Vec<f64>)>,
+}
+
+impl PathData {
+    /// Returns an empty path
+    pub fn new() -> PathData {
+        PathData::default()
+    }
+
+    /// Moves to the point without drawing
+    pub fn move_to(mut self, x: f64, y: f64) -> PathData {
+        self.commands.push(('M', vec![x, y]));
+        self
+    }
+
+    /// Draws a line to the point
+    pub fn line_to(mut self, x: f64, y: f64) -> PathData {
+        self.commands.push(('L', vec![x, y]));
+        self
+    }
+
+    /// Draws a cubic curve to the point, with the two control points
+    pub fn curve_to(mut self, c1: (f64, f64), c2: (f64, f64), x: f64, y: f64) -> PathData {
+        self.commands
+            .push(('C', vec![c1.0, c1.1, c2.0, c2.1, x, y]));
+        self
+    }
+
+    /// Closes the current subpath with a line to its start
+    pub fn close(mut self) -> PathData {
+        self.commands.push(('Z', Vec::new()));
+        self
+    }
+}
+
+impl std::fmt::Display for PathData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let precision = f.precision().unwrap_or(DEFAULT_PRECISION);
+        let commands: Vec<String> = self
+            .commands
+            .iter()
+            .map(|(command, values)| {
+                let values: Vec<String> = values.iter().map(|v| number(*v, precision)).collect();
+                format!("{}{}", command, values.join(" "))
+            })
+            .collect();
+        write!(f, "{}", commands.join(" "))
+    }
+}
+
+// Formats a coordinate with at most the number of decimals
+pub(crate) fn number(value: f64, precision: usize) -> String {
+    let mut text = format!("{:.*}", precision, value);
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    if text == "-0" {
+        text.remove(0);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_shapes() {
+        let mut svg = SVGWriter::new(10.0, 10.0);
+        svg.polygon(&[(0.0, 0.0), (10.0, 0.0), (5.0, 8.66)]);
+        assert!(svg.try_attr("stroke width", 1).is_err());
+        svg.text_at(1.0, 9.0, "a < b");
+        assert!(svg.try_attr("fill", "red").is_err());
+        let content = svg.finish().unwrap();
+        assert!(content.ends_with(
+            "<polygon points=\"0,0 10,0 5,8.66\"/><text x=\"1\" y=\"9\">a &lt; b</text></svg>"
+        ));
+        assert_eq!(number(-0.001, 2), "0");
+        assert_eq!(number(2.5, 2), "2.5");
+        assert_eq!(number(100.0, 0), "100");
+        let path = PathData::new().move_to(0.0, 1.0 / 3.0).close();
+        assert_eq!(format!("{:.3}", path), "M0 0.333 Z");
+    }
+}
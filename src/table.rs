@@ -0,0 +1,248 @@
+//! A builder for HTML report tables with formatted cells.
+//!
+//! The [`TableBuilder`] collects a header and rows of cells and writes them as 'table' into an HTMLWriter.
+//! Number-cells are formatted by the [`CellFormat`] of their column, with thousands-separators, currency
+//! symbols or as percentage. Numbers are right-aligned and negative values get a styling class, so financial
+//! tables don't need any formatting per cell.
+//!
+//! ```
+//! # use mllwriter::{Cell, CellFormat, HTMLWriter, TableBuilder};
+//! let mut table = TableBuilder::new(&["Item", "Price"]);
+//! table.set_column_format(1, CellFormat::Currency { symbol: "$".to_string(), decimals: 2 });
+//! table.add_row(vec![Cell::text("Rent"), Cell::number(-1250.0)]);
+//!
+//! let mut wr = HTMLWriter::new();
+//! table.write(&mut wr);
+//! assert_eq!(
+//!     wr.content,
+//!     "<table><thead><tr><th>Item</th><th>Price</th></tr></thead><tbody><tr><td>Rent</td>\
+//!      <td style=\"text-align: right\" class=\"negative\">-$1,250.00</td></tr></tbody></table>"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::format::group_digits;
+use crate::{escape_html_text, HTMLWriter, MLLWriter};
+
+/// The content of a table cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// Text, which is written as it is
+    Text(String),
+    /// A number, which is formatted by the format of its column
+    Number(f64),
+    /// An empty cell
+    Empty,
+}
+
+impl Cell {
+    /// Returns a text-cell
+    pub fn text(text: &str) -> Cell {
+        Cell::Text(text.to_string())
+    }
+
+    /// Returns a number-cell
+    pub fn number(value: f64) -> Cell {
+        Cell::Number(value)
+    }
+}
+
+/// The format of the number-cells of a column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CellFormat {
+    /// The number with the given number of decimals
+    Number { decimals: usize },
+    /// The number with a currency symbol in front, e.g. "$1,250.00"
+    Currency { symbol: String, decimals: usize },
+    /// The number multiplied by 100 with a '%' at the end, e.g. 0.125 as "12.5%"
+    Percent { decimals: usize },
+}
+
+impl Default for CellFormat {
+    fn default() -> Self {
+        CellFormat::Number { decimals: 0 }
+    }
+}
+
+/// Collects the cells of a table and writes them into an HTMLWriter
+#[derive(Debug, Clone)]
+pub struct TableBuilder {
+    header: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+    formats: Vec<CellFormat>,
+    // the separators of the thousands and the decimals
+    thousands_separator: String,
+    decimal_separator: String,
+    right_align_numbers: bool,
+    negative_class: Option<String>,
+}
+
+impl TableBuilder {
+    /// Returns a new table with the given header, an empty header writes no 'thead'
+    pub fn new(header: &[&str]) -> TableBuilder {
+        TableBuilder {
+            header: header.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+            formats: Vec::new(),
+            thousands_separator: ",".to_string(),
+            decimal_separator: ".".to_string(),
+            right_align_numbers: true,
+            negative_class: Some("negative".to_string()),
+        }
+    }
+
+    /// Sets the format of the number-cells of the column
+    pub fn set_column_format(&mut self, column: usize, format: CellFormat) {
+        if self.formats.len() <= column {
+            self.formats.resize(column + 1, CellFormat::default());
+        }
+        self.formats[column] = format;
+    }
+
+    /// Sets the separators of the thousands and the decimals, an empty thousands-separator disables grouping
+    pub fn set_separators(&mut self, thousands: &str, decimal: &str) {
+        self.thousands_separator = thousands.to_string();
+        self.decimal_separator = decimal.to_string();
+    }
+
+    /// Sets the separators of the locale
+    #[cfg(feature = "num-format")]
+    pub fn set_locale(&mut self, locale: crate::Locale) {
+        self.set_separators(locale.separator(), locale.decimal());
+    }
+
+    /// Enables or disables the right-alignment of number-cells, it is enabled by default
+    pub fn set_right_align_numbers(&mut self, right_align: bool) {
+        self.right_align_numbers = right_align;
+    }
+
+    /// Sets the class of cells with negative values, "negative" by default, None disables it
+    pub fn set_negative_class(&mut self, class: Option<&str>) {
+        self.negative_class = class.map(|c| c.to_string());
+    }
+
+    /// Adds a row of cells
+    pub fn add_row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(cells);
+    }
+
+    /// Returns the number formatted by the format of the column
+    pub fn format_number(&self, column: usize, value: f64) -> String {
+        let format = self.formats.get(column).cloned().unwrap_or_default();
+        let (value, decimals) = match format {
+            CellFormat::Number { decimals } | CellFormat::Currency { decimals, .. } => {
+                (value, decimals)
+            }
+            CellFormat::Percent { decimals } => (value * 100.0, decimals),
+        };
+        let digits = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+        let mut formatted = String::new();
+        // A value rounded to zero has no sign
+        if value < 0.0 && digits.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            formatted.push('-');
+        }
+        if let CellFormat::Currency { symbol, .. } = &format {
+            formatted.push_str(symbol);
+        }
+        formatted.push_str(&group_digits(integer, 3, 3, &self.thousands_separator));
+        if let Some(fraction) = fraction {
+            formatted.push_str(&self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        if let CellFormat::Percent { .. } = format {
+            formatted.push('%');
+        }
+        formatted
+    }
+
+    /// Writes the table into the writer
+    pub fn write(&self, wr: &mut HTMLWriter) {
+        wr.open_tag("table");
+        if !self.header.is_empty() {
+            wr.open_tag("thead");
+            wr.open_tag("tr");
+            for h in &self.header {
+                write_cell(wr, "th", h);
+            }
+            wr.close_tag();
+            wr.close_tag();
+        }
+        wr.open_tag("tbody");
+        for row in &self.rows {
+            wr.open_tag("tr");
+            for (column, cell) in row.iter().enumerate() {
+                match cell {
+                    Cell::Text(text) => write_cell(wr, "td", text),
+                    Cell::Number(value) => {
+                        let formatted = self.format_number(column, *value);
+                        wr.open_tag("td");
+                        if self.right_align_numbers {
+                            wr.add_property("style", "text-align: right");
+                        }
+                        // The sign after the rounding, so "0" isn't styled as negative
+                        let negative = formatted.starts_with('-');
+                        if let Some(class) = self.negative_class.as_ref().filter(|_| negative) {
+                            wr.add_property("class", class);
+                        }
+                        write_text(wr, &formatted);
+                        wr.close_tag();
+                    }
+                    Cell::Empty => write_cell(wr, "td", ""),
+                }
+            }
+            wr.close_tag();
+        }
+        wr.close_tag();
+        wr.close_tag();
+    }
+}
+
+// Writes a cell with escaped text
+fn write_cell(wr: &mut HTMLWriter, tag: &str, text: &str) {
+    wr.open_tag(tag);
+    write_text(wr, text);
+    wr.close_tag();
+}
+
+fn write_text(wr: &mut HTMLWriter, text: &str) {
+    if !text.is_empty() {
+        wr.write_str(&escape_html_text(text))
+            .expect("text inside of a cell is always allowed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_number_formats() {
+        let mut table = TableBuilder::new(&[]);
+        table.set_column_format(1, CellFormat::Percent { decimals: 1 });
+        table.set_column_format(
+            2,
+            CellFormat::Currency {
+                symbol: "€".to_string(),
+                decimals: 2,
+            },
+        );
+        assert_eq!(table.format_number(0, 1234567.0), "1,234,567");
+        assert_eq!(table.format_number(0, -0.4), "0");
+        assert_eq!(table.format_number(1, 0.125), "12.5%");
+        table.set_separators(".", ",");
+        assert_eq!(table.format_number(2, -1234.5), "-€1.234,50");
+        table.set_right_align_numbers(false);
+        table.add_row(vec![Cell::number(-0.4), Cell::number(-0.5)]);
+        let mut wr = HTMLWriter::new();
+        table.write(&mut wr);
+        assert_eq!(
+            wr.content,
+            "<table><tbody><tr><td>0</td><td class=\"negative\">-50,0%</td></tr></tbody></table>"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Typed names of elements: [`HtmlTag`] for the HTMLWriter and [`XmlName`] for the XMLWriter.
+//!
+//! The known tags of HTML are variants of [`HtmlTag`], so a typo doesn't compile and their names don't have to be
+//! checked. Other tags are an explicit ```HtmlTag::Custom()```, which has to be the name of a custom element with a
+//! '-', e.g. "x-menu". An [`XmlName`] is checked once, when it is created, by the rules of XML-names, so it can
+//! have upper case letters, '-', '.' and a namespace prefix.
+//!
+//! ```
+//! # use mllwriter::{HtmlTag, HTMLWriter, MLLWriter, XmlName, XMLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_element(HtmlTag::Nav);
+//! wr.single_element(HtmlTag::Br);
+//! wr.open_element(HtmlTag::Custom("x-menu".to_string()));
+//! wr.close_tag();
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<nav><br><x-menu></x-menu></nav>");
+//!
+//! let mut wr = XMLWriter::new();
+//! let tile = XmlName::new("TileColor").unwrap();
+//! wr.open_element(&tile);
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<TileColor></TileColor>");
+//! assert!(XmlName::new("1st").is_err());
+//! ```
+
+use crate::{check, HTMLWriter, MLLWriter, WriterError, XMLWriter};
+
+macro_rules! html_tags {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// The known tags of HTML, and custom ones
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum HtmlTag {
+            $(
+                #[doc = concat!("'", $name, "'")]
+                $variant,
+            )*
+            /// A custom element, whose name contains a '-' like "x-menu"
+            Custom(String),
+        }
+
+        // The elements of HTML5, the same table as the variants of HtmlTag
+        pub(crate) const HTML5_ELEMENTS: &[&str] = &[$($name),*];
+
+        impl HtmlTag {
+            /// Returns the name of the tag
+            pub fn name(&self) -> &str {
+                match self {
+                    $(HtmlTag::$variant => $name,)*
+                    HtmlTag::Custom(name) => name,
+                }
+            }
+        }
+    };
+}
+
+html_tags! {
+    A => "a", Abbr => "abbr", Address => "address", Area => "area", Article => "article", Aside => "aside",
+    Audio => "audio", B => "b", Base => "base", Bdi => "bdi", Bdo => "bdo", Blockquote => "blockquote", Body => "body",
+    Br => "br", Button => "button", Canvas => "canvas", Caption => "caption", Cite => "cite", Code => "code",
+    Col => "col", Colgroup => "colgroup", Data => "data", Datalist => "datalist", Dd => "dd", Del => "del",
+    Details => "details", Dfn => "dfn", Dialog => "dialog", Div => "div", Dl => "dl", Dt => "dt", Em => "em",
+    Embed => "embed", Fieldset => "fieldset", Figcaption => "figcaption", Figure => "figure", Footer => "footer",
+    Form => "form", H1 => "h1", H2 => "h2", H3 => "h3", H4 => "h4", H5 => "h5", H6 => "h6", Head => "head",
+    Header => "header", Hgroup => "hgroup", Hr => "hr", Html => "html", I => "i", Iframe => "iframe", Img => "img",
+    Input => "input", Ins => "ins", Kbd => "kbd", Label => "label", Legend => "legend", Li => "li", Link => "link",
+    Main => "main", Map => "map", Mark => "mark", Math => "math", Menu => "menu", Meta => "meta", Meter => "meter",
+    Nav => "nav", Noscript => "noscript", Object => "object", Ol => "ol", Optgroup => "optgroup", Option => "option",
+    Output => "output", P => "p", Picture => "picture", Pre => "pre", Progress => "progress", Q => "q", Rp => "rp",
+    Rt => "rt", Ruby => "ruby", S => "s", Samp => "samp", Script => "script", Search => "search", Section => "section",
+    Select => "select", Slot => "slot", Small => "small", Source => "source", Span => "span", Strong => "strong",
+    Style => "style", Sub => "sub", Summary => "summary", Sup => "sup", Svg => "svg", Table => "table",
+    Tbody => "tbody", Td => "td", Template => "template", Textarea => "textarea", Tfoot => "tfoot", Th => "th",
+    Thead => "thead", Time => "time", Title => "title", Tr => "tr", Track => "track", U => "u", Ul => "ul",
+    Var => "var", Video => "video", Wbr => "wbr",
+}
+
+/// A name of an XML-element, which follows the rules of XML-names
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XmlName(String);
+
+impl XmlName {
+    /// Returns the name, if it starts with a letter, '_' or ':' and continues with letters, digits, '-', '_', '.'
+    /// or ':'
+    pub fn new(name: &str) -> Result<XmlName, WriterError> {
+        if is_xml_name(name) && !name.to_ascii_lowercase().starts_with("xml") {
+            Ok(XmlName(name.to_string()))
+        } else {
+            Err(WriterError::InvalidXmlName(name.to_string()))
+        }
+    }
+
+    /// Returns the name
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl HTMLWriter {
+    /// Opens the element of the typed tag, the name of a custom element has to contain a '-'
+    pub fn open_element(&mut self, tag: HtmlTag) {
+        check(self.try_open_element(tag));
+    }
+
+    /// Like ```open_element()```, but returns the error instead of panicking
+    pub fn try_open_element(&mut self, tag: HtmlTag) -> Result<(), WriterError> {
+        check_custom(&tag)?;
+        self.write_open_tag(tag.name())
+    }
+
+    /// Writes the element of the typed tag without content
+    pub fn single_element(&mut self, tag: HtmlTag) {
+        check(check_custom(&tag));
+        check(self.write_single_tag(tag.name()));
+    }
+}
+
+impl XMLWriter {
+    /// Opens the element with the name
+    pub fn open_element(&mut self, name: &XmlName) {
+        check(self.try_open_element(name));
+    }
+
+    /// Like ```open_element()```, but returns the error instead of panicking
+    pub fn try_open_element(&mut self, name: &XmlName) -> Result<(), WriterError> {
+        self.write_open_tag(name.as_str())
+    }
+
+    /// Writes the element with the name without content
+    pub fn single_element(&mut self, name: &XmlName) {
+        check(self.try_single_element(name));
+    }
+
+    /// Like ```single_element()```, but returns the error instead of panicking
+    pub fn try_single_element(&mut self, name: &XmlName) -> Result<(), WriterError> {
+        self.write_single_tag(name.as_str())
+    }
+}
+
+// Writes a complete element with the attributes, e.g. the SVG-attributes "viewBox" or "stroke-width", and with the
+// text if there is one
+pub(crate) fn xml_element(
+    wr: &mut XMLWriter,
+    tag: &str,
+    attributes: &[(&str, String)],
+    text: Option<&str>,
+) {
+    let name = xml_name(tag);
+    match text {
+        Some(text) => {
+            wr.open_element(&name);
+            add_attributes(wr, attributes);
+            wr.text(text);
+            wr.close_tag();
+        }
+        None => {
+            wr.single_element(&name);
+            add_attributes(wr, attributes);
+        }
+    }
+}
+
+// Adds the attributes to the last start-tag
+pub(crate) fn add_attributes(wr: &mut XMLWriter, attributes: &[(&str, String)]) {
+    for (name, value) in attributes {
+        wr.add_property(name, value);
+    }
+}
+
+// Returns the name of an element, which is known to be valid
+pub(crate) fn xml_name(name: &str) -> XmlName {
+    check(XmlName::new(name))
+}
+
+// Returns true if the name follows the rules of XML-names, names starting with "xml" are reserved for XML itself,
+// e.g. "xmlns" or "xml:lang"
+pub(crate) fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+// Checks the name of a custom element: it starts with a lowercase letter, continues with lowercase letters, digits
+// and '-', and contains at least one '-', which keeps it apart from the tags of HTML
+fn check_custom(tag: &HtmlTag) -> Result<(), WriterError> {
+    match tag {
+        HtmlTag::Custom(name)
+            if !name.starts_with(|c: char| c.is_ascii_lowercase())
+                || !name.contains('-')
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') =>
+        {
+            Err(WriterError::InvalidTagName(name.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_tags() {
+        let mut wr = HTMLWriter::new();
+        assert!(wr
+            .try_open_element(HtmlTag::Custom("My-Tag".to_string()))
+            .is_err());
+        assert!(wr
+            .try_open_element(HtmlTag::Custom("menu".to_string()))
+            .is_err());
+        assert_eq!(HtmlTag::Menu.name(), "menu");
+        assert_eq!(HtmlTag::Kbd.name(), "kbd");
+        assert!(wr.try_open_element(HtmlTag::Img).is_err());
+        assert_eq!(HtmlTag::Figcaption.name(), "figcaption");
+        assert!(XmlName::new("svg:rect").is_ok());
+        assert!(XmlName::new("xmlfoo").is_err());
+        assert!(XmlName::new("a b").is_err());
+    }
+}
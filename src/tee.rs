@@ -0,0 +1,259 @@
+//! Writing two documents in one pass.
+//!
+//! The [`TeeWriter`] forwards every call to two writers, e.g. pretty HTML for the disk and minified HTML for the
+//! network, or the same content as HTML and XML. A rejected call is rejected by the first writer, before the
+//! second one gets it, so both stay at the same point of their documents. Only errors, which the second writer
+//! raises alone, e.g. because of a stricter validation, leave the first one a step ahead.
+//!
+//! ```
+//! # use mllwriter::{FormatMode, HTMLWriter, MLLWriter, TeeWriter};
+//! let mut pretty = HTMLWriter::new();
+//! pretty.set_format_mode(FormatMode::Pretty);
+//! let mut minified = HTMLWriter::new();
+//! minified.set_format_mode(FormatMode::Minified);
+//! let mut wr = TeeWriter::new(pretty, minified);
+//! wr.open_tag("ul");
+//! wr.open_tag("li");
+//! wr.text("tea");
+//! wr.close_tag();
+//! wr.close_tag();
+//! let (pretty, minified) = wr.into_inner();
+//! assert_eq!(pretty.content, "<ul>\n    <li>tea</li>\n</ul>");
+//! assert_eq!(minified.content, "<ul><li>tea</li></ul>");
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::banner::BannerStyle;
+use crate::provenance::TimestampPolicy;
+use crate::{check, MLLWriter, Properties, WriterError, WriterProfile};
+
+/// A writer, which forwards every call to two writers
+#[derive(Debug, Clone, Default)]
+pub struct TeeWriter<A: MLLWriter, B: MLLWriter> {
+    first: A,
+    second: B,
+}
+
+impl<A: MLLWriter, B: MLLWriter> TeeWriter<A, B> {
+    /// Returns a writer, which forwards to both writers
+    pub fn new(first: A, second: B) -> TeeWriter<A, B> {
+        TeeWriter { first, second }
+    }
+
+    /// Gives access to the first writer, e.g. for its own methods
+    pub fn first(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Gives access to the second writer, e.g. for its own methods
+    pub fn second(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Returns both writers
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    // Runs the fallible operation on the first writer, and on the second one if the first accepted it
+    fn both(
+        &mut self,
+        first: impl FnOnce(&mut A) -> Result<(), WriterError>,
+        second: impl FnOnce(&mut B) -> Result<(), WriterError>,
+    ) -> Result<(), WriterError> {
+        first(&mut self.first)?;
+        second(&mut self.second)
+    }
+}
+
+impl<A: MLLWriter, B: MLLWriter> MLLWriter for TeeWriter<A, B> {
+    fn open_tag(&mut self, tag: &str) {
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.both(|wr| wr.try_open_tag(tag), |wr| wr.try_open_tag(tag))
+    }
+
+    fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.open_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn close_tag(&mut self) {
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        self.both(|wr| wr.try_close_tag(), |wr| wr.try_close_tag())
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.both(|wr| wr.try_single_tag(tag), |wr| wr.try_single_tag(tag))
+    }
+
+    fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.single_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn add_property(&mut self, name: &str, value: &str) {
+        check(self.try_add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.both(
+            |wr| wr.try_add_property(name, value),
+            |wr| wr.try_add_property(name, value),
+        )
+    }
+
+    fn add_properties(&mut self, properties: &Properties) {
+        self.first.add_properties(properties);
+        self.second.add_properties(properties);
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.both(
+            |wr| wr.try_add_comment(comment),
+            |wr| wr.try_add_comment(comment),
+        )
+    }
+
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.both(|wr| wr.try_text(text), |wr| wr.try_text(text))
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.both(|wr| wr.try_raw(raw), |wr| wr.try_raw(raw))
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        self.first.file_banner(lines, style);
+        self.second.file_banner(lines, style);
+    }
+
+    /// Both writers get the same timestamp, if it is taken now
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        let timestamp_policy = match timestamp_policy {
+            TimestampPolicy::Now => TimestampPolicy::Fixed(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+            ),
+            policy => policy,
+        };
+        self.first.stamp_provenance(tool, version, timestamp_policy);
+        self.second
+            .stamp_provenance(tool, version, timestamp_policy);
+    }
+
+    fn line_feed(&mut self, n: usize) {
+        self.first.line_feed(n);
+        self.second.line_feed(n);
+    }
+
+    fn blank_line(&mut self) {
+        self.first.blank_line();
+        self.second.blank_line();
+    }
+
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.first.ensure_blank_lines(n);
+        self.second.ensure_blank_lines(n);
+    }
+
+    fn line_feed_inc(&mut self) {
+        self.first.line_feed_inc();
+        self.second.line_feed_inc();
+    }
+
+    fn line_feed_dec(&mut self) {
+        self.first.line_feed_dec();
+        self.second.line_feed_dec();
+    }
+
+    fn inc_indent_step(&mut self) {
+        self.first.inc_indent_step();
+        self.second.inc_indent_step();
+    }
+
+    fn dec_indent_step(&mut self) {
+        self.first.dec_indent_step();
+        self.second.dec_indent_step();
+    }
+
+    fn set_indent_step(&mut self, indent_step: usize) {
+        self.first.set_indent_step(indent_step);
+        self.second.set_indent_step(indent_step);
+    }
+
+    fn set_indent_step_size(&mut self, indent_step_size: usize) {
+        self.first.set_indent_step_size(indent_step_size);
+        self.second.set_indent_step_size(indent_step_size);
+    }
+
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.first.apply_profile(profile);
+        self.second.apply_profile(profile);
+    }
+
+    fn end_document(&mut self) {
+        self.first.end_document();
+        self.second.end_document();
+    }
+
+    fn clear(&mut self) {
+        self.first.clear();
+        self.second.clear();
+    }
+}
+
+impl<A, B> std::fmt::Write for TeeWriter<A, B>
+where
+    A: MLLWriter + std::fmt::Write,
+    B: MLLWriter + std::fmt::Write,
+{
+    /// Writes the text as it is into both writers
+    fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        self.first.write_str(s)?;
+        self.second.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HTMLWriter, XMLWriter};
+
+    #[test]
+    fn tee_rejects_in_both() {
+        let mut wr = TeeWriter::new(HTMLWriter::new(), XMLWriter::new());
+        wr.open_tag("a");
+        wr.add_property("href", "/");
+        assert!(wr.try_open_tag("Bad").is_err());
+        wr.close_tag();
+        // The XMLWriter alone rejects a second root
+        assert!(wr.try_open_tag("b").is_err());
+        let (html, xml) = wr.into_inner();
+        assert_eq!(html.content, "<a href=\"/\"></a><b>");
+        assert_eq!(xml.content, "<a href=\"/\"></a>");
+    }
+}
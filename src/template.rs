@@ -0,0 +1,94 @@
+//! Templates of markup with placeholders, which are written by the calls of a writer.
+//!
+//! ```mll_write!(wr, "...", name = value)``` compiles the markup of the template literal into the calls of
+//! ```open_tag()```, ```add_property()```, ```text()``` and ```close_tag()```, so it is checked and escaped like any
+//! other content: the values of the placeholders ```{name}``` in text and in attribute values are escaped, and so
+//! are the literal parts of the template. Character references like ```&nbsp;``` are kept in text, in attribute
+//! values the references of XML and numbers are written as their characters. A placeholder without a value takes
+//! the variable of its name, like in ```format!()```, but it has to be listed. ```{{``` and ```}}``` write the
+//! braces.
+//!
+//! The line-feeds of the template are written by the writer at its indent: a line indented deeper than the one
+//! before is written by ```line_feed_inc()```, a line indented less by ```line_feed_dec()```. Empty elements are
+//! written as ```<br/>```, comments and declarations aren't supported. The template is parsed when it is compiled,
+//! so a malformed template, a placeholder without a value or an unused value are errors of the compiler. The errors
+//! of the writer, like an invalid attribute name, are returned.
+//!
+//! ```
+//! # use mllwriter::{mll_write, HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("main");
+//! wr.line_feed_inc();
+//! let text = "Tom & Jerry";
+//! mll_write!(
+//!     wr,
+//!     "<div class=\"{cls}\">
+//!         <p>{text}&nbsp;!</p><br/>
+//!     </div>",
+//!     cls = "card",
+//!     text
+//! )
+//! .unwrap();
+//! wr.line_feed_dec();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<main>\n    <div class=\"card\">\n        <p>Tom &amp; Jerry&nbsp;!</p><br>\n    </div>\n</main>"
+//! );
+//! ```
+//!
+//! ```compile_fail
+//! # use mllwriter::{mll_write, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! // the placeholder '{b}' has no value
+//! mll_write!(wr, "<a>{b}</a>");
+//! ```
+//!
+//! ```compile_fail
+//! # use mllwriter::{mll_write, XMLWriter};
+//! let mut wr = XMLWriter::new();
+//! // the end-tag 'a' doesn't match 'b'
+//! mll_write!(wr, "<a><b></a>");
+//! ```
+
+/// Writes the template literal with the values of its placeholders by the writer, see the module template
+#[macro_export]
+macro_rules! mll_write {
+    (@value $name:ident) => {
+        $name
+    };
+    (@value $name:ident $value:expr) => {
+        $value
+    };
+    ($wr:expr, $template:literal $(, $name:ident $(= $value:expr)?)* $(,)?) => {
+        $crate::__mll_write!(
+            $crate,
+            ($wr),
+            $template
+            $(, $name = ($crate::mll_write!(@value $name $($value)?)))*
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HTMLWriter, MLLWriter, WriterError, XMLWriter};
+
+    #[test]
+    fn template_calls() {
+        let mut wr = XMLWriter::new();
+        let id = 7;
+        assert!(mll_write!(wr, "<a id='{id}' t=\"{{x}}&amp;&#x41;\"></a>", id).is_ok());
+        assert_eq!(wr.content, "<a id=\"7\" t=\"{x}&amp;A\"></a>");
+        let mut wr = HTMLWriter::new();
+        let wr = &mut wr;
+        wr.open_tag("p");
+        assert!(mll_write!(wr, r"a &nbsp;& b</p>").is_ok());
+        assert_eq!(wr.content, "<p>a &nbsp;&amp; b</p>");
+        let mut wr = XMLWriter::new();
+        assert!(matches!(
+            mll_write!(wr, "text in the prolog"),
+            Err(WriterError::IllegalTransition { .. })
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Escaped and raw content of HTML- and XML-documents.
+//!
+//! The values of ```add_property()``` and ```add_properties()``` are escaped, so quotes and ampersands can't break
+//! the markup. ```text()``` writes escaped text content. Values, which are escaped already, are written as they
+//! are by the ```*_raw```-variants, texts and markup by ```raw()```.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter};
+//! let mut wr = HTMLWriter::new();
+//! wr.open_tag("abbr");
+//! wr.add_property("title", "he said \"hi\" & left");
+//! wr.close_tag();
+//! wr.open_tag("p");
+//! wr.text("1 < 2");
+//! wr.raw(" &amp; more");
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "<abbr title=\"he said &quot;hi&quot; &amp; left\"></abbr><p>1 &lt; 2 &amp; more</p>"
+//! );
+//! ```
+
+use crate::{check, HTMLWriter, Properties, XMLWriter};
+
+impl HTMLWriter {
+    /// Adds the property with the value as it is, e.g. a value which is escaped already
+    pub fn add_property_raw(&mut self, name: &str, value: &str) {
+        check(self.write_property(name, value, "add_property_raw"));
+    }
+
+    /// Adds the properties with the values as they are
+    pub fn add_properties_raw(&mut self, properties: &Properties) {
+        check(self.write_properties(properties, false, "add_properties_raw"));
+    }
+}
+
+impl XMLWriter {
+    /// Adds the property with the value as it is, e.g. a value which is escaped already
+    pub fn add_property_raw(&mut self, name: &str, value: &str) {
+        check(self.write_property(name, value, "add_property_raw"));
+    }
+
+    /// Adds the properties with the values as they are
+    pub fn add_properties_raw(&mut self, properties: &Properties) {
+        check(self.write_properties(properties, false, "add_properties_raw"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JSONWriter, MLLWriter, TOMLWriter};
+
+    #[test]
+    fn text_escaped_properties() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        let mut properties = Properties::new("x", "it's");
+        properties.add("y", "<&>");
+        wr.add_properties(&properties);
+        wr.open_tag("b");
+        wr.add_property_raw("z", "&#x41;");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "<a x=\"it&#39;s\" y=\"&lt;&amp;&gt;\"><b z=\"&#x41;\"></b></a>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid attribute name 'a b\"='")]
+    fn text_invalid_property_names() {
+        let mut wr = XMLWriter::new();
+        wr.open_tag("a");
+        wr.add_properties_raw(&Properties::new("a b\"=", "1"));
+    }
+
+    #[test]
+    fn text_of_data_writers() {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        assert!(wr.try_text("a").is_err());
+        wr.open_array("tags");
+        wr.text("say \"hi\"");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "{\n  \"tags\":\n  [\n    \"say \\\"hi\\\"\"\n  ]\n}"
+        );
+        let mut wr = TOMLWriter::new();
+        assert!(wr.try_text("a").is_err());
+        wr.raw("# raw");
+        assert_eq!(wr.content, "# raw");
+    }
+}
@@ -0,0 +1,420 @@
+//! Implementation of the TOMLWriter for writing TOML-files, e.g. Cargo-style configs.
+//!
+//! Keys in front of the first table belong to the root table. ```open_tag()``` starts a table with its header,
+//! nested tables get the dotted path of their parents, e.g. ```[a.b]```, and ```open_array_table()``` starts an
+//! item of an array of tables, e.g. ```[[bin]]```. As TOML has no end of a table, the keys of a table have to be
+//! written before its nested tables. A table can only be defined once, only the items of an array of tables repeat
+//! their header.
+//!
+//! Values are written as they are, like in the JSONWriter: numbers and booleans by ```to_string()```, strings by
+//! ```toml_string()``` and arrays by ```toml_array()```. Keys are quoted automatically, if they aren't bare keys.
+//!
+//! ```
+//! # use mllwriter::{toml_array, toml_string, MLLWriter, TOMLWriter};
+//! let mut wr = TOMLWriter::new();
+//! wr.add_comment("generated");
+//! wr.open_tag("package");
+//! wr.add_property("name", &toml_string("shop"));
+//! wr.add_property("edition", &toml_string("2021"));
+//! wr.add_property("publish", &false.to_string());
+//! wr.close_tag();
+//! wr.open_tag("profile");
+//! wr.open_tag("release");
+//! wr.add_property("opt-level", &3.to_string());
+//! wr.add_property("features", &toml_array(&[toml_string("a"), toml_string("b")]));
+//! wr.close_tag();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "# generated\n\n[package]\nname = \"shop\"\nedition = \"2021\"\npublish = false\n\n[profile]\n\n\
+//!      [profile.release]\nopt-level = 3\nfeatures = [\"a\", \"b\"]"
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use crate::banner::{self, BannerStyle};
+use crate::provenance::{self, TimestampPolicy};
+use crate::{check, DocumentState, MLLWriter, Properties, WriterCore, WriterError, WriterProfile};
+
+/// Implementation of the TOMLWriter for writing TOML-files. There is no indent of nested tables.
+#[derive(Debug, Clone)]
+pub struct TOMLWriter {
+    /// Content held by the writer
+    pub content: String,
+    /// WriterCore in a composition
+    pub core: WriterCore,
+    /// The path of the table of the last written header, internal
+    header: Vec<String>,
+    /// Blank lines in front of the next entry, internal
+    blank_lines: usize,
+    /// The paths of the defined tables and whether they are arrays of tables, internal
+    tables: HashMap<Vec<String>, bool>,
+}
+
+impl TOMLWriter {
+    pub fn new() -> TOMLWriter {
+        let mut core = WriterCore::new(0);
+        // Tables follow each other, the root table has no header
+        core.single_root = false;
+        TOMLWriter {
+            content: String::new(),
+            core,
+            header: Vec::new(),
+            blank_lines: 0,
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Opens an item of the array of tables, which is closed by ```close_tag()```, e.g. ```[[bin]]```
+    pub fn open_array_table(&mut self, name: &str) {
+        check(self.try_open_array_table(name));
+    }
+
+    /// Like ```open_array_table()```, but returns an error instead of panicking, e.g. if the name is defined as
+    /// table already
+    pub fn try_open_array_table(&mut self, name: &str) -> Result<(), WriterError> {
+        self.open_table(name, "open_array_table", true)
+    }
+
+    // Writes the header of a table, separated by a blank line from the entries before
+    fn open_table(
+        &mut self,
+        name: &str,
+        operation: &'static str,
+        array: bool,
+    ) -> Result<(), WriterError> {
+        if name.is_empty() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation,
+                reason: "a table needs a name",
+            });
+        }
+        self.core.check_begin_element(operation)?;
+        let mut path = self.core.block_stack.clone();
+        path.push(name.to_string());
+        match self.tables.get(&path) {
+            Some(defined) if !array || !defined => {
+                return Err(WriterError::IllegalTransition {
+                    state: self.core.state,
+                    operation,
+                    reason: "the table is defined already, only an array of tables can repeat it",
+                })
+            }
+            // Each item of an array of tables starts with new nested tables
+            Some(_) => self
+                .tables
+                .retain(|table, _| !table.starts_with(&path) || table.len() == path.len()),
+            None => (),
+        }
+        self.tables.insert(path, array);
+        self.core.begin_element(operation)?;
+        self.core.block_stack.push(name.to_string());
+        self.core.state = DocumentState::InElement;
+        self.header = self.core.block_stack.clone();
+        let path = self
+            .header
+            .iter()
+            .map(|key| toml_key(key))
+            .collect::<Vec<_>>()
+            .join(".");
+        self.ensure_blank_lines(1);
+        self.begin_entry();
+        if array {
+            self.content.push_str(&format!("[[{}]]", path));
+        } else {
+            self.content.push_str(&format!("[{}]", path));
+        }
+        Ok(())
+    }
+
+    // Returns an empty writer with the options of this one, for take_content()
+    pub(crate) fn next_document(&self) -> TOMLWriter {
+        TOMLWriter {
+            core: self.core.sub_core(),
+            ..TOMLWriter::new()
+        }
+    }
+
+    // Starts a new line for the next entry
+    fn begin_entry(&mut self) {
+        let blank_lines = std::mem::take(&mut self.blank_lines);
+        if !self.content.is_empty() {
+            self.content
+                .push_str(&self.core.newline().repeat(blank_lines + 1));
+        }
+    }
+}
+
+impl Default for TOMLWriter {
+    fn default() -> Self {
+        TOMLWriter::new()
+    }
+}
+
+impl MLLWriter for TOMLWriter {
+    /// Writes the header of the table with the path of its parents
+    fn open_tag(&mut self, tag: &str) {
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.open_table(tag, "open_tag", false)
+    }
+
+    fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.open_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    /// Writes nothing, the next keys belong to the next table
+    fn close_tag(&mut self) {
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        self.core.end_element("close_tag")?;
+        Ok(())
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, _tag: &str) -> Result<(), WriterError> {
+        Err(WriterError::UnsupportedOperation {
+            operation: "single_tag",
+            reason: "there is no single_element in the TOMLWriter",
+        })
+    }
+
+    fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.single_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn add_property(&mut self, name: &str, value: &str) {
+        check(self.try_add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("add_property")?;
+        if self.header != self.core.block_stack {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "add_property",
+                reason: "the keys of a table have to be written before its nested tables",
+            });
+        }
+        self.begin_entry();
+        self.content.push_str(&toml_key(name));
+        self.content.push_str(" = ");
+        self.content.push_str(value);
+        Ok(())
+    }
+
+    fn add_properties(&mut self, properties: &Properties) {
+        properties
+            .p
+            .iter()
+            .for_each(|x| self.add_property(&x.0, &x.1));
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("add_comment")?;
+        self.begin_entry();
+        // Each line of the comment is a comment line of its own, the content would continue otherwise
+        let separator = format!("{}# ", self.core.newline());
+        self.content.push_str("# ");
+        self.content
+            .push_str(&comment.replace("\r\n", "\n").replace('\n', &separator));
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, _text: &str) -> Result<(), WriterError> {
+        Err(WriterError::UnsupportedOperation {
+            operation: "text",
+            reason: "there is no text content in the TOMLWriter, see add_property()",
+        })
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("raw")?;
+        self.content.push_str(raw);
+        Ok(())
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        check(self.core.begin_markup("file_banner"));
+        self.begin_entry();
+        let newline = self.core.newline();
+        banner::write_banner(&mut self.content, newline, "", lines, style, "# ", "");
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.add_comment(&provenance::provenance_comment(
+            tool,
+            version,
+            timestamp_policy,
+        ));
+    }
+
+    fn line_feed(&mut self, n: usize) {
+        self.core.line_feed(&mut self.content, n);
+    }
+
+    fn blank_line(&mut self) {
+        self.ensure_blank_lines(1);
+    }
+
+    /// The blank lines are written in front of the next entry
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.blank_lines = self.blank_lines.max(n);
+    }
+
+    fn line_feed_inc(&mut self) {
+        self.core.line_feed_inc(&mut self.content);
+    }
+
+    fn line_feed_dec(&mut self) {
+        self.core.line_feed_dec(&mut self.content);
+    }
+
+    fn inc_indent_step(&mut self) {
+        self.core.inc_indent_step();
+    }
+
+    fn dec_indent_step(&mut self) {
+        self.core.dec_indent_step();
+    }
+
+    fn set_indent_step(&mut self, indent_step: usize) {
+        self.core.set_indent_step(indent_step);
+    }
+
+    fn set_indent_step_size(&mut self, indent_step_size: usize) {
+        self.core.set_indent_step_size(indent_step_size);
+    }
+
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.core.apply_profile(profile);
+    }
+
+    fn end_document(&mut self) {
+        check(self.core.end_document("end_document"));
+    }
+
+    fn clear(&mut self) {
+        self.core.clear(0);
+        self.content.clear();
+        self.header.clear();
+        self.blank_lines = 0;
+        self.tables.clear();
+    }
+}
+
+impl std::fmt::Display for TOMLWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "indent_step_size: {}\nindent: {}\nblock_stack: {:?}\n{}\n",
+            self.core.indent_step_size,
+            self.core.indent.len(),
+            self.core.block_stack,
+            self.content
+        )
+    }
+}
+
+impl std::fmt::Write for TOMLWriter {
+    fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        self.content.write_str(s)
+    }
+}
+
+/// Returns the text as basic TOML-string in double quotes
+pub fn toml_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Returns the values, which are written as they are, as inline TOML-array
+pub fn toml_array<S: AsRef<str>>(values: &[S]) -> String {
+    let values: Vec<&str> = values.iter().map(AsRef::as_ref).collect();
+    format!("[{}]", values.join(", "))
+}
+
+// Returns the key bare, if possible, otherwise quoted
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_array_tables() {
+        let mut wr = TOMLWriter::new();
+        wr.add_property("version", &1.to_string());
+        wr.add_comment("a\r\n[b]");
+        wr.open_array_table("bin");
+        wr.add_property("name", &toml_string("a\"b"));
+        wr.close_tag();
+        wr.open_array_table("bin");
+        wr.add_property("my key", "[]");
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "version = 1\n# a\n# [b]\n\n[[bin]]\nname = \"a\\\"b\"\n\n[[bin]]\n\"my key\" = []"
+        );
+        assert!(wr.try_add_property("late", "1").is_err());
+        wr.open_array_table("bin");
+        wr.open_tag("dep");
+        wr.close_tag();
+        wr.close_tag();
+        wr.open_array_table("bin");
+        wr.open_tag("dep");
+        wr.close_tag();
+        assert!(wr.try_open_tag("dep").is_err());
+        wr.close_tag();
+        assert!(wr.try_open_tag("bin").is_err());
+        wr.open_tag("package");
+        wr.close_tag();
+        assert!(wr.try_open_tag("package").is_err());
+        assert!(wr.try_open_array_table("package").is_err());
+    }
+}
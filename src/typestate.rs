@@ -0,0 +1,184 @@
+//! Typestate layer on top of the markup-writers (HTMLWriter and XMLWriter).
+//!
+//! The MLLWriter-trait is flexible, but illegal call sequences (e.g. a property after some text) are
+//! only rejected at runtime. The [`ElementBuilder`] encodes the state of an element in its type, so
+//! those sequences don't compile at all. Properties can only be added as long as the builder is in
+//! [`InStartTag`]. Writing content (text, children or line-feeds) consumes the builder and returns an
+//! [`ElementBuilder`] in [`HasChildren`]. Children are built in closures, which have to return the
+//! [`Closed`]-token of their builder, so no element can be left open.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, ElementBuilder};
+//! let mut wr = HTMLWriter::new();
+//!
+//! ElementBuilder::new(&mut wr, "div")
+//!     .property("class", "container")
+//!     .child("p", |p| p.text("Hello").close())
+//!     .single("br")
+//!     .close();
+//! assert_eq!(wr.content, "<div class=\"container\"><p>Hello</p><br></div>");
+//! ```
+//!
+//! Adding a property after the element got content does not compile:
+//! ```compile_fail
+//! # use mllwriter::{HTMLWriter, ElementBuilder};
+//! let mut wr = HTMLWriter::new();
+//! ElementBuilder::new(&mut wr, "p").text("Hello").property("class", "intro").close();
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{check, MLLWriter, Properties};
+
+/// State of an [`ElementBuilder`] directly after its start-tag, properties can be added
+pub struct InStartTag;
+
+/// State of an [`ElementBuilder`] after content has been written, no properties anymore
+pub struct HasChildren;
+
+/// Token which proves that an element has been closed. It can only be created by ```close()```.
+#[must_use = "the Closed-token has to be returned from the child-closure"]
+pub struct Closed {
+    _private: (),
+}
+
+/// Builds one element on a markup-writer, the state of the element is given by the type-parameter S.
+#[must_use = "an element has to be closed with close()"]
+pub struct ElementBuilder<'w, W, S>
+where
+    W: MLLWriter + std::fmt::Write,
+{
+    writer: &'w mut W,
+    _state: PhantomData<S>,
+}
+
+impl<'w, W> ElementBuilder<'w, W, InStartTag>
+where
+    W: MLLWriter + std::fmt::Write,
+{
+    /// Opens a new element with the given tag on the writer
+    pub fn new(writer: &'w mut W, tag: &str) -> ElementBuilder<'w, W, InStartTag> {
+        writer.open_tag(tag);
+        ElementBuilder {
+            writer,
+            _state: PhantomData,
+        }
+    }
+
+    /// Adds a property to the start-tag of the element
+    pub fn property(self, name: &str, value: &str) -> ElementBuilder<'w, W, InStartTag> {
+        self.writer.add_property(name, value);
+        self
+    }
+
+    /// Adds several properties at once to the start-tag of the element
+    pub fn properties(self, properties: &Properties) -> ElementBuilder<'w, W, InStartTag> {
+        self.writer.add_properties(properties);
+        self
+    }
+}
+
+impl<'w, W, S> ElementBuilder<'w, W, S>
+where
+    W: MLLWriter + std::fmt::Write,
+{
+    // Every content-method ends the start-tag, that's why it moves the builder into HasChildren
+    fn into_children(self) -> ElementBuilder<'w, W, HasChildren> {
+        ElementBuilder {
+            writer: self.writer,
+            _state: PhantomData,
+        }
+    }
+
+    /// Writes text content into the element, escaped like ```MLLWriter::text()```
+    pub fn text(self, text: &str) -> ElementBuilder<'w, W, HasChildren> {
+        check(self.writer.try_text(text));
+        self.into_children()
+    }
+
+    /// Builds a child element in the given closure, which has to close the child
+    pub fn child<F>(self, tag: &str, f: F) -> ElementBuilder<'w, W, HasChildren>
+    where
+        F: FnOnce(ElementBuilder<'_, W, InStartTag>) -> Closed,
+    {
+        let _closed = f(ElementBuilder::new(&mut *self.writer, tag));
+        self.into_children()
+    }
+
+    /// Writes a single-tag child element, e.g. 'br'
+    pub fn single(self, tag: &str) -> ElementBuilder<'w, W, HasChildren> {
+        self.writer.single_tag(tag);
+        self.into_children()
+    }
+
+    /// Writes a single-tag child element with the given properties, e.g. 'img'
+    pub fn single_w_properties(
+        self,
+        tag: &str,
+        properties: &Properties,
+    ) -> ElementBuilder<'w, W, HasChildren> {
+        self.writer.single_tag(tag);
+        self.writer.add_properties(properties);
+        self.into_children()
+    }
+
+    /// Adds n line feed(s) and the current indent
+    pub fn line_feed(self, n: usize) -> ElementBuilder<'w, W, HasChildren> {
+        self.writer.line_feed(n);
+        self.into_children()
+    }
+
+    /// Increases the indent and adds a line feed
+    pub fn line_feed_inc(self) -> ElementBuilder<'w, W, HasChildren> {
+        self.writer.line_feed_inc();
+        self.into_children()
+    }
+
+    /// Decreases the indent and adds a line feed
+    pub fn line_feed_dec(self) -> ElementBuilder<'w, W, HasChildren> {
+        self.writer.line_feed_dec();
+        self.into_children()
+    }
+
+    /// Closes the element and returns the token proving it
+    pub fn close(self) -> Closed {
+        self.writer.close_tag();
+        Closed { _private: () }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HTMLWriter, XMLWriter};
+
+    #[test]
+    fn typestate_nested_elements() {
+        let mut wr = XMLWriter::new();
+        let mut props = Properties::new("id", "1");
+        props.add("lang", "de");
+
+        let _ = ElementBuilder::new(&mut wr, "root")
+            .properties(&props)
+            .line_feed_inc()
+            .child("item", |item| {
+                item.property("key", "a").text("value").close()
+            })
+            .line_feed_dec()
+            .close();
+        assert_eq!(
+            wr.content,
+            "<root id=\"1\" lang=\"de\">\n  <item key=\"a\">value</item>\n</root>"
+        );
+        assert!(wr.core.block_stack.is_empty());
+    }
+
+    #[test]
+    fn typestate_text_is_escaped() {
+        let mut wr = HTMLWriter::new();
+        let _ = ElementBuilder::new(&mut wr, "p")
+            .text("a < b & <script>")
+            .close();
+        assert_eq!(wr.content, "<p>a &lt; b &amp; &lt;script&gt;</p>");
+    }
+}
@@ -0,0 +1,92 @@
+//! Void and self-closing elements of HTML, XHTML and XML.
+//!
+//! ```single_tag()``` writes an element without content. The [`VoidStyle`] of the writer decides about its
+//! notation: ```<br>``` in HTML5, ```<br />``` in XHTML or ```<br/>``` in XML. The notation is kept, when
+//! properties are added. The HTMLWriter knows the void elements of HTML and rejects them in ```open_tag()```,
+//! because they can't have content or an end-tag.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, VoidStyle};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_void_style(VoidStyle::Xhtml);
+//! wr.single_tag_w_property("img", "src", "a.png");
+//! assert_eq!(wr.content, "<img src=\"a.png\" />");
+//! assert!(wr.try_open_tag("img").is_err());
+//! ```
+
+use crate::{HTMLWriter, XMLWriter};
+
+/// The notations of elements without content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum VoidStyle {
+    /// HTML5 void elements, e.g. ```<br>```
+    #[default]
+    Html,
+    /// XHTML-compatible, e.g. ```<br />```
+    Xhtml,
+    /// Self-closing XML, e.g. ```<br/>```
+    Xml,
+}
+
+impl VoidStyle {
+    /// Returns the end of the tag, e.g. " />"
+    pub fn tag_end(self) -> &'static str {
+        match self {
+            VoidStyle::Html => ">",
+            VoidStyle::Xhtml => " />",
+            VoidStyle::Xml => "/>",
+        }
+    }
+}
+
+impl HTMLWriter {
+    /// Sets the notation of the elements written by ```single_tag()```, by default HTML5
+    pub fn set_void_style(&mut self, void_style: VoidStyle) {
+        self.void_style = void_style;
+    }
+}
+
+impl XMLWriter {
+    /// Sets the notation of the elements written by ```single_tag()```, by default without a '/' like HTML5
+    pub fn set_void_style(&mut self, void_style: VoidStyle) {
+        self.void_style = void_style;
+    }
+}
+
+// Returns the end of the last start-tag of the content, which is kept when properties are inserted
+pub(crate) fn last_tag_end(content: &str) -> &'static str {
+    if content.ends_with(" />") {
+        " />"
+    } else if content.ends_with("/>") {
+        "/>"
+    } else {
+        ">"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MLLWriter, Properties};
+
+    #[test]
+    fn void_styles() {
+        let mut wr = XMLWriter::new();
+        wr.set_void_style(VoidStyle::Xml);
+        wr.open_tag("a");
+        wr.single_tag("b");
+        let mut properties = Properties::new("x", "1");
+        properties.add("y", "2");
+        wr.add_properties(&properties);
+        wr.single_tag("c");
+        wr.close_tag();
+        assert_eq!(wr.content, "<a><b x=\"1\" y=\"2\"/><c/></a>");
+        let mut wr = HTMLWriter::new();
+        assert_eq!(
+            wr.try_open_tag("br").unwrap_err().to_string(),
+            "'br' is a void element without content or end-tag, use single_tag()"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Warnings about the corrections of the HTMLWriter.
+//!
+//! The writer corrects some input instead of rejecting it: with implicit closing elements are closed implicitly,
+//! with lenient attributes those with invalid names are dropped; the profile "html5-lenient" enables both.
+//! Sequences, which would end a 'script'- or 'style'-element early, are always escaped. Each correction is
+//! recorded as [`Warning`], so callers can log what was fixed instead of shipping altered output silently.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, MLLWriter, WarningKind};
+//! let mut wr = HTMLWriter::new();
+//! wr.set_implicit_close(true);
+//! wr.set_lenient_attributes(true);
+//! wr.open_tag("p");
+//! wr.add_property("data-id", "1");
+//! wr.add_property("Data-Id", "2");
+//! wr.open_tag("p");
+//! wr.close_tag();
+//! assert_eq!(wr.content, "<p data-id=\"1\"></p><p></p>");
+//! let warnings = wr.take_warnings();
+//! assert_eq!(warnings[0].kind, WarningKind::DroppedAttribute);
+//! assert_eq!(warnings[1].to_string(), "implicit close of 'p' at byte 15");
+//! ```
+
+use crate::HTMLWriter;
+
+/// The kinds of corrections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// An element was closed implicitly by the next one
+    ImplicitClose,
+    /// A sequence, which would end the element early, was escaped
+    EscapedSequence,
+    /// An attribute with an invalid name was dropped
+    DroppedAttribute,
+}
+
+/// A correction of the writer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// The kind of the correction
+    pub kind: WarningKind,
+    /// The corrected part, e.g. the name of the element or of the attribute
+    pub subject: String,
+    /// The position in the content in bytes
+    pub offset: usize,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let kind = match self.kind {
+            WarningKind::ImplicitClose => "implicit close of",
+            WarningKind::EscapedSequence => "escaped sequence",
+            WarningKind::DroppedAttribute => "dropped attribute",
+        };
+        write!(f, "{} '{}' at byte {}", kind, self.subject, self.offset)
+    }
+}
+
+impl HTMLWriter {
+    /// Returns the warnings about the corrections so far
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the warnings about the corrections so far and removes them from the writer
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    // Records the correction at the current position
+    pub(crate) fn warn(&mut self, kind: WarningKind, subject: &str) {
+        self.warnings.push(Warning {
+            kind,
+            subject: subject.to_string(),
+            offset: self.content.len(),
+        });
+    }
+
+    // Escapes the end-tag sequence in the text of a raw text element, e.g. "</script", so the text can't end the
+    // element early. Browsers match end-tags regardless of case, so "</SCRIPT" is escaped as well.
+    pub(crate) fn escape_end_tag(&mut self, text: &str, sequence: &str) -> String {
+        let lower = text.to_ascii_lowercase();
+        if !lower.contains(sequence) {
+            return text.to_string();
+        }
+        self.warn(WarningKind::EscapedSequence, sequence);
+        let mut escaped = String::with_capacity(text.len() + 1);
+        let mut last = 0;
+        for (i, _) in lower.match_indices(sequence) {
+            escaped.push_str(&text[last..i]);
+            escaped.push_str("<\\/");
+            last = i + 2;
+        }
+        escaped.push_str(&text[last..]);
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_of_escapes() {
+        let mut wr = HTMLWriter::new();
+        wr.consent_inline_script("stats", "a(\"</script>\")");
+        wr.critical_css(
+            "p { color: red }",
+            "a.css",
+            crate::StylesheetLoading::Preload,
+        );
+        assert_eq!(
+            wr.warnings(),
+            [Warning {
+                kind: WarningKind::EscapedSequence,
+                subject: "</script".to_string(),
+                offset: wr.content.find("a(").unwrap(),
+            }]
+        );
+        let mut wr = HTMLWriter::new();
+        wr.consent_inline_script("a", "x</SCRIPT><script>alert(1)</ScRiPt>");
+        assert!(wr
+            .content
+            .contains("x<\\/SCRIPT><script>alert(1)<\\/ScRiPt>"));
+        assert_eq!(wr.content.matches("</script>").count(), 1);
+    }
+
+    #[test]
+    fn warnings_of_lenient_attributes() {
+        use crate::{MLLWriter, WriterError};
+        // Implicit closing alone doesn't drop invalid attributes
+        let mut wr = HTMLWriter::new();
+        wr.set_implicit_close(true);
+        wr.open_tag("p");
+        assert_eq!(
+            wr.try_add_property("Data-Id", "2"),
+            Err(WriterError::InvalidAttributeName("Data-Id".to_string()))
+        );
+        wr.set_implicit_close(false);
+        wr.set_lenient_attributes(true);
+        wr.add_property("Data-Id", "2");
+        assert_eq!(wr.content, "<p>");
+        assert_eq!(wr.warnings()[0].kind, WarningKind::DroppedAttribute);
+    }
+}
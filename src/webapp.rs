@@ -0,0 +1,199 @@
+//! The metadata of installable web apps.
+//!
+//! The [`WebManifest`] is written as ```manifest.webmanifest``` with the JSONWriter, ```browserconfig_xml()```
+//! writes the tile of Windows with the XMLWriter. ```HTMLWriter::add_web_app_links()``` links both from the head,
+//! with the theme color of the manifest, so the set stays consistent.
+//!
+//! ```
+//! # use mllwriter::{HTMLWriter, ManifestDisplay, WebManifest};
+//! let mut manifest = WebManifest::new("Weather Station");
+//! manifest.set_short_name("Weather");
+//! manifest.set_display(ManifestDisplay::Standalone);
+//! manifest.set_colors("#0a84ff", "#ffffff");
+//! manifest.add_icon("/icon-192.png", "192x192", "image/png");
+//! assert!(manifest.to_json().contains("\"display\": \"standalone\""));
+//!
+//! let mut wr = HTMLWriter::new();
+//! wr.add_web_app_links("/manifest.webmanifest", &manifest, Some("/browserconfig.xml"));
+//! assert_eq!(
+//!     wr.content,
+//!     "<link rel=\"manifest\" href=\"/manifest.webmanifest\"><meta name=\"theme-color\" content=\"#0a84ff\">\
+//!      <meta name=\"msapplication-config\" content=\"/browserconfig.xml\">"
+//! );
+//! ```
+
+use crate::format::json_string;
+use crate::tag::xml_element;
+use crate::{HTMLWriter, JSONWriter, MLLWriter, VoidStyle, XMLWriter};
+
+/// The display modes of web apps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestDisplay {
+    /// The whole screen without any browser-UI
+    Fullscreen,
+    /// An own window like a native app
+    Standalone,
+    /// An own window with minimal navigation
+    MinimalUi,
+    /// A regular tab of the browser
+    Browser,
+}
+
+impl ManifestDisplay {
+    /// Returns the name of the mode, e.g. "minimal-ui"
+    pub fn name(self) -> &'static str {
+        match self {
+            ManifestDisplay::Fullscreen => "fullscreen",
+            ManifestDisplay::Standalone => "standalone",
+            ManifestDisplay::MinimalUi => "minimal-ui",
+            ManifestDisplay::Browser => "browser",
+        }
+    }
+}
+
+/// The web app manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebManifest {
+    name: String,
+    short_name: Option<String>,
+    start_url: String,
+    display: ManifestDisplay,
+    theme_color: Option<String>,
+    background_color: Option<String>,
+    // source, sizes and mime-type
+    icons: Vec<(String, String, String)>,
+}
+
+impl WebManifest {
+    /// Returns a manifest of the app, which starts at "/" in a browser tab
+    pub fn new(name: &str) -> WebManifest {
+        WebManifest {
+            name: name.to_string(),
+            short_name: None,
+            start_url: "/".to_string(),
+            display: ManifestDisplay::Browser,
+            theme_color: None,
+            background_color: None,
+            icons: Vec::new(),
+        }
+    }
+
+    /// Sets the name for places with little space, e.g. below an icon
+    pub fn set_short_name(&mut self, short_name: &str) {
+        self.short_name = Some(short_name.to_string());
+    }
+
+    /// Sets the URL, which is loaded when the app is started
+    pub fn set_start_url(&mut self, start_url: &str) {
+        self.start_url = start_url.to_string();
+    }
+
+    /// Sets the display mode
+    pub fn set_display(&mut self, display: ManifestDisplay) {
+        self.display = display;
+    }
+
+    /// Sets the theme color of the UI and the background color of the splash screen
+    pub fn set_colors(&mut self, theme_color: &str, background_color: &str) {
+        self.theme_color = Some(theme_color.to_string());
+        self.background_color = Some(background_color.to_string());
+    }
+
+    /// Adds an icon, the sizes are e.g. "192x192" or "any"
+    pub fn add_icon(&mut self, src: &str, sizes: &str, mime_type: &str) {
+        self.icons
+            .push((src.to_string(), sizes.to_string(), mime_type.to_string()));
+    }
+
+    /// Returns the theme color, if set
+    pub fn theme_color(&self) -> Option<&str> {
+        self.theme_color.as_deref()
+    }
+
+    /// Returns the manifest as JSON
+    pub fn to_json(&self) -> String {
+        let mut wr = JSONWriter::new();
+        wr.open_tag("");
+        wr.add_property("name", &json_string(&self.name));
+        if let Some(short_name) = &self.short_name {
+            wr.add_property("short_name", &json_string(short_name));
+        }
+        wr.add_property("start_url", &json_string(&self.start_url));
+        wr.add_property("display", &json_string(self.display.name()));
+        if let Some(theme_color) = &self.theme_color {
+            wr.add_property("theme_color", &json_string(theme_color));
+        }
+        if let Some(background_color) = &self.background_color {
+            wr.add_property("background_color", &json_string(background_color));
+        }
+        if !self.icons.is_empty() {
+            wr.open_array("icons");
+            for (src, sizes, mime_type) in &self.icons {
+                wr.open_tag("");
+                wr.add_property("src", &json_string(src));
+                wr.add_property("sizes", &json_string(sizes));
+                wr.add_property("type", &json_string(mime_type));
+                wr.close_tag();
+            }
+            wr.close_tag();
+        }
+        wr.close_tag();
+        wr.content
+    }
+}
+
+/// Returns the ```browserconfig.xml``` with the 150x150 tile image and the tile color
+pub fn browserconfig_xml(tile_image: &str, tile_color: &str) -> String {
+    let mut wr = XMLWriter::new();
+    wr.set_void_style(VoidStyle::Xml);
+    wr.core.set_indent_step_size(2);
+    wr.core.set_auto_layout(true);
+    wr.declaration("1.0", Some("utf-8"), None);
+    wr.open_tag("browserconfig");
+    wr.open_tag("msapplication");
+    wr.open_tag("tile");
+    xml_element(
+        &mut wr,
+        "square150x150logo",
+        &[("src", tile_image.to_string())],
+        None,
+    );
+    xml_element(&mut wr, "TileColor", &[], Some(tile_color));
+    wr.close_tag();
+    wr.close_tag();
+    wr.close_tag();
+    wr.content
+}
+
+impl HTMLWriter {
+    /// Links the manifest and, if given, the browserconfig from the head, with the theme color of the manifest
+    pub fn add_web_app_links(
+        &mut self,
+        manifest_href: &str,
+        manifest: &WebManifest,
+        browserconfig_href: Option<&str>,
+    ) {
+        self.add_link("manifest", manifest_href);
+        if let Some(theme_color) = manifest.theme_color() {
+            self.add_meta("theme-color", theme_color);
+        }
+        if let Some(href) = browserconfig_href {
+            self.add_meta("msapplication-config", href);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browserconfig_tile() {
+        assert_eq!(
+            browserconfig_xml("/mstile-150x150.png", "#da532c"),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<browserconfig>\n  <msapplication>\n    <tile>\n      \
+             <square150x150logo src=\"/mstile-150x150.png\"/>\n      <TileColor>#da532c</TileColor>\n    \
+             </tile>\n  </msapplication>\n</browserconfig>"
+        );
+    }
+}
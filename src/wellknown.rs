@@ -0,0 +1,197 @@
+//! Structured writers of well-known text files: ```security.txt``` (RFC 9116) and ```humans.txt```.
+//!
+//! Both are lists of "Key: value"-lines, written by the same key-value engine. The [`SecurityTxt`] is validated
+//! when it is written: it needs at least one contact and an expiry, which is in the future. The [`HumansTxt`]
+//! groups its fields into sections, e.g. "TEAM".
+//!
+//! ```
+//! # use mllwriter::SecurityTxt;
+//! let mut security = SecurityTxt::new(1_767_225_600);
+//! security.add_contact("mailto:security@example.com");
+//! security.set_preferred_languages(&["en", "de"]);
+//! assert_eq!(
+//!     security.to_text(1_735_689_600).unwrap(),
+//!     "Contact: mailto:security@example.com\nExpires: 2026-01-01T00:00:00Z\nPreferred-Languages: en, de\n"
+//! );
+//! assert!(security.to_text(1_767_225_600).is_err());
+//! ```
+
+use crate::provenance::format_rfc3339;
+use crate::WriterError;
+
+// Writes the fields as "Key: value"-lines
+fn key_value_lines(out: &mut String, fields: &[(&str, String)]) {
+    for (key, value) in fields {
+        out.push_str(key);
+        out.push_str(": ");
+        // A line-feed would start a new field
+        out.push_str(&value.replace(['\r', '\n'], " "));
+        out.push('\n');
+    }
+}
+
+/// The ```/.well-known/security.txt``` with the contacts for reporting security issues
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityTxt {
+    contacts: Vec<String>,
+    // seconds since the unix epoch
+    expires: u64,
+    encryption: Vec<String>,
+    acknowledgments: Vec<String>,
+    preferred_languages: Vec<String>,
+    canonical: Vec<String>,
+    policy: Vec<String>,
+    hiring: Vec<String>,
+}
+
+impl SecurityTxt {
+    /// Returns the file, which expires at the time in seconds since the unix epoch
+    pub fn new(expires: u64) -> SecurityTxt {
+        SecurityTxt {
+            contacts: Vec::new(),
+            expires,
+            encryption: Vec::new(),
+            acknowledgments: Vec::new(),
+            preferred_languages: Vec::new(),
+            canonical: Vec::new(),
+            policy: Vec::new(),
+            hiring: Vec::new(),
+        }
+    }
+
+    /// Adds a contact as URI, e.g. "mailto:security@example.com" or "https://example.com/report", in the order of
+    /// preference
+    pub fn add_contact(&mut self, uri: &str) {
+        self.contacts.push(uri.to_string());
+    }
+
+    /// Adds the URI of a key for encrypted reports
+    pub fn add_encryption(&mut self, uri: &str) {
+        self.encryption.push(uri.to_string());
+    }
+
+    /// Adds the URI of the page, which thanks the reporters
+    pub fn add_acknowledgments(&mut self, uri: &str) {
+        self.acknowledgments.push(uri.to_string());
+    }
+
+    /// Sets the languages of the reports as language tags, e.g. "en"
+    pub fn set_preferred_languages(&mut self, languages: &[&str]) {
+        self.preferred_languages = languages.iter().map(|l| l.to_string()).collect();
+    }
+
+    /// Adds the URI, where the file is published
+    pub fn add_canonical(&mut self, uri: &str) {
+        self.canonical.push(uri.to_string());
+    }
+
+    /// Adds the URI of the security policy
+    pub fn add_policy(&mut self, uri: &str) {
+        self.policy.push(uri.to_string());
+    }
+
+    /// Adds the URI of the security-related jobs
+    pub fn add_hiring(&mut self, uri: &str) {
+        self.hiring.push(uri.to_string());
+    }
+
+    /// Returns the file at the time in seconds since the unix epoch. It fails, if there is no contact or if the
+    /// file is expired.
+    pub fn to_text(&self, now: u64) -> Result<String, WriterError> {
+        if self.contacts.is_empty() {
+            return Err(invalid("security.txt", "at least one Contact is required"));
+        }
+        if self.expires <= now {
+            return Err(invalid("security.txt", "the Expires-date has passed"));
+        }
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        fields.extend(self.contacts.iter().map(|c| ("Contact", c.clone())));
+        fields.push(("Expires", format_rfc3339(self.expires)));
+        fields.extend(self.encryption.iter().map(|e| ("Encryption", e.clone())));
+        fields.extend(
+            self.acknowledgments
+                .iter()
+                .map(|a| ("Acknowledgments", a.clone())),
+        );
+        if !self.preferred_languages.is_empty() {
+            fields.push(("Preferred-Languages", self.preferred_languages.join(", ")));
+        }
+        fields.extend(self.canonical.iter().map(|c| ("Canonical", c.clone())));
+        fields.extend(self.policy.iter().map(|p| ("Policy", p.clone())));
+        fields.extend(self.hiring.iter().map(|h| ("Hiring", h.clone())));
+        let mut text = String::new();
+        key_value_lines(&mut text, &fields);
+        Ok(text)
+    }
+}
+
+/// The ```/humans.txt``` with the people and the technology behind the site
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HumansTxt {
+    // the sections with their fields
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl HumansTxt {
+    pub fn new() -> HumansTxt {
+        HumansTxt::default()
+    }
+
+    /// Starts a new section, e.g. "TEAM", "THANKS" or "SITE"
+    pub fn add_section(&mut self, name: &str) {
+        self.sections.push((name.to_uppercase(), Vec::new()));
+    }
+
+    /// Adds the field to the last section. It fails, if there is no section yet.
+    pub fn add(&mut self, key: &str, value: &str) -> Result<(), WriterError> {
+        let Some((_, fields)) = self.sections.last_mut() else {
+            return Err(invalid("humans.txt", "fields belong into a section"));
+        };
+        fields.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    /// Returns the file, each section starts with its name as comment, e.g. ```/* TEAM */```
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (i, (name, fields)) in self.sections.iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            text.push_str(&format!("/* {} */\n", name));
+            let fields: Vec<(&str, String)> = fields
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone()))
+                .collect();
+            key_value_lines(&mut text, &fields);
+        }
+        text
+    }
+}
+
+fn invalid(file: &'static str, reason: &'static str) -> WriterError {
+    WriterError::InvalidWellKnownFile { file, reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wellknown_files() {
+        assert_eq!(
+            SecurityTxt::new(10).to_text(0),
+            Err(invalid("security.txt", "at least one Contact is required"))
+        );
+        let mut humans = HumansTxt::new();
+        assert!(humans.add("Name", "x").is_err());
+        humans.add_section("team");
+        humans.add("Developer", "Max\nMuster").unwrap();
+        humans.add_section("site");
+        humans.add("Language", "English").unwrap();
+        assert_eq!(
+            humans.to_text(),
+            "/* TEAM */\nDeveloper: Max Muster\n\n/* SITE */\nLanguage: English\n"
+        );
+    }
+}
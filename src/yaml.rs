@@ -0,0 +1,523 @@
+//! Implementation of the YAMLWriter for writing YAML-files, e.g. configs.
+//!
+//! The YAMLWriter follows the model of the JSONWriter: the root mapping is opened by ```open_tag("")```, a nested
+//! mapping by ```open_tag()``` with its key, and ```add_property()``` writes ```key: value```. Sequences are opened by
+//! ```open_list()```, their items are written by ```add_item()```, or are mappings and sequences without a key. The
+//! indent is given by the nesting, empty mappings and sequences are written as ```{}``` and ```[]```.
+//!
+//! Values are written as they are, ```yaml_string()``` quotes a text only when YAML would take it for something
+//! else, e.g. a number, a boolean or a comment. Keys are quoted the same way automatically.
+//!
+//! ```
+//! # use mllwriter::{yaml_string, MLLWriter, YAMLWriter};
+//! let mut wr = YAMLWriter::new();
+//! wr.open_tag("");
+//! wr.open_tag("server");
+//! wr.add_property("host", &yaml_string("example.com"));
+//! wr.add_property("port", "8080");
+//! wr.close_tag();
+//! wr.open_list("users");
+//! wr.open_tag("");
+//! wr.add_property("name", &yaml_string("admin"));
+//! wr.add_property("password", &yaml_string("#secret"));
+//! wr.close_tag();
+//! wr.close_tag();
+//! wr.close_tag();
+//! assert_eq!(
+//!     wr.content,
+//!     "server:\n  host: example.com\n  port: 8080\nusers:\n  - name: admin\n    password: \"#secret\""
+//! );
+//! ```
+
+use crate::banner::{self, BannerStyle};
+use crate::provenance::{self, TimestampPolicy};
+use crate::{check, DocumentState, MLLWriter, Properties, WriterCore, WriterError, WriterProfile};
+
+/// Implementation of the YAMLWriter for writing YAML-files. Default indent-step-size is 2.
+#[derive(Debug, Clone)]
+pub struct YAMLWriter {
+    /// Content held by the writer
+    pub content: String,
+    /// WriterCore in a composition
+    pub core: WriterCore,
+    /// Depths of the open sequences, internal
+    lists: Vec<usize>,
+    /// The entry follows a dash on the same line, internal
+    inline: bool,
+    /// The innermost mapping or sequence has no entry yet, internal
+    empty: bool,
+    /// Blank lines in front of the next entry, internal
+    blank_lines: usize,
+}
+
+impl YAMLWriter {
+    pub fn new() -> YAMLWriter {
+        YAMLWriter {
+            content: String::new(),
+            core: WriterCore::new(2),
+            lists: Vec::new(),
+            inline: false,
+            empty: false,
+            blank_lines: 0,
+        }
+    }
+
+    /// Opens a sequence, which is closed by ```close_tag()```. Like mappings, a nested sequence needs a key, but
+    /// the items of a sequence have none.
+    pub fn open_list(&mut self, name: &str) {
+        check(self.try_open_list(name));
+    }
+
+    /// Like ```open_list()```, but returns the error instead of panicking
+    pub fn try_open_list(&mut self, name: &str) -> Result<(), WriterError> {
+        self.open_block(name, "open_list")?;
+        self.lists.push(self.core.block_stack.len());
+        Ok(())
+    }
+
+    /// Adds an item to the current sequence, the value is written as it is
+    pub fn add_item(&mut self, value: &str) {
+        check(self.try_add_item(value));
+    }
+
+    /// Like ```add_item()```, but returns the error instead of panicking
+    pub fn try_add_item(&mut self, value: &str) -> Result<(), WriterError> {
+        self.write_item(value, "add_item")
+    }
+
+    // Writes the value as it is as item of the current sequence
+    fn write_item(&mut self, value: &str, operation: &'static str) -> Result<(), WriterError> {
+        self.core.begin_member(operation)?;
+        if !self.in_list() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation,
+                reason: "items can only be added to a sequence",
+            });
+        }
+        self.begin_entry();
+        self.content.push_str("- ");
+        self.content.push_str(value);
+        Ok(())
+    }
+
+    // Returns an empty writer with the options of this one, for take_content()
+    pub(crate) fn next_document(&self) -> YAMLWriter {
+        YAMLWriter {
+            core: self.core.sub_core(),
+            ..YAMLWriter::new()
+        }
+    }
+
+    // Returns true if the current block is a sequence
+    fn in_list(&self) -> bool {
+        self.lists.last() == Some(&self.core.block_stack.len())
+    }
+
+    // Opens a mapping or a sequence, the root and the items of a sequence have no key
+    fn open_block(&mut self, name: &str, operation: &'static str) -> Result<(), WriterError> {
+        let root = self.core.state == DocumentState::Prolog;
+        let reason = if root && !name.is_empty() {
+            Some("the root block cannot have a name")
+        } else if !root && self.in_list() && !name.is_empty() {
+            Some("the items of a sequence have no key")
+        } else if !root && !self.in_list() && name.is_empty() {
+            Some("a nested block needs a key")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation,
+                reason,
+            });
+        }
+        let item = !root && self.in_list();
+        self.core.begin_element(operation)?;
+        if item {
+            // The first entry of the item follows the dash, so the dash is padded to the indent-step
+            self.begin_entry();
+            self.content.push('-');
+            self.content
+                .push_str(&" ".repeat(self.core.indent_step_size.max(2) - 1));
+            self.inline = true;
+        } else if !root {
+            self.begin_entry();
+            self.content.push_str(&yaml_string(name));
+            self.content.push(':');
+        }
+        self.core.block_stack.push(name.to_string());
+        self.core.state = DocumentState::InElement;
+        self.empty = true;
+        Ok(())
+    }
+
+    // Starts a new line with the indent of the current block, unless the entry follows a dash
+    fn begin_entry(&mut self) {
+        self.empty = false;
+        if std::mem::take(&mut self.inline) {
+            return;
+        }
+        // YAML doesn't allow tabs in the indent
+        let depth = self.core.block_stack.len().saturating_sub(1);
+        self.core.indent = " ".repeat(depth * self.core.indent_step_size);
+        if !self.content.is_empty() {
+            let blank_lines = std::mem::take(&mut self.blank_lines);
+            self.content
+                .push_str(&self.core.newline().repeat(blank_lines + 1));
+        }
+        self.content.push_str(&self.core.indent);
+    }
+}
+
+impl Default for YAMLWriter {
+    fn default() -> Self {
+        YAMLWriter::new()
+    }
+}
+
+impl MLLWriter for YAMLWriter {
+    /// Inside of a sequence the mapping has no key
+    fn open_tag(&mut self, tag: &str) {
+        check(self.try_open_tag(tag));
+    }
+
+    fn try_open_tag(&mut self, tag: &str) -> Result<(), WriterError> {
+        self.open_block(tag, "open_tag")
+    }
+
+    fn open_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.open_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    /// Closes the current mapping or sequence
+    fn close_tag(&mut self) {
+        check(self.try_close_tag());
+    }
+
+    fn try_close_tag(&mut self) -> Result<(), WriterError> {
+        let list = self.in_list();
+        self.core.end_element("close_tag")?;
+        if list {
+            self.lists.pop();
+        }
+        if std::mem::take(&mut self.empty) {
+            let empty = if list { "[]" } else { "{}" };
+            if !std::mem::take(&mut self.inline) && !self.content.is_empty() {
+                self.content.push(' ');
+            }
+            self.content.push_str(empty);
+        }
+        Ok(())
+    }
+
+    fn single_tag(&mut self, tag: &str) {
+        check(self.try_single_tag(tag));
+    }
+
+    fn try_single_tag(&mut self, _tag: &str) -> Result<(), WriterError> {
+        Err(WriterError::UnsupportedOperation {
+            operation: "single_tag",
+            reason: "there is no single_element in the YAMLWriter",
+        })
+    }
+
+    fn single_tag_w_property(&mut self, tag: &str, prop: &str, value: &str) {
+        self.single_tag(tag);
+        self.add_property(prop, value);
+    }
+
+    fn add_property(&mut self, name: &str, value: &str) {
+        check(self.try_add_property(name, value));
+    }
+
+    fn try_add_property(&mut self, name: &str, value: &str) -> Result<(), WriterError> {
+        self.core.begin_member("add_property")?;
+        if self.in_list() {
+            return Err(WriterError::IllegalTransition {
+                state: self.core.state,
+                operation: "add_property",
+                reason: "the items of a sequence have no key, see add_item()",
+            });
+        }
+        self.begin_entry();
+        self.content.push_str(&yaml_string(name));
+        self.content.push_str(": ");
+        self.content.push_str(value);
+        Ok(())
+    }
+
+    fn add_properties(&mut self, properties: &Properties) {
+        properties
+            .p
+            .iter()
+            .for_each(|x| self.add_property(&x.0, &x.1));
+    }
+
+    /// Writes a '#'-comment on its own line
+    fn add_comment(&mut self, comment: &str) {
+        check(self.try_add_comment(comment));
+    }
+
+    fn try_add_comment(&mut self, comment: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("add_comment")?;
+        let inline = std::mem::take(&mut self.inline);
+        let empty = self.empty;
+        self.begin_entry();
+        // Each line of the comment is a comment line of its own, the content would continue otherwise
+        let separator = format!("{}{}# ", self.core.newline(), self.core.indent);
+        self.content.push_str("# ");
+        self.content
+            .push_str(&comment.replace("\r\n", "\n").replace('\n', &separator));
+        // A comment is no entry of the block
+        self.empty = empty;
+        self.inline = inline;
+        Ok(())
+    }
+
+    /// Writes the text as item of the current sequence, outside of sequences text needs a key
+    fn text(&mut self, text: &str) {
+        check(self.try_text(text));
+    }
+
+    fn try_text(&mut self, text: &str) -> Result<(), WriterError> {
+        self.write_item(&yaml_string(text), "text")
+    }
+
+    fn raw(&mut self, raw: &str) {
+        check(self.try_raw(raw));
+    }
+
+    fn try_raw(&mut self, raw: &str) -> Result<(), WriterError> {
+        self.core.begin_markup("raw")?;
+        self.content.push_str(raw);
+        Ok(())
+    }
+
+    fn file_banner(&mut self, lines: &[&str], style: BannerStyle) {
+        check(self.core.begin_markup("file_banner"));
+        let newline = self.core.newline();
+        if !self.content.is_empty() {
+            self.content.push_str(newline);
+        }
+        let indent = self.core.indent.clone();
+        banner::write_banner(&mut self.content, newline, &indent, lines, style, "# ", "");
+    }
+
+    fn stamp_provenance(&mut self, tool: &str, version: &str, timestamp_policy: TimestampPolicy) {
+        self.add_comment(&provenance::provenance_comment(
+            tool,
+            version,
+            timestamp_policy,
+        ));
+    }
+
+    fn line_feed(&mut self, n: usize) {
+        self.core.line_feed(&mut self.content, n);
+    }
+
+    fn blank_line(&mut self) {
+        self.ensure_blank_lines(1);
+    }
+
+    /// The blank lines are written in front of the next entry
+    fn ensure_blank_lines(&mut self, n: usize) {
+        self.blank_lines = self.blank_lines.max(n);
+    }
+
+    fn line_feed_inc(&mut self) {
+        self.core.line_feed_inc(&mut self.content);
+    }
+
+    fn line_feed_dec(&mut self) {
+        self.core.line_feed_dec(&mut self.content);
+    }
+
+    fn inc_indent_step(&mut self) {
+        self.core.inc_indent_step();
+    }
+
+    fn dec_indent_step(&mut self) {
+        self.core.dec_indent_step();
+    }
+
+    fn set_indent_step(&mut self, indent_step: usize) {
+        self.core.set_indent_step(indent_step);
+    }
+
+    fn set_indent_step_size(&mut self, indent_step_size: usize) {
+        self.core.set_indent_step_size(indent_step_size);
+    }
+
+    fn apply_profile(&mut self, profile: &WriterProfile) {
+        self.core.apply_profile(profile);
+    }
+
+    fn end_document(&mut self) {
+        check(self.core.end_document("end_document"));
+    }
+
+    fn clear(&mut self) {
+        self.core.clear(2);
+        self.content.clear();
+        self.lists.clear();
+        self.inline = false;
+        self.empty = false;
+        self.blank_lines = 0;
+    }
+}
+
+impl std::fmt::Display for YAMLWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "indent_step_size: {}\nindent: {}\nblock_stack: {:?}\n{}\n",
+            self.core.indent_step_size,
+            self.core.indent.len(),
+            self.core.block_stack,
+            self.content
+        )
+    }
+}
+
+impl std::fmt::Write for YAMLWriter {
+    fn write_str(&mut self, s: &str) -> Result<(), std::fmt::Error> {
+        self.content.write_str(s)
+    }
+}
+
+/// Returns the text as YAML-scalar, in double quotes if YAML would take the plain text for something else, i.e.
+/// for the null, bool, number and timestamp forms of YAML 1.1 and 1.2 and the merge key ```<<```
+pub fn yaml_string(text: &str) -> String {
+    const RESERVED: [&str; 13] = [
+        "", "~", "null", "Null", "NULL", "true", "True", "TRUE", "false", "False", "FALSE", "<<",
+        "=",
+    ];
+    let plain = !RESERVED.contains(&text)
+        && text.parse::<f64>().is_err()
+        && !is_number(text)
+        && !is_timestamp(text)
+        && !text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@` ".contains(c))
+        && !text.ends_with([' ', ':'])
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.chars().any(char::is_control)
+        && !matches!(
+            text.to_ascii_lowercase().as_str(),
+            "yes" | "no" | "on" | "off" | "y" | "n"
+        );
+    if plain {
+        return text.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Returns true for the numbers of YAML 1.1 and 1.2, e.g. "0x1F", "0o17", "1_000", ".inf" or "1:30", also for
+// similar forms like "1.2.3", which are quoted to be sure
+fn is_number(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if matches!(
+        unsigned,
+        ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN"
+    ) {
+        return true;
+    }
+    let prefixed = |prefixes: [&str; 2], radix: u32| {
+        prefixes.iter().any(|prefix| {
+            unsigned.strip_prefix(prefix).is_some_and(|digits| {
+                !digits.is_empty() && digits.chars().all(|c| c == '_' || c.is_digit(radix))
+            })
+        })
+    };
+    if prefixed(["0x", "0X"], 16) || prefixed(["0o", "0O"], 8) || prefixed(["0b", "0B"], 2) {
+        return true;
+    }
+    unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && unsigned.chars().any(|c| c.is_ascii_digit())
+        && unsigned
+            .chars()
+            .all(|c| c.is_ascii_digit() || "._:eE+-".contains(c))
+}
+
+// Returns true for the timestamps of YAML 1.1, which start with a date like "2024-01-01"
+fn is_timestamp(text: &str) -> bool {
+    let mut parts = text.splitn(3, '-');
+    let (Some(year), Some(month), Some(rest)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let day_len = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let digits = |s: &str, min: usize, max: usize| {
+        (min..=max).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit())
+    };
+    digits(year, 4, 4)
+        && digits(month, 1, 2)
+        && digits(&rest[..day_len], 1, 2)
+        && (day_len == rest.len() || rest[day_len..].starts_with(['T', 't', ' ']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_nested_sequences() {
+        let mut wr = YAMLWriter::new();
+        wr.set_indent_step_size(4);
+        wr.open_tag("");
+        wr.add_comment("generated");
+        wr.open_list("matrix");
+        wr.add_comment("a\nb: 1");
+        wr.open_list("");
+        wr.add_item("1");
+        wr.add_item("2");
+        wr.close_tag();
+        wr.open_tag("");
+        wr.close_tag();
+        wr.close_tag();
+        wr.open_list("empty");
+        wr.close_tag();
+        wr.close_tag();
+        assert_eq!(
+            wr.content,
+            "# generated\nmatrix:\n    # a\n    # b: 1\n    -   - 1\n        - 2\n    -   {}\nempty: []"
+        );
+        assert_eq!(yaml_string("yes"), "\"yes\"");
+        assert_eq!(yaml_string("a\"b\n"), "\"a\\\"b\\n\"");
+        assert_eq!(yaml_string("v1.2"), "v1.2");
+        for special in [
+            ".inf",
+            "-.Inf",
+            ".NaN",
+            "0x1F",
+            "0o17",
+            "0b101",
+            "1_000",
+            "1:30",
+            "2024-01-01",
+            "2001-12-14t21:59:43.10-05:00",
+            "<<",
+            "1e3",
+            "+12",
+        ] {
+            assert_eq!(yaml_string(special), format!("\"{}\"", special));
+        }
+        assert_eq!(yaml_string("0xyz"), "0xyz");
+        assert_eq!(yaml_string("2024-01-01x"), "2024-01-01x");
+    }
+}